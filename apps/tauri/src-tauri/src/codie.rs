@@ -0,0 +1,373 @@
+//! CODIE parser
+//!
+//! Recursive-descent parser for `PRIMITIVE{key:value, CHILD{...}}`. Inside
+//! braces an item is either a `key:value` prop or a child primitive, and a
+//! prop value is text, a quoted string or a nested primitive. Several
+//! primitives may follow each other at the top level.
+
+use std::fmt;
+
+/// Deepest nesting of `{...}` accepted; the parser and renderer recurse
+/// once per level, so untrusted input must not choose the stack depth
+const MAX_DEPTH: usize = 64;
+
+/// A parsed primitive
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub primitive: String,
+    pub props: Vec<(String, Prop)>,
+    pub children: Vec<Node>,
+}
+
+/// Value of a `key:value` prop
+#[derive(Debug, Clone, PartialEq)]
+pub enum Prop {
+    Text(String),
+    Node(Node),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte offset into the input
+    pub position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse one or more top-level primitives
+pub fn parse(input: &str) -> Result<Vec<Node>, ParseError> {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        depth: 0,
+    };
+    let mut nodes = Vec::new();
+
+    parser.skip_whitespace();
+    if parser.at_end() {
+        return Err(parser.error("Empty CODIE"));
+    }
+
+    while !parser.at_end() {
+        nodes.push(parser.node()?);
+        parser.skip_whitespace();
+        if parser.eat(',') {
+            parser.skip_whitespace();
+        }
+    }
+
+    Ok(nodes)
+}
+
+/// Render parsed primitives as nested `gentlyos-*` elements. Props become
+/// `data-*` attributes, nested prop values become children tagged with
+/// `data-slot`, and all text is HTML-escaped.
+pub fn render(nodes: &[Node]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        render_node(node, None, &mut out);
+    }
+    out
+}
+
+fn render_node(node: &Node, slot: Option<&str>, out: &mut String) {
+    let tag = node.primitive.to_lowercase();
+
+    out.push_str(&format!("<gentlyos-{}", tag));
+    if let Some(slot) = slot {
+        out.push_str(&format!(r#" data-slot="{}""#, escape_html(slot)));
+    }
+    for (key, prop) in &node.props {
+        if let Prop::Text(text) = prop {
+            out.push_str(&format!(r#" data-{}="{}""#, key.to_lowercase(), escape_html(text)));
+        }
+    }
+    out.push('>');
+
+    let mut has_content = false;
+    for (key, prop) in &node.props {
+        if let Prop::Node(child) = prop {
+            render_node(child, Some(key), out);
+            has_content = true;
+        }
+    }
+    for child in &node.children {
+        render_node(child, None, out);
+        has_content = true;
+    }
+    if !has_content {
+        out.push_str(&escape_html(&node.primitive));
+    }
+
+    out.push_str(&format!("</gentlyos-{}>", tag));
+}
+
+/// Escape text for use in element content and quoted attribute values
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// Braces currently open
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            message: message.into(),
+            position: self.pos,
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    /// `[A-Za-z_][A-Za-z0-9_-]*`
+    fn ident(&mut self) -> Option<&str> {
+        let start = self.pos;
+        let rest = &self.input[start..];
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return None;
+        }
+
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        Some(&self.input[start..start + len])
+    }
+
+    /// `PRIMITIVE` or `PRIMITIVE{...}`
+    fn node(&mut self) -> Result<Node, ParseError> {
+        let primitive = match self.ident() {
+            Some(name) => name.to_string(),
+            None => return Err(self.error("Expected primitive name")),
+        };
+        self.named_node(primitive)
+    }
+
+    fn named_node(&mut self, primitive: String) -> Result<Node, ParseError> {
+        let mut node = Node {
+            primitive,
+            props: Vec::new(),
+            children: Vec::new(),
+        };
+
+        self.skip_whitespace();
+        let open = self.pos;
+        if !self.eat('{') {
+            return match self.peek() {
+                None | Some(',') | Some('}') => Ok(node),
+                Some(c) => Err(self.error(format!("Unexpected '{}'", c))),
+            };
+        }
+        if self.depth == MAX_DEPTH {
+            return Err(ParseError {
+                message: format!("Nesting deeper than {} levels", MAX_DEPTH),
+                position: open,
+            });
+        }
+        self.depth += 1;
+
+        loop {
+            self.skip_whitespace();
+            if self.eat('}') {
+                self.depth -= 1;
+                return Ok(node);
+            }
+            if self.at_end() {
+                return Err(ParseError {
+                    message: format!("Unclosed '{{' for {}", node.primitive),
+                    position: open,
+                });
+            }
+
+            let name = match self.ident() {
+                Some(name) => name.to_string(),
+                None => {
+                    let c = self.peek().unwrap_or_default();
+                    return Err(self.error(format!("Unexpected '{}'", c)));
+                }
+            };
+
+            self.skip_whitespace();
+            if self.eat(':') {
+                let value = self.value()?;
+                node.props.push((name, value));
+            } else {
+                node.children.push(self.named_node(name)?);
+            }
+
+            self.skip_whitespace();
+            if !self.eat(',') && self.peek() != Some('}') && !self.at_end() {
+                let c = self.peek().unwrap_or_default();
+                return Err(self.error(format!("Expected ',' or '}}', found '{}'", c)));
+            }
+        }
+    }
+
+    /// Quoted string, nested primitive or bare text up to `,` or `}`
+    fn value(&mut self) -> Result<Prop, ParseError> {
+        self.skip_whitespace();
+
+        if self.eat('"') {
+            return self.quoted().map(Prop::Text);
+        }
+
+        let start = self.pos;
+        if let Some(name) = self.ident().map(str::to_string) {
+            self.skip_whitespace();
+            if self.peek() == Some('{') {
+                return self.named_node(name).map(Prop::Node);
+            }
+        }
+
+        self.pos = start;
+        let rest = &self.input[start..];
+        let len = rest.find([',', '{', '}']).unwrap_or(rest.len());
+        self.pos += len;
+        if self.peek() == Some('{') {
+            return Err(self.error("Unexpected '{' in value"));
+        }
+
+        Ok(Prop::Text(rest[..len].trim().to_string()))
+    }
+
+    /// Body of a `"..."` string; `\"` and `\\` are escapes
+    fn quoted(&mut self) -> Result<String, ParseError> {
+        let open = self.pos - 1;
+        let mut text = String::new();
+        let mut chars = self.input[self.pos..].char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(text);
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => text.push(escaped),
+                    None => break,
+                },
+                _ => text.push(c),
+            }
+        }
+
+        Err(ParseError {
+            message: "Unterminated string".to_string(),
+            position: open,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_primitives() {
+        let nodes = parse(r#"APP{GRD{CRD{TXT{t:"Hello"}},BTN{t:"Click",a:submit}}}"#).unwrap();
+        assert_eq!(nodes.len(), 1);
+
+        let grd = &nodes[0].children[0];
+        assert_eq!(grd.primitive, "GRD");
+        assert_eq!(grd.children.len(), 2);
+        assert_eq!(grd.children[0].children[0].props[0], ("t".into(), Prop::Text("Hello".into())));
+        assert_eq!(grd.children[1].props[1], ("a".into(), Prop::Text("submit".into())));
+
+        // A nested primitive as a prop value
+        let nodes = parse("MOD{title:Settings, body:LST{n:3}}").unwrap();
+        match &nodes[0].props[1].1 {
+            Prop::Node(child) => assert_eq!(child.primitive, "LST"),
+            other => panic!("expected a node, got {:?}", other),
+        }
+
+        let html = render(&nodes);
+        assert!(html.starts_with(r#"<gentlyos-mod data-title="Settings">"#));
+        assert!(html.contains(r#"<gentlyos-lst data-slot="body" data-n="3">LST</gentlyos-lst>"#));
+    }
+
+    #[test]
+    fn test_multiple_siblings() {
+        let nodes = parse("NAV{t:Home} BTN{t:Go}, TXT").unwrap();
+        let names: Vec<&str> = nodes.iter().map(|n| n.primitive.as_str()).collect();
+        assert_eq!(names, ["NAV", "BTN", "TXT"]);
+        assert_eq!(render(&nodes).matches("<gentlyos-").count(), 3);
+    }
+
+    #[test]
+    fn test_values_are_escaped() {
+        let nodes = parse(r#"TXT{t:"<img src=x onerror=alert(1)>", c:"a\"b"}"#).unwrap();
+        let html = render(&nodes);
+        assert!(!html.contains("<img"));
+        assert!(html.contains(r#"data-t="&lt;img src=x onerror=alert(1)&gt;""#));
+        assert!(html.contains(r#"data-c="a&quot;b""#));
+    }
+
+    #[test]
+    fn test_unbalanced_braces_are_errors() {
+        let err = parse("APP{GRD{BTN{t:Click}}").unwrap_err();
+        assert_eq!(err.position, 3);
+        assert!(err.message.contains("Unclosed"));
+
+        assert!(parse("APP{t:x}}").is_err());
+        assert!(parse("APP{t:a{b}").is_err());
+        assert!(parse(r#"TXT{t:"open}"#).is_err());
+        assert!(parse("   ").is_err());
+    }
+
+    #[test]
+    fn test_nesting_depth_is_limited() {
+        let nested = |depth: usize| format!("{}{}", "A{".repeat(depth), "}".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+
+        let err = parse(&nested(MAX_DEPTH + 1)).unwrap_err();
+        assert!(err.message.contains("Nesting"), "{}", err);
+        assert_eq!(err.position, 2 * MAX_DEPTH + 1);
+
+        // Deep enough to overflow the stack without the limit
+        let err = parse(&format!("A{{v:{}}}", nested(100_000))).unwrap_err();
+        assert!(err.message.contains("Nesting"), "{}", err);
+    }
+}
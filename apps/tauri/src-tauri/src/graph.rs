@@ -0,0 +1,165 @@
+//! Neural graph
+//!
+//! Nodes are numbered in insertion order and edges are directed. The graph is
+//! kept as an adjacency list and serialized to a flat snapshot for the
+//! frontend to render.
+
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    pub id: u32,
+    pub label: String,
+    pub xor: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GraphEdge {
+    pub from: u32,
+    pub to: u32,
+}
+
+/// Everything the frontend needs to draw the graph
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphSnapshot {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphError {
+    UnknownNode(u32),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::UnknownNode(id) => write!(f, "Unknown graph node {}", id),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+#[derive(Debug, Default)]
+pub struct Graph {
+    nodes: Vec<GraphNode>,
+    /// Outgoing edges, indexed by node id
+    adjacency: Vec<Vec<u32>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum()
+    }
+
+    /// Most recently added node
+    pub fn last(&self) -> Option<&GraphNode> {
+        self.nodes.last()
+    }
+
+    pub fn add_node(&mut self, label: impl Into<String>, xor: impl Into<String>) -> GraphNode {
+        let node = GraphNode {
+            id: self.nodes.len() as u32,
+            label: label.into(),
+            xor: xor.into(),
+        };
+        self.nodes.push(node.clone());
+        self.adjacency.push(Vec::new());
+        node
+    }
+
+    /// Add `from -> to`; returns false if the edge already existed
+    pub fn add_edge(&mut self, from: u32, to: u32) -> Result<bool, GraphError> {
+        self.node(to)?;
+        self.node(from)?;
+
+        let targets = &mut self.adjacency[from as usize];
+        if targets.contains(&to) {
+            return Ok(false);
+        }
+        targets.push(to);
+        Ok(true)
+    }
+
+    pub fn node(&self, id: u32) -> Result<&GraphNode, GraphError> {
+        self.nodes
+            .get(id as usize)
+            .ok_or(GraphError::UnknownNode(id))
+    }
+
+    /// Nodes reachable from `id` over one edge, in the order they were linked
+    pub fn neighbors(&self, id: u32) -> Result<Vec<&GraphNode>, GraphError> {
+        self.node(id)?;
+        Ok(self.adjacency[id as usize]
+            .iter()
+            .map(|&to| &self.nodes[to as usize])
+            .collect())
+    }
+
+    pub fn snapshot(&self) -> GraphSnapshot {
+        let edges = self
+            .adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(from, targets)| {
+                targets.iter().map(move |&to| GraphEdge {
+                    from: from as u32,
+                    to,
+                })
+            })
+            .collect();
+
+        GraphSnapshot {
+            nodes: self.nodes.clone(),
+            edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edges_and_neighbors() {
+        let mut graph = Graph::new();
+        let a = graph.add_node("boot", "1A2").id;
+        let b = graph.add_node("query", "3F0").id;
+        let c = graph.add_node("query", "07C").id;
+
+        assert!(graph.add_edge(a, b).unwrap());
+        assert!(graph.add_edge(a, c).unwrap());
+        assert!(!graph.add_edge(a, b).unwrap());
+        assert_eq!(graph.edge_count(), 2);
+
+        let neighbors: Vec<u32> = graph.neighbors(a).unwrap().iter().map(|n| n.id).collect();
+        assert_eq!(neighbors, [b, c]);
+        assert!(graph.neighbors(c).unwrap().is_empty());
+
+        assert_eq!(graph.add_edge(a, 9), Err(GraphError::UnknownNode(9)));
+        assert!(graph.neighbors(9).is_err());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut graph = Graph::new();
+        graph.add_node("boot", "1A2");
+        graph.add_node("query", "3F0");
+        graph.add_edge(0, 1).unwrap();
+
+        let snapshot = graph.snapshot();
+        assert_eq!(snapshot.nodes.len(), graph.node_count());
+        assert_eq!(snapshot.edges, [GraphEdge { from: 0, to: 1 }]);
+        assert_eq!(snapshot.nodes[1].xor, "3F0");
+    }
+}
@@ -8,11 +8,13 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod codie;
 mod xor;
 mod graph;
 
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
+use std::path::PathBuf;
 use std::sync::Mutex;
 use tauri::{
     CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
@@ -21,8 +23,12 @@ use tauri::{
 
 // Global state
 struct AppState {
-    xor_chain: Mutex<Vec<String>>,
-    graph_nodes: Mutex<u32>,
+    xor_chain: Mutex<Vec<xor::ChainLink>>,
+    /// Counter mixed into the next link; restored from the persisted chain
+    xor_counter: Mutex<u64>,
+    /// Where the chain is persisted; `None` if there is no app data dir
+    chain_path: Option<PathBuf>,
+    graph: Mutex<graph::Graph>,
     boot_xor: Mutex<String>,
     initialized: Mutex<bool>,
 }
@@ -52,83 +58,117 @@ struct Status {
     graph_nodes: u32,
 }
 
-/// Generate XOR key from state
-fn generate_xor(state: &str, previous: Option<&str>) -> String {
+/// Hash content
+fn hash_content(content: &str) -> String {
     let mut hasher = Sha256::new();
-    hasher.update(state.as_bytes());
+    hasher.update(content.as_bytes());
     let hash = hasher.finalize();
+    hex::encode(&hash[..16])
+}
 
-    let state_part = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]);
-    let time_part = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis() as u32;
+/// Append a link for `state_input` to the chain and advance the counter
+fn push_link(state: &AppState, chain: &mut Vec<xor::ChainLink>, state_input: &str) -> String {
+    let mut counter = state.xor_counter.lock().unwrap();
+    let link = xor::next_link(state_input, *counter, chain.last());
+    *counter += 1;
 
-    let prev_part = previous
-        .and_then(|p| u32::from_str_radix(p, 16).ok())
-        .unwrap_or(0);
+    let value = link.xor.clone();
+    chain.push(link);
+    value
+}
 
-    let xor_value = (state_part ^ time_part ^ prev_part) & 0xFFF;
-    format!("{:03X}", xor_value)
+/// Persist the chain if the app has somewhere to put it
+fn save_chain(state: &AppState, chain: &[xor::ChainLink]) -> Result<(), String> {
+    match &state.chain_path {
+        Some(path) => xor::save_chain(path, chain)
+            .map_err(|e| format!("Failed to save XOR chain to {}: {}", path.display(), e)),
+        None => Ok(()),
+    }
 }
 
-/// Hash content
-fn hash_content(content: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(content.as_bytes());
-    let hash = hasher.finalize();
-    hex::encode(&hash[..16])
+fn current_status(state: &AppState) -> Status {
+    Status {
+        initialized: *state.initialized.lock().unwrap(),
+        mode: "production".to_string(),
+        license: "Personal".to_string(),
+        boot_xor: state.boot_xor.lock().unwrap().clone(),
+        xor_chain: state.xor_chain.lock().unwrap().len(),
+        graph_nodes: state.graph.lock().unwrap().node_count() as u32,
+    }
 }
 
 // Tauri commands
 
 #[tauri::command]
-fn initialize(state: State<AppState>) -> Status {
-    let boot_state = format!("boot:{}", chrono::Utc::now().timestamp());
-    let xor = generate_xor(&boot_state, None);
+fn initialize(state: State<AppState>) -> Result<Status, String> {
+    let mut chain = state.xor_chain.lock().unwrap();
 
-    *state.boot_xor.lock().unwrap() = xor.clone();
-    state.xor_chain.lock().unwrap().push(xor.clone());
-    *state.initialized.lock().unwrap() = true;
+    // Pick up the chain from previous runs
+    if let Some(path) = &state.chain_path {
+        *chain = xor::load_chain(path)
+            .map_err(|e| format!("Failed to load XOR chain from {}: {}", path.display(), e))?;
+    }
 
-    println!("[GENTLYOS] Initialized with XOR: {}", xor);
+    *state.xor_counter.lock().unwrap() = chain.last().map_or(0, |link| link.counter + 1);
 
-    Status {
-        initialized: true,
-        mode: "production".to_string(),
-        license: "Personal".to_string(),
-        boot_xor: xor,
-        xor_chain: 1,
-        graph_nodes: 0,
+    // First run (or after a reset): start the chain with a boot link
+    if chain.is_empty() {
+        push_link(&state, &mut chain, "boot");
+        save_chain(&state, &chain)?;
     }
+
+    let boot_xor = chain[0].xor.clone();
+    println!("[GENTLYOS] Initialized with XOR: {} (chain length {})", boot_xor, chain.len());
+    drop(chain);
+
+    *state.boot_xor.lock().unwrap() = boot_xor;
+    *state.initialized.lock().unwrap() = true;
+
+    Ok(current_status(&state))
 }
 
 #[tauri::command]
 fn get_status(state: State<AppState>) -> Status {
-    Status {
-        initialized: *state.initialized.lock().unwrap(),
-        mode: "production".to_string(),
-        license: "Personal".to_string(),
-        boot_xor: state.boot_xor.lock().unwrap().clone(),
-        xor_chain: state.xor_chain.lock().unwrap().len(),
-        graph_nodes: *state.graph_nodes.lock().unwrap(),
+    current_status(&state)
+}
+
+#[tauri::command]
+fn reset_chain(state: State<AppState>) -> Result<Status, String> {
+    let mut chain = state.xor_chain.lock().unwrap();
+    if let Some(path) = &state.chain_path {
+        xor::remove_chain(path)
+            .map_err(|e| format!("Failed to remove XOR chain {}: {}", path.display(), e))?;
     }
+    chain.clear();
+    drop(chain);
+
+    *state.xor_counter.lock().unwrap() = 0;
+    state.boot_xor.lock().unwrap().clear();
+    println!("[GENTLYOS] XOR chain reset");
+
+    Ok(current_status(&state))
 }
 
 #[tauri::command]
-fn process_interaction(interaction: Interaction, state: State<AppState>) -> ProcessResult {
-    // Generate new XOR
-    let chain = state.xor_chain.lock().unwrap();
-    let previous = chain.last().map(|s| s.as_str());
+fn process_interaction(
+    interaction: Interaction,
+    state: State<AppState>,
+) -> Result<ProcessResult, String> {
+    // Generate new XOR and add it to the chain under one lock
+    let mut chain = state.xor_chain.lock().unwrap();
     let prompt = interaction.prompt.clone().unwrap_or_default();
-    let xor = generate_xor(&prompt, previous);
+    let xor = push_link(&state, &mut chain, &prompt);
+    save_chain(&state, &chain)?;
     drop(chain);
 
-    // Add to chain
-    state.xor_chain.lock().unwrap().push(xor.clone());
-
-    // Increment graph nodes
-    *state.graph_nodes.lock().unwrap() += 1;
+    // Record the interaction as a graph node following the previous one
+    let mut graph = state.graph.lock().unwrap();
+    let previous = graph.last().map(|node| node.id);
+    let node = graph.add_node(interaction.action.clone(), xor.clone());
+    if let Some(previous) = previous {
+        graph.add_edge(previous, node.id).map_err(|e| e.to_string())?;
+    }
+    drop(graph);
 
     // Determine route (simplified - would use ML in real impl)
     let route = if prompt.len() > 100 || prompt.contains("redesign") || prompt.contains("analyze") {
@@ -140,55 +180,77 @@ fn process_interaction(interaction: Interaction, state: State<AppState>) -> Proc
     println!("[GENTLYOS] Processed: {} -> XOR: {} via {}",
              interaction.action, xor, route);
 
-    ProcessResult {
+    Ok(ProcessResult {
         response: format!("Processed '{}' via {} model",
                          interaction.action, route),
         xor,
         route: route.to_string(),
-    }
+    })
 }
 
 #[tauri::command]
-fn parse_codie(codie: String) -> String {
-    // Simple CODIE parser
-    // Format: PRIMITIVE{key:value,key:value}
-
-    let re = regex::Regex::new(r"(\w+)\{([^}]*)\}").ok();
-
-    if let Some(regex) = re {
-        if let Some(caps) = regex.captures(&codie) {
-            let primitive = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-            let props = caps.get(2).map(|m| m.as_str()).unwrap_or("");
-
-            return format!(
-                r#"<gentlyos-{} data-props="{}">{}</gentlyos-{}>"#,
-                primitive.to_lowercase(),
-                props,
-                primitive,
-                primitive.to_lowercase()
-            );
-        }
-    }
-
-    format!("<div>{}</div>", codie)
+fn parse_codie(codie: String) -> Result<String, String> {
+    // Format: PRIMITIVE{key:value,CHILD{...}}, see codie.rs
+    let nodes = codie::parse(&codie).map_err(|e| format!("Invalid CODIE: {}", e))?;
+    Ok(codie::render(&nodes))
 }
 
 #[tauri::command]
-fn hydrate_codie(codie: String) -> String {
+fn hydrate_codie(codie: String) -> Result<String, String> {
     // Hydrate CODIE to HTML
-    let parsed = parse_codie(codie);
+    let parsed = parse_codie(codie)?;
 
-    format!(
+    Ok(format!(
         r#"<div style="padding: 1rem; background: #1a1a2e; border-radius: 0.5rem; color: #e2e8f0;">
             {}
         </div>"#,
         parsed
-    )
+    ))
 }
 
 #[tauri::command]
 fn get_xor_chain(state: State<AppState>) -> Vec<String> {
-    state.xor_chain.lock().unwrap().clone()
+    state
+        .xor_chain
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|link| link.xor.clone())
+        .collect()
+}
+
+/// Recompute every link of `chain` against the recorded history
+#[tauri::command]
+fn verify_chain(chain: Vec<String>, state: State<AppState>) -> bool {
+    xor::verify_chain(&state.xor_chain.lock().unwrap(), &chain)
+}
+
+#[tauri::command]
+fn add_graph_node(label: String, xor: String, state: State<AppState>) -> graph::GraphNode {
+    state.graph.lock().unwrap().add_node(label, xor)
+}
+
+#[tauri::command]
+fn add_graph_edge(from: u32, to: u32, state: State<AppState>) -> Result<(), String> {
+    state
+        .graph
+        .lock()
+        .unwrap()
+        .add_edge(from, to)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_graph(state: State<AppState>) -> graph::GraphSnapshot {
+    state.graph.lock().unwrap().snapshot()
+}
+
+#[tauri::command]
+fn graph_neighbors(node_id: u32, state: State<AppState>) -> Result<Vec<graph::GraphNode>, String> {
+    let graph = state.graph.lock().unwrap();
+    let neighbors = graph.neighbors(node_id).map_err(|e| e.to_string())?;
+    Ok(neighbors.into_iter().cloned().collect())
 }
 
 #[tauri::command]
@@ -232,19 +294,30 @@ fn main() {
             },
             _ => {}
         })
-        .manage(AppState {
-            xor_chain: Mutex::new(Vec::new()),
-            graph_nodes: Mutex::new(0),
-            boot_xor: Mutex::new(String::new()),
-            initialized: Mutex::new(false),
+        .setup(|app| {
+            app.manage(AppState {
+                xor_chain: Mutex::new(Vec::new()),
+                xor_counter: Mutex::new(0),
+                chain_path: xor::chain_path(&app.config()),
+                graph: Mutex::new(graph::Graph::new()),
+                boot_xor: Mutex::new(String::new()),
+                initialized: Mutex::new(false),
+            });
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             initialize,
             get_status,
+            reset_chain,
             process_interaction,
             parse_codie,
             hydrate_codie,
             get_xor_chain,
+            verify_chain,
+            add_graph_node,
+            add_graph_edge,
+            get_graph,
+            graph_neighbors,
             hash,
         ])
         .run(tauri::generate_context!())
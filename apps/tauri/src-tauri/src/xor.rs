@@ -0,0 +1,145 @@
+//! XOR chain links and persistence
+//!
+//! Each link is `(state_hash ^ counter ^ previous) & 0xFFF`, where the
+//! counter increases by one per link. Links record their state hash and
+//! counter, so the chain can be recomputed and verified later. The chain is
+//! stored as a JSON array of links in the app data dir and rewritten after
+//! every new link.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// File name of the persisted chain inside the app data dir
+pub const CHAIN_FILE: &str = "xor_chain.json";
+
+/// One link of the chain with the inputs needed to recompute it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainLink {
+    pub xor: String,
+    pub state_hash: u32,
+    pub counter: u64,
+}
+
+/// First four bytes of the SHA-256 of `state`
+pub fn state_hash(state: &str) -> u32 {
+    let hash = Sha256::digest(state.as_bytes());
+    u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+}
+
+/// XOR value for a link; the same inputs always give the same value
+pub fn link_xor(state_hash: u32, counter: u64, previous: Option<&str>) -> String {
+    let prev_part = previous
+        .and_then(|p| u32::from_str_radix(p, 16).ok())
+        .unwrap_or(0);
+
+    let xor_value = (state_hash ^ counter as u32 ^ prev_part) & 0xFFF;
+    format!("{:03X}", xor_value)
+}
+
+/// Build the link for `state` following `previous`
+pub fn next_link(state: &str, counter: u64, previous: Option<&ChainLink>) -> ChainLink {
+    let state_hash = state_hash(state);
+    ChainLink {
+        xor: link_xor(state_hash, counter, previous.map(|l| l.xor.as_str())),
+        state_hash,
+        counter,
+    }
+}
+
+/// Check that `chain` is exactly the recorded `links`: every value is
+/// recomputed from its state hash, counter and the preceding value, and
+/// counters strictly increase
+pub fn verify_chain(links: &[ChainLink], chain: &[String]) -> bool {
+    if links.len() != chain.len() {
+        return false;
+    }
+
+    links.iter().enumerate().all(|(i, link)| {
+        let previous = i.checked_sub(1).map(|p| chain[p].as_str());
+        let counter_ok = i == 0 || links[i - 1].counter < link.counter;
+
+        counter_ok
+            && link.xor == chain[i]
+            && link_xor(link.state_hash, link.counter, previous) == chain[i]
+    })
+}
+
+/// Location of the chain file for this app, if the platform has a data dir
+pub fn chain_path(config: &tauri::Config) -> Option<PathBuf> {
+    tauri::api::path::app_data_dir(config).map(|dir| dir.join(CHAIN_FILE))
+}
+
+/// Load the chain, treating a missing file as an empty chain (first run)
+pub fn load_chain(path: &Path) -> io::Result<Vec<ChainLink>> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Write the chain atomically: a temp file next to the target is synced and
+/// then renamed over it, so a crash leaves either the old or the new chain
+pub fn save_chain(path: &Path, chain: &[ChainLink]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(&serde_json::to_vec(chain)?)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&tmp, path)
+}
+
+/// Delete the persisted chain; a missing file is not an error
+pub fn remove_chain(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(states: &[&str]) -> Vec<ChainLink> {
+        let mut links: Vec<ChainLink> = Vec::new();
+        for (counter, state) in states.iter().enumerate() {
+            let link = next_link(state, counter as u64, links.last());
+            links.push(link);
+        }
+        links
+    }
+
+    #[test]
+    fn test_chain_is_reproducible() {
+        let states = ["boot", "hello", "analyze this"];
+        assert_eq!(build(&states), build(&states));
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let links = build(&["boot", "hello", "analyze this"]);
+        let chain: Vec<String> = links.iter().map(|l| l.xor.clone()).collect();
+        assert!(verify_chain(&links, &chain));
+
+        // A tampered, truncated or reordered chain fails
+        let mut tampered = chain.clone();
+        tampered[1] = format!("{:03X}", u32::from_str_radix(&chain[1], 16).unwrap() ^ 1);
+        assert!(!verify_chain(&links, &tampered));
+        assert!(!verify_chain(&links, &chain[..2]));
+
+        let mut reordered = links.clone();
+        reordered.swap(1, 2);
+        let values: Vec<String> = reordered.iter().map(|l| l.xor.clone()).collect();
+        assert!(!verify_chain(&reordered, &values));
+    }
+}
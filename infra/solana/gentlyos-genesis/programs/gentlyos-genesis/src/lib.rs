@@ -26,6 +26,17 @@ pub mod gentlyos_genesis {
     ) -> Result<()> {
         let genesis = &mut ctx.accounts.genesis;
 
+        // The account is init_if_needed so a repeat call reaches this check
+        // instead of failing at the account layer
+        require!(!genesis.is_initialized, GenesisError::AlreadyInitialized);
+
+        require!(
+            !version.is_empty() && version.len() <= Genesis::MAX_VERSION_LEN,
+            GenesisError::InvalidVersion
+        );
+        require!(serial.len() <= Genesis::MAX_SERIAL_LEN, GenesisError::SerialTooLong);
+        require!(is_btc_block_hash(&btc_block_hash), GenesisError::InvalidBtcBlockHash);
+
         genesis.version = version;
         genesis.serial = serial;
         genesis.btc_block_hash = btc_block_hash;
@@ -150,6 +161,12 @@ pub mod gentlyos_genesis {
         btc_height: u64,
         event_data: String,
     ) -> Result<()> {
+        require!(
+            checkpoint_name.len() <= Checkpoint::MAX_NAME_LEN
+                && event_data.len() <= Checkpoint::MAX_EVENT_DATA_LEN,
+            GenesisError::InputTooLong
+        );
+
         let checkpoint = &mut ctx.accounts.checkpoint;
 
         checkpoint.name = checkpoint_name.clone();
@@ -186,6 +203,41 @@ pub mod gentlyos_genesis {
             GenesisError::InvalidWalletType
         );
 
+        require!(
+            !ctx.accounts.from_wallet.is_frozen && !ctx.accounts.to_wallet.is_frozen,
+            GenesisError::WalletFrozen
+        );
+
+        // Only the wallet owner can move its tokens
+        require_keys_eq!(
+            ctx.accounts.from_owner.key(),
+            ctx.accounts.from_wallet.owner,
+            GenesisError::OwnerMismatch
+        );
+
+        // Both the wallet record and the token account must cover the amount
+        require!(
+            ctx.accounts.from_wallet.value >= amount,
+            GenesisError::InsufficientBalance
+        );
+        require!(
+            ctx.accounts.from_token_account.amount >= amount,
+            GenesisError::InsufficientBalance
+        );
+
+        let from_value = ctx
+            .accounts
+            .from_wallet
+            .value
+            .checked_sub(amount)
+            .ok_or(GenesisError::InsufficientBalance)?;
+        let to_value = ctx
+            .accounts
+            .to_wallet
+            .value
+            .checked_add(amount)
+            .ok_or(GenesisError::ArithmeticOverflow)?;
+
         // Transfer tokens
         let cpi_accounts = Transfer {
             from: ctx.accounts.from_token_account.to_account_info(),
@@ -197,8 +249,8 @@ pub mod gentlyos_genesis {
         token::transfer(cpi_ctx, amount)?;
 
         // Update wallet values
-        ctx.accounts.from_wallet.value -= amount;
-        ctx.accounts.to_wallet.value += amount;
+        ctx.accounts.from_wallet.value = from_value;
+        ctx.accounts.to_wallet.value = to_value;
 
         emit!(TransferEvent {
             event_type: "TOKEN_TRANSFER".to_string(),
@@ -213,6 +265,69 @@ pub mod gentlyos_genesis {
         Ok(())
     }
 
+    /// Freeze or unfreeze a user wallet (incident response)
+    /// Only the genesis authority can call this
+    pub fn set_wallet_frozen(ctx: Context<SetWalletFrozen>, frozen: bool) -> Result<()> {
+        let wallet = &mut ctx.accounts.wallet;
+
+        // OS wallets are always frozen
+        require!(
+            wallet.wallet_type == WalletType::User,
+            GenesisError::InvalidWalletType
+        );
+
+        wallet.is_frozen = frozen;
+
+        emit!(WalletFreezeEvent {
+            event_type: if frozen { "WALLET_FREEZE" } else { "WALLET_UNFREEZE" }.to_string(),
+            path: wallet.path.clone(),
+            frozen,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Close an empty wallet and return its rent to `recipient`
+    /// Both the wallet record and its token account must hold nothing
+    /// User wallets are closed by their owner, OS wallets by the genesis authority
+    pub fn close_wallet(ctx: Context<CloseWallet>) -> Result<()> {
+        let wallet = &ctx.accounts.wallet;
+        let genesis = &mut ctx.accounts.genesis;
+        let closer = ctx.accounts.closer.key();
+
+        let expected_closer = match wallet.wallet_type {
+            WalletType::User => wallet.owner,
+            WalletType::Os => genesis.authority,
+        };
+        require_keys_eq!(closer, expected_closer, GenesisError::Unauthorized);
+
+        // Tokens would be stranded once the record is gone
+        require!(wallet.value == 0, GenesisError::WalletNotEmpty);
+        require!(
+            ctx.accounts.token_account.amount == 0,
+            GenesisError::WalletNotEmpty
+        );
+
+        genesis.total_wallets = genesis.total_wallets.saturating_sub(1);
+
+        emit!(WalletCloseEvent {
+            event_type: match wallet.wallet_type {
+                WalletType::User => "WALLET_CLOSE_USER",
+                WalletType::Os => "WALLET_CLOSE_OS",
+            }
+            .to_string(),
+            path: wallet.path.clone(),
+            closer,
+            recipient: ctx.accounts.recipient.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Anchor's `close = recipient` moves the lamports and zeroes the account
+        Ok(())
+    }
+
     /// Audit an OS event
     pub fn audit_event(
         ctx: Context<AuditEvent>,
@@ -221,6 +336,8 @@ pub mod gentlyos_genesis {
         btc_hash: String,
         btc_height: u64,
     ) -> Result<()> {
+        require!(target.len() <= AuditLog::MAX_TARGET_LEN, GenesisError::InputTooLong);
+
         let audit = &mut ctx.accounts.audit;
 
         audit.event_type = event_type.clone();
@@ -243,6 +360,12 @@ pub mod gentlyos_genesis {
     }
 }
 
+/// A BTC block hash as 64 lowercase hex characters
+fn is_btc_block_hash(hash: &str) -> bool {
+    hash.len() == Genesis::BTC_BLOCK_HASH_LEN
+        && hash.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
 // ============================================
 // ACCOUNTS
 // ============================================
@@ -250,7 +373,7 @@ pub mod gentlyos_genesis {
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(
-        init,
+        init_if_needed,
         payer = authority,
         space = 8 + Genesis::SIZE,
         seeds = [b"genesis"],
@@ -369,6 +492,44 @@ pub struct TransferUserTokens<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[derive(Accounts)]
+pub struct SetWalletFrozen<'info> {
+    #[account(mut)]
+    pub wallet: Account<'info, Wallet>,
+
+    #[account(
+        seeds = [b"genesis"],
+        bump,
+        has_one = authority @ GenesisError::Unauthorized
+    )]
+    pub genesis: Account<'info, Genesis>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseWallet<'info> {
+    #[account(mut, close = recipient)]
+    pub wallet: Account<'info, Wallet>,
+
+    #[account(mut, seeds = [b"genesis"], bump)]
+    pub genesis: Account<'info, Genesis>,
+
+    /// The wallet's token account: held by its owner, in its type's mint
+    #[account(
+        constraint = token_account.owner == wallet.owner
+            @ GenesisError::TokenAccountMismatch,
+        constraint = token_account.mint == wallet.mint(&genesis)
+            @ GenesisError::TokenAccountMismatch
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    pub closer: Signer<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+}
+
 #[derive(Accounts)]
 pub struct AuditEvent<'info> {
     #[account(
@@ -406,6 +567,11 @@ pub struct Genesis {
 }
 
 impl Genesis {
+    /// String budgets in SIZE, less the 4-byte length prefix
+    pub const MAX_VERSION_LEN: usize = 32 - 4;
+    pub const MAX_SERIAL_LEN: usize = 64 - 4;
+    pub const BTC_BLOCK_HASH_LEN: usize = 64;
+
     pub const SIZE: usize = 32 + 64 + 128 + 8 + 8 + 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1;
 }
 
@@ -424,6 +590,14 @@ pub struct Wallet {
 
 impl Wallet {
     pub const SIZE: usize = 256 + 8 + 128 + 8 + 8 + 1 + 1 + 32 + 33;
+
+    /// Mint this wallet's tokens are issued from
+    pub fn mint(&self, genesis: &Genesis) -> Pubkey {
+        match self.wallet_type {
+            WalletType::Os => genesis.os_mint,
+            WalletType::User => genesis.user_mint,
+        }
+    }
 }
 
 #[account]
@@ -437,6 +611,10 @@ pub struct Checkpoint {
 }
 
 impl Checkpoint {
+    /// String budgets in SIZE, less the 4-byte length prefix
+    pub const MAX_NAME_LEN: usize = 64 - 4;
+    pub const MAX_EVENT_DATA_LEN: usize = 256 - 4;
+
     pub const SIZE: usize = 64 + 128 + 8 + 8 + 256 + 32;
 }
 
@@ -451,6 +629,9 @@ pub struct AuditLog {
 }
 
 impl AuditLog {
+    /// String budget in SIZE, less the 4-byte length prefix
+    pub const MAX_TARGET_LEN: usize = 256 - 4;
+
     pub const SIZE: usize = 32 + 256 + 32 + 128 + 8 + 8;
 }
 
@@ -501,6 +682,24 @@ pub struct TransferEvent {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct WalletFreezeEvent {
+    pub event_type: String,
+    pub path: String,
+    pub frozen: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WalletCloseEvent {
+    pub event_type: String,
+    pub path: String,
+    pub closer: Pubkey,
+    pub recipient: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct AuditLogEvent {
     pub event_type: String,
@@ -525,4 +724,22 @@ pub enum GenesisError {
     InsufficientBalance,
     #[msg("Genesis already initialized")]
     AlreadyInitialized,
+    #[msg("Signer does not own the source wallet")]
+    OwnerMismatch,
+    #[msg("Wallet value overflow")]
+    ArithmeticOverflow,
+    #[msg("Version must be non-empty and at most 28 bytes")]
+    InvalidVersion,
+    #[msg("Serial must be at most 60 bytes")]
+    SerialTooLong,
+    #[msg("BTC block hash must be 64 lowercase hex characters")]
+    InvalidBtcBlockHash,
+    #[msg("Only the genesis authority can do this")]
+    Unauthorized,
+    #[msg("String argument is longer than the account reserves for it")]
+    InputTooLong,
+    #[msg("Wallet still holds tokens; transfer them out before closing it")]
+    WalletNotEmpty,
+    #[msg("Token account does not belong to this wallet")]
+    TokenAccountMismatch,
 }
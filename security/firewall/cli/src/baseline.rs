@@ -0,0 +1,67 @@
+//! Baseline files: JSON arrays of finding fingerprints to suppress
+//!
+//! `--write-baseline` records every current finding so later scans with
+//! `--baseline` only report what is new.
+
+use firewall_core::Finding;
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Load the fingerprints stored in a baseline file
+pub fn load(path: &Path) -> io::Result<HashSet<String>> {
+    let text = fs::read_to_string(path)?;
+    serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write the fingerprints of `findings` (sorted, one per finding) to `path`
+pub fn write(path: &Path, findings: &[Finding]) -> io::Result<()> {
+    write_fingerprints(path, &findings.iter().map(Finding::fingerprint).collect())
+}
+
+/// Write already-computed fingerprints to `path`
+pub fn write_fingerprints(path: &Path, fingerprints: &BTreeSet<String>) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(fingerprints)?;
+    fs::write(path, json + "\n")
+}
+
+/// Drop findings whose fingerprint is in the baseline, returning how many
+/// were suppressed
+pub fn suppress(findings: &mut Vec<Finding>, baseline: &HashSet<String>) -> usize {
+    let before = findings.len();
+    findings.retain(|f| !baseline.contains(&f.fingerprint()));
+    before - findings.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use firewall_core::Severity;
+    use serde_json::json;
+
+    fn finding(location: &str) -> Finding {
+        Finding {
+            finding_type: "hardcoded_public_ip".to_string(),
+            value: json!({ "ip": "203.0.113.7" }),
+            confidence: 0.8,
+            location: location.to_string(),
+            severity: Severity::Medium,
+            metadata: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_suppresses_known_findings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+
+        write(&path, &[finding("a.js")]).unwrap();
+        let baseline = load(&path).unwrap();
+
+        let mut current = vec![finding("a.js"), finding("b.js")];
+        assert_eq!(suppress(&mut current, &baseline), 1);
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].location, "b.js");
+    }
+}
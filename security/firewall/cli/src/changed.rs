@@ -0,0 +1,66 @@
+//! Changed-file discovery for `--changed-only`, via the `git` CLI
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn git(dir: &Path, args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Files under `root` that differ from `since` (default `HEAD`) in the
+/// working tree, plus untracked files not ignored by git. Deleted files are
+/// left out. Paths are absolute. `since` must name a commit; it is never
+/// passed to git where it could be taken for an option.
+pub fn changed_files(root: &Path, since: Option<&str>) -> io::Result<Vec<PathBuf>> {
+    let dir = if root.is_dir() {
+        root
+    } else {
+        root.parent().unwrap_or(Path::new("."))
+    };
+
+    let since = since.unwrap_or("HEAD");
+    if since.is_empty() || since.starts_with('-') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid git ref '{}'", since),
+        ));
+    }
+
+    let toplevel = git(dir, &["rev-parse", "--show-toplevel"])?;
+    let toplevel = PathBuf::from(String::from_utf8_lossy(&toplevel).trim());
+    let scope = root.canonicalize()?;
+
+    let commit = git(
+        &toplevel,
+        &["rev-parse", "--verify", "--quiet", "--end-of-options", &format!("{}^{{commit}}", since)],
+    )
+    .map_err(|_| io::Error::other(format!("'{}' is not a commit", since)))?;
+    let commit = String::from_utf8_lossy(&commit).trim().to_string();
+
+    let diffed = git(
+        &toplevel,
+        &["diff", "--name-only", "-z", "--diff-filter=d", "--end-of-options", &commit],
+    )?;
+    let untracked = git(&toplevel, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+
+    let mut files: Vec<PathBuf> = diffed
+        .split(|&b| b == 0)
+        .chain(untracked.split(|&b| b == 0))
+        .filter(|name| !name.is_empty())
+        .map(|name| toplevel.join(String::from_utf8_lossy(name).as_ref()))
+        .filter(|path| path.is_file())
+        .filter(|path| path.canonicalize().is_ok_and(|p| p.starts_with(&scope)))
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
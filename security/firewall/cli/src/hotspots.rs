@@ -0,0 +1,121 @@
+//! Directories that produce the most severe findings
+//!
+//! Findings are grouped by the first directory of their location under the
+//! scan root; files directly in the root (and tree-level findings located
+//! at the root itself) fall under ".". Each group is weighted by the sum of
+//! [`risk_weight`] times confidence over its findings, so one directory
+//! full of confident criticals stands out from many scattered lows.
+
+use firewall_core::{risk_weight, Finding, Severity};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+/// Directories listed in the text summary
+pub const TOP_DIRECTORIES: usize = 5;
+
+/// Findings under one top-level directory
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hotspot {
+    /// Directory relative to the scan root, or "." for the root itself
+    pub directory: String,
+    pub findings: usize,
+    pub critical: usize,
+    /// Severity-weighted total
+    pub weight: f32,
+    /// Share of the scan's total weight, 0.0 - 1.0
+    pub share: f32,
+}
+
+/// Top-level directory of `location` under `root`
+fn top_directory(root: &Path, location: &str) -> String {
+    let relative = Path::new(location).strip_prefix(root).unwrap_or(Path::new(location));
+    let mut components = relative.components().filter(|c| matches!(c, Component::Normal(_)));
+    match (components.next(), components.next()) {
+        (Some(dir), Some(_)) => dir.as_os_str().to_string_lossy().into_owned(),
+        _ => ".".to_string(),
+    }
+}
+
+/// Up to `limit` directories with findings, heaviest first; ties go to the
+/// directory with more findings, then by name, so the order is stable
+pub fn hottest(root: &Path, findings: &[Finding], limit: usize) -> Vec<Hotspot> {
+    let mut groups: BTreeMap<String, Hotspot> = BTreeMap::new();
+    for finding in findings {
+        let directory = top_directory(root, &finding.location);
+        let group = groups.entry(directory.clone()).or_insert_with(|| Hotspot {
+            directory,
+            findings: 0,
+            critical: 0,
+            weight: 0.0,
+            share: 0.0,
+        });
+        group.findings += 1;
+        group.critical += usize::from(finding.severity == Severity::Critical);
+        group.weight += risk_weight(finding.severity) * finding.confidence.clamp(0.0, 1.0);
+    }
+
+    let total: f32 = groups.values().map(|g| g.weight).sum();
+    let mut hotspots: Vec<Hotspot> = groups
+        .into_values()
+        .filter(|g| g.weight > 0.0)
+        .map(|mut g| {
+            g.share = g.weight / total;
+            g
+        })
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.weight
+            .total_cmp(&a.weight)
+            .then(b.findings.cmp(&a.findings))
+            .then_with(|| a.directory.cmp(&b.directory))
+    });
+    hotspots.truncate(limit);
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn finding(location: &str, severity: Severity) -> Finding {
+        Finding {
+            finding_type: "test".to_string(),
+            value: json!(null),
+            confidence: 1.0,
+            location: location.to_string(),
+            severity,
+            metadata: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_directories_ranked_by_weighted_severity() {
+        let root = Path::new("/scan");
+        let findings = vec![
+            finding("/scan/vendor/a.js", Severity::Low),
+            finding("/scan/vendor/b.js", Severity::Low),
+            finding("/scan/vendor/c.js", Severity::Low),
+            finding("/scan/payloads/x/drop.sh", Severity::Critical),
+            finding("/scan/payloads/y.ps1", Severity::Critical),
+            finding("/scan/setup.py", Severity::High),
+            finding("/scan", Severity::Medium),
+            finding("/scan/docs/readme.md", Severity::Info),
+            // Equal weights: more findings first, then by name
+            finding("/scan/gamma/z.js", Severity::Medium),
+            finding("/scan/beta/z.js", Severity::Medium),
+            finding("/scan/alpha/z.js", Severity::Low),
+            finding("/scan/alpha/w.js", Severity::Low),
+        ];
+
+        let hot = hottest(root, &findings, 10);
+        let order: Vec<&str> = hot.iter().map(|h| h.directory.as_str()).collect();
+        assert_eq!(order, ["payloads", ".", "vendor", "alpha", "beta", "gamma"]);
+        assert_eq!(hot[0].critical, 2);
+        assert_eq!(hot[1].findings, 2);
+        assert!((hot[0].share - 20.0 / 36.0).abs() < 1e-6);
+
+        assert_eq!(hottest(root, &findings, 2).len(), 2);
+    }
+}
@@ -0,0 +1,205 @@
+//! JUnit XML output for CI test dashboards
+//!
+//! Each skill becomes a `<testsuite>` and each finding a `<testcase>` in
+//! it, failing when its severity reaches the `--fail-on` threshold. Skills
+//! that reported nothing get a single passing case, so a clean scan is an
+//! all-pass report rather than an empty document.
+
+use firewall_core::{Finding, Severity, SkillRegistry};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Suite for findings whose type no registered skill explains (custom rules)
+const UNATTRIBUTED: &str = "other";
+
+/// Escape text for an XML attribute or element body
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newlines aren't allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Test case name: the finding type and where it was found
+fn case_name(finding: &Finding) -> String {
+    match finding.value.get("line").and_then(|l| l.as_u64()) {
+        Some(line) => format!("{} at {}:{}", finding.finding_type, finding.location, line),
+        None => format!("{} at {}", finding.finding_type, finding.location),
+    }
+}
+
+/// Build a JUnit report for `findings`. `skills` are the skills that ran
+/// (every registered skill when empty); a finding at or above `fail_on`
+/// is a failed test case.
+pub fn report(
+    findings: &[Finding],
+    registry: &SkillRegistry,
+    skills: &[String],
+    fail_on: Severity,
+) -> String {
+    let mut suites: BTreeMap<String, Vec<&Finding>> = if skills.is_empty() {
+        registry.list().into_iter().map(|name| (name.to_string(), Vec::new())).collect()
+    } else {
+        skills.iter().map(|name| (name.clone(), Vec::new())).collect()
+    };
+    for finding in findings {
+        let skill = registry
+            .explain(&finding.finding_type)
+            .map_or_else(|| UNATTRIBUTED.to_string(), |(skill, _)| skill);
+        suites.entry(skill).or_default().push(finding);
+    }
+
+    let failures = |findings: &[&Finding]| {
+        findings.iter().filter(|f| f.severity >= fail_on).count()
+    };
+    let total_tests: usize = suites.values().map(|f| f.len().max(1)).sum();
+    let total_failures: usize = suites.values().map(|f| failures(f)).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"firewall\" tests=\"{}\" failures=\"{}\">",
+        total_tests, total_failures
+    );
+
+    for (skill, findings) in &suites {
+        let skill = escape(skill);
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            skill,
+            findings.len().max(1),
+            failures(findings)
+        );
+
+        if findings.is_empty() {
+            let _ = writeln!(xml, "    <testcase classname=\"{0}\" name=\"{0}\"/>", skill);
+        }
+        for finding in findings {
+            let name = escape(&case_name(finding));
+            if finding.severity < fail_on {
+                let _ = writeln!(xml, "    <testcase classname=\"{}\" name=\"{}\"/>", skill, name);
+                continue;
+            }
+
+            let description = finding
+                .metadata
+                .get("description")
+                .and_then(|d| d.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| finding.finding_type.replace('_', " "));
+            let _ = writeln!(xml, "    <testcase classname=\"{}\" name=\"{}\">", skill, name);
+            let body = format!(
+                "{} finding ({:.0}% confidence) at {}\n{}",
+                finding.severity,
+                finding.confidence * 100.0,
+                finding.location,
+                description
+            );
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                escape(&description),
+                finding.severity,
+                escape(&body)
+            );
+            let _ = writeln!(xml, "    </testcase>");
+        }
+
+        let _ = writeln!(xml, "  </testsuite>");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use firewall_core::create_default_registry;
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+    use serde_json::json;
+
+    fn finding(finding_type: &str, severity: Severity, description: &str) -> Finding {
+        Finding {
+            finding_type: finding_type.to_string(),
+            value: json!({ "line": 7 }),
+            confidence: 0.9,
+            location: "src/<app>.js".to_string(),
+            severity,
+            metadata: json!({ "description": description }),
+        }
+    }
+
+    /// Parse `xml`, returning (element name, failures attribute) for every
+    /// testsuite plus the number of failure elements
+    fn parse(xml: &str) -> (Vec<(String, usize)>, usize) {
+        let mut reader = Reader::from_str(xml);
+        let (mut suites, mut failures) = (Vec::new(), 0);
+        loop {
+            match reader.read_event().expect("report is well-formed XML") {
+                Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                    b"testsuite" => {
+                        let attr = |key: &[u8]| {
+                            let value = e.try_get_attribute(key).unwrap().unwrap();
+                            value.unescape_value().unwrap().into_owned()
+                        };
+                        suites.push((attr(b"name"), attr(b"failures").parse().unwrap()));
+                    }
+                    b"failure" => failures += 1,
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        (suites, failures)
+    }
+
+    #[test]
+    fn test_failures_grouped_by_skill() {
+        let registry = create_default_registry();
+        let findings = [
+            finding("keyboard_injection", Severity::Critical, "Shell to \"10.0.0.1\" & <back>"),
+            finding("c2_beaconing", Severity::Low, "polls every 5 s"),
+            finding("custom_rule", Severity::High, "matched rule"),
+        ];
+        let skills = ["detect_injection_attacks", "detect_network_patterns"].map(String::from);
+
+        let xml = report(&findings, &registry, &skills, Severity::Medium);
+        let (suites, failures) = parse(&xml);
+        assert_eq!(failures, 2);
+        assert_eq!(
+            suites,
+            [
+                ("detect_injection_attacks".to_string(), 1),
+                ("detect_network_patterns".to_string(), 0),
+                ("other".to_string(), 1),
+            ]
+        );
+        assert!(xml.contains("Shell to &quot;10.0.0.1&quot; &amp; &lt;back&gt;"));
+        assert!(xml.contains("name=\"keyboard_injection at src/&lt;app&gt;.js:7\""));
+    }
+
+    #[test]
+    fn test_clean_scan_is_all_pass() {
+        let registry = create_default_registry();
+        let xml = report(&[], &registry, &[], Severity::Info);
+
+        let (suites, failures) = parse(&xml);
+        assert_eq!(failures, 0);
+        assert_eq!(suites.len(), registry.list().len());
+        assert!(suites.iter().all(|(_, failed)| *failed == 0));
+    }
+}
@@ -2,10 +2,31 @@
 //!
 //! Security scanning tool with ML-trainable detection skills.
 
+mod baseline;
+mod changed;
+mod hotspots;
+mod junit;
+mod markdown;
+mod ndjson;
+mod quarantine;
+mod sarif;
+mod watch;
+
 use clap::{Parser, Subcommand};
 use colored::Colorize;
-use firewall_core::{create_default_registry, export_tool_schemas, scan_path, Severity};
+use firewall_core::{
+    create_default_registry, downgrade_test_fixture, risk_score, scan_content_report,
+    scan_path_report_with_options, scan_path_with_options, PathFilter, ProgressCallback,
+    ScanCache, ScanConfig, ScanOptions, ScanProgress, ScanReport, ScanRun, Severity,
+    TrustedHashes, DEFAULT_MAX_FILE_BYTES, FINDING_SCHEMA_VERSION,
+};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::collections::HashSet;
+use std::io::{IsTerminal, Read};
+use tracing_subscriber::filter::LevelFilter;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "firewall")]
@@ -13,18 +34,30 @@ use std::path::PathBuf;
 #[command(version)]
 #[command(about = "GentlyOS Firewall - ML-trainable security detection", long_about = None)]
 struct Cli {
+    /// Log to stderr: -v for scan summaries, -vv for each skill run and
+    /// skipped file, -vvv for everything. With `skills`, -v also shows
+    /// detailed info
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+// Parsed once at startup, so the size of the Scan variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Scan a file or directory for threats
     Scan {
         /// Path to scan
-        path: PathBuf,
+        #[arg(required_unless_present = "stdin")]
+        path: Option<PathBuf>,
 
-        /// Output format (text, json)
+        /// Output format (text, json, sarif, junit, markdown, ndjson). ndjson streams one
+        /// finding per line as it is found, unsorted and without cross-skill deduplication.
+        /// junit fails a test case for each finding at or above --fail-on. markdown renders
+        /// tables grouped by severity for PR comments
         #[arg(short, long, default_value = "text")]
         format: String,
 
@@ -35,13 +68,189 @@ enum Commands {
         /// Minimum severity to report (info, low, medium, high, critical)
         #[arg(long, default_value = "low")]
         min_severity: String,
+
+        /// Lowest reported severity that makes the exit code nonzero
+        #[arg(long, default_value = "info")]
+        fail_on: String,
+
+        /// Suppress findings whose fingerprints are listed in this file
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Record the fingerprints of all current findings to this file
+        #[arg(long)]
+        write_baseline: Option<PathBuf>,
+
+        /// Stop starting new work after this many seconds and report partial results
+        #[arg(long)]
+        time_budget_secs: Option<u64>,
+
+        /// Abandon any single skill run that takes longer than this
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+
+        /// Skip files larger than this instead of reading them (default 50 MB)
+        #[arg(long, value_name = "BYTES")]
+        max_file_bytes: Option<u64>,
+
+        /// Disable colored output and the progress bar
+        #[arg(long)]
+        no_color: bool,
+
+        /// Always report progress on stderr: a live bar on a terminal, otherwise a
+        /// status line every few seconds. Without it the bar only appears on an
+        /// interactive terminal
+        #[arg(long)]
+        progress: bool,
+
+        /// Scan with at most this many worker threads (default: one per CPU)
+        #[arg(short, long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// Only scan files whose path relative to the target matches this glob (repeatable)
+        #[arg(long)]
+        include: Vec<String>,
+
+        /// Skip files whose path relative to the target matches this glob (repeatable)
+        #[arg(long)]
+        exclude: Vec<String>,
+
+        /// Also skip this directory: a name matches at any depth, a path with '/' is
+        /// relative to the target, e.g. ./dist (repeatable). VCS, dependency and build
+        /// directories (.git, node_modules, target, .venv, ...) are always skipped
+        #[arg(long, value_name = "DIR")]
+        skip_dir: Vec<String>,
+
+        /// Custom rules file (JSON or TOML) to run as detect_custom_rules
+        #[arg(long)]
+        rules: Option<PathBuf>,
+
+        /// Only scan files git reports as changed (plus untracked files)
+        #[arg(long)]
+        changed_only: bool,
+
+        /// Git ref to diff against for --changed-only (default HEAD); implies --changed-only
+        #[arg(long, value_name = "GIT_REF")]
+        since: Option<String>,
+
+        /// TOML file of per-finding-type overrides (min_confidence, severity_override,
+        /// disabled) and correlation rules that replace the built-in ones
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        /// File of SHA-256 hashes (one per line, sha256sum output works); matching files
+        /// are not analyzed. Every file is hashed first, so this costs an extra read
+        #[arg(long, value_name = "FILE")]
+        trust_hashes: Option<PathBuf>,
+
+        /// Keep full severity for findings under tests/, fixtures/, testdata/, __tests__/
+        /// or spec/ (by default they are capped at medium)
+        #[arg(long)]
+        no_test_downgrade: bool,
+
+        /// With --format json, also report each skill's findings, completeness and stats
+        #[arg(long)]
+        detailed: bool,
+
+        /// Move files with findings at or above --quarantine-severity into this directory
+        #[arg(long, value_name = "DIR")]
+        quarantine: Option<PathBuf>,
+
+        /// Lowest reported severity that gets a file quarantined
+        #[arg(long, default_value = "high")]
+        quarantine_severity: String,
+
+        /// With --quarantine, only print which files would be moved
+        #[arg(long)]
+        dry_run: bool,
+
+        /// After the scan, keep watching the directory and print findings introduced
+        /// by file changes (text format only)
+        #[arg(long)]
+        watch: bool,
+
+        /// Scan content read from stdin instead of a path; tree-level checks are skipped
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "path", "changed_only", "since", "quarantine", "watch", "trust_hashes"
+            ]
+        )]
+        stdin: bool,
+
+        /// File name to scan stdin as; its extension selects language-specific checks
+        #[arg(long, value_name = "NAME", default_value = "stdin", requires = "stdin")]
+        as_name: String,
+
+        /// Reuse findings for files whose mtime and size are unchanged since the scan
+        /// that wrote this cache file, and update it afterwards
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["skill", "watch", "stdin"])]
+        cache: Option<PathBuf>,
+
+        /// Order of reported findings: severity (then confidence), confidence (then
+        /// severity) or location (path, then line)
+        #[arg(
+            long,
+            default_value = "severity",
+            value_parser = ["severity", "confidence", "location"]
+        )]
+        sort: String,
+
+        /// Print only the first N findings after sorting; the summary still counts all
+        /// of them. Text output only unless --json-top is given
+        #[arg(long, value_name = "N")]
+        top: Option<usize>,
+
+        /// Apply --top to --format json as well; the report then carries
+        /// total_findings and a risk score over every finding
+        #[arg(long, requires = "top", conflicts_with = "detailed")]
+        json_top: bool,
+
+        /// Run every detector on every file instead of skipping detectors that don't
+        /// apply to a file's sniffed type (e.g. the SVG checks on a PNG)
+        #[arg(long)]
+        all_skills: bool,
+
+        /// Replace excerpts of scanned content in findings (previews, matched strings,
+        /// hosts) with their length and a SHA-256 prefix, for reports that get shared
+        #[arg(long)]
+        redact: bool,
     },
 
-    /// List available detection skills
-    Skills {
-        /// Show detailed info
-        #[arg(short, long)]
-        verbose: bool,
+    /// List available detection skills (-v for detailed info)
+    Skills,
+
+    /// Print every skill's name, description, categories, parameter schema and
+    /// finding types as one JSON document
+    Catalog,
+
+    /// Explain a finding type: what it means, why it's flagged, common false
+    /// positives and how to fix it
+    Explain {
+        /// Finding type, e.g. guid_modular_correlation
+        finding_type: String,
+    },
+
+    /// Compare two saved `scan --format json` reports by finding fingerprint and
+    /// report what was added and removed; exits like `scan` on the added findings
+    Diff {
+        /// Earlier report
+        old: PathBuf,
+
+        /// Later report
+        new: PathBuf,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Lowest added severity that makes the exit code nonzero
+        #[arg(long, default_value = "info")]
+        fail_on: String,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
     },
 
     /// Export skill schemas for ML training
@@ -66,9 +275,30 @@ enum Commands {
         /// Additional JSON parameters
         #[arg(short, long)]
         params: Option<String>,
+
+        /// Give up if the skill takes longer than this
+        #[arg(long)]
+        timeout_secs: Option<u64>,
     },
 }
 
+/// Exit code when a skill or the scan itself fails
+const EXIT_ERROR: i32 = 10;
+
+/// Scan exit code from the highest reported severity: 0 when nothing at or
+/// above `fail_on` was found, otherwise 1 (info/low/medium), 2 (high) or
+/// 3 (critical)
+fn exit_code(highest: Option<Severity>, fail_on: Severity) -> i32 {
+    match highest {
+        Some(highest) if highest >= fail_on => match highest {
+            Severity::Critical => 3,
+            Severity::High => 2,
+            _ => 1,
+        },
+        _ => 0,
+    }
+}
+
 fn severity_color(severity: &Severity) -> colored::ColoredString {
     match severity {
         Severity::Critical => "CRITICAL".red().bold(),
@@ -79,18 +309,87 @@ fn severity_color(severity: &Severity) -> colored::ColoredString {
     }
 }
 
-fn parse_min_severity(s: &str) -> Severity {
-    match s.to_lowercase().as_str() {
-        "critical" => Severity::Critical,
-        "high" => Severity::High,
-        "medium" => Severity::Medium,
-        "low" => Severity::Low,
-        _ => Severity::Info,
+/// Redraw rate of the progress bar
+const PROGRESS_HZ: u8 = 4;
+
+/// Interval between plain status lines when stderr is not a terminal
+const PROGRESS_LINE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Progress reporting on stderr; stdout is never touched
+///
+/// Without `forced` (`--progress`), returns `None` (no progress output at
+/// all) when stderr is not a terminal or colors are disabled, so piped and
+/// machine-readable output stays clean. With it, a terminal gets the bar
+/// and anything else gets a throttled status line.
+fn progress_callback(no_color: bool, forced: bool) -> Option<ProgressCallback> {
+    let interactive = std::io::stderr().is_terminal();
+    if !forced && (no_color || !interactive) {
+        return None;
     }
+
+    if !interactive {
+        let last = std::sync::Mutex::new(None::<std::time::Instant>);
+        return Some(Arc::new(move |p: &ScanProgress<'_>| {
+            let finished = p.files_done == p.files_total;
+            let mut last = last.lock().unwrap_or_else(|e| e.into_inner());
+            if finished || last.is_none_or(|at| at.elapsed() >= PROGRESS_LINE_INTERVAL) {
+                *last = Some(std::time::Instant::now());
+                eprintln!(
+                    "Scanned {}/{} files, {} findings so far",
+                    p.files_done, p.files_total, p.findings
+                );
+            }
+        }));
+    }
+
+    let bar = ProgressBar::with_draw_target(None, ProgressDrawTarget::stderr_with_hz(PROGRESS_HZ));
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.cyan} [{elapsed_precise}] {bar:30.cyan/blue} {pos}/{len} files (ETA {eta}) {msg}",
+        )
+        .unwrap()
+        .progress_chars("=> "),
+    );
+
+    Some(Arc::new(move |p: &ScanProgress<'_>| {
+        bar.set_length(p.files_total as u64);
+        bar.set_position(p.files_done as u64);
+        bar.set_message(format!("{} findings | {}", p.findings, p.current.display()));
+
+        if p.files_done == p.files_total {
+            bar.finish_and_clear();
+        }
+    }))
+}
+
+/// Parse a severity flag, exiting with an error on anything unrecognized
+/// rather than quietly widening the filter
+fn parse_severity_arg(flag: &str, value: &str) -> Severity {
+    value.parse().unwrap_or_else(|e| {
+        eprintln!("{}: invalid {}: {}", "Error".red(), flag, e);
+        std::process::exit(EXIT_ERROR);
+    })
+}
+
+/// Send core's `tracing` output to stderr at a level set by `-v` count
+fn init_logging(verbose: u8) {
+    let level = match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_target(false)
+        .init();
 }
 
 fn main() {
     let cli = Cli::parse();
+    init_logging(cli.verbose);
 
     match cli.command {
         Commands::Scan {
@@ -98,64 +397,489 @@ fn main() {
             format,
             skill,
             min_severity,
+            fail_on,
+            baseline,
+            write_baseline,
+            time_budget_secs,
+            timeout_secs,
+            max_file_bytes,
+            no_color,
+            progress,
+            jobs,
+            include,
+            exclude,
+            mut skip_dir,
+            rules,
+            changed_only,
+            since,
+            config,
+            trust_hashes,
+            no_test_downgrade,
+            detailed,
+            quarantine,
+            quarantine_severity,
+            dry_run,
+            watch,
+            stdin,
+            as_name,
+            cache,
+            sort,
+            top,
+            json_top,
+            all_skills,
+            redact,
         } => {
-            let min_sev = parse_min_severity(&min_severity);
+            let min_sev = parse_severity_arg("--min-severity", &min_severity);
+            let fail_on = parse_severity_arg("--fail-on", &fail_on);
+            // Findings on stdin content are reported against --as-name
+            let path = path.unwrap_or_else(|| PathBuf::from(&as_name));
+            let config = config
+                .map(|path| {
+                    ScanConfig::load(&path).unwrap_or_else(|e| {
+                        eprintln!("{}: {}", "Error".red(), e);
+                        std::process::exit(EXIT_ERROR);
+                    })
+                })
+                .unwrap_or_default();
 
-            println!();
-            println!("{}", "╔══════════════════════════════════════════════════════════════════╗".cyan());
-            println!("{}", "║             GentlyOS FIREWALL - Security Scan                    ║".cyan());
-            println!("{}", "║             Zero Trust: If we didn't build it, it's a threat.    ║".cyan());
-            println!("{}", "╚══════════════════════════════════════════════════════════════════╝".cyan());
-            println!();
+            if no_color {
+                colored::control::set_override(false);
+            }
+
+            // Config overrides, then the test-fixture cap, as in a full scan
+            let adjust = |finding| {
+                config.apply_to(finding).map(|mut finding| {
+                    if !no_test_downgrade {
+                        downgrade_test_fixture(&path, &mut finding);
+                    }
+                    finding
+                })
+            };
+
+            if let Some(jobs) = jobs {
+                if jobs == 0 {
+                    eprintln!("{}: --jobs must be at least 1", "Error".red());
+                    std::process::exit(EXIT_ERROR);
+                }
+                if let Err(e) = rayon::ThreadPoolBuilder::new().num_threads(jobs).build_global() {
+                    eprintln!("{}: cannot set up {} worker threads: {}", "Error".red(), jobs, e);
+                    std::process::exit(EXIT_ERROR);
+                }
+            }
+
+            let quarantine_min = parse_severity_arg("--quarantine-severity", &quarantine_severity);
+            if quarantine.is_some() && (format == "ndjson" || watch) {
+                eprintln!(
+                    "{}: --quarantine is not supported with --format ndjson or --watch",
+                    "Error".red()
+                );
+                std::process::exit(EXIT_ERROR);
+            }
+
+            // Don't rescan what earlier runs quarantined inside the target
+            let quarantine_in_tree = quarantine.as_ref().and_then(|dir| {
+                let dir = dir.canonicalize().ok()?;
+                let rel = dir.strip_prefix(path.canonicalize().ok()?).ok()?;
+                (!rel.as_os_str().is_empty()).then(|| format!("./{}", rel.display()))
+            });
+            skip_dir.extend(quarantine_in_tree);
+
+            if trust_hashes.is_some() && (format == "ndjson" || skill.is_some()) {
+                eprintln!(
+                    "{}: --trust-hashes is not supported with --format ndjson or --skill",
+                    "Error".red()
+                );
+                std::process::exit(EXIT_ERROR);
+            }
+            let trusted_hashes = trust_hashes.map(|path| {
+                Arc::new(TrustedHashes::load(&path).unwrap_or_else(|e| {
+                    eprintln!("{}: {}", "Error".red(), e);
+                    std::process::exit(EXIT_ERROR);
+                }))
+            });
+
+            if detailed && format != "json" {
+                eprintln!("{}: --detailed requires --format json", "Error".red());
+                std::process::exit(EXIT_ERROR);
+            }
+
+            if watch {
+                let conflict = if format != "text" {
+                    Some(format!("--format {}", format))
+                } else if changed_only || since.is_some() {
+                    Some("--changed-only".to_string())
+                } else if !path.is_dir() {
+                    Some("a file target (watch a directory instead)".to_string())
+                } else {
+                    None
+                };
+                if let Some(conflict) = conflict {
+                    eprintln!("{}: --watch is not supported with {}", "Error".red(), conflict);
+                    std::process::exit(EXIT_ERROR);
+                }
+            }
+
+            let changed = (changed_only || since.is_some()).then(|| {
+                changed::changed_files(&path, since.as_deref()).unwrap_or_else(|e| {
+                    eprintln!("{}: cannot list changed files: {}", "Error".red(), e);
+                    std::process::exit(EXIT_ERROR);
+                })
+            });
+
+            if stdin && format == "ndjson" {
+                eprintln!("{}: --stdin is not supported with --format ndjson", "Error".red());
+                std::process::exit(EXIT_ERROR);
+            }
+            if cache.is_some() && format == "ndjson" {
+                eprintln!("{}: --cache is not supported with --format ndjson", "Error".red());
+                std::process::exit(EXIT_ERROR);
+            }
+            if sort != "severity" && format == "ndjson" {
+                eprintln!("{}: --sort is not supported with --format ndjson", "Error".red());
+                std::process::exit(EXIT_ERROR);
+            }
+            if json_top && format != "json" {
+                eprintln!("{}: --json-top requires --format json", "Error".red());
+                std::process::exit(EXIT_ERROR);
+            }
+            let cache = cache.map(|path| Arc::new(ScanCache::open(&path)));
+
+            // Streamed output skips the buffered report entirely
+            if format == "ndjson" {
+                if changed.is_some() {
+                    eprintln!(
+                        "{}: --changed-only is not supported with --format ndjson",
+                        "Error".red()
+                    );
+                    std::process::exit(EXIT_ERROR);
+                }
+
+                let known = baseline.as_ref().map(|path| {
+                    baseline::load(path).unwrap_or_else(|e| {
+                        eprintln!(
+                            "{}: cannot read baseline {}: {}",
+                            "Error".red(),
+                            path.display(),
+                            e
+                        );
+                        std::process::exit(EXIT_ERROR);
+                    })
+                });
+                let sink = ndjson::Sink::new(min_sev, known, write_baseline.is_some(), redact);
+
+                let mut registry = create_default_registry();
+                let result = rules
+                    .as_ref()
+                    .map_or(Ok(()), |rules_path| registry.register_custom_rules(rules_path))
+                    .and_then(|()| PathFilter::new(&include, &exclude))
+                    .map(|filter| filter.with_skip_dirs(&skip_dir))
+                    .and_then(|filter| match &skill {
+                        Some(skill_name) => {
+                            let params = serde_json::json!({
+                                "path": path.display().to_string(),
+                                "include": include,
+                                "exclude": exclude,
+                                "skip_dirs": skip_dir,
+                                "max_file_bytes": max_file_bytes
+                            });
+                            let output = match timeout_secs {
+                                Some(secs) => registry.invoke_with_timeout(
+                                    skill_name,
+                                    params,
+                                    Duration::from_secs(secs),
+                                ),
+                                None => registry.invoke(skill_name, params),
+                            }?;
+                            let findings: Vec<_> =
+                                output.findings.into_iter().filter_map(adjust).collect();
+                            findings.iter().for_each(|f| sink.emit(f));
+                            Ok(findings.len())
+                        }
+                        None => registry.scan_all_streaming(
+                            &path.display().to_string(),
+                            &filter,
+                            all_skills,
+                            max_file_bytes.unwrap_or(DEFAULT_MAX_FILE_BYTES),
+                            |_, finding| {
+                                if let Some(finding) = adjust(finding) {
+                                    sink.emit(&finding);
+                                }
+                            },
+                        ),
+                    });
+
+                if let Err(e) = result {
+                    eprintln!("{}: {}", "Error".red(), e);
+                    std::process::exit(EXIT_ERROR);
+                }
+
+                let summary = sink.finish();
+                if let Some(path) = &write_baseline {
+                    if let Err(e) = baseline::write_fingerprints(path, &summary.fingerprints) {
+                        eprintln!(
+                            "{}: cannot write baseline {}: {}",
+                            "Error".red(),
+                            path.display(),
+                            e
+                        );
+                        std::process::exit(EXIT_ERROR);
+                    }
+                }
+                if summary.suppressed > 0 {
+                    eprintln!("{} finding(s) suppressed by baseline", summary.suppressed);
+                }
+                std::process::exit(exit_code(summary.highest, fail_on));
+            }
+
+            // Machine-readable formats get nothing on stdout but the findings
+            if format == "text" {
+                println!();
+                println!("{}", "╔══════════════════════════════════════════════════════════════════╗".cyan());
+                println!("{}", "║             GentlyOS FIREWALL - Security Scan                    ║".cyan());
+                println!("{}", "║             Zero Trust: If we didn't build it, it's a threat.    ║".cyan());
+                println!("{}", "╚══════════════════════════════════════════════════════════════════╝".cyan());
+                println!();
+            }
 
             let path_str = path.display().to_string();
 
-            if let Some(skill_name) = skill {
+            // What the watch loop needs once the initial scan has consumed the flags
+            let watch_options = watch.then(|| ScanOptions {
+                skill_timeout: timeout_secs.map(Duration::from_secs),
+                include: include.clone(),
+                exclude: exclude.clone(),
+                skip_dirs: skip_dir.clone(),
+                rules_path: rules.clone(),
+                config: Some(config.clone()),
+                no_test_downgrade,
+                trusted_hashes: trusted_hashes.clone(),
+                max_file_bytes,
+                all_skills,
+                ..Default::default()
+            });
+            let watch_skill = skill.clone();
+
+            let mut report = if stdin {
+                let mut content = Vec::new();
+                if let Err(e) = std::io::stdin().read_to_end(&mut content) {
+                    eprintln!("{}: cannot read stdin: {}", "Error".red(), e);
+                    std::process::exit(EXIT_ERROR);
+                }
+                let content = String::from_utf8_lossy(&content);
+                let options = ScanOptions {
+                    rules_path: rules,
+                    config: Some(config.clone()),
+                    no_test_downgrade,
+                    all_skills,
+                    ..Default::default()
+                };
+                scan_stdin(&as_name, &content, skill.as_deref(), &options).unwrap_or_else(|e| {
+                    eprintln!("{}: {}", "Error".red(), e);
+                    std::process::exit(EXIT_ERROR);
+                })
+            } else if let Some(skill_name) = skill {
                 // Run specific skill
-                let registry = create_default_registry();
-                let params = serde_json::json!({ "path": path_str });
-
-                match registry.invoke(&skill_name, params) {
-                    Ok(output) => {
-                        let filtered: Vec<_> = output
-                            .findings
-                            .into_iter()
-                            .filter(|f| f.severity >= min_sev)
-                            .collect();
-
-                        if format == "json" {
-                            println!("{}", serde_json::to_string_pretty(&filtered).unwrap());
-                        } else {
-                            print_findings(&filtered);
-                        }
+                let mut registry = create_default_registry();
+                let files: Vec<String> = changed
+                    .iter()
+                    .flatten()
+                    .map(|f| f.display().to_string())
+                    .collect();
+                if let Some(rules_path) = &rules {
+                    if let Err(e) = registry.register_custom_rules(rules_path) {
+                        eprintln!("{}: {}", "Error".red(), e);
+                        std::process::exit(EXIT_ERROR);
+                    }
+                }
+                let params = serde_json::json!({
+                    "path": path_str,
+                    "include": include,
+                    "exclude": exclude,
+                    "files": files,
+                    "max_file_bytes": max_file_bytes
+                });
+
+                let run = ScanRun::start(&path_str, [skill_name.as_str()]);
+                let result = match timeout_secs {
+                    // An empty file list means "walk everything", so skip the run
+                    _ if changed.as_ref().is_some_and(|c| c.is_empty()) => {
+                        Ok(firewall_core::SkillOutput::empty())
+                    }
+                    Some(secs) => {
+                        registry.invoke_with_timeout(&skill_name, params, Duration::from_secs(secs))
+                    }
+                    None => registry.invoke(&skill_name, params),
+                };
+
+                match result {
+                    Ok(mut output) => {
+                        output.findings = output.findings.into_iter().filter_map(adjust).collect();
+                        ScanReport::merge(vec![(skill_name, output)]).with_run(run)
                     }
                     Err(e) => {
                         eprintln!("{}: {}", "Error".red(), e);
+                        std::process::exit(EXIT_ERROR);
                     }
                 }
             } else {
                 // Run all skills
-                match scan_path(&path_str) {
-                    Ok(findings) => {
-                        let filtered: Vec<_> = findings
-                            .into_iter()
-                            .filter(|f| f.severity >= min_sev)
-                            .collect();
-
-                        if format == "json" {
-                            println!("{}", serde_json::to_string_pretty(&filtered).unwrap());
-                        } else {
-                            print_findings(&filtered);
+                let options = ScanOptions {
+                    time_budget: time_budget_secs.map(Duration::from_secs),
+                    skill_timeout: timeout_secs.map(Duration::from_secs),
+                    progress: progress_callback(no_color, progress),
+                    include,
+                    exclude,
+                    skip_dirs: skip_dir,
+                    rules_path: rules,
+                    files: changed,
+                    config: Some(config),
+                    no_test_downgrade,
+                    trusted_hashes,
+                    cache: cache.clone(),
+                    max_file_bytes,
+                    all_skills,
+                    ..Default::default()
+                };
+
+                match scan_path_report_with_options(&path_str, &options) {
+                    Ok(report) => {
+                        if let Some(Err(e)) = cache.map(|cache| cache.save()) {
+                            eprintln!("{}: cannot write scan cache: {}", "Warning".yellow(), e);
+                        }
+
+                        let output = &report.aggregate;
+                        if !output.complete {
+                            eprintln!(
+                                "{}: time budget exceeded, results are partial",
+                                "Warning".yellow()
+                            );
                         }
+
+                        let timeouts = output.metadata["skill_timeouts"].as_u64().unwrap_or(0);
+                        if timeouts > 0 {
+                            eprintln!(
+                                "{}: {} skill run(s) timed out and were skipped",
+                                "Warning".yellow(),
+                                timeouts
+                            );
+                        }
+
+                        report
                     }
                     Err(e) => {
                         eprintln!("{}: {}", "Error".red(), e);
+                        std::process::exit(EXIT_ERROR);
                     }
                 }
+            };
+            if format == "json" {
+                report.tag_run_id();
+            }
+            let findings = report.findings().to_vec();
+
+            if let Some(path) = &write_baseline {
+                if let Err(e) = baseline::write(path, &findings) {
+                    eprintln!(
+                        "{}: cannot write baseline {}: {}",
+                        "Error".red(),
+                        path.display(),
+                        e
+                    );
+                    std::process::exit(EXIT_ERROR);
+                }
+                eprintln!(
+                    "Baseline of {} finding(s) written to {}",
+                    findings.len(),
+                    path.display()
+                );
             }
+
+            let mut filtered: Vec<_> = findings
+                .into_iter()
+                .filter(|f| f.severity >= min_sev)
+                .collect();
+
+            let known = baseline.as_ref().map(|path| {
+                baseline::load(path).unwrap_or_else(|e| {
+                    eprintln!(
+                        "{}: cannot read baseline {}: {}",
+                        "Error".red(),
+                        path.display(),
+                        e
+                    );
+                    std::process::exit(EXIT_ERROR);
+                })
+            });
+            let suppressed = known
+                .as_ref()
+                .map_or(0, |known| baseline::suppress(&mut filtered, known));
+            sort_findings(&mut filtered, &sort);
+
+            if detailed {
+                report.retain(|f| {
+                    f.severity >= min_sev
+                        && known.as_ref().is_none_or(|known| !known.contains(&f.fingerprint()))
+                });
+                if redact {
+                    report.redact();
+                }
+                print_detailed_report(&report, &path);
+            } else {
+                // --top trims text output; JSON only when asked for with --json-top
+                let top = top.filter(|_| format != "json" || json_top);
+                // Only the printed copy is redacted; baselines and --watch match the originals
+                let redacted: Vec<_>;
+                let shown = if redact {
+                    redacted = filtered.iter().map(|f| f.redacted()).collect();
+                    &redacted
+                } else {
+                    &filtered
+                };
+                print_report(&format, shown, report.run.as_ref(), &path, fail_on, top);
+            }
+
+            if suppressed > 0 {
+                eprintln!("{} finding(s) suppressed by baseline", suppressed);
+            }
+
+            if let Some(dir) = &quarantine {
+                if let Err(e) = quarantine_files(&path, &filtered, quarantine_min, dir, dry_run) {
+                    eprintln!("{}: quarantine failed: {}", "Error".red(), e);
+                    std::process::exit(EXIT_ERROR);
+                }
+            }
+
+            if let Some(options) = watch_options {
+                let result = PathFilter::new(&options.include, &options.exclude).and_then(|filter| {
+                    let filter = filter.with_skip_dirs(&options.skip_dirs);
+                    let mut registry = create_default_registry();
+                    if let Some(rules_path) = &options.rules_path {
+                        registry.register_custom_rules(rules_path)?;
+                    }
+
+                    let scan = |files: &[PathBuf]| {
+                        rescan(&registry, &path_str, watch_skill.as_deref(), files, &options)
+                    };
+                    let keep = |f: &firewall_core::Finding| {
+                        f.severity >= min_sev
+                            && known.as_ref().is_none_or(|known| !known.contains(&f.fingerprint()))
+                    };
+                    watch::run(&path, &filter, filtered, scan, keep, redact)
+                        .map_err(|e| firewall_core::SkillError::Io(std::io::Error::other(e)))
+                });
+
+                if let Err(e) = result {
+                    eprintln!("{}: {}", "Error".red(), e);
+                }
+                std::process::exit(EXIT_ERROR);
+            }
+
+            let highest = filtered.iter().map(|f| f.severity).max();
+            std::process::exit(exit_code(highest, fail_on));
         }
 
-        Commands::Skills { verbose } => {
+        Commands::Skills => {
+            let verbose = cli.verbose > 0;
             let registry = create_default_registry();
 
             println!();
@@ -180,8 +904,121 @@ fn main() {
             }
         }
 
-        Commands::Export { output, format: _ } => {
-            let schemas = export_tool_schemas();
+        Commands::Catalog => {
+            let catalog = create_default_registry().catalog();
+            println!("{}", serde_json::to_string_pretty(&catalog).unwrap());
+        }
+
+        Commands::Explain { finding_type } => {
+            let registry = create_default_registry();
+            let Some((skill_name, explanation)) = registry.explain(&finding_type) else {
+                eprintln!(
+                    "{}: unknown finding type '{}' (run `firewall skills` to list detectors)",
+                    "Error".red(),
+                    finding_type
+                );
+                std::process::exit(EXIT_ERROR);
+            };
+
+            println!();
+            println!("{}", explanation.finding_type.white().bold());
+            println!("  Reported by: {}", skill_name.cyan());
+            let techniques = registry
+                .get(&skill_name)
+                .and_then(|skill| {
+                    skill
+                        .attack_techniques()
+                        .iter()
+                        .find(|(kind, _)| *kind == finding_type)
+                        .map(|(_, ids)| ids.join(", "))
+                });
+            if let Some(techniques) = techniques {
+                println!("  ATT&CK: {}", techniques);
+            }
+
+            for (heading, body) in [
+                ("What it means", &explanation.description),
+                ("Why it's flagged", &explanation.rationale),
+                ("Common false positives", &explanation.false_positives),
+                ("Remediation", &explanation.remediation),
+            ] {
+                println!();
+                println!("{}", heading.green().bold());
+                println!("  {}", body);
+            }
+            println!();
+        }
+
+        Commands::Diff {
+            old,
+            new,
+            format,
+            fail_on,
+            no_color,
+        } => {
+            let fail_on = parse_severity_arg("--fail-on", &fail_on);
+            if no_color {
+                colored::control::set_override(false);
+            }
+
+            let load = |path: &PathBuf| {
+                load_report_findings(path).unwrap_or_else(|e| {
+                    eprintln!("{}: cannot read {}: {}", "Error".red(), path.display(), e);
+                    std::process::exit(EXIT_ERROR);
+                })
+            };
+            let diff = ScanDiff::new(load(&old), load(&new));
+
+            if format == "json" {
+                let json = serde_json::json!({
+                    "schema_version": FINDING_SCHEMA_VERSION,
+                    "old": old.display().to_string(),
+                    "new": new.display().to_string(),
+                    "summary": {
+                        "added": diff.added.len(),
+                        "removed": diff.removed.len(),
+                        "unchanged": diff.unchanged
+                    },
+                    "added": diff.added,
+                    "removed": diff.removed
+                });
+                println!("{}", serde_json::to_string_pretty(&json).unwrap());
+            } else {
+                println!();
+                println!("Comparing {} -> {}", old.display(), new.display());
+                println!(
+                    "  {} added, {} removed, {} unchanged",
+                    format!("+{}", diff.added.len()).red().bold(),
+                    format!("-{}", diff.removed.len()).green(),
+                    diff.unchanged
+                );
+                println!();
+                if diff.added.is_empty() {
+                    println!("{}", "✓ No new findings".green());
+                } else {
+                    print_findings(&diff.added, None);
+                }
+            }
+
+            let highest = diff.added.iter().map(|f| f.severity).max();
+            std::process::exit(exit_code(highest, fail_on));
+        }
+
+        Commands::Export { output, format } => {
+            let registry = create_default_registry();
+            let schemas = match format.to_lowercase().as_str() {
+                "openai" => registry.export_schemas(),
+                "anthropic" => registry.export_schemas_anthropic(),
+                "mcp" => registry.export_schemas_mcp(),
+                other => {
+                    eprintln!(
+                        "{}: unknown export format '{}' (expected openai, anthropic or mcp)",
+                        "Error".red(),
+                        other
+                    );
+                    std::process::exit(EXIT_ERROR);
+                }
+            };
             let json = serde_json::to_string_pretty(&schemas).unwrap();
 
             match output {
@@ -199,6 +1036,7 @@ fn main() {
             skill,
             path,
             params,
+            timeout_secs,
         } => {
             let registry = create_default_registry();
 
@@ -216,19 +1054,288 @@ fn main() {
                 }
             }
 
-            match registry.invoke(&skill, json_params) {
+            let result = match timeout_secs {
+                Some(secs) => registry.invoke_with_timeout(&skill, json_params, Duration::from_secs(secs)),
+                None => registry.invoke(&skill, json_params),
+            };
+
+            match result {
                 Ok(output) => {
                     println!("{}", serde_json::to_string_pretty(&output).unwrap());
                 }
                 Err(e) => {
                     eprintln!("{}: {}", "Error".red(), e);
+                    std::process::exit(EXIT_ERROR);
                 }
             }
         }
     }
 }
 
-fn print_findings(findings: &[firewall_core::Finding]) {
+/// Rescan `files` (relative to `path`) for `scan --watch`, with either one
+/// skill from `registry` or the full scan pipeline
+fn rescan(
+    registry: &firewall_core::SkillRegistry,
+    path: &str,
+    skill: Option<&str>,
+    files: &[PathBuf],
+    options: &ScanOptions,
+) -> firewall_core::SkillResult<Vec<firewall_core::Finding>> {
+    match skill {
+        Some(skill_name) => {
+            let params = serde_json::json!({
+                "path": path,
+                "include": options.include,
+                "exclude": options.exclude,
+                "skip_dirs": options.skip_dirs,
+                "files": files
+            });
+            let output = match options.skill_timeout {
+                Some(timeout) => registry.invoke_with_timeout(skill_name, params, timeout),
+                None => registry.invoke(skill_name, params),
+            }?;
+            let mut findings = match &options.config {
+                Some(config) => config.apply(output.findings),
+                None => output.findings,
+            };
+            if !options.no_test_downgrade {
+                let root = std::path::Path::new(path);
+                findings.iter_mut().for_each(|f| downgrade_test_fixture(root, f));
+            }
+            Ok(findings)
+        }
+        None => {
+            let options = ScanOptions {
+                files: Some(files.to_vec()),
+                ..options.clone()
+            };
+            scan_path_with_options(path, &options).map(|output| output.findings)
+        }
+    }
+}
+
+/// Scan stdin `content` for `scan --stdin` as if it were a file called
+/// `name`, with either one skill or every per-file skill
+fn scan_stdin(
+    name: &str,
+    content: &str,
+    skill: Option<&str>,
+    options: &ScanOptions,
+) -> firewall_core::SkillResult<ScanReport> {
+    let Some(skill_name) = skill else {
+        return scan_content_report(name, content, options);
+    };
+
+    let mut registry = create_default_registry();
+    if let Some(rules_path) = &options.rules_path {
+        registry.register_custom_rules(rules_path)?;
+    }
+    let skill = registry.get(skill_name).ok_or_else(|| {
+        firewall_core::SkillError::InvalidParams(format!("Unknown skill: {}", skill_name))
+    })?;
+    let mut findings = skill.analyze_content(name, content)?;
+    if let Some(config) = &options.config {
+        findings = config.apply(findings);
+    }
+    if !options.no_test_downgrade {
+        let root = std::path::Path::new("");
+        findings.iter_mut().for_each(|f| downgrade_test_fixture(root, f));
+    }
+
+    let output = firewall_core::SkillOutput::with_findings(findings);
+    Ok(ScanReport::merge(vec![(skill_name.to_string(), output)])
+        .with_run(ScanRun::start(name, [skill_name])))
+}
+
+/// Findings saved by `scan --format json`: the `findings` of the plain or
+/// `--detailed` report, the aggregate of a serialized [`ScanReport`], or a
+/// bare findings array
+fn load_report_findings(path: &std::path::Path) -> Result<Vec<firewall_core::Finding>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut json: serde_json::Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+
+    let findings = if json.is_array() {
+        json.take()
+    } else if let Some(findings) = json.get_mut("findings") {
+        findings.take()
+    } else if let Some(findings) = json.pointer_mut("/aggregate/findings") {
+        findings.take()
+    } else {
+        return Err("not a scan report or findings array".to_string());
+    };
+    serde_json::from_value(findings).map_err(|e| e.to_string())
+}
+
+/// Two scans' findings compared by fingerprint
+struct ScanDiff {
+    /// In the new scan only, in its order
+    added: Vec<firewall_core::Finding>,
+
+    /// In the old scan only, in its order
+    removed: Vec<firewall_core::Finding>,
+
+    /// Findings of the new scan that the old one also had
+    unchanged: usize,
+}
+
+impl ScanDiff {
+    fn new(old: Vec<firewall_core::Finding>, new: Vec<firewall_core::Finding>) -> Self {
+        let fingerprints = |findings: &[firewall_core::Finding]| -> HashSet<String> {
+            findings.iter().map(|f| f.fingerprint()).collect()
+        };
+        let (old_prints, new_prints) = (fingerprints(&old), fingerprints(&new));
+
+        let (unchanged, added): (Vec<_>, Vec<_>) = new
+            .into_iter()
+            .partition(|f| old_prints.contains(&f.fingerprint()));
+        let removed = old
+            .into_iter()
+            .filter(|f| !new_prints.contains(&f.fingerprint()))
+            .collect();
+
+        Self {
+            added,
+            removed,
+            unchanged: unchanged.len(),
+        }
+    }
+}
+
+/// Write scan findings to stdout in the requested format
+fn print_report(
+    format: &str,
+    findings: &[firewall_core::Finding],
+    run: Option<&ScanRun>,
+    root: &std::path::Path,
+    fail_on: Severity,
+    top: Option<usize>,
+) {
+    match format {
+        "json" => {
+            let mut report = serde_json::json!({
+                "schema_version": FINDING_SCHEMA_VERSION,
+                "run": run,
+                "risk_score": rounded_risk_score(findings),
+                "findings": &findings[..top.unwrap_or(usize::MAX).min(findings.len())]
+            });
+            if top.is_some() {
+                report["total_findings"] = serde_json::json!(findings.len());
+            }
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+        "sarif" => {
+            let log = sarif::report(findings, &create_default_registry());
+            println!("{}", serde_json::to_string_pretty(&log).unwrap());
+        }
+        "junit" => {
+            let skills = run.map_or(&[][..], |run| &run.skills[..]);
+            print!("{}", junit::report(findings, &create_default_registry(), skills, fail_on));
+        }
+        "markdown" => print!("{}", markdown::report(findings, run, top)),
+        _ => {
+            print_findings(findings, top);
+            print_hotspots(root, findings);
+        }
+    }
+}
+
+/// List the directories under `root` with the heaviest findings, when
+/// findings come from more than one
+fn print_hotspots(root: &std::path::Path, findings: &[firewall_core::Finding]) {
+    let hotspots = hotspots::hottest(root, findings, hotspots::TOP_DIRECTORIES);
+    if hotspots.len() < 2 {
+        return;
+    }
+
+    println!();
+    println!("{}", "Hottest directories:".bold());
+    let width = hotspots.iter().map(|h| h.directory.len()).max().unwrap_or(0);
+    for hotspot in &hotspots {
+        println!(
+            "  {:<width$}  {:>3.0}%  {} finding(s), {} critical",
+            hotspot.directory,
+            hotspot.share * 100.0,
+            hotspot.findings,
+            hotspot.critical,
+            width = width
+        );
+    }
+}
+
+/// Move (or with `dry_run`, list) files flagged at `min_severity` or above
+fn quarantine_files(
+    root: &std::path::Path,
+    findings: &[firewall_core::Finding],
+    min_severity: Severity,
+    dir: &std::path::Path,
+    dry_run: bool,
+) -> std::io::Result<()> {
+    let (entries, skipped) = quarantine::plan(root, findings, min_severity, dir);
+
+    for location in &skipped {
+        eprintln!("{}: not quarantining {} (not a regular file)", "Note".cyan(), location);
+    }
+    for entry in &entries {
+        eprintln!(
+            "{} {} -> {}",
+            if dry_run { "Would move" } else { "Moving" },
+            entry.original.display(),
+            entry.quarantined.display()
+        );
+    }
+
+    if dry_run || entries.is_empty() {
+        return Ok(());
+    }
+
+    quarantine::apply(dir, &entries)?;
+    eprintln!(
+        "Quarantined {} file(s); manifest: {}",
+        entries.len(),
+        dir.join(quarantine::MANIFEST).display()
+    );
+    Ok(())
+}
+
+/// JSON report with each skill's merged output next to the findings
+fn print_detailed_report(report: &ScanReport, root: &std::path::Path) {
+    let json = serde_json::json!({
+        "schema_version": FINDING_SCHEMA_VERSION,
+        "run": report.run,
+        "risk_score": rounded_risk_score(report.findings()),
+        "findings": report.findings(),
+        "complete": report.aggregate.complete,
+        "metadata": report.aggregate.metadata,
+        "skills": report.skills,
+        "hotspots": hotspots::hottest(root, report.findings(), hotspots::TOP_DIRECTORIES)
+    });
+    println!("{}", serde_json::to_string_pretty(&json).unwrap());
+}
+
+/// Risk score to one decimal place, for display
+fn rounded_risk_score(findings: &[firewall_core::Finding]) -> f32 {
+    (risk_score(findings) * 10.0).round() / 10.0
+}
+
+/// Reorder findings for display: "severity" keeps the report's order
+/// (severity, then confidence); "confidence" puts the surest first;
+/// "location" groups them by file in line order. Ties keep their order.
+fn sort_findings(findings: &mut [firewall_core::Finding], order: &str) {
+    let line = |f: &firewall_core::Finding| f.value.get("line").and_then(|l| l.as_u64());
+    match order {
+        "confidence" => findings.sort_by(|a, b| {
+            b.confidence.total_cmp(&a.confidence).then(b.severity.cmp(&a.severity))
+        }),
+        "location" => findings.sort_by(|a, b| {
+            a.location.cmp(&b.location).then(line(a).cmp(&line(b)))
+        }),
+        _ => {}
+    }
+}
+
+/// Print findings, only the first `top` if given; the counts and risk
+/// score in the summary cover all of them
+fn print_findings(findings: &[firewall_core::Finding], top: Option<usize>) {
     if findings.is_empty() {
         println!("{}", "✓ No threats detected".green());
         return;
@@ -245,7 +1352,8 @@ fn print_findings(findings: &[firewall_core::Finding]) {
     );
     println!();
 
-    for finding in findings {
+    let shown = top.unwrap_or(usize::MAX).min(findings.len());
+    for finding in &findings[..shown] {
         println!(
             "  [{}] {}",
             severity_color(&finding.severity),
@@ -262,6 +1370,10 @@ fn print_findings(findings: &[firewall_core::Finding]) {
 
         println!();
     }
+    if shown < findings.len() {
+        println!("(showing {} of {})", shown, findings.len());
+        println!();
+    }
 
     // Summary
     let critical = findings.iter().filter(|f| f.severity == Severity::Critical).count();
@@ -278,4 +1390,6 @@ fn print_findings(findings: &[firewall_core::Finding]) {
             .bold()
         );
     }
+
+    println!("Risk score: {:.0}/100", risk_score(findings));
 }
@@ -0,0 +1,224 @@
+//! Markdown output for pull request comments and wikis
+//!
+//! A summary header with per-severity counts and the risk score, then one
+//! table per severity, critical first, with type, location, confidence and
+//! description columns. Text taken from findings is escaped so paths and
+//! descriptions can't break a table, render as markup or turn into links.
+
+use firewall_core::{risk_score, Finding, ScanRun, Severity};
+use std::fmt::Write;
+
+/// Heading for a severity's table
+fn heading(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "Critical",
+        Severity::High => "High",
+        Severity::Medium => "Medium",
+        Severity::Low => "Low",
+        Severity::Info => "Info",
+    }
+}
+
+/// Escape text for a table cell: Markdown punctuation is backslash-escaped,
+/// line breaks become spaces, and `://` and `www.` are broken up so GitHub
+/// doesn't autolink URLs found in scanned files
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("://") {
+            escaped.push_str(":\\/\\/");
+            rest = &rest[3..];
+            continue;
+        }
+        if rest.starts_with("www.") {
+            escaped.push_str("www\\.");
+            rest = &rest[4..];
+            continue;
+        }
+
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~' | '&' | '#' | '!' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' | '\n' => escaped.push(' '),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    escaped
+}
+
+/// Finding type as a code span, unless it contains characters a code span
+/// can't hold inside a table
+fn finding_type(finding: &Finding) -> String {
+    let name = &finding.finding_type;
+    if name.contains(['`', '|']) || name.chars().any(char::is_control) {
+        escape(name)
+    } else {
+        format!("`{}`", name)
+    }
+}
+
+/// Location with the line number when the finding has one
+fn location(finding: &Finding) -> String {
+    match finding.value.get("line").and_then(|l| l.as_u64()) {
+        Some(line) => format!("{}:{}", escape(&finding.location), line),
+        None => escape(&finding.location),
+    }
+}
+
+/// Render `findings` as a Markdown report, listing only the first `top` if
+/// given; the summary counts and risk score cover all of them
+pub fn report(findings: &[Finding], run: Option<&ScanRun>, top: Option<usize>) -> String {
+    let mut md = String::from("## GentlyOS Firewall scan\n\n");
+    if let Some(run) = run {
+        let _ = writeln!(
+            md,
+            "Scanned {} with firewall-core {} at {}\n",
+            escape(&run.root),
+            run.version,
+            run.scanned_at.format("%Y-%m-%d %H:%M UTC")
+        );
+    }
+
+    if findings.is_empty() {
+        md.push_str("No threats detected.\n");
+        return md;
+    }
+
+    let _ = writeln!(
+        md,
+        "**{} {}**, risk score **{:.0}/100**\n",
+        findings.len(),
+        if findings.len() == 1 { "finding" } else { "findings" },
+        risk_score(findings)
+    );
+    md.push_str("| Severity | Findings |\n|---|---:|\n");
+    for severity in Severity::ALL.into_iter().rev() {
+        let count = findings.iter().filter(|f| f.severity == severity).count();
+        let _ = writeln!(md, "| {} | {} |", heading(severity), count);
+    }
+
+    let shown = &findings[..top.unwrap_or(usize::MAX).min(findings.len())];
+    for severity in Severity::ALL.into_iter().rev() {
+        let group: Vec<&Finding> = shown.iter().filter(|f| f.severity == severity).collect();
+        if group.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(md, "\n### {} ({})\n", heading(severity), group.len());
+        md.push_str("| Type | Location | Confidence | Description |\n|---|---|---:|---|\n");
+        for finding in group {
+            let description = finding
+                .metadata
+                .get("description")
+                .and_then(|d| d.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| finding.finding_type.replace('_', " "));
+            let _ = writeln!(
+                md,
+                "| {} | {} | {:.0}% | {} |",
+                finding_type(finding),
+                location(finding),
+                finding.confidence * 100.0,
+                escape(&description)
+            );
+        }
+    }
+
+    if shown.len() < findings.len() {
+        let _ = writeln!(md, "\n_Showing {} of {} findings._", shown.len(), findings.len());
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn finding(
+        finding_type: &str,
+        severity: Severity,
+        location: &str,
+        line: Option<u64>,
+        description: &str,
+    ) -> Finding {
+        Finding {
+            finding_type: finding_type.to_string(),
+            value: line.map_or_else(|| json!({}), |line| json!({ "line": line })),
+            confidence: 0.9,
+            location: location.to_string(),
+            severity,
+            metadata: json!({ "description": description }),
+        }
+    }
+
+    #[test]
+    fn test_report_snapshot() {
+        let findings = [
+            finding(
+                "keyboard_injection",
+                Severity::Critical,
+                "src/a|b.js",
+                Some(7),
+                "Shell to <b>http://evil.example</b> & *more*",
+            ),
+            finding("hardcoded_public_ip", Severity::Medium, "my_app/[x].py", Some(3), "IP\nhere"),
+            finding("custom`rule", Severity::Medium, "notes.md", None, "see www.example.com"),
+            finding("file_trusted", Severity::Info, "vendor/lib.js", None, "Trusted by hash"),
+        ];
+
+        let expected = "\
+## GentlyOS Firewall scan
+
+**4 findings**, risk score **64/100**
+
+| Severity | Findings |
+|---|---:|
+| Critical | 1 |
+| High | 0 |
+| Medium | 2 |
+| Low | 0 |
+| Info | 1 |
+
+### Critical (1)
+
+| Type | Location | Confidence | Description |
+|---|---|---:|---|
+| `keyboard_injection` | src/a\\|b.js:7 | 90% | Shell to \\<b\\>http:\\/\\/evil.example\\</b\\> \\& \\*more\\* |
+
+### Medium (2)
+
+| Type | Location | Confidence | Description |
+|---|---|---:|---|
+| `hardcoded_public_ip` | my\\_app/\\[x\\].py:3 | 90% | IP here |
+| custom\\`rule | notes.md | 90% | see www\\.example.com |
+
+### Info (1)
+
+| Type | Location | Confidence | Description |
+|---|---|---:|---|
+| `file_trusted` | vendor/lib.js | 90% | Trusted by hash |
+";
+        assert_eq!(report(&findings, None, None), expected);
+    }
+
+    #[test]
+    fn test_top_limits_tables_not_summary() {
+        let findings = [
+            finding("keyboard_injection", Severity::Critical, "a.js", None, "one"),
+            finding("clipboard_access", Severity::High, "b.js", None, "two"),
+        ];
+
+        let md = report(&findings, None, Some(1));
+        assert!(md.contains("| Critical | 1 |\n| High | 1 |"));
+        assert!(md.contains("### Critical (1)"));
+        assert!(!md.contains("### High"));
+        assert!(md.ends_with("_Showing 1 of 2 findings._\n"));
+        assert_eq!(report(&[], None, None), "## GentlyOS Firewall scan\n\nNo threats detected.\n");
+    }
+}
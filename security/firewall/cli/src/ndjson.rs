@@ -0,0 +1,95 @@
+//! Streaming NDJSON output: one finding per line, written as soon as a
+//! skill produces it
+
+use firewall_core::{Finding, Severity};
+use std::collections::{BTreeSet, HashSet};
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+#[derive(Default)]
+struct State {
+    highest: Option<Severity>,
+    suppressed: usize,
+    fingerprints: BTreeSet<String>,
+}
+
+/// Applies the severity filter and baseline to each finding, then prints
+/// it; safe to call from several scan threads at once
+pub struct Sink {
+    min_severity: Severity,
+    baseline: Option<HashSet<String>>,
+    record_fingerprints: bool,
+    redact: bool,
+    state: Mutex<State>,
+}
+
+/// Totals once the scan is over
+pub struct Summary {
+    /// Highest severity printed
+    pub highest: Option<Severity>,
+
+    /// Findings dropped because they were in the baseline
+    pub suppressed: usize,
+
+    /// Fingerprints of every finding seen (when recording was requested)
+    pub fingerprints: BTreeSet<String>,
+}
+
+impl Sink {
+    pub fn new(
+        min_severity: Severity,
+        baseline: Option<HashSet<String>>,
+        record_fingerprints: bool,
+        redact: bool,
+    ) -> Self {
+        Self {
+            min_severity,
+            baseline,
+            record_fingerprints,
+            redact,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    pub fn emit(&self, finding: &Finding) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let needs_fingerprint = self.record_fingerprints || self.baseline.is_some();
+        let fingerprint = needs_fingerprint.then(|| finding.fingerprint());
+        if self.record_fingerprints {
+            state.fingerprints.extend(fingerprint.clone());
+        }
+
+        if finding.severity < self.min_severity {
+            return;
+        }
+        if let (Some(baseline), Some(fp)) = (&self.baseline, &fingerprint) {
+            if baseline.contains(fp) {
+                state.suppressed += 1;
+                return;
+            }
+        }
+
+        state.highest = state.highest.max(Some(finding.severity));
+
+        // Hold the lock while writing so lines from different threads never interleave
+        let line = if self.redact {
+            serde_json::to_string(&finding.redacted())
+        } else {
+            serde_json::to_string(finding)
+        }
+        .expect("findings always serialize");
+        let mut stdout = io::stdout().lock();
+        let _ = writeln!(stdout, "{}", line);
+        let _ = stdout.flush();
+    }
+
+    pub fn finish(self) -> Summary {
+        let state = self.state.into_inner().unwrap_or_else(|e| e.into_inner());
+        Summary {
+            highest: state.highest,
+            suppressed: state.suppressed,
+            fingerprints: state.fingerprints,
+        }
+    }
+}
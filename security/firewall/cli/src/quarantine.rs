@@ -0,0 +1,255 @@
+//! `--quarantine`: move flagged files out of the scanned tree
+//!
+//! Files are moved, never deleted, into the quarantine directory under the
+//! same path they had relative to the scan root. A `manifest.json` there
+//! maps each original path to its quarantined path and the findings that
+//! triggered the move. Only regular files are moved; symlinks and
+//! directories are left alone.
+
+use firewall_core::{Finding, Severity};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Manifest file name inside the quarantine directory
+pub const MANIFEST: &str = "manifest.json";
+
+/// A finding that caused a file to be quarantined
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trigger {
+    pub finding_type: String,
+    pub severity: Severity,
+    pub fingerprint: String,
+}
+
+/// One planned or completed move
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub original: PathBuf,
+    pub quarantined: PathBuf,
+    pub findings: Vec<Trigger>,
+}
+
+/// Plan moves for every regular file with a finding at or above
+/// `min_severity`. Destinations keep the path relative to `root`; a name
+/// already taken (on disk, earlier in the plan, or by the manifest) gets a
+/// `.1`, `.2`, ... suffix. Returns the plan and the locations that were skipped.
+pub fn plan(
+    root: &Path,
+    findings: &[Finding],
+    min_severity: Severity,
+    dir: &Path,
+) -> (Vec<Entry>, Vec<String>) {
+    let mut by_location: BTreeMap<&str, Vec<Trigger>> = BTreeMap::new();
+    for finding in findings.iter().filter(|f| f.severity >= min_severity) {
+        by_location.entry(&finding.location).or_default().push(Trigger {
+            finding_type: finding.finding_type.clone(),
+            severity: finding.severity,
+            fingerprint: finding.fingerprint(),
+        });
+    }
+
+    let quarantine_root = dir.canonicalize().ok();
+    // A flagged manifest.json at the root must not be overwritten by ours
+    let mut taken: HashSet<PathBuf> = HashSet::from([dir.join(MANIFEST)]);
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (location, triggers) in by_location {
+        let original = Path::new(location);
+
+        // symlink_metadata doesn't follow links, so a link is never a file here
+        let is_file = fs::symlink_metadata(original).is_ok_and(|m| m.file_type().is_file());
+        let already_quarantined = quarantine_root.as_ref().is_some_and(|q| {
+            original.canonicalize().is_ok_and(|p| p.starts_with(q))
+        });
+        if !is_file || already_quarantined {
+            skipped.push(location.to_string());
+            continue;
+        }
+
+        let relative = match original.strip_prefix(root) {
+            Ok(rel) if !rel.as_os_str().is_empty() => rel.to_path_buf(),
+            _ => PathBuf::from(original.file_name().unwrap_or(original.as_os_str())),
+        };
+        let quarantined = free_path(&dir.join(relative), &taken);
+        taken.insert(quarantined.clone());
+
+        entries.push(Entry {
+            original: original.to_path_buf(),
+            quarantined,
+            findings: triggers,
+        });
+    }
+
+    (entries, skipped)
+}
+
+/// `path`, or the first of `path.1`, `path.2`, ... not on disk or in `taken`
+fn free_path(path: &Path, taken: &HashSet<PathBuf>) -> PathBuf {
+    let is_free = |p: &Path| !taken.contains(p) && fs::symlink_metadata(p).is_err();
+    if is_free(path) {
+        return path.to_path_buf();
+    }
+
+    (1..)
+        .map(|n| {
+            let mut name = OsString::from(path.as_os_str());
+            name.push(format!(".{}", n));
+            PathBuf::from(name)
+        })
+        .find(|candidate| is_free(candidate))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Move a file, falling back to copy + remove across filesystems
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(_) => {
+            fs::copy(from, to)?;
+            fs::remove_file(from)
+        }
+    }
+}
+
+/// Carry out `entries` and append them to the manifest in `dir`. Stops at
+/// the first failed move; files moved before it are still recorded.
+pub fn apply(dir: &Path, entries: &[Entry]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let manifest_path = dir.join(MANIFEST);
+    let mut manifest: Vec<Entry> = match fs::read_to_string(&manifest_path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+
+    let mut result = Ok(());
+    for entry in entries {
+        if let Err(e) = move_file(&entry.original, &entry.quarantined) {
+            result = Err(io::Error::new(
+                e.kind(),
+                format!("cannot move {}: {}", entry.original.display(), e),
+            ));
+            break;
+        }
+        manifest.push(entry.clone());
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)?;
+    fs::write(&manifest_path, json + "\n")?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn finding(location: &Path, severity: Severity) -> Finding {
+        Finding {
+            finding_type: "clipboard_access".to_string(),
+            value: json!({}),
+            confidence: 0.9,
+            location: location.display().to_string(),
+            severity,
+            metadata: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_moves_flagged_files_and_keeps_structure() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("src");
+        let dir = tmp.path().join("quarantine");
+        fs::create_dir_all(root.join("lib")).unwrap();
+        fs::write(root.join("lib/bad.js"), "x").unwrap();
+        fs::write(root.join("ok.js"), "y").unwrap();
+
+        let findings = vec![
+            finding(&root.join("lib/bad.js"), Severity::Critical),
+            finding(&root.join("ok.js"), Severity::Low),
+        ];
+        let (entries, skipped) = plan(&root, &findings, Severity::High, &dir);
+        assert!(skipped.is_empty());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].quarantined, dir.join("lib/bad.js"));
+
+        apply(&dir, &entries).unwrap();
+        assert!(!root.join("lib/bad.js").exists());
+        assert!(root.join("ok.js").exists());
+        assert_eq!(fs::read_to_string(dir.join("lib/bad.js")).unwrap(), "x");
+
+        let manifest: Vec<Entry> =
+            serde_json::from_str(&fs::read_to_string(dir.join(MANIFEST)).unwrap()).unwrap();
+        assert_eq!(manifest, entries);
+        assert_eq!(manifest[0].findings[0].finding_type, "clipboard_access");
+    }
+
+    #[test]
+    fn test_name_collisions_get_a_suffix() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("src");
+        let dir = tmp.path().join("quarantine");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(root.join("bad.js"), "new").unwrap();
+        fs::write(dir.join("bad.js"), "from an earlier run").unwrap();
+
+        let findings = vec![finding(&root.join("bad.js"), Severity::High)];
+        let (entries, _) = plan(&root, &findings, Severity::High, &dir);
+        assert_eq!(entries[0].quarantined, dir.join("bad.js.1"));
+
+        apply(&dir, &entries).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("bad.js")).unwrap(), "from an earlier run");
+        assert_eq!(fs::read_to_string(dir.join("bad.js.1")).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_flagged_manifest_does_not_collide_with_ours() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("extension");
+        let dir = tmp.path().join("quarantine");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join(MANIFEST), "{\"permissions\": [\"clipboardRead\"]}").unwrap();
+
+        let findings = vec![finding(&root.join(MANIFEST), Severity::High)];
+        let (entries, _) = plan(&root, &findings, Severity::High, &dir);
+        assert_eq!(entries[0].quarantined, dir.join("manifest.json.1"));
+
+        apply(&dir, &entries).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("manifest.json.1")).unwrap(),
+            "{\"permissions\": [\"clipboardRead\"]}"
+        );
+        let manifest: Vec<Entry> =
+            serde_json::from_str(&fs::read_to_string(dir.join(MANIFEST)).unwrap()).unwrap();
+        assert_eq!(manifest, entries);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_and_directories_are_skipped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("src");
+        fs::create_dir_all(root.join("pkg")).unwrap();
+        fs::write(tmp.path().join("target.js"), "x").unwrap();
+        std::os::unix::fs::symlink(tmp.path().join("target.js"), root.join("link.js")).unwrap();
+
+        let findings = vec![
+            finding(&root.join("link.js"), Severity::Critical),
+            finding(&root.join("pkg"), Severity::Critical),
+        ];
+        let (entries, skipped) = plan(&root, &findings, Severity::High, &tmp.path().join("q"));
+        assert!(entries.is_empty());
+        assert_eq!(skipped.len(), 2);
+    }
+}
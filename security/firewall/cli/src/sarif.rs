@@ -0,0 +1,169 @@
+//! SARIF 2.1.0 output for CI code-scanning integrations
+
+use firewall_core::{Finding, Severity, SkillRegistry, VERSION};
+use serde_json::{json, Value};
+use std::path::Path;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF result level for a severity
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Info => "note",
+    }
+}
+
+/// URI for an artifact location: relative paths stay relative, absolute
+/// ones become `file://` URIs, and directories get a trailing slash
+fn artifact_uri(location: &str) -> String {
+    let path = Path::new(location);
+    let mut uri = location.replace('\\', "/").replace(' ', "%20");
+
+    if path.is_dir() && !uri.ends_with('/') {
+        uri.push('/');
+    }
+
+    if path.is_absolute() {
+        format!("file://{}", uri)
+    } else {
+        uri
+    }
+}
+
+fn result(finding: &Finding) -> Value {
+    let message = finding
+        .metadata
+        .get("description")
+        .and_then(|d| d.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| finding.finding_type.replace('_', " "));
+
+    let mut physical = json!({
+        "artifactLocation": { "uri": artifact_uri(&finding.location) }
+    });
+    if let Some(line) = finding.value.get("line").and_then(|l| l.as_u64()) {
+        physical["region"] = json!({ "startLine": line.max(1) });
+        if let Some(column) = finding.value.get("column").and_then(|c| c.as_u64()) {
+            physical["region"]["startColumn"] = json!(column.max(1));
+        }
+    }
+
+    let mut result = json!({
+        "ruleId": finding.finding_type,
+        "level": level(finding.severity),
+        "message": { "text": message },
+        "locations": [{ "physicalLocation": physical }],
+        "properties": {
+            "confidence": finding.confidence,
+            "severity": finding.severity,
+            "value": finding.value
+        }
+    });
+    if let Some(techniques) = finding.metadata.get("attack_techniques") {
+        result["properties"]["attack_techniques"] = techniques.clone();
+    }
+
+    result
+}
+
+/// Build a SARIF log with one run covering `findings`
+pub fn report(findings: &[Finding], registry: &SkillRegistry) -> Value {
+    let rules: Vec<Value> = registry
+        .list()
+        .into_iter()
+        .filter_map(|name| registry.get(name))
+        .map(|skill| {
+            let mut techniques: Vec<&str> = skill
+                .attack_techniques()
+                .iter()
+                .flat_map(|(_, ids)| ids.iter().copied())
+                .collect();
+            techniques.sort_unstable();
+            techniques.dedup();
+
+            json!({
+                "id": skill.name(),
+                "shortDescription": { "text": skill.name() },
+                "fullDescription": { "text": skill.description() },
+                "properties": {
+                    "tags": skill.categories(),
+                    "attack_techniques": techniques
+                }
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "gentlyos-firewall",
+                    "version": VERSION,
+                    "rules": rules
+                }
+            },
+            "results": findings.iter().map(result).collect::<Vec<_>>()
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use firewall_core::create_default_registry;
+
+    fn finding(location: &str, severity: Severity) -> Finding {
+        Finding {
+            finding_type: "screenshot_collection".to_string(),
+            value: json!({ "count": 12 }),
+            confidence: 0.85,
+            location: location.to_string(),
+            severity,
+            metadata: json!({
+                "description": "12 screenshots collected",
+                "attack_techniques": ["T1113"]
+            }),
+        }
+    }
+
+    #[test]
+    fn test_results_and_rules() {
+        let registry = create_default_registry();
+        let log = report(&[finding("src/a.js", Severity::Critical)], &registry);
+
+        let run = &log["runs"][0];
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), registry.list().len());
+
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "screenshot_collection");
+        assert_eq!(result["level"], "error");
+        assert_eq!(result["message"]["text"], "12 screenshots collected");
+        assert_eq!(result["properties"]["confidence"], 0.85f32 as f64);
+        assert_eq!(result["properties"]["attack_techniques"], json!(["T1113"]));
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/a.js"
+        );
+    }
+
+    #[test]
+    fn test_directory_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().display().to_string();
+
+        let log = report(&[finding(&location, Severity::Low)], &create_default_registry());
+        let result = &log["runs"][0]["results"][0];
+        let uri = result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+            .as_str()
+            .unwrap();
+
+        assert_eq!(result["level"], "note");
+        assert!(uri.starts_with("file:///"));
+        assert!(uri.ends_with('/'));
+    }
+}
@@ -0,0 +1,234 @@
+//! `scan --watch`: rescan changed files and report new findings
+//!
+//! After the initial scan, file changes are debounced and only the touched
+//! files are rescanned. Findings are diffed per location against the
+//! previous run, so a file going from clean to dirty (or gaining a new
+//! finding) is reported once, and fixing then reintroducing it reports it
+//! again.
+
+use colored::Colorize;
+use firewall_core::{Finding, PathFilter, SkillResult};
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Quiet period before a burst of file events triggers a rescan
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Fingerprints from the latest scan of each finding location
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    by_location: HashMap<String, HashSet<String>>,
+}
+
+impl Snapshot {
+    /// Record a rescan of `locations` and return the findings that were not
+    /// present for their location last time. Locations that were rescanned
+    /// but produced no findings are cleared.
+    pub fn update(&mut self, locations: &[String], findings: Vec<Finding>) -> Vec<Finding> {
+        let mut previous: HashMap<String, HashSet<String>> = HashMap::new();
+        let touched = locations.iter().chain(findings.iter().map(|f| &f.location));
+        for location in touched {
+            if !previous.contains_key(location) {
+                let old = self.by_location.remove(location).unwrap_or_default();
+                previous.insert(location.clone(), old);
+            }
+        }
+
+        let mut introduced = Vec::new();
+        for finding in findings {
+            let fingerprint = finding.fingerprint();
+            let is_new = !previous[&finding.location].contains(&fingerprint);
+            let current = self.by_location.entry(finding.location.clone()).or_default();
+            if current.insert(fingerprint) && is_new {
+                introduced.push(finding);
+            }
+        }
+
+        introduced
+    }
+}
+
+/// `HH:MM:SS` (UTC) of the current time
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+        % 86_400;
+    format!("{:02}:{:02}:{:02}Z", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+fn print_new(finding: &Finding) {
+    let description = finding
+        .metadata
+        .get("description")
+        .and_then(|d| d.as_str())
+        .unwrap_or("");
+
+    println!(
+        "[{}] {} {} {} {}",
+        timestamp().dimmed(),
+        super::severity_color(&finding.severity),
+        finding.finding_type.white().bold(),
+        finding.location,
+        description.dimmed()
+    );
+}
+
+/// Files under `root` touched by a batch of events, relative to `root`.
+/// Anything inside a skipped directory (`.git`, `node_modules`, ...) is
+/// ignored.
+fn changed_files(root: &Path, filter: &PathFilter, paths: &[PathBuf]) -> Vec<PathBuf> {
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+    let relative: BTreeSet<PathBuf> = paths
+        .iter()
+        .filter_map(|path| path.strip_prefix(&canonical_root).ok())
+        .filter(|rel| !filter.in_skipped_dir(root, &root.join(rel)))
+        .filter(|rel| !root.join(rel).is_dir())
+        .filter(|rel| filter.allows(root, &root.join(rel)))
+        .map(Path::to_path_buf)
+        .collect();
+
+    relative.into_iter().collect()
+}
+
+/// Watch `root` until interrupted, rescanning changed files with `scan`
+///
+/// `scan` rescans the given files (relative to `root`). `initial` are the
+/// findings already reported by the first scan; `keep` decides which
+/// findings are reported at all. With `redact`, new findings are printed
+/// [redacted](Finding::redacted).
+pub fn run<S, K>(
+    root: &Path,
+    filter: &PathFilter,
+    initial: Vec<Finding>,
+    scan: S,
+    keep: K,
+    redact: bool,
+) -> notify_debouncer_mini::notify::Result<()>
+where
+    S: Fn(&[PathBuf]) -> SkillResult<Vec<Finding>>,
+    K: Fn(&Finding) -> bool,
+{
+    let mut snapshot = Snapshot::default();
+    snapshot.update(&[], initial);
+
+    let (tx, rx) = mpsc::channel::<DebounceEventResult>();
+    let mut debouncer = new_debouncer(DEBOUNCE, tx)?;
+    debouncer.watcher().watch(root, RecursiveMode::Recursive)?;
+
+    eprintln!(
+        "{} {} for changes (Ctrl+C to stop)",
+        "Watching".cyan().bold(),
+        root.display()
+    );
+
+    for events in rx {
+        let events = match events {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("{}: watch error: {}", "Warning".yellow(), e);
+                continue;
+            }
+        };
+
+        let paths: Vec<PathBuf> = events.into_iter().map(|event| event.path).collect();
+        let files = changed_files(root, filter, &paths);
+        if files.is_empty() {
+            continue;
+        }
+
+        let findings = match scan(&files) {
+            Ok(findings) => findings,
+            Err(e) => {
+                eprintln!("{}: rescan failed: {}", "Warning".yellow(), e);
+                continue;
+            }
+        };
+
+        let locations: Vec<String> = files
+            .iter()
+            .map(|file| root.join(file).display().to_string())
+            .collect();
+        let findings = findings.into_iter().filter(|f| keep(f)).collect();
+        for finding in snapshot.update(&locations, findings) {
+            print_new(&if redact { finding.redacted() } else { finding });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use firewall_core::Severity;
+    use serde_json::json;
+
+    fn finding(location: &str, ip: &str) -> Finding {
+        Finding {
+            finding_type: "hardcoded_public_ip".to_string(),
+            value: json!({ "ip": ip }),
+            confidence: 0.8,
+            location: location.to_string(),
+            severity: Severity::Medium,
+            metadata: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_only_new_findings_are_reported() {
+        let mut snapshot = Snapshot::default();
+        snapshot.update(&[], vec![finding("a.js", "203.0.113.7")]);
+
+        // Unchanged rescan reports nothing
+        let rescan = vec![finding("a.js", "203.0.113.7")];
+        assert!(snapshot.update(&["a.js".to_string()], rescan).is_empty());
+
+        // A second finding in the same file is new
+        let rescan = vec![finding("a.js", "203.0.113.7"), finding("a.js", "198.51.100.4")];
+        let new = snapshot.update(&["a.js".to_string()], rescan);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].value["ip"], "198.51.100.4");
+
+        // Clean, then dirty again: reported again
+        assert!(snapshot.update(&["a.js".to_string()], Vec::new()).is_empty());
+        let rescan = vec![finding("a.js", "203.0.113.7")];
+        assert_eq!(snapshot.update(&["a.js".to_string()], rescan).len(), 1);
+
+        // Other locations are untouched by a rescan of a.js
+        let rescan = vec![finding("b.js", "203.0.113.7")];
+        assert_eq!(snapshot.update(&["b.js".to_string()], rescan).len(), 1);
+        assert!(snapshot.update(&["a.js".to_string()], vec![finding("a.js", "203.0.113.7")])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_changed_files_are_relative_and_filtered() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/app.js"), "").unwrap();
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+
+        let canonical = root.canonicalize().unwrap();
+        let events = vec![
+            canonical.join("src/app.js"),
+            canonical.join("src/app.js"),
+            canonical.join("src"),
+            canonical.join(".git/index"),
+            canonical.join("notes.txt"),
+            canonical.join("deleted.js"),
+        ];
+        let filter = PathFilter::new(&[], &["*.txt".to_string()]).unwrap();
+
+        let files = changed_files(root, &filter, &events);
+        assert_eq!(files, [PathBuf::from("deleted.js"), PathBuf::from("src/app.js")]);
+    }
+}
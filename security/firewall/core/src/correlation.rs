@@ -0,0 +1,256 @@
+//! Severity escalation for findings that co-occur in one file
+//!
+//! Some findings are unremarkable alone and alarming together: a script
+//! that opens the microphone, holds a hardcoded address and waits on a long
+//! timer is a surveillance implant. After a full scan, every
+//! [`CorrelationRule`] whose required finding types all appear at one
+//! location emits a synthesized finding (`correlated_threat` unless the
+//! rule says otherwise) at the rule's severity, listing the findings that
+//! triggered it.
+//!
+//! The built-in rules are [`default_rules`]. A `correlations` list in the
+//! scan config replaces them (an empty list turns correlation off):
+//!
+//! ```toml
+//! [[correlations]]
+//! name = "surveillance_implant"
+//! requires = ["microphone_access", "hardcoded_public_ip|c2_beaconing", "long_timer_delay"]
+//! emit = { type = "correlated_threat", severity = "critical" }
+//! ```
+//!
+//! Each `requires` entry is a finding type, or several separated by `|` of
+//! which any one will do.
+
+use crate::skills::{Finding, Severity};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Finding type emitted by the built-in rules
+pub const CORRELATED_THREAT: &str = "correlated_threat";
+
+/// Separates alternative finding types in a `requires` entry
+const ALTERNATIVES: char = '|';
+
+/// Network findings that place a remote endpoint in the file
+const NETWORK: &str = "c2_beaconing|hardcoded_public_ip|suspicious_ports|dns_tunneling\
+                       |encoded_ip_address|potential_dga_domain|suspicious_hosting_endpoint";
+
+/// Built-in rules: name, required finding types, emitted severity
+const DEFAULT_RULES: &[(&str, &[&str], Severity)] = &[
+    (
+        "surveillance_implant",
+        &["microphone_access", NETWORK, "long_timer_delay|long_sleep_delay|scheduling_detected"],
+        Severity::Critical,
+    ),
+    (
+        "obfuscated_dropper",
+        &[
+            "decode_eval_chain|dynamic_eval|packed_eval|string_array_obfuscation",
+            "unverified_download_execution|c2_beaconing|hardcoded_public_ip",
+        ],
+        Severity::Critical,
+    ),
+    (
+        "screen_capture_exfiltration",
+        &["screenshot_collection", NETWORK],
+        Severity::Critical,
+    ),
+    (
+        "input_hijack_exfiltration",
+        &["clipboard_access|keyboard_injection|hid_device_access", NETWORK],
+        Severity::High,
+    ),
+    (
+        "delayed_logic_bomb",
+        &["potential_time_bomb|timestamp_trigger|logic_bomb_env_trigger", NETWORK],
+        Severity::High,
+    ),
+];
+
+/// What a rule emits when it fires
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorrelationEmit {
+    /// Finding type of the synthesized finding
+    #[serde(rename = "type", default = "correlated_threat")]
+    pub finding_type: String,
+
+    /// Severity of the synthesized finding
+    pub severity: Severity,
+}
+
+fn correlated_threat() -> String {
+    CORRELATED_THREAT.to_string()
+}
+
+/// Finding types that together warrant a finding of their own
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorrelationRule {
+    /// Rule name, recorded on the findings it emits
+    pub name: String,
+
+    /// Finding types that must all appear at one location; an entry may
+    /// list alternatives separated by `|`
+    pub requires: Vec<String>,
+
+    /// Finding emitted when every requirement is met
+    pub emit: CorrelationEmit,
+}
+
+impl CorrelationRule {
+    /// Whether the rule has a type to emit and at least one requirement,
+    /// none of them with an empty alternative
+    pub fn is_valid(&self) -> bool {
+        !self.emit.finding_type.is_empty()
+            && !self.requires.is_empty()
+            && self
+                .requires
+                .iter()
+                .all(|entry| entry.split(ALTERNATIVES).all(|t| !t.trim().is_empty()))
+    }
+
+    /// The most confident finding meeting each requirement, or `None` if
+    /// any requirement is unmet
+    fn matches<'a>(&self, findings: &[&'a Finding]) -> Option<Vec<&'a Finding>> {
+        self.requires
+            .iter()
+            .map(|entry| {
+                findings
+                    .iter()
+                    .copied()
+                    .filter(|f| entry.split(ALTERNATIVES).any(|t| t.trim() == f.finding_type))
+                    .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+            })
+            .collect()
+    }
+}
+
+/// The built-in correlation rules
+pub fn default_rules() -> Vec<CorrelationRule> {
+    DEFAULT_RULES
+        .iter()
+        .map(|(name, requires, severity)| CorrelationRule {
+            name: name.to_string(),
+            requires: requires.iter().map(|r| r.to_string()).collect(),
+            emit: CorrelationEmit {
+                finding_type: correlated_threat(),
+                severity: *severity,
+            },
+        })
+        .collect()
+}
+
+/// Findings synthesized by `rules` from `findings`, grouped by location.
+/// Each carries the contributing findings in `value.findings`; its
+/// confidence is that of the least confident contributor.
+pub fn correlate<'a>(
+    rules: &[CorrelationRule],
+    findings: impl IntoIterator<Item = &'a Finding>,
+) -> Vec<Finding> {
+    let mut by_location: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        by_location.entry(&finding.location).or_default().push(finding);
+    }
+
+    let mut correlated = Vec::new();
+    for (location, findings) in by_location {
+        for rule in rules {
+            let Some(matched) = rule.matches(&findings) else {
+                continue;
+            };
+
+            let confidence = matched.iter().map(|f| f.confidence).fold(1.0, f32::min);
+            let types: Vec<&str> = matched.iter().map(|f| f.finding_type.as_str()).collect();
+            let contributing: Vec<_> = matched
+                .iter()
+                .map(|f| {
+                    json!({
+                        "finding_type": f.finding_type,
+                        "severity": f.severity,
+                        "confidence": f.confidence,
+                        "line": f.value.get("line")
+                    })
+                })
+                .collect();
+
+            correlated.push(Finding {
+                finding_type: rule.emit.finding_type.clone(),
+                value: json!({ "rule": rule.name, "findings": contributing }),
+                confidence,
+                location: location.to_string(),
+                severity: rule.emit.severity,
+                metadata: json!({
+                    "pattern": "correlation rule",
+                    "rule": rule.name,
+                    "description": format!("{} in one file", types.join(" + "))
+                }),
+            });
+        }
+    }
+    correlated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(finding_type: &str, location: &str, confidence: f32) -> Finding {
+        Finding {
+            finding_type: finding_type.to_string(),
+            value: json!({ "line": 3 }),
+            confidence,
+            location: location.to_string(),
+            severity: Severity::Medium,
+            metadata: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_rule_fires_only_when_every_requirement_meets_at_one_location() {
+        let findings = [
+            finding("microphone_access", "implant.js", 0.7),
+            finding("hardcoded_public_ip", "implant.js", 0.9),
+            finding("suspicious_ports", "implant.js", 0.75),
+            finding("long_timer_delay", "implant.js", 0.8),
+            // Spread over two files, the same types don't correlate
+            finding("microphone_access", "recorder.js", 0.7),
+            finding("long_timer_delay", "recorder.js", 0.7),
+            finding("hardcoded_public_ip", "config.js", 0.9),
+        ];
+
+        let correlated = correlate(&default_rules(), &findings);
+        assert_eq!(correlated.len(), 1, "{:?}", correlated);
+        let threat = &correlated[0];
+        assert_eq!(threat.finding_type, CORRELATED_THREAT);
+        assert_eq!(threat.severity, Severity::Critical);
+        assert_eq!(threat.location, "implant.js");
+        assert_eq!(threat.confidence, 0.7);
+        assert_eq!(threat.value["rule"], "surveillance_implant");
+        // The most confident network finding stands for the alternatives
+        let types: Vec<&str> = threat.value["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["finding_type"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["microphone_access", "hardcoded_public_ip", "long_timer_delay"]);
+    }
+
+    #[test]
+    fn test_rule_validity() {
+        let rule = |requires: &[&str]| CorrelationRule {
+            name: "r".to_string(),
+            requires: requires.iter().map(|r| r.to_string()).collect(),
+            emit: CorrelationEmit {
+                finding_type: correlated_threat(),
+                severity: Severity::High,
+            },
+        };
+        assert!(rule(&["a", "b|c"]).is_valid());
+        assert!(!rule(&[]).is_valid());
+        assert!(!rule(&["a", "b|"]).is_valid());
+        assert!(default_rules().iter().all(CorrelationRule::is_valid));
+    }
+}
@@ -5,20 +5,128 @@
 //! - Audio steganography indicators
 //! - Microphone access patterns
 //! - Sound-based data exfiltration
+//! - Near-ultrasonic energy in WAV recordings (FFT over sample windows)
+//! - FSK and morse-like on/off keying in generated Web Audio tone sequences
 
+use super::filetype::{sniff_file, FileKind};
 use crate::skills::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, read_text_lossy, schema, tag_attack_techniques,
+    AttackMapping, Explanation, Explanations, Finding, ScanParams, Severity, Skill, SkillError,
+    SkillOutput, SkillResult,
 };
+use hound::{SampleFormat, WavReader};
 use regex::Regex;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
 use serde_json::{json, Value};
 use std::fs;
 use std::path::Path;
-use walkdir::WalkDir;
+
+/// ATT&CK techniques per finding type
+const ATTACK_TECHNIQUES: AttackMapping = &[
+    ("microphone_access", &["T1123"]),
+    ("ultrasonic_frequency", &["T1011"]),
+    ("ultrasonic_content", &["T1011"]),
+    ("audio_anomaly", &["T1027.003"]),
+    ("audio_covert_modulation", &["T1011"]),
+];
+
+/// Lower edge of the near-ultrasonic band used for covert audio channels
+const ULTRASONIC_MIN_HZ: f32 = 18_000.0;
+
+/// FFT window length in frames
+const FFT_SIZE: usize = 4096;
+
+/// Windows analyzed per file, spread evenly across the recording
+const MAX_FFT_WINDOWS: usize = 128;
+
+/// Ultrasonic peak level (dBFS) that counts as deliberate content
+const ULTRASONIC_DB_THRESHOLD: f32 = -50.0;
+
+/// Share of total spectral energy that must sit above `ULTRASONIC_MIN_HZ`
+const ULTRASONIC_ENERGY_RATIO: f32 = 0.01;
+
+/// Scheduled tone or gain changes needed before a sequence is judged
+const MIN_KEYING_EVENTS: usize = 8;
+
+/// Most distinct tones an FSK alphabet uses; melodies use more
+const MAX_FSK_TONES: usize = 4;
+
+/// Share of consecutive events that must switch tone or on/off state
+const MIN_SWITCH_RATIO: f32 = 0.6;
+
+/// Longest gap, in keying units, that morse timing uses (word space)
+const MAX_MORSE_UNITS: f32 = 7.0;
+
+/// How far a gap may sit from a whole number of units and still count
+const UNIT_TOLERANCE: f32 = 0.15;
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "audio_anomaly",
+        "An audio file whose structure or content is unusual: long runs of digital silence, \
+         malformed headers, or sample data that does not look like recorded sound.",
+        "Data can be hidden in audio that still plays back, and odd framing is a common side \
+         effect of tools that embed or transmit payloads through sound.",
+        "Synthesized test tones, generated silence padding, and files written by lightweight \
+         encoders or game engines.",
+        "Play the file and compare it with a known-good copy from the original source. Delete it \
+         if nobody on the team recognizes where it came from.",
+    ),
+    Explanation::new(
+        "audio_covert_modulation",
+        "Code that schedules a tone sequence which switches rapidly between a few fixed \
+         frequencies (frequency-shift keying) or turns a tone on and off on a regular beat \
+         (morse-like keying).",
+        "That is how data is modulated onto sound. A page or program that plays it can signal to \
+         a nearby device or smuggle data out over a speaker without touching the network.",
+        "Modem and fax emulators, morse trainers, ham radio tools, and alarm or notification \
+         sounds built from two alternating tones.",
+        "Decode the sequence (the finding lists the tones and timing) and check what data drives \
+         it. Remove keying code that has no documented purpose, especially next to data \
+         collection.",
+    ),
+    Explanation::new(
+        "microphone_access",
+        "Code that opens the microphone or an audio capture stream.",
+        "Silent recording is a standard spyware capability, and capture combined with a network \
+         client is how audio gets exfiltrated.",
+        "Voice chat, dictation, video conferencing, and audio tooling that legitimately records.",
+        "Confirm the feature needs the microphone and that capture only starts on an explicit \
+         user action. Remove the capture code if nothing in the product uses it.",
+    ),
+    Explanation::new(
+        "ultrasonic_content",
+        "Audio with a significant share of its energy above 18 kHz, beyond what most adults can \
+         hear.",
+        "Near-ultrasonic tones carry data between devices without anyone noticing. Ad trackers \
+         and air-gap exfiltration research both use them.",
+        "High-resolution music masters, instrument samples, and test signals made for measurement \
+         equipment.",
+        "Look at a spectrogram of the file. If the high band holds structured tones rather than \
+         harmonics of the audible content, treat the file as a covert channel and find out what \
+         emits it.",
+    ),
+    Explanation::new(
+        "ultrasonic_frequency",
+        "Code that generates or listens for tones at near-ultrasonic frequencies, such as \
+         oscillators or filters tuned above 18 kHz.",
+        "This is the sending or receiving side of an inaudible data channel between devices.",
+        "Hearing tests, pest-repeller apps, audio plugin DSP, and proximity pairing features that \
+         are disclosed to users.",
+        "Check whether the feature is documented and opt-in. Undisclosed ultrasonic beacons \
+         should be removed.",
+    ),
+];
 
 pub struct AudioDetector {
     audio_api_regex: Regex,
     frequency_regex: Regex,
     mic_regex: Regex,
+    frequency_schedule_regex: Regex,
+    tone_array_regex: Regex,
+    gain_schedule_regex: Regex,
 }
 
 impl AudioDetector {
@@ -34,6 +142,18 @@ impl AudioDetector {
             mic_regex: Regex::new(
                 r"(?i)\b(microphone|audio.*input|record.*audio|MediaRecorder)\b"
             ).unwrap(),
+            // osc.frequency.setValueAtTime(1200, t) / osc.frequency.value = 1200
+            frequency_schedule_regex: Regex::new(
+                r"\bfrequency\s*(?:\.\s*(?:setValueAtTime|linearRampToValueAtTime|exponentialRampToValueAtTime|setTargetAtTime)\s*\(|\.\s*value\s*=)\s*(\d+(?:\.\d+)?)"
+            ).unwrap(),
+            // Literal tone tables: [1200, 2200, 2200, 1200, ...]
+            tone_array_regex: Regex::new(
+                r"\[\s*(\d{2,5}(?:\.\d+)?(?:\s*,\s*\d{2,5}(?:\.\d+)?){7,})\s*,?\s*\]"
+            ).unwrap(),
+            // gain.gain.setValueAtTime(1, t + 0.3): level and time expression
+            gain_schedule_regex: Regex::new(
+                r"\bgain\s*\.\s*(?:setValueAtTime|linearRampToValueAtTime)\s*\(\s*(\d+(?:\.\d+)?)\s*,\s*([^)]*)\)"
+            ).unwrap(),
         }
     }
 
@@ -115,6 +235,69 @@ impl AudioDetector {
         findings
     }
 
+    /// Detect FSK or on/off keying in tone sequences scheduled through Web Audio
+    fn detect_covert_modulation(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        if !self.audio_api_regex.is_match(content) {
+            return findings;
+        }
+
+        let scheduled: Vec<f32> = self
+            .frequency_schedule_regex
+            .captures_iter(content)
+            .filter_map(|c| c[1].parse().ok())
+            .collect();
+        let mut sequences = vec![scheduled];
+        // Tone tables only count when something schedules frequencies
+        if !sequences[0].is_empty() {
+            sequences.extend(self.tone_array_regex.captures_iter(content).map(|c| {
+                c[1].split(',').filter_map(|v| v.trim().parse().ok()).collect()
+            }));
+        }
+        if let Some(value) = sequences.iter().find_map(|tones| fsk_signature(tones)) {
+            findings.push(Finding {
+                finding_type: "audio_covert_modulation".to_string(),
+                value,
+                confidence: 0.75,
+                location: path.display().to_string(),
+                severity: Severity::Medium,
+                metadata: json!({
+                    "pattern": "Frequency-shift keying",
+                    "description": "Tone sequence alternates between a few discrete frequencies"
+                }),
+            });
+        }
+
+        let gates: Vec<(bool, Option<f32>)> = self
+            .gain_schedule_regex
+            .captures_iter(content)
+            .filter_map(|c| {
+                let level: f32 = c[1].parse().ok()?;
+                Some((level > 0.0, trailing_offset(&c[2])))
+            })
+            .collect();
+        if let Some(value) = on_off_signature(&gates) {
+            let morse = value["morse_timing"].as_bool().unwrap_or(false);
+            findings.push(Finding {
+                finding_type: "audio_covert_modulation".to_string(),
+                value,
+                confidence: if morse { 0.85 } else { 0.7 },
+                location: path.display().to_string(),
+                severity: Severity::Medium,
+                metadata: json!({
+                    "pattern": "On/off keying",
+                    "description": if morse {
+                        "Tone is gated on and off with morse-style 1/3/7 unit timing"
+                    } else {
+                        "Tone is gated on and off in a regular pattern"
+                    }
+                }),
+            });
+        }
+
+        findings
+    }
+
     /// Detect audio file manipulation
     fn detect_audio_manipulation(&self, path: &Path) -> Vec<Finding> {
         let mut findings = Vec::new();
@@ -171,40 +354,232 @@ impl AudioDetector {
         findings
     }
 
+    /// Decode a PCM/float WAV to normalized mono samples
+    fn read_wav_mono(path: &Path) -> Option<(hound::WavSpec, Vec<f32>)> {
+        let mut reader = WavReader::open(path).ok()?;
+        let spec = reader.spec();
+        let channels = spec.channels as usize;
+        if channels == 0 {
+            return None;
+        }
+
+        let interleaved: Vec<f32> = match spec.sample_format {
+            SampleFormat::Int if (8..=32).contains(&spec.bits_per_sample) => {
+                let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .samples::<i32>()
+                    .map_while(Result::ok)
+                    .map(|s| s as f32 / scale)
+                    .collect()
+            }
+            SampleFormat::Float => reader.samples::<f32>().map_while(Result::ok).collect(),
+            SampleFormat::Int => return None,
+        };
+
+        let mono = interleaved
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect();
+        Some((spec, mono))
+    }
+
+    /// Flag WAV files with significant energy above 18 kHz
+    fn detect_ultrasonic_content(&self, path: &Path) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        let Some((spec, mono)) = Self::read_wav_mono(path) else {
+            return findings;
+        };
+
+        // Telephony-rate files can't carry anything above Nyquist
+        let nyquist = spec.sample_rate as f32 / 2.0;
+        if nyquist <= ULTRASONIC_MIN_HZ || mono.len() < FFT_SIZE {
+            return findings;
+        }
+
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(FFT_SIZE);
+        let hann: Vec<f32> = (0..FFT_SIZE)
+            .map(|i| {
+                0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FFT_SIZE as f32).cos()
+            })
+            .collect();
+        let window_gain: f32 = hann.iter().sum();
+        let bin_hz = spec.sample_rate as f32 / FFT_SIZE as f32;
+        let first_ultrasonic_bin = (ULTRASONIC_MIN_HZ / bin_hz).ceil() as usize;
+
+        let stride = (mono.len() / MAX_FFT_WINDOWS).max(FFT_SIZE);
+        let mut total_power = 0.0f64;
+        let mut ultrasonic_power = 0.0f64;
+        let mut peak = (0usize, 0.0f32);
+        let mut windows = 0;
+        let mut buffer = vec![Complex::new(0.0f32, 0.0); FFT_SIZE];
+
+        for start in (0..=mono.len() - FFT_SIZE).step_by(stride).take(MAX_FFT_WINDOWS) {
+            for (slot, (sample, w)) in buffer.iter_mut().zip(mono[start..].iter().zip(&hann)) {
+                *slot = Complex::new(sample * w, 0.0);
+            }
+            fft.process(&mut buffer);
+            windows += 1;
+
+            // Skip DC; only the first half of the spectrum is unique
+            for (bin, value) in buffer.iter().enumerate().take(FFT_SIZE / 2).skip(1) {
+                let magnitude = value.norm();
+                let power = f64::from(magnitude) * f64::from(magnitude);
+                total_power += power;
+                if bin >= first_ultrasonic_bin {
+                    ultrasonic_power += power;
+                    if magnitude > peak.1 {
+                        peak = (bin, magnitude);
+                    }
+                }
+            }
+        }
+
+        if total_power <= 0.0 || peak.1 <= 0.0 {
+            return findings;
+        }
+
+        let ratio = (ultrasonic_power / total_power) as f32;
+        let peak_db = 20.0 * (2.0 * peak.1 / window_gain).log10();
+        let peak_hz = peak.0 as f32 * bin_hz;
+
+        if peak_db >= ULTRASONIC_DB_THRESHOLD && ratio >= ULTRASONIC_ENERGY_RATIO {
+            findings.push(Finding {
+                finding_type: "ultrasonic_content".to_string(),
+                value: json!({
+                    "peak_frequency_hz": peak_hz.round(),
+                    "peak_db": (peak_db * 10.0).round() / 10.0,
+                    "ultrasonic_energy_ratio": ratio,
+                    "sample_rate": spec.sample_rate,
+                    "channels": spec.channels,
+                    "bits_per_sample": spec.bits_per_sample,
+                    "windows_analyzed": windows
+                }),
+                confidence: if ratio >= 0.1 { 0.9 } else { 0.75 },
+                location: path.display().to_string(),
+                severity: Severity::High,
+                metadata: json!({
+                    "pattern": "Near-ultrasonic audio content",
+                    "description": format!(
+                        "Energy peak at {:.0} Hz ({:.1} dBFS); {:.1}% of energy above 18 kHz",
+                        peak_hz,
+                        peak_db,
+                        ratio * 100.0
+                    )
+                }),
+            });
+        }
+
+        findings
+    }
+
     /// Analyze a single file
-    fn analyze_file(&self, path: &Path) -> Vec<Finding> {
+    fn analyze_file(&self, path: &Path, max_bytes: u64, analyze_audio: bool) -> Vec<Finding> {
         let mut findings = Vec::new();
 
         // Check audio files for anomalies
-        findings.extend(self.detect_audio_manipulation(path));
+        if analyze_audio {
+            findings.extend(self.detect_audio_manipulation(path));
+            if matches!(sniff_file(path), Ok(FileKind::Wav)) {
+                findings.extend(self.detect_ultrasonic_content(path));
+            }
+        }
 
         // Check code files for audio API usage
-        if let Ok(content) = fs::read_to_string(path) {
-            findings.extend(self.detect_ultrasonic(path, &content));
-            findings.extend(self.detect_mic_access(path, &content));
+        if let Ok(content) = read_text_lossy(path, max_bytes) {
+            findings.extend(self.analyze_text(path, &content));
         }
 
         findings
     }
 
-    /// Analyze a directory
-    fn analyze_directory(&self, path: &Path, recursive: bool) -> Vec<Finding> {
+    /// Run the source-code checks over `content`, reported against `path`
+    fn analyze_text(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
+        findings.extend(self.detect_ultrasonic(path, content));
+        findings.extend(self.detect_mic_access(path, content));
+        findings.extend(self.detect_covert_modulation(path, content));
 
-        let walker = if recursive {
-            WalkDir::new(path)
-        } else {
-            WalkDir::new(path).max_depth(1)
-        };
+        findings
+    }
+}
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                findings.extend(self.analyze_file(entry.path()));
-            }
-        }
+/// FSK summary when `tones` switches often between a small set of
+/// frequencies, each used more than once
+fn fsk_signature(tones: &[f32]) -> Option<Value> {
+    if tones.len() < MIN_KEYING_EVENTS {
+        return None;
+    }
+    let mut distinct: Vec<u32> = tones.iter().map(|f| f.round() as u32).collect();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if !(2..=MAX_FSK_TONES).contains(&distinct.len()) {
+        return None;
+    }
+    let uses = |tone: u32| tones.iter().filter(|f| f.round() as u32 == tone).count();
+    if distinct.iter().any(|&tone| uses(tone) < 2) {
+        return None;
+    }
 
-        findings
+    let changes = tones.windows(2).filter(|w| w[0].round() != w[1].round()).count();
+    let ratio = changes as f32 / (tones.len() - 1) as f32;
+    (ratio >= MIN_SWITCH_RATIO).then(|| {
+        json!({
+            "scheme": "fsk",
+            "tones": distinct,
+            "events": tones.len(),
+            "tone_changes": changes,
+            "switch_ratio": ratio
+        })
+    })
+}
+
+/// On/off keying summary when gain events mostly alternate; timing is
+/// checked against morse's 1/3/7 unit gaps when every event has a literal
+/// time offset
+fn on_off_signature(gates: &[(bool, Option<f32>)]) -> Option<Value> {
+    if gates.len() < MIN_KEYING_EVENTS {
+        return None;
+    }
+    let toggles = gates.windows(2).filter(|w| w[0].0 != w[1].0).count();
+    let ratio = toggles as f32 / (gates.len() - 1) as f32;
+    if ratio < MIN_SWITCH_RATIO {
+        return None;
+    }
+
+    let times: Option<Vec<f32>> = gates.iter().map(|&(_, t)| t).collect();
+    let gaps: Vec<f32> = times
+        .map(|t| t.windows(2).map(|w| w[1] - w[0]).filter(|&g| g > 0.0).collect())
+        .unwrap_or_default();
+    let unit = gaps.iter().copied().fold(f32::INFINITY, f32::min);
+    let morse = gaps.len() + 1 >= MIN_KEYING_EVENTS
+        && gaps.iter().all(|&gap| {
+            let units = gap / unit;
+            units <= MAX_MORSE_UNITS + UNIT_TOLERANCE
+                && (units - units.round()).abs() <= UNIT_TOLERANCE
+        })
+        && gaps.iter().any(|&gap| gap / unit > 1.5);
+
+    Some(json!({
+        "scheme": "on_off_keying",
+        "events": gates.len(),
+        "toggles": toggles,
+        "switch_ratio": ratio,
+        "morse_timing": morse,
+        "unit_seconds": morse.then_some(unit)
+    }))
+}
+
+/// Trailing numeric offset of a time expression like `t + 0.35` or `1.2`
+fn trailing_offset(expr: &str) -> Option<f32> {
+    let expr = expr.trim();
+    let start = expr
+        .rfind(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map_or(0, |i| i + 1);
+    if start > 0 && !expr[..start].trim_end().ends_with('+') {
+        return None;
     }
+    expr[start..].parse().ok()
 }
 
 impl Default for AudioDetector {
@@ -220,7 +595,8 @@ impl Skill for AudioDetector {
 
     fn description(&self) -> &str {
         "Detects audio-based covert channels including ultrasonic communication, \
-         microphone access patterns, and audio file anomalies."
+         microphone access patterns, near-ultrasonic energy in WAV files, \
+         and audio file anomalies."
     }
 
     fn schema(&self) -> Value {
@@ -247,22 +623,182 @@ impl Skill for AudioDetector {
             )));
         }
 
-        let findings = if path.is_file() {
-            self.analyze_file(path)
-        } else {
-            self.analyze_directory(path, scan_params.recursive)
-        };
+        let analyze_audio = params
+            .get("analyze_audio_files")
+            .and_then(Value::as_bool)
+            .unwrap_or(true);
+
+        let max_bytes = scan_params.max_file_bytes();
+        let (findings, stats) =
+            scan_params.scan_files(|file| self.analyze_file(file, max_bytes, analyze_audio))?;
 
         let threshold = self.confidence_threshold();
-        let filtered: Vec<Finding> = findings
+        let mut filtered: Vec<Finding> = findings
             .into_iter()
             .filter(|f| f.confidence >= threshold)
             .collect();
 
-        Ok(SkillOutput::with_findings(filtered))
+        tag_attack_techniques(&mut filtered, self.attack_techniques());
+
+        Ok(SkillOutput::with_findings_and_stats(filtered, stats))
+    }
+
+    fn attack_techniques(&self) -> AttackMapping {
+        ATTACK_TECHNIQUES
+    }
+
+    fn analyze_content(&self, name: &str, content: &str) -> SkillResult<Vec<Finding>> {
+        let threshold = self.confidence_threshold();
+        let mut findings = self.analyze_text(Path::new(name), content);
+        findings.retain(|f| f.confidence >= threshold);
+        tag_attack_techniques(&mut findings, self.attack_techniques());
+        Ok(findings)
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
     }
 
     fn categories(&self) -> Vec<&str> {
         vec!["audio", "covert_channel", "exfiltration"]
     }
+
+    /// Audio files and source code; images, archives and executables carry
+    /// neither samples nor audio API calls
+    fn applies_to(&self, _path: &Path, kind: FileKind) -> bool {
+        matches!(
+            kind,
+            FileKind::Unknown | FileKind::Wav | FileKind::Mp3 | FileKind::Ogg | FileKind::Flac
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::{WavSpec, WavWriter};
+
+    fn write_tone(path: &Path, spec: WavSpec, freq: f32, amplitude: f32) {
+        let mut writer = WavWriter::create(path, spec).unwrap();
+        let full_scale = ((1i64 << (spec.bits_per_sample - 1)) - 1) as f32;
+        for n in 0..spec.sample_rate / 2 {
+            let t = n as f32 / spec.sample_rate as f32;
+            let wave = (2.0 * std::f32::consts::PI * freq * t).sin();
+            let sample = (amplitude * wave * full_scale) as i32;
+            for _ in 0..spec.channels {
+                writer.write_sample(sample).unwrap();
+            }
+        }
+        writer.finalize().unwrap();
+    }
+
+    fn spec(sample_rate: u32, channels: u16, bits_per_sample: u16) -> WavSpec {
+        WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample,
+            sample_format: SampleFormat::Int,
+        }
+    }
+
+    #[test]
+    fn test_ultrasonic_tone_detected_in_stereo_16bit() {
+        let detector = AudioDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+        let wav = dir.path().join("beacon.wav");
+        write_tone(&wav, spec(44_100, 2, 16), 19_500.0, 0.1);
+
+        let findings = detector.detect_ultrasonic_content(&wav);
+        assert_eq!(findings.len(), 1);
+        let peak = findings[0].value["peak_frequency_hz"].as_f64().unwrap();
+        assert!((peak - 19_500.0).abs() < 20.0, "peak at {}", peak);
+        let db = findings[0].value["peak_db"].as_f64().unwrap();
+        assert!((db + 20.0).abs() < 1.5, "level {}", db);
+        assert_eq!(findings[0].value["channels"], 2);
+    }
+
+    /// Bell 202-style FSK beacon and a morse sender, the way covert audio
+    /// channels are usually written for the browser
+    const KEYING_FIXTURE: &str = r#"
+const ctx = new AudioContext();
+const osc = ctx.createOscillator();
+const gain = ctx.createGain();
+osc.connect(gain).connect(ctx.destination);
+const t = ctx.currentTime;
+osc.frequency.setValueAtTime(1200, t + 0.00);
+osc.frequency.setValueAtTime(2200, t + 0.01);
+osc.frequency.setValueAtTime(1200, t + 0.02);
+osc.frequency.setValueAtTime(1200, t + 0.03);
+osc.frequency.setValueAtTime(2200, t + 0.04);
+osc.frequency.setValueAtTime(1200, t + 0.05);
+osc.frequency.setValueAtTime(2200, t + 0.06);
+osc.frequency.setValueAtTime(2200, t + 0.07);
+osc.frequency.setValueAtTime(1200, t + 0.08);
+// "SOS": dot dot dot, dash dash dash, dot dot dot (unit 0.1s)
+gain.gain.setValueAtTime(1, t + 0.0);
+gain.gain.setValueAtTime(0, t + 0.1);
+gain.gain.setValueAtTime(1, t + 0.2);
+gain.gain.setValueAtTime(0, t + 0.3);
+gain.gain.setValueAtTime(1, t + 0.4);
+gain.gain.setValueAtTime(0, t + 0.5);
+gain.gain.setValueAtTime(1, t + 0.8);
+gain.gain.setValueAtTime(0, t + 1.1);
+gain.gain.setValueAtTime(1, t + 1.2);
+gain.gain.setValueAtTime(0, t + 1.5);
+osc.start();
+"#;
+
+    #[test]
+    fn test_fsk_and_morse_keying_detected() {
+        let detector = AudioDetector::new();
+        let findings = detector.detect_covert_modulation(Path::new("beacon.js"), KEYING_FIXTURE);
+        assert_eq!(findings.len(), 2);
+
+        let fsk = &findings[0].value;
+        assert_eq!(fsk["scheme"], "fsk");
+        assert_eq!(fsk["tones"], json!([1200, 2200]));
+        assert_eq!(fsk["events"], 9);
+
+        let ook = &findings[1].value;
+        assert_eq!(ook["scheme"], "on_off_keying");
+        assert_eq!(ook["morse_timing"], true);
+        assert!((ook["unit_seconds"].as_f64().unwrap() - 0.1).abs() < 1e-3);
+        assert!(findings[1].confidence > findings[0].confidence);
+    }
+
+    #[test]
+    fn test_melody_and_fades_not_keying() {
+        let detector = AudioDetector::new();
+        // Eight-note scale: too many distinct tones for FSK
+        let melody = "const o = ctx.createOscillator();\n\
+                      [262, 294, 330, 349, 392, 440, 494, 523].forEach((f, i) =>\n\
+                        o.frequency.setValueAtTime(f, t + i * 0.5));\n\
+                      o.frequency.value = 262;";
+        assert!(detector.detect_covert_modulation(Path::new("song.js"), melody).is_empty());
+
+        // A single fade in and out is not keying
+        let fade = "const g = ctx.createGain(); ctx.createOscillator();\n\
+                    g.gain.setValueAtTime(0, t); g.gain.linearRampToValueAtTime(1, t + 1);\n\
+                    g.gain.linearRampToValueAtTime(0, t + 3);";
+        assert!(detector.detect_covert_modulation(Path::new("fade.js"), fade).is_empty());
+    }
+
+    #[test]
+    fn test_audible_and_low_rate_audio_not_flagged() {
+        let detector = AudioDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+
+        let audible = dir.path().join("tone.wav");
+        write_tone(&audible, spec(48_000, 1, 24), 440.0, 0.5);
+        assert!(detector.detect_ultrasonic_content(&audible).is_empty());
+
+        // 8 kHz telephony can't represent 18 kHz
+        let phone = dir.path().join("call.wav");
+        write_tone(&phone, spec(8_000, 1, 16), 3_000.0, 0.5);
+        assert!(detector.detect_ultrasonic_content(&phone).is_empty());
+    }
 }
@@ -1,47 +1,231 @@
 //! Cipher Pattern Detector
 //!
 //! Detects cryptographic patterns that are UPDATE-PROOF:
-//! - Mathematical constant seeds (φ, π, e, √2, etc.)
+//! - Mathematical constant seeds (φ, π, e, √2, etc.) as scaled decimal or
+//!   hex integers, or as float literals
 //! - Power-of-2 grid structures
 //! - Self-referencing hash patterns
 //! - GUID modular correlations
 //! - Low-discrepancy sequence indicators
+//! - Substitution boxes: 256-byte tables that permute 0..=255, and the
+//!   AES and Blowfish tables
 
 use crate::skills::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, offset_to_line_col, read_text_lossy, schema, Explanation,
+    Explanations, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
 };
 use regex::Regex;
 use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::fs;
+use std::ops::Range;
 use std::path::Path;
-use walkdir::WalkDir;
+
+/// Files larger than this skip the self-referencing hash check by default;
+/// each candidate hash means re-hashing the whole file
+const DEFAULT_SELF_REFERENCE_MAX_BYTES: usize = 1024 * 1024;
+
+/// At most this many hash candidates per algorithm are verified per file
+const MAX_SELF_REFERENCE_CANDIDATES: usize = 256;
+
+/// What a self-referencing file holds in place of its own digest while
+/// the digest is computed, as long as the longest supported hash
+const HASH_PLACEHOLDER: [u8; 64] = [b'0'; 64];
+
+/// Substitution boxes reported per file
+const MAX_SBOXES: usize = 8;
+
+/// Opening bytes of well-known byte S-boxes: (kind, first eight entries)
+const KNOWN_SBOX_PREFIXES: &[(&str, [u8; 8])] = &[
+    ("aes_forward", [0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5]),
+    ("aes_inverse", [0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38]),
+];
 
 /// Mathematical constants used as cipher seeds
 const KNOWN_CONSTANTS: &[(&str, f64)] = &[
-    ("phi", 1.6180339887498948482),
-    ("phi_minus_1", 0.6180339887498948482),
-    ("pi", 3.1415926535897932385),
-    ("e", 2.7182818284590452354),
-    ("sqrt2", 1.4142135623730950488),
-    ("sqrt3", 1.7320508075688772935),
-    ("sqrt5", 2.2360679774997896964),
-    ("ln2", 0.6931471805599453094),
-    ("ln10", 2.3025850929940456840),
-    ("euler_gamma", 0.5772156649015328606),
+    ("phi", 1.618_033_988_749_895),
+    ("phi_minus_1", 0.618_033_988_749_895),
+    ("pi", std::f64::consts::PI),
+    ("e", std::f64::consts::E),
+    ("sqrt2", std::f64::consts::SQRT_2),
+    ("sqrt3", 1.732_050_807_568_877_2),
+    ("sqrt5", 2.236_067_977_499_79),
+    ("ln2", std::f64::consts::LN_2),
+    ("ln10", std::f64::consts::LN_10),
+    ("euler_gamma", 0.577_215_664_901_532_9),
 ];
 
 /// Scales used to convert constants to integers
 const SCALES: &[f64] = &[1e3, 1e6, 1e7, 1e8, 1e9, 1e10, 1e12];
 
+/// Hex literals below this are too short to tell a scaled constant from
+/// noise (the decimal form needs six digits too)
+const MIN_HEX_SEED: u64 = 100_000;
+
+/// Built-in constants and scales plus any passed via `extra_constants`
+/// and `extra_scales`
+struct ConstantTables {
+    constants: Vec<(String, f64)>,
+    scales: Vec<f64>,
+}
+
+impl ConstantTables {
+    fn builtin() -> Self {
+        Self {
+            constants: KNOWN_CONSTANTS
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect(),
+            scales: SCALES.to_vec(),
+        }
+    }
+
+    /// Merge the per-call tables from `params` into the built-in ones
+    fn from_params(params: &Value) -> SkillResult<Self> {
+        let mut tables = Self::builtin();
+
+        if let Some(extra) = params.get("extra_constants") {
+            let entries = extra.as_array().ok_or_else(|| {
+                SkillError::InvalidParams("extra_constants must be an array".to_string())
+            })?;
+            for entry in entries {
+                let name = entry.get("name").and_then(Value::as_str);
+                let value = entry.get("value").and_then(Value::as_f64);
+                match (name, value) {
+                    (Some(name), Some(value)) if value.is_finite() => {
+                        tables.constants.push((name.to_string(), value));
+                    }
+                    _ => {
+                        return Err(SkillError::InvalidParams(format!(
+                            "extra_constants entries need a name and a finite value, got {}",
+                            entry
+                        )))
+                    }
+                }
+            }
+        }
+
+        if let Some(extra) = params.get("extra_scales") {
+            let entries = extra.as_array().ok_or_else(|| {
+                SkillError::InvalidParams("extra_scales must be an array".to_string())
+            })?;
+            for entry in entries {
+                match entry.as_f64() {
+                    Some(scale) if scale.is_finite() && scale > 0.0 => tables.scales.push(scale),
+                    _ => {
+                        return Err(SkillError::InvalidParams(format!(
+                            "extra_scales must be finite positive numbers, got {}",
+                            entry
+                        )))
+                    }
+                }
+            }
+        }
+
+        Ok(tables)
+    }
+}
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "cipher_hint_identifier",
+        "An identifier or string that names a classical or custom cipher, such as Bacon, \
+         Vigenere, or a hand-rolled substitution.",
+        "Malware and puzzle-style covert channels often use simple ciphers to hide configuration, \
+         and the names survive in variable and function names.",
+        "Cryptography coursework, CTF tooling, and libraries that implement these ciphers on \
+         purpose.",
+        "Find what the cipher protects. Data that needs confidentiality should use a vetted \
+         algorithm from a maintained library, and hidden configuration should be decoded and \
+         reviewed.",
+    ),
+    Explanation::new(
+        "guid_modular_correlation",
+        "A set of GUIDs where more than 30% reduce to the same value modulo 64, 256, 1024, or \
+         131072.",
+        "Random GUIDs spread evenly over every modulus. Clustering means they were chosen rather \
+         than generated, which is how identifiers get used to index a hidden lookup table or to \
+         carry a covert signal.",
+        "Fixtures that reuse one GUID with a changed suffix, sequential GUIDs from a database, \
+         and very small samples where chance clustering is likely.",
+        "Check how the GUIDs were produced. Regenerate them with a proper random source if they \
+         should be random, and trace any code that reduces GUIDs by the flagged modulus.",
+    ),
+    Explanation::new(
+        "math_constant_seed",
+        "A well-known mathematical constant such as pi, e, or the golden ratio used as a numeric \
+         literal, often scaled to an integer.",
+        "Constants like these seed low-discrepancy sequences and key schedules, and appear where \
+         code derives positions or keys deterministically to hide data.",
+        "Geometry, physics, and graphics code, and hash functions that use these constants by \
+         design, like the golden-ratio multiplier in Fibonacci hashing.",
+        "Check that the constant is used for arithmetic and not to derive offsets or keys. \
+         Document intentional uses next to the literal.",
+    ),
+    Explanation::new(
+        "power2_grid",
+        "Arrays or dimensions laid out as powers of two in a grid, such as 256x256 or 1024 cells.",
+        "Steganographic and lookup-table schemes place payload bits on power-of-two grids so \
+         positions can be computed with shifts and masks.",
+        "Texture atlases, FFT buffers, hash tables, and most image processing code.",
+        "Only worth pursuing together with other cipher findings in the same file. Check what \
+         indexes the grid and where its contents come from.",
+    ),
+    Explanation::new(
+        "substitution_box",
+        "A lookup table of 256 byte values that is a permutation of 0-255, or the start of a \
+         well-known cipher table such as the AES or Blowfish S-boxes.",
+        "A byte permutation is the core of a substitution cipher. Finding one in source means \
+         the code carries its own cipher rather than calling a crypto library, which is how \
+         malware hides strings and configuration.",
+        "Self-contained crypto implementations in embedded or no-dependency code, test vectors, \
+         and shuffled lookup tables in games and hashing (Pearson hashing uses one).",
+        "Find what the table encrypts or decodes. Replace hand-rolled ciphers with a maintained \
+         library, and decode any data they protect for review.",
+    ),
+    Explanation::new(
+        "self_referencing_hash",
+        "A file that contains a hash of its own contents, or of a region of itself.",
+        "Self-verifying payloads use this to detect tampering or to unlock only when unmodified, \
+         which is a common anti-analysis trick.",
+        "Build manifests, lockfiles, and integrity-checked archives that list digests of their \
+         own sections.",
+        "Confirm the hash belongs to a known integrity mechanism. If nothing in the build writes \
+         it, find the code that reads it.",
+    ),
+    Explanation::new(
+        "sequence_indicator",
+        "Code that generates a quasi-random sequence (Halton, Sobol, Weyl) or uses the constants \
+         that define one.",
+        "Low-discrepancy sequences give evenly spread positions that are reproducible from a \
+         seed. This suits scattering hidden bits through a carrier.",
+        "Monte Carlo simulation, sampling in rendering, and numerical libraries.",
+        "Check what the sequence indexes. If it picks byte or pixel positions in files or images, \
+         review it as a possible embedding routine.",
+    ),
+];
+
+/// A numeric literal that may encode a constant
+#[derive(Debug, Clone, Copy)]
+enum ConstantLiteral {
+    /// Decimal or hex integer: a constant times one of the scales
+    Scaled(u64),
+    /// Float literal and its number of fractional digits
+    Float(f64, usize),
+}
+
 /// Cipher pattern detector
 pub struct CipherDetector {
     number_regex: Regex,
+    float_regex: Regex,
+    hex_regex: Regex,
     dimension_regex: Regex,
     md5_regex: Regex,
     sha256_regex: Regex,
     guid_regex: Regex,
+    byte_table_regex: Regex,
+    blowfish_regex: Regex,
     sequence_keywords: HashMap<&'static str, &'static str>,
 }
 
@@ -56,6 +240,8 @@ impl CipherDetector {
 
         Self {
             number_regex: Regex::new(r"\b(\d{6,12})\b").unwrap(),
+            float_regex: Regex::new(r"\b\d{1,3}\.(\d{5,17})\b").unwrap(),
+            hex_regex: Regex::new(r"\b0[xX]([0-9a-fA-F]{5,16})\b").unwrap(),
             dimension_regex: Regex::new(r"(\d+)\s*[xX×]\s*(\d+)(?:\s*[xX×]\s*(\d+))?").unwrap(),
             md5_regex: Regex::new(r"\b([0-9a-fA-F]{32})\b").unwrap(),
             sha256_regex: Regex::new(r"\b([0-9a-fA-F]{64})\b").unwrap(),
@@ -63,14 +249,24 @@ impl CipherDetector {
                 r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}",
             )
             .unwrap(),
+            // Eight or more comma-separated byte literals (decimal or 0xNN)
+            byte_table_regex: Regex::new(
+                r"(?:(?:0[xX][0-9a-fA-F]{1,2}|\d{1,3})\b[uU]?\s*,\s*){7,}(?:0[xX][0-9a-fA-F]{1,2}|\d{1,3})\b"
+            )
+            .unwrap(),
+            // Start of the Blowfish P-array (pi's hex digits) or first S-box
+            blowfish_regex: Regex::new(
+                r"(?i)0x243f6a88\s*,\s*0x85a308d3\s*,\s*0x13198a2e|0xd1310ba6\s*,\s*0x98dfb5ac\s*,\s*0x2ffd72db"
+            )
+            .unwrap(),
             sequence_keywords,
         }
     }
 
     /// Check if a number is a scaled mathematical constant
-    fn check_constant(&self, value: u64) -> Option<(&str, f64, f64)> {
-        for (name, const_val) in KNOWN_CONSTANTS {
-            for &scale in SCALES {
+    fn check_constant(tables: &ConstantTables, value: u64) -> Option<(&str, f64, f64)> {
+        for (name, const_val) in &tables.constants {
+            for &scale in &tables.scales {
                 let expected = (*const_val * scale) as u64;
                 let tolerance = (scale / 1000.0) as u64;
 
@@ -84,35 +280,119 @@ impl CipherDetector {
         None
     }
 
+    /// Check if a float is a constant written out to its precision; more
+    /// digits make a coincidence less likely
+    fn check_float(tables: &ConstantTables, value: f64, digits: usize) -> Option<(&str, f64)> {
+        let tolerance = 10f64.powi(-(digits as i32));
+        tables
+            .constants
+            .iter()
+            .find(|(_, const_val)| (value - const_val).abs() <= tolerance)
+            .map(|(name, _)| (name.as_str(), (0.4 + 0.05 * digits as f64).min(0.95)))
+    }
+
+    /// Constant, scale (1 for floats) and confidence for any literal form
+    fn check_literal(
+        tables: &ConstantTables,
+        literal: ConstantLiteral,
+    ) -> Option<(&str, f64, f64)> {
+        match literal {
+            ConstantLiteral::Scaled(value) => Self::check_constant(tables, value),
+            ConstantLiteral::Float(value, digits) => Self::check_float(tables, value, digits)
+                .map(|(name, confidence)| (name, 1.0, confidence)),
+        }
+    }
+
+    /// Decimal, hex and float literals in `content`, in source order, with
+    /// their encoding and source text
+    fn constant_literals<'a>(
+        &self,
+        content: &'a str,
+    ) -> Vec<(&'static str, &'a str, ConstantLiteral)> {
+        let mut literals = Vec::new();
+        let mut float_spans = Vec::new();
+
+        for cap in self.float_regex.captures_iter(content) {
+            let whole = cap.get(0).unwrap();
+            float_spans.push(whole.range());
+            if let Ok(value) = whole.as_str().parse::<f64>() {
+                let literal = ConstantLiteral::Float(value, cap[1].len());
+                literals.push((whole.start(), "float", whole.as_str(), literal));
+            }
+        }
+        for cap in self.number_regex.captures_iter(content) {
+            let m = cap.get(1).unwrap();
+            // The fraction of a float is not an integer of its own
+            if float_spans.iter().any(|span| span.contains(&m.start())) {
+                continue;
+            }
+            if let Ok(value) = m.as_str().parse::<u64>() {
+                literals.push((m.start(), "decimal", m.as_str(), ConstantLiteral::Scaled(value)));
+            }
+        }
+        for cap in self.hex_regex.captures_iter(content) {
+            let whole = cap.get(0).unwrap();
+            match u64::from_str_radix(&cap[1], 16) {
+                Ok(value) if value >= MIN_HEX_SEED => {
+                    let literal = ConstantLiteral::Scaled(value);
+                    literals.push((whole.start(), "hex", whole.as_str(), literal));
+                }
+                _ => {}
+            }
+        }
+
+        literals.sort_by_key(|&(start, ..)| start);
+        literals
+            .into_iter()
+            .map(|(_, encoding, text, literal)| (encoding, text, literal))
+            .collect()
+    }
+
     /// Check if a number is a power of 2
     fn is_power_of_2(n: u64) -> bool {
         n > 0 && (n & (n - 1)) == 0
     }
 
     /// Detect mathematical constant seeds in text
-    fn detect_math_constants(&self, path: &Path, content: &str) -> Vec<Finding> {
+    fn detect_math_constants(
+        &self,
+        path: &Path,
+        content: &str,
+        tables: &ConstantTables,
+    ) -> Vec<Finding> {
         let mut findings = Vec::new();
 
-        for cap in self.number_regex.captures_iter(content) {
-            if let Ok(num) = cap[1].parse::<u64>() {
-                if let Some((const_name, scale, confidence)) = self.check_constant(num) {
-                    findings.push(Finding {
-                        finding_type: "math_constant_seed".to_string(),
-                        value: json!({
-                            "number": num,
-                            "constant": const_name,
-                            "scale": scale
-                        }),
-                        confidence: confidence as f32,
-                        location: path.display().to_string(),
-                        severity: Severity::High,
-                        metadata: json!({
-                            "pattern": "Mathematical constant used as seed",
-                            "description": format!("{} scaled by {}", const_name, scale)
-                        }),
-                    });
-                }
-            }
+        for (encoding, text, literal) in self.constant_literals(content) {
+            let Some((const_name, scale, confidence)) = Self::check_literal(tables, literal)
+            else {
+                continue;
+            };
+            let number = match literal {
+                ConstantLiteral::Scaled(value) => json!(value),
+                ConstantLiteral::Float(value, _) => json!(value),
+            };
+            findings.push(Finding {
+                finding_type: "math_constant_seed".to_string(),
+                value: json!({
+                    "number": number,
+                    "literal": text,
+                    "encoding": encoding,
+                    "constant": const_name,
+                    "scale": scale
+                }),
+                confidence: confidence as f32,
+                location: path.display().to_string(),
+                severity: Severity::High,
+                metadata: json!({
+                    "pattern": "Mathematical constant used as seed",
+                    "description": match literal {
+                        ConstantLiteral::Scaled(_) => {
+                            format!("{} scaled by {} ({} literal)", const_name, scale, encoding)
+                        }
+                        ConstantLiteral::Float(..) => format!("{} as a float literal", const_name),
+                    }
+                }),
+            });
         }
 
         findings
@@ -151,57 +431,98 @@ impl CipherDetector {
         findings
     }
 
+    /// Byte ranges of `regex` group 1, in order, skipping all-zero matches
+    /// (an unfilled placeholder is not a hash)
+    fn hash_spans(regex: &Regex, content: &str) -> Vec<Range<usize>> {
+        regex
+            .captures_iter(content)
+            .filter_map(|cap| cap.get(1))
+            .filter(|m| !m.as_str().bytes().all(|b| b == b'0'))
+            .map(|m| m.range())
+            .take(MAX_SELF_REFERENCE_CANDIDATES)
+            .collect()
+    }
+
+    /// `content` in three pieces: before `span`, a run of ASCII zeros as
+    /// long as `span`, and after it. Hashing these is hashing the file as
+    /// it was before the digest was written into its placeholder.
+    fn with_placeholder<'a>(content: &'a str, span: &Range<usize>) -> [&'a [u8]; 3] {
+        [
+            &content.as_bytes()[..span.start],
+            &HASH_PLACEHOLDER[..span.len()],
+            &content.as_bytes()[span.end..],
+        ]
+    }
+
+    /// MD5 of `content` with `span` zeroed, streamed piece by piece instead
+    /// of building the patched string
+    fn md5_with_placeholder(content: &str, span: &Range<usize>) -> String {
+        let mut context = md5::Context::new();
+        for piece in Self::with_placeholder(content, span) {
+            context.consume(piece);
+        }
+        format!("{:x}", context.compute())
+    }
+
+    /// SHA256 of `content` with `span` zeroed
+    fn sha256_with_placeholder(content: &str, span: &Range<usize>) -> String {
+        let mut hasher = Sha256::new();
+        for piece in Self::with_placeholder(content, span) {
+            hasher.update(piece);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
     /// Detect self-referencing hash patterns
-    fn detect_self_reference(&self, path: &Path, content: &str) -> Vec<Finding> {
+    ///
+    /// Each hash-shaped span is checked by zeroing just that span and
+    /// hashing the result, so other copies of the same string stay part of
+    /// the content. At most [`MAX_SELF_REFERENCE_CANDIDATES`] spans per
+    /// algorithm are checked, and files over `max_bytes` are skipped
+    /// entirely.
+    fn detect_self_reference(
+        &self,
+        path: &Path,
+        content: &str,
+        max_bytes: usize,
+    ) -> Vec<Finding> {
         let mut findings = Vec::new();
 
-        // Check MD5 hashes
-        for cap in self.md5_regex.captures_iter(content) {
-            let hash_val = &cap[1];
-            let content_without = content.replace(hash_val, "");
-            let computed = format!("{:x}", md5::compute(content_without.as_bytes()));
-
-            if computed.eq_ignore_ascii_case(hash_val) {
-                findings.push(Finding {
-                    finding_type: "self_referencing_hash".to_string(),
-                    value: json!({
-                        "hash": hash_val,
-                        "algorithm": "md5",
-                        "verified": true
-                    }),
-                    confidence: 0.99,
-                    location: path.display().to_string(),
-                    severity: Severity::Critical,
-                    metadata: json!({
-                        "pattern": "Self-referencing MD5 hash",
-                        "description": "File contains hash of itself (minus the hash)"
-                    }),
-                });
-            }
+        if content.len() > max_bytes {
+            return findings;
         }
 
-        // Check SHA256 hashes
-        for cap in self.sha256_regex.captures_iter(content) {
-            let hash_val = &cap[1];
-            let content_without = content.replace(hash_val, "");
-            let mut hasher = Sha256::new();
-            hasher.update(content_without.as_bytes());
-            let computed = format!("{:x}", hasher.finalize());
+        let candidates = Self::hash_spans(&self.md5_regex, content)
+            .into_iter()
+            .map(|span| (span, "md5"))
+            .chain(
+                Self::hash_spans(&self.sha256_regex, content)
+                    .into_iter()
+                    .map(|span| (span, "sha256")),
+            );
+
+        for (span, algorithm) in candidates {
+            let hash_val = &content[span.clone()];
+            let computed = match algorithm {
+                "md5" => Self::md5_with_placeholder(content, &span),
+                _ => Self::sha256_with_placeholder(content, &span),
+            };
 
             if computed.eq_ignore_ascii_case(hash_val) {
                 findings.push(Finding {
                     finding_type: "self_referencing_hash".to_string(),
                     value: json!({
                         "hash": hash_val,
-                        "algorithm": "sha256",
+                        "algorithm": algorithm,
+                        "offset": span.start,
                         "verified": true
                     }),
                     confidence: 0.99,
                     location: path.display().to_string(),
                     severity: Severity::Critical,
                     metadata: json!({
-                        "pattern": "Self-referencing SHA256 hash",
-                        "description": "File contains hash of itself (minus the hash)"
+                        "pattern": format!("Self-referencing {} hash", algorithm.to_uppercase()),
+                        "description": "File contains hash of itself (with the hash zeroed)"
                     }),
                 });
             }
@@ -318,40 +639,169 @@ impl CipherDetector {
         findings
     }
 
-    /// Analyze a single file
-    fn analyze_file(&self, path: &Path) -> Vec<Finding> {
+    /// Detect substitution boxes: a 256-entry window of a byte table that
+    /// is a permutation of 0..=255 (not just 0..=255 in order), tables that
+    /// open with the AES S-box bytes, and the Blowfish constants
+    fn detect_sbox_tables(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
+        let mut report = |offset: usize, kind: &str, length: usize, permutation: bool| {
+            let (line, _) = offset_to_line_col(content, offset);
+            let custom = kind == "permutation";
+            findings.push(Finding {
+                finding_type: "substitution_box".to_string(),
+                value: json!({
+                    "kind": kind,
+                    "length": length,
+                    "permutation": permutation,
+                    "line": line
+                }),
+                confidence: match (custom, permutation) {
+                    (false, true) => 0.95,
+                    (true, _) => 0.85,
+                    (false, false) => 0.8,
+                },
+                location: path.display().to_string(),
+                // A custom permutation means a home-made cipher; a known
+                // table is a hand-rolled copy of a standard one
+                severity: if custom { Severity::High } else { Severity::Medium },
+                metadata: json!({
+                    "pattern": "Substitution box",
+                    "description": if custom {
+                        format!("{}-entry byte permutation (custom S-box)", length)
+                    } else {
+                        format!("{} S-box table embedded in source", kind)
+                    }
+                }),
+            });
+        };
 
-        // Try to read as text
-        if let Ok(content) = fs::read_to_string(path) {
-            findings.extend(self.detect_math_constants(path, &content));
-            findings.extend(self.detect_grid_patterns(path, &content));
-            findings.extend(self.detect_self_reference(path, &content));
-            findings.extend(self.detect_guid_patterns(path, &content));
-            findings.extend(self.detect_sequence_patterns(path, &content));
+        for mat in self.byte_table_regex.find_iter(content) {
+            let Some(values) = parse_byte_table(mat.as_str()) else {
+                continue;
+            };
+            let known = |window: &[u8]| {
+                KNOWN_SBOX_PREFIXES
+                    .iter()
+                    .find(|(_, prefix)| window.starts_with(prefix))
+                    .map(|(kind, _)| *kind)
+            };
+
+            let permutations = permutation_windows(&values);
+            for &start in &permutations {
+                let kind = known(&values[start..]).unwrap_or("permutation");
+                report(mat.start(), kind, 256, true);
+            }
+            if permutations.is_empty() {
+                if let Some(kind) = (0..values.len()).find_map(|i| known(&values[i..])) {
+                    report(mat.start(), kind, values.len(), false);
+                }
+            }
+        }
+
+        for mat in self.blowfish_regex.find_iter(content) {
+            report(mat.start(), "blowfish", 0, false);
         }
 
+        findings.truncate(MAX_SBOXES);
         findings
     }
 
-    /// Analyze a directory recursively
-    fn analyze_directory(&self, path: &Path, recursive: bool) -> Vec<Finding> {
+    /// Analyze a single file
+    fn analyze_file(
+        &self,
+        path: &Path,
+        max_bytes: u64,
+        self_reference_max_bytes: usize,
+        tables: &ConstantTables,
+    ) -> Vec<Finding> {
+        read_text_lossy(path, max_bytes)
+            .map(|content| self.analyze_text(path, &content, self_reference_max_bytes, tables))
+            .unwrap_or_default()
+    }
+
+    /// Run every check over `content`, reported against `path`
+    fn analyze_text(
+        &self,
+        path: &Path,
+        content: &str,
+        self_reference_max_bytes: usize,
+        tables: &ConstantTables,
+    ) -> Vec<Finding> {
         let mut findings = Vec::new();
+        findings.extend(self.detect_math_constants(path, content, tables));
+        findings.extend(self.detect_grid_patterns(path, content));
+        findings.extend(self.detect_self_reference(path, content, self_reference_max_bytes));
+        findings.extend(self.detect_guid_patterns(path, content));
+        findings.extend(self.detect_sequence_patterns(path, content));
+        findings.extend(self.detect_sbox_tables(path, content));
 
-        let walker = if recursive {
-            WalkDir::new(path)
-        } else {
-            WalkDir::new(path).max_depth(1)
-        };
+        findings
+    }
+}
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                findings.extend(self.analyze_file(entry.path()));
+/// Values of a comma-separated byte table, or `None` if any is over 255
+fn parse_byte_table(table: &str) -> Option<Vec<u8>> {
+    table
+        .split(',')
+        .map(|item| {
+            let item = item.trim().trim_end_matches(['u', 'U']);
+            match item.strip_prefix("0x").or_else(|| item.strip_prefix("0X")) {
+                Some(hex) => u8::from_str_radix(hex, 16).ok(),
+                None => item.parse().ok(),
             }
+        })
+        .collect()
+}
+
+/// Start of each non-overlapping 256-entry window of `values` holding
+/// every byte exactly once, other than 0..=255 in ascending or descending
+/// order (identity tables aren't ciphers)
+fn permutation_windows(values: &[u8]) -> Vec<usize> {
+    const WINDOW: usize = 256;
+    let mut starts = Vec::new();
+    if values.len() < WINDOW {
+        return starts;
+    }
+
+    // Slide a window, counting how many byte values it holds exactly once
+    fn adjust(counts: &mut [u16; 256], singles: &mut usize, byte: u8, added: bool) {
+        let count = &mut counts[usize::from(byte)];
+        if *count == 1 {
+            *singles -= 1;
         }
+        if added {
+            *count += 1;
+        } else {
+            *count -= 1;
+        }
+        if *count == 1 {
+            *singles += 1;
+        }
+    }
+    let mut counts = [0u16; 256];
+    let mut singles = 0usize;
 
-        findings
+    let mut next_allowed = 0;
+    for (end, &byte) in values.iter().enumerate() {
+        adjust(&mut counts, &mut singles, byte, true);
+        if end >= WINDOW {
+            adjust(&mut counts, &mut singles, values[end - WINDOW], false);
+        }
+
+        let Some(start) = (end + 1).checked_sub(WINDOW) else {
+            continue;
+        };
+        if singles == WINDOW && start >= next_allowed {
+            let window = &values[start..=end];
+            let ordered = window.windows(2).all(|w| w[0] < w[1])
+                || window.windows(2).all(|w| w[0] > w[1]);
+            if !ordered {
+                starts.push(start);
+                next_allowed = end + 1;
+            }
+        }
     }
+    starts
 }
 
 impl Default for CipherDetector {
@@ -367,8 +817,8 @@ impl Skill for CipherDetector {
 
     fn description(&self) -> &str {
         "Detects cryptographic patterns including mathematical constant seeds, \
-         power-of-2 grids, self-referencing hashes, GUID correlations, and \
-         low-discrepancy sequence indicators. These patterns are update-proof \
+         power-of-2 grids, self-referencing hashes, GUID correlations, \
+         low-discrepancy sequence indicators, and substitution boxes. These patterns are update-proof \
          as they detect methodology, not specific values."
     }
 
@@ -379,7 +829,29 @@ impl Skill for CipherDetector {
             json!({
                 "path": schema::string_param("File or directory to scan"),
                 "recursive": schema::bool_param("Scan directories recursively", true),
-                "deep_scan": schema::bool_param("Perform deeper binary analysis", false)
+                "deep_scan": schema::bool_param("Perform deeper binary analysis", false),
+                "self_reference_max_bytes": {
+                    "type": "integer",
+                    "description": "Skip the self-referencing hash check on files larger than this",
+                    "default": DEFAULT_SELF_REFERENCE_MAX_BYTES
+                },
+                "extra_constants": {
+                    "type": "array",
+                    "description": "Additional seed constants as {name, value} objects",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "value": { "type": "number" }
+                        },
+                        "required": ["name", "value"]
+                    }
+                },
+                "extra_scales": {
+                    "type": "array",
+                    "description": "Additional positive scales applied to every constant",
+                    "items": { "type": "number" }
+                }
             }),
             vec!["path"],
         )
@@ -396,11 +868,16 @@ impl Skill for CipherDetector {
             )));
         }
 
-        let findings = if path.is_file() {
-            self.analyze_file(path)
-        } else {
-            self.analyze_directory(path, scan_params.recursive)
-        };
+        let max_bytes = scan_params.max_file_bytes();
+        let self_reference_max_bytes = params
+            .get("self_reference_max_bytes")
+            .and_then(Value::as_u64)
+            .map_or(DEFAULT_SELF_REFERENCE_MAX_BYTES, |n| n as usize);
+        let tables = ConstantTables::from_params(&params)?;
+
+        let (findings, stats) = scan_params.scan_files(|file| {
+            self.analyze_file(file, max_bytes, self_reference_max_bytes, &tables)
+        })?;
 
         // Filter by confidence threshold
         let threshold = self.confidence_threshold();
@@ -409,7 +886,27 @@ impl Skill for CipherDetector {
             .filter(|f| f.confidence >= threshold)
             .collect();
 
-        Ok(SkillOutput::with_findings(filtered))
+        Ok(SkillOutput::with_findings_and_stats(filtered, stats))
+    }
+
+    fn analyze_content(&self, name: &str, content: &str) -> SkillResult<Vec<Finding>> {
+        let threshold = self.confidence_threshold();
+        let mut findings = self.analyze_text(
+            Path::new(name),
+            content,
+            DEFAULT_SELF_REFERENCE_MAX_BYTES,
+            &ConstantTables::builtin(),
+        );
+        findings.retain(|f| f.confidence >= threshold);
+        Ok(findings)
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
     }
 
     fn categories(&self) -> Vec<&str> {
@@ -420,19 +917,225 @@ impl Skill for CipherDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::{Duration, Instant};
 
     #[test]
-    fn test_math_constant_detection() {
+    fn test_self_reference_verified() {
+        let detector = CipherDetector::new();
+        let template = format!("release manifest\nchecksum: {}\n", "0".repeat(32));
+        let hash = format!("{:x}", md5::compute(&template));
+        let content = template.replace(&"0".repeat(32), &hash);
+
+        let findings = detector.detect_self_reference(Path::new("m.txt"), &content, usize::MAX);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["algorithm"], "md5");
+        assert_eq!(findings[0].value["offset"], 27);
+
+        // The same construction with SHA-256
+        let template = format!("id = \"{}\"\nname = \"payload\"\n", "0".repeat(64));
+        let hash = format!("{:x}", Sha256::digest(template.as_bytes()));
+        let content = template.replace(&"0".repeat(64), &hash);
+        let findings = detector.detect_self_reference(Path::new("m.toml"), &content, usize::MAX);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["algorithm"], "sha256");
+
+        // Over the size limit the check is skipped
+        assert!(detector.detect_self_reference(Path::new("m.txt"), &content, 8).is_empty());
+    }
+
+    #[test]
+    fn test_self_reference_near_misses() {
+        let detector = CipherDetector::new();
+
+        // Digest of the file with the hash deleted rather than zeroed
+        let body = "release manifest\nchecksum: \n";
+        let hash = format!("{:x}", md5::compute(body));
+        let content = body.replace("checksum: ", &format!("checksum: {}", hash));
+        let path = Path::new("m.txt");
+        assert!(detector.detect_self_reference(path, &content, usize::MAX).is_empty());
+
+        // A genuine self-reference whose digest is then quoted a second
+        // time: the copy is part of what was hashed, so it no longer verifies
+        let template = format!("checksum: {}\n", "0".repeat(32));
+        let hash = format!("{:x}", md5::compute(&template));
+        let content = format!("{}mirror: {}\n", template.replace(&"0".repeat(32), &hash), hash);
+        assert!(detector.detect_self_reference(path, &content, usize::MAX).is_empty());
+
+        // An unfilled placeholder is never a candidate
+        let zeros = format!("checksum: {}\n", "0".repeat(32));
+        assert!(detector.detect_self_reference(path, &zeros, usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_many_hashes_finish_quickly() {
+        let detector = CipherDetector::new();
+        let mut content = String::new();
+        for i in 0..500u32 {
+            let digest = format!("{:x}", md5::compute(i.to_le_bytes()));
+            content.push_str(&format!("file_{}.bin {}\n", i, digest));
+            // Repeats count against the candidate cap like any other span
+            content.push_str(&format!("mirror {}\n", digest));
+        }
+
+        let started = Instant::now();
+        let findings = detector.detect_self_reference(
+            Path::new("SHASUMS"),
+            &content,
+            DEFAULT_SELF_REFERENCE_MAX_BYTES,
+        );
+
+        assert!(findings.is_empty());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    /// The AES S-box, built from the GF(2^8) inverse and affine transform
+    fn aes_sbox() -> Vec<u8> {
+        let mut sbox = vec![0x63u8; 256];
+        let (mut p, mut q) = (1u8, 1u8);
+        loop {
+            p ^= (p << 1) ^ if p & 0x80 != 0 { 0x1b } else { 0 };
+            q ^= q << 1;
+            q ^= q << 2;
+            q ^= q << 4;
+            if q & 0x80 != 0 {
+                q ^= 0x09;
+            }
+            let affine = (1..=4).fold(q, |acc, bits| acc ^ q.rotate_left(bits));
+            sbox[usize::from(p)] = affine ^ 0x63;
+            if p == 1 {
+                return sbox;
+            }
+        }
+    }
+
+    fn c_array(name: &str, values: impl IntoIterator<Item = u8>) -> String {
+        let items: Vec<String> = values.into_iter().map(|v| format!("0x{:02x}", v)).collect();
+        format!("static const uint8_t {}[] = {{\n    {}\n}};\n", name, items.join(", "))
+    }
+
+    #[test]
+    fn test_sbox_tables() {
+        let detector = CipherDetector::new();
+        // An affine permutation: (167 * i + 13) mod 256, 167 being odd
+        let custom = (0..=255u8).map(|i| i.wrapping_mul(167).wrapping_add(13));
+        let source = format!(
+            "{}{}// header\nint fixed[] = {{99, 124, 119, 123, 242, 107, 111, 197, 48}};\n",
+            c_array("sbox", aes_sbox()),
+            c_array("mix", custom)
+        );
+
+        let findings = detector.detect_sbox_tables(Path::new("cipher.c"), &source);
+        let kinds: Vec<(&str, bool)> = findings
+            .iter()
+            .map(|f| (f.value["kind"].as_str().unwrap(), f.value["permutation"] == true))
+            .collect();
+        assert_eq!(
+            kinds,
+            [("aes_forward", true), ("permutation", true), ("aes_forward", false)]
+        );
+        assert_eq!(findings[1].severity, Severity::High);
+        assert_eq!(findings[1].value["line"], 5);
+    }
+
+    #[test]
+    fn test_byte_tables_that_are_not_sboxes() {
         let detector = CipherDetector::new();
+        let identity = c_array("identity", 0..=255u8);
+        let reversed = c_array("reversed", (0..=255u8).rev());
+        let gamma = c_array("gamma", (0..=255u16).map(|i| (i * i / 255) as u8));
+        let wide: Vec<String> = (0..300).map(|i| i.to_string()).collect();
+        let wide = format!("int t[] = {{{}}};", wide.join(","));
+
+        for source in [identity, reversed, gamma, wide] {
+            let findings = detector.detect_sbox_tables(Path::new("t.c"), &source);
+            assert!(findings.is_empty(), "{}", source);
+        }
+
+        let blowfish = "uint32_t P[18] = { 0x243F6A88, 0x85A308D3, 0x13198A2E, 0x03707344 };";
+        let findings = detector.detect_sbox_tables(Path::new("bf.c"), blowfish);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["kind"], "blowfish");
+    }
+
+    #[test]
+    fn test_math_constant_detection() {
+        let tables = ConstantTables::builtin();
 
         // φ * 1e9 = 1618033988
-        assert!(detector.check_constant(1618033988).is_some());
+        assert!(CipherDetector::check_constant(&tables, 1618033988).is_some());
 
         // π * 1e9 = 3141592653
-        assert!(detector.check_constant(3141592653).is_some());
+        assert!(CipherDetector::check_constant(&tables, 3141592653).is_some());
 
         // Random number should not match
-        assert!(detector.check_constant(1234567890).is_none());
+        assert!(CipherDetector::check_constant(&tables, 1234567890).is_none());
+    }
+
+    #[test]
+    fn test_phi_as_float_hex_and_scaled_integer() {
+        let detector = CipherDetector::new();
+        let tables = ConstantTables::builtin();
+        // 0x60713D44 = 1618033988 = φ * 1e9
+        let content = "const A = 1.618034;\nconst B = 0x60713D44;\nconst C = 1618033988;\n";
+        let findings = detector.detect_math_constants(Path::new("seed.js"), content, &tables);
+
+        let encodings: Vec<_> = findings.iter().map(|f| f.value["encoding"].clone()).collect();
+        assert_eq!(encodings, [json!("float"), json!("hex"), json!("decimal")]);
+        assert!(findings.iter().all(|f| f.value["constant"] == "phi"));
+        assert_eq!(findings[0].value["number"], 1.618034);
+        assert_eq!(findings[0].value["scale"], 1.0);
+        assert!(findings[0].confidence >= 0.7);
+        assert_eq!(findings[1].value["number"], 1618033988u64);
+        assert_eq!(findings[1].value["literal"], "0x60713D44");
+        assert_eq!(findings[1].value["scale"], 1e9);
+
+        // The fraction of 1.618034 is φ-1 scaled by 1e6, but it is one literal
+        assert_eq!(findings.len(), 3);
+
+        // Short floats, unrelated floats and small hex are left alone
+        let benign = "x = 3.14; y = 1.23456789; mask = 0xFFFF; ratio = 0.5;";
+        assert!(detector.detect_math_constants(Path::new("a.js"), benign, &tables).is_empty());
+        let pi_5: f64 = "3.14159".parse().unwrap();
+        let (_, confidence) = CipherDetector::check_float(&tables, pi_5, 5).unwrap();
+        assert!(confidence < 0.7);
+    }
+
+    #[test]
+    fn test_extra_constants_and_scales() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("seed.js");
+        // Feigenbaum δ (4.6692016...) scaled by 1e5 is not in the built-in tables
+        std::fs::write(&target, "const SEED = 466920;").unwrap();
+
+        let detector = CipherDetector::new();
+        let base = json!({ "path": target.to_str().unwrap() });
+        assert!(detector.execute(base).unwrap().findings.is_empty());
+
+        let output = detector
+            .execute(json!({
+                "path": target.to_str().unwrap(),
+                "extra_constants": [{ "name": "feigenbaum", "value": 4.669_201_609 }],
+                "extra_scales": [1e5]
+            }))
+            .unwrap();
+        let seed = output
+            .findings
+            .iter()
+            .find(|f| f.finding_type == "math_constant_seed")
+            .unwrap();
+        assert_eq!(seed.value["constant"], "feigenbaum");
+        assert_eq!(seed.value["number"], 466920);
+
+        for bad in [
+            json!({ "extra_scales": [0.0] }),
+            json!({ "extra_scales": [-10.0] }),
+            json!({ "extra_constants": [{ "name": "x" }] }),
+            json!({ "extra_constants": { "name": "x", "value": 1.0 } }),
+        ] {
+            let mut params = bad;
+            params["path"] = json!(target.to_str().unwrap());
+            assert!(matches!(detector.execute(params), Err(SkillError::InvalidParams(_))));
+        }
     }
 
     #[test]
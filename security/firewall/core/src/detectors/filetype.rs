@@ -0,0 +1,405 @@
+//! File Type Sniffing
+//!
+//! Identifies files by their magic bytes rather than their extension, so
+//! detectors can branch on what a file really is. Also provides the
+//! extension mismatch skill:
+//! - Executables disguised as images, documents or media (dropper trick)
+//! - Archives and documents renamed to another known type
+
+use crate::skills::{
+    explained_types, find_explanation, read_capped, schema, Explanation, Explanations, Finding,
+    ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+};
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// Bytes read from the start of a file for sniffing
+pub const SNIFF_BYTES: u64 = 64;
+
+/// File type as identified by magic bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileKind {
+    Png,
+    Jpeg,
+    Gif,
+    Bmp,
+    Webp,
+    Ico,
+    Pdf,
+    Zip,
+    Gzip,
+    SevenZip,
+    Rar,
+    Ole,
+    Elf,
+    Pe,
+    MachO,
+    JavaClass,
+    Wav,
+    Mp3,
+    Ogg,
+    Flac,
+    /// No known signature; usually text
+    Unknown,
+}
+
+impl FileKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileKind::Png => "PNG",
+            FileKind::Jpeg => "JPEG",
+            FileKind::Gif => "GIF",
+            FileKind::Bmp => "BMP",
+            FileKind::Webp => "WebP",
+            FileKind::Ico => "ICO",
+            FileKind::Pdf => "PDF",
+            FileKind::Zip => "ZIP",
+            FileKind::Gzip => "gzip",
+            FileKind::SevenZip => "7z",
+            FileKind::Rar => "RAR",
+            FileKind::Ole => "OLE compound document",
+            FileKind::Elf => "ELF",
+            FileKind::Pe => "PE",
+            FileKind::MachO => "Mach-O",
+            FileKind::JavaClass => "Java class",
+            FileKind::Wav => "WAV",
+            FileKind::Mp3 => "MP3",
+            FileKind::Ogg => "Ogg",
+            FileKind::Flac => "FLAC",
+            FileKind::Unknown => "unknown",
+        }
+    }
+
+    /// Extensions a file of this kind is expected to carry (lowercase)
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            FileKind::Png => &["png", "apng"],
+            FileKind::Jpeg => &["jpg", "jpeg", "jpe", "jfif"],
+            FileKind::Gif => &["gif"],
+            FileKind::Bmp => &["bmp", "dib"],
+            FileKind::Webp => &["webp"],
+            FileKind::Ico => &["ico", "cur"],
+            FileKind::Pdf => &["pdf"],
+            // Many formats are ZIP containers
+            FileKind::Zip => &[
+                "zip", "jar", "war", "apk", "aar", "docx", "xlsx", "pptx", "odt", "ods", "odp",
+                "epub", "xpi", "crx", "vsix", "whl", "nupkg", "ipa", "kmz",
+            ],
+            FileKind::Gzip => &["gz", "tgz"],
+            FileKind::SevenZip => &["7z"],
+            FileKind::Rar => &["rar"],
+            FileKind::Ole => &["doc", "xls", "ppt", "msi", "msg", "vsd"],
+            FileKind::Elf => &["elf", "so", "o", "ko", "axf"],
+            FileKind::Pe => &["exe", "dll", "sys", "scr", "ocx", "cpl", "efi", "drv", "com", "mui"],
+            FileKind::MachO => &["dylib", "bundle", "o"],
+            FileKind::JavaClass => &["class"],
+            FileKind::Wav => &["wav", "wave"],
+            FileKind::Mp3 => &["mp3"],
+            FileKind::Ogg => &["ogg", "oga", "ogv", "opus"],
+            FileKind::Flac => &["flac"],
+            FileKind::Unknown => &[],
+        }
+    }
+
+    /// Native executable formats
+    pub fn is_executable(&self) -> bool {
+        matches!(self, FileKind::Elf | FileKind::Pe | FileKind::MachO)
+    }
+
+    /// Formats whose payload is compressed, so high byte entropy is expected
+    pub fn is_compressed(&self) -> bool {
+        matches!(
+            self,
+            FileKind::Png
+                | FileKind::Jpeg
+                | FileKind::Gif
+                | FileKind::Webp
+                | FileKind::Pdf
+                | FileKind::Zip
+                | FileKind::Gzip
+                | FileKind::SevenZip
+                | FileKind::Rar
+                | FileKind::Mp3
+                | FileKind::Ogg
+                | FileKind::Flac
+        )
+    }
+
+    /// Kind whose extensions include `extension`, if any
+    pub fn from_extension(extension: &str) -> Option<FileKind> {
+        let extension = extension.to_ascii_lowercase();
+        KNOWN_KINDS
+            .iter()
+            .copied()
+            .find(|kind| kind.extensions().contains(&extension.as_str()))
+    }
+}
+
+/// Every kind except `Unknown`, in the order `from_extension` prefers them
+const KNOWN_KINDS: &[FileKind] = &[
+    FileKind::Png,
+    FileKind::Jpeg,
+    FileKind::Gif,
+    FileKind::Bmp,
+    FileKind::Webp,
+    FileKind::Ico,
+    FileKind::Pdf,
+    FileKind::Zip,
+    FileKind::Gzip,
+    FileKind::SevenZip,
+    FileKind::Rar,
+    FileKind::Ole,
+    FileKind::Elf,
+    FileKind::Pe,
+    FileKind::MachO,
+    FileKind::JavaClass,
+    FileKind::Wav,
+    FileKind::Mp3,
+    FileKind::Ogg,
+    FileKind::Flac,
+];
+
+/// Identify a file from its first bytes
+pub fn sniff(bytes: &[u8]) -> FileKind {
+    let at = |offset: usize, magic: &[u8]| bytes.get(offset..offset + magic.len()) == Some(magic);
+
+    if at(0, b"\x89PNG\r\n\x1a\n") {
+        FileKind::Png
+    } else if at(0, &[0xFF, 0xD8, 0xFF]) {
+        FileKind::Jpeg
+    } else if at(0, b"GIF87a") || at(0, b"GIF89a") {
+        FileKind::Gif
+    } else if at(0, b"RIFF") && at(8, b"WEBP") {
+        FileKind::Webp
+    } else if at(0, b"RIFF") && at(8, b"WAVE") {
+        FileKind::Wav
+    } else if at(0, b"BM") && bytes.len() >= 14 {
+        FileKind::Bmp
+    } else if at(0, &[0x00, 0x00, 0x01, 0x00]) || at(0, &[0x00, 0x00, 0x02, 0x00]) {
+        FileKind::Ico
+    } else if at(0, b"%PDF-") {
+        FileKind::Pdf
+    } else if at(0, b"PK\x03\x04") || at(0, b"PK\x05\x06") || at(0, b"PK\x07\x08") {
+        FileKind::Zip
+    } else if at(0, &[0x1F, 0x8B]) {
+        FileKind::Gzip
+    } else if at(0, b"7z\xBC\xAF\x27\x1C") {
+        FileKind::SevenZip
+    } else if at(0, b"Rar!\x1A\x07") {
+        FileKind::Rar
+    } else if at(0, &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+        FileKind::Ole
+    } else if at(0, b"\x7FELF") {
+        FileKind::Elf
+    } else if at(0, b"MZ") {
+        FileKind::Pe
+    } else if at(0, &[0xFE, 0xED, 0xFA, 0xCE])
+        || at(0, &[0xFE, 0xED, 0xFA, 0xCF])
+        || at(0, &[0xCE, 0xFA, 0xED, 0xFE])
+        || at(0, &[0xCF, 0xFA, 0xED, 0xFE])
+    {
+        FileKind::MachO
+    } else if at(0, &[0xCA, 0xFE, 0xBA, 0xBE]) {
+        // Fat Mach-O and Java class files share a magic; a fat header's
+        // architecture count is far below the lowest class file version (45)
+        match bytes.get(4..8) {
+            Some(n) if u32::from_be_bytes([n[0], n[1], n[2], n[3]]) < 45 => FileKind::MachO,
+            _ => FileKind::JavaClass,
+        }
+    } else if at(0, b"ID3") || (bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] & 0xE0 == 0xE0)
+    {
+        FileKind::Mp3
+    } else if at(0, b"OggS") {
+        FileKind::Ogg
+    } else if at(0, b"fLaC") {
+        FileKind::Flac
+    } else {
+        FileKind::Unknown
+    }
+}
+
+/// Sniff the start of the file at `path`
+pub fn sniff_file(path: &Path) -> std::io::Result<FileKind> {
+    read_capped(path, SNIFF_BYTES).map(|bytes| sniff(&bytes))
+}
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "extension_mismatch",
+        "A file whose contents do not match its extension, for example an executable named \
+         photo.jpg.",
+        "Disguising an executable or archive as a harmless type gets it past upload filters and \
+         careless users, and is a common dropper technique.",
+        "Images saved in a different format than their extension, renamed archives such as .jar \
+         or .docx (which are zip files), and extensionless build outputs.",
+        "Check the actual type reported in the finding. Rename the file to match, or delete it if \
+         an executable is posing as a document or image.",
+    ),
+];
+
+/// Flags files whose magic bytes disagree with their extension
+pub struct ExtensionMismatchDetector;
+
+impl ExtensionMismatchDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Compare a file's extension with its sniffed kind
+    fn analyze_file(&self, path: &Path) -> Option<Finding> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        let expected = FileKind::from_extension(&extension)?;
+
+        // Text or truncated files have no signature to disagree with
+        let actual = sniff_file(path).ok()?;
+        if actual == FileKind::Unknown || actual.extensions().contains(&extension.as_str()) {
+            return None;
+        }
+
+        let (severity, confidence) = if actual.is_executable() {
+            (Severity::Critical, 0.95)
+        } else {
+            (Severity::Medium, 0.8)
+        };
+
+        Some(Finding {
+            finding_type: "extension_mismatch".to_string(),
+            value: json!({
+                "extension": extension,
+                "expected": expected.as_str(),
+                "actual": actual.as_str()
+            }),
+            confidence,
+            location: path.display().to_string(),
+            severity,
+            metadata: json!({
+                "pattern": "File content does not match extension",
+                "description": format!(
+                    ".{} file is actually {}{}",
+                    extension,
+                    actual.as_str(),
+                    if actual.is_executable() { " (disguised executable)" } else { "" }
+                )
+            }),
+        })
+    }
+}
+
+impl Default for ExtensionMismatchDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Skill for ExtensionMismatchDetector {
+    fn name(&self) -> &str {
+        "detect_extension_mismatch"
+    }
+
+    fn description(&self) -> &str {
+        "Detects files whose magic bytes disagree with their extension, such \
+         as executables disguised as images or documents."
+    }
+
+    fn schema(&self) -> Value {
+        schema::skill_schema(
+            self.name(),
+            self.description(),
+            json!({
+                "path": schema::string_param("File or directory to scan"),
+                "recursive": schema::bool_param("Scan directories recursively", true)
+            }),
+            vec!["path"],
+        )
+    }
+
+    fn execute(&self, params: Value) -> SkillResult<SkillOutput> {
+        let scan_params = ScanParams::from_value(&params)?;
+        let path = scan_params.path();
+
+        if !path.exists() {
+            return Err(SkillError::InvalidParams(format!(
+                "Path does not exist: {}",
+                path.display()
+            )));
+        }
+
+        let (findings, stats) =
+            scan_params.scan_files(|file| self.analyze_file(file).into_iter().collect())?;
+
+        let threshold = self.confidence_threshold();
+        let filtered: Vec<Finding> = findings
+            .into_iter()
+            .filter(|f| f.confidence >= threshold)
+            .collect();
+
+        Ok(SkillOutput::with_findings_and_stats(filtered, stats))
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["filetype", "dropper", "pattern_detection"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_sniff_magic_bytes() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), FileKind::Png);
+        assert_eq!(sniff(&[0xFF, 0xD8, 0xFF, 0xE0]), FileKind::Jpeg);
+        assert_eq!(sniff(b"GIF89a\x01\x00"), FileKind::Gif);
+        assert_eq!(sniff(b"%PDF-1.7\n"), FileKind::Pdf);
+        assert_eq!(sniff(b"PK\x03\x04\x14\x00"), FileKind::Zip);
+        assert_eq!(sniff(b"\x7FELF\x02\x01\x01"), FileKind::Elf);
+        assert_eq!(sniff(b"MZ\x90\x00\x03\x00"), FileKind::Pe);
+        assert_eq!(sniff(&[0xCF, 0xFA, 0xED, 0xFE, 7, 0, 0, 1]), FileKind::MachO);
+        assert_eq!(sniff(&[0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 2]), FileKind::MachO);
+        assert_eq!(sniff(&[0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52]), FileKind::JavaClass);
+        assert_eq!(sniff(b"RIFF\x24\x08\x00\x00WAVEfmt "), FileKind::Wav);
+        assert_eq!(sniff(b"RIFF\x24\x08\x00\x00WEBPVP8 "), FileKind::Webp);
+        assert_eq!(sniff(b"fn main() {}"), FileKind::Unknown);
+        assert_eq!(sniff(b""), FileKind::Unknown);
+    }
+
+    #[test]
+    fn test_disguised_executable_is_flagged() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("invoice.png"), b"MZ\x90\x00\x03\x00\x00\x00").unwrap();
+        fs::write(dir.path().join("logo.png"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        fs::write(dir.path().join("report.docx"), b"PK\x03\x04\x14\x00").unwrap();
+        fs::write(dir.path().join("notes.pdf"), b"not really a pdf").unwrap();
+        fs::write(dir.path().join("backup.jpg"), b"PK\x03\x04\x14\x00").unwrap();
+
+        let output = ExtensionMismatchDetector::new()
+            .execute(json!({ "path": dir.path().to_str().unwrap() }))
+            .unwrap();
+
+        assert_eq!(output.findings.len(), 2);
+        let dropper = output
+            .findings
+            .iter()
+            .find(|f| f.location.ends_with("invoice.png"))
+            .unwrap();
+        assert_eq!(dropper.severity, Severity::Critical);
+        assert_eq!(dropper.value["actual"], "PE");
+
+        let archive = output
+            .findings
+            .iter()
+            .find(|f| f.location.ends_with("backup.jpg"))
+            .unwrap();
+        assert_eq!(archive.severity, Severity::Medium);
+    }
+}
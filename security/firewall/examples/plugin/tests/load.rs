@@ -0,0 +1,65 @@
+//! Load the example plugin's cdylib into a registry and run its skill
+
+use firewall_core::plugin::{PluginRegistrar, RegisterFn, STATUS_VERSION_MISMATCH};
+use firewall_core::{create_default_registry, SkillRegistry};
+use serde_json::json;
+use std::ffi::{c_void, CString};
+use std::path::PathBuf;
+
+/// The cdylib cargo built alongside this test, in `target/<profile>/deps`
+/// or one level up
+fn plugin_path() -> PathBuf {
+    let name = format!(
+        "{}firewall_example_plugin{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+    let exe = std::env::current_exe().unwrap();
+    let deps = exe.parent().unwrap();
+    [deps.join(&name), deps.parent().unwrap().join(&name)]
+        .into_iter()
+        .find(|path| path.exists())
+        .expect("plugin cdylib not built")
+}
+
+#[test]
+fn loads_plugin_and_invokes_its_skill() {
+    let mut registry = create_default_registry();
+    let builtin = registry.list().len();
+
+    let names = unsafe { registry.load_plugin(&plugin_path()) }.unwrap();
+    assert_eq!(names, ["detect_vendor_marker"]);
+    assert_eq!(registry.list().len(), builtin + 1);
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("dropper.bin");
+    std::fs::write(&file, format!("header {} payload", firewall_example_plugin::MARKER)).unwrap();
+
+    let output = registry
+        .invoke("detect_vendor_marker", json!({ "path": file.display().to_string() }))
+        .unwrap();
+    assert_eq!(output.findings.len(), 1);
+    assert_eq!(output.findings[0].finding_type, "vendor_marker");
+    assert_eq!(output.findings[0].value["offset"], 7);
+
+    // Plugin params are validated against the plugin's own schema
+    assert!(registry.invoke("detect_vendor_marker", json!({ "path": 1 })).is_err());
+}
+
+#[test]
+fn entry_point_rejects_other_host_versions() {
+    // The rlib build of the same entry point the cdylib exports
+    let entry: RegisterFn = firewall_example_plugin::gentlyos_register_skills;
+    let version = CString::new("0.0.0-other").unwrap();
+    let mut registrar = PluginRegistrar::default();
+    let status = unsafe { entry(version.as_ptr(), &mut registrar as *mut _ as *mut c_void) };
+    assert_eq!(status, STATUS_VERSION_MISMATCH);
+}
+
+#[test]
+fn non_plugin_library_is_an_error() {
+    let mut registry = SkillRegistry::new();
+    let not_a_plugin = tempfile::NamedTempFile::new().unwrap();
+    assert!(unsafe { registry.load_plugin(not_a_plugin.path()) }.is_err());
+    assert!(registry.list().is_empty());
+}